@@ -0,0 +1,27 @@
+//! Builds and writes a CSI from a BCF file.
+//!
+//! The input BCF must be coordinate-sorted.
+//!
+//! This writes the output to stdout rather than `<src>.csi`.
+//!
+//! The output is similar to the output of `bcftools index <src>`.
+
+use std::{
+    env,
+    io::{self, BufWriter},
+};
+
+use noodles_bcf as bcf;
+use noodles_csi as csi;
+
+fn main() -> io::Result<()> {
+    let src = env::args().nth(1).expect("missing src");
+
+    let index = bcf::index(src)?;
+
+    let stdout = io::stdout().lock();
+    let mut writer = csi::io::Writer::new(BufWriter::new(stdout));
+    writer.write_index(&index)?;
+
+    Ok(())
+}
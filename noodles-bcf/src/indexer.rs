@@ -0,0 +1,142 @@
+use std::{fs::File, io, path::Path};
+
+use noodles_csi::{
+    self as csi,
+    binning_index::{index::reference_sequence::bin::Chunk, Indexer},
+};
+use noodles_vcf::variant::Record as _;
+
+use super::{
+    io::{
+        indexed_writer::{DEFAULT_DEPTH, DEFAULT_MIN_SHIFT},
+        Reader,
+    },
+    Record,
+};
+
+/// Indexes a BCF file.
+///
+/// This builds a coordinate-sorted index (CSI) with a min shift of 14 and a depth of 5. The
+/// input must be coordinate-sorted and BGZF-compressed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use noodles_bcf as bcf;
+/// let index = bcf::index("sample.bcf")?;
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn index<P>(src: P) -> io::Result<csi::Index>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = File::open(src).map(Reader::new)?;
+    let header = reader.read_header()?;
+
+    let mut indexer = Indexer::new(DEFAULT_MIN_SHIFT, DEFAULT_DEPTH);
+
+    let mut record = Record::default();
+    let mut start_position = reader.get_ref().virtual_position();
+
+    while reader.read_record(&mut record)? != 0 {
+        let end_position = reader.get_ref().virtual_position();
+        let chunk = Chunk::new(start_position, end_position);
+
+        let reference_sequence_id = record.reference_sequence_id()?;
+
+        let start = record
+            .variant_start()
+            .transpose()?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing position"))?;
+
+        let end = record.variant_end(&header)?;
+
+        indexer.add_record(Some((reference_sequence_id, start, end, true)), chunk)?;
+
+        start_position = end_position;
+    }
+
+    Ok(indexer.build(header.string_maps().contigs().len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, io::Write as _, process};
+
+    use noodles_bgzf as bgzf;
+    use noodles_core::Position;
+    use noodles_csi::BinningIndex;
+    use noodles_vcf::{
+        self as vcf,
+        header::StringMaps,
+        variant::{io::Write as _, RecordBuf},
+    };
+
+    use super::*;
+    use crate::io::Writer;
+
+    #[test]
+    fn test_index() -> Result<(), Box<dyn std::error::Error>> {
+        // The contig string map has a gap at index 1.
+        const RAW_HEADER: &str = "##fileformat=VCFv4.5
+##contig=<ID=sq0,IDX=0>
+##contig=<ID=sq1,IDX=2>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+";
+
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        *header.string_maps_mut() = StringMaps::try_from(&header)?;
+
+        // The VCF header writer does not write `IDX`, so only the records are taken from an
+        // uncompressed BCF.
+        let mut encoder = Writer::from(Vec::new());
+        encoder.write_header(&header)?;
+
+        let mut ends = vec![encoder.get_ref().len()];
+
+        for (name, start) in [("sq0", 8), ("sq1", 13)] {
+            let record = RecordBuf::builder()
+                .set_reference_sequence_name(name)
+                .set_variant_start(Position::try_from(start)?)
+                .set_reference_bases("A")
+                .build();
+
+            encoder.write_variant_record(&header, &record)?;
+            ends.push(encoder.get_ref().len());
+        }
+
+        let buf = encoder.into_inner();
+
+        let src = env::temp_dir().join(format!("noodles-bcf-index-{}.bcf", process::id()));
+        let mut writer = File::create(&src).map(bgzf::Writer::new)?;
+
+        let text = format!("{RAW_HEADER}\0");
+        writer.write_all(b"BCF\x02\x02")?;
+        writer.write_all(&u32::try_from(text.len())?.to_le_bytes())?;
+        writer.write_all(text.as_bytes())?;
+
+        let mut chunks = Vec::new();
+
+        // Each record ends its block.
+        for range in ends.windows(2) {
+            let start_position = writer.virtual_position();
+            writer.write_all(&buf[range[0]..range[1]])?;
+            writer.flush()?;
+            let end_position = writer.virtual_position();
+            chunks.push(Chunk::new(start_position, end_position));
+        }
+
+        writer.finish()?;
+
+        let result = index(&src);
+        fs::remove_file(&src)?;
+        let index = result?;
+
+        assert_eq!(index.reference_sequences().len(), 3);
+        assert_eq!(index.query(0, (..).into())?, [chunks[0]]);
+        assert!(index.query(1, (..).into())?.is_empty());
+        assert_eq!(index.query(2, (..).into())?, [chunks[1]]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+//! Indexed BCF writer.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+use noodles_bgzf as bgzf;
+use noodles_csi::{
+    self as csi,
+    binning_index::{
+        index::reference_sequence::{bin::Chunk, index::BinnedIndex},
+        Indexer,
+    },
+};
+use noodles_vcf::{self as vcf, header::StringMaps};
+
+use super::Writer;
+use crate::Record;
+
+pub(crate) const DEFAULT_MIN_SHIFT: u8 = 14;
+pub(crate) const DEFAULT_DEPTH: u8 = 5;
+
+/// An indexed BCF writer.
+///
+/// This builds a coordinate-sorted index (CSI) of the records as they are written. Records must
+/// be written in coordinate order.
+pub struct IndexedWriter<W>
+where
+    W: Write,
+{
+    inner: Writer<bgzf::Writer<W>>,
+    min_shift: u8,
+    depth: u8,
+    indexer: Indexer<BinnedIndex>,
+    string_maps: StringMaps,
+}
+
+impl<W> IndexedWriter<W>
+where
+    W: Write,
+{
+    /// Creates an indexed BCF writer with a default min shift and depth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf as bcf;
+    /// let writer = bcf::io::IndexedWriter::new(io::sink());
+    /// ```
+    pub fn new(writer: W) -> Self {
+        Builder::default().build_from_writer(writer)
+    }
+
+    fn from_parts(inner: Writer<bgzf::Writer<W>>, min_shift: u8, depth: u8) -> Self {
+        Self {
+            inner,
+            min_shift,
+            depth,
+            indexer: Indexer::new(min_shift, depth),
+            string_maps: StringMaps::default(),
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf as bcf;
+    /// let writer = bcf::io::IndexedWriter::new(Vec::new());
+    /// assert!(writer.get_ref().get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &bgzf::Writer<W> {
+        self.inner.get_ref()
+    }
+
+    /// Writes a VCF header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut writer = bcf::io::IndexedWriter::new(io::sink());
+    ///
+    /// let header = vcf::Header::default();
+    /// writer.write_header(&header)?;
+    /// # Ok::<(), io::Error>(())
+    /// ```
+    pub fn write_header(&mut self, header: &vcf::Header) -> io::Result<()> {
+        self.inner.write_header(header)?;
+
+        self.string_maps = StringMaps::try_from(header)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(())
+    }
+
+    /// Writes a record and adds it to the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::{
+    ///         record::value::{map::Contig, Map},
+    ///         StringMaps,
+    ///     },
+    /// };
+    ///
+    /// let mut writer = bcf::io::IndexedWriter::new(io::sink());
+    ///
+    /// let mut header = vcf::Header::builder()
+    ///     .add_contig("sq0", Map::<Contig>::new())
+    ///     .build();
+    /// *header.string_maps_mut() = StringMaps::try_from(&header)?;
+    ///
+    /// writer.write_header(&header)?;
+    ///
+    /// let record = bcf::Record::default();
+    /// writer.write_record(&header, &record)?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write_record(&mut self, header: &vcf::Header, record: &Record) -> io::Result<()> {
+        self.write_indexed(header, record, |inner, header, record| {
+            inner.write_record(header, record)
+        })
+    }
+
+    /// Writes a record without re-encoding it.
+    ///
+    /// See [`crate::io::Writer::write_raw_record`]. The header is used to resolve the reference
+    /// sequence name and end position of the record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::{
+    ///         record::value::{map::Contig, Map},
+    ///         StringMaps,
+    ///     },
+    /// };
+    ///
+    /// let mut writer = bcf::io::IndexedWriter::new(io::sink());
+    ///
+    /// let mut header = vcf::Header::builder()
+    ///     .add_contig("sq0", Map::<Contig>::new())
+    ///     .build();
+    /// *header.string_maps_mut() = StringMaps::try_from(&header)?;
+    ///
+    /// writer.write_header(&header)?;
+    ///
+    /// let record = bcf::Record::default();
+    /// writer.write_raw_record(&header, &record)?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write_raw_record(&mut self, header: &vcf::Header, record: &Record) -> io::Result<()> {
+        self.write_indexed(header, record, |inner, _, record| {
+            inner.write_raw_record(record)
+        })
+    }
+
+    /// Finishes the output stream and returns the index of the written records.
+    ///
+    /// The returned index is typically written to `<dst>.csi`. Records written after finishing
+    /// start a new index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut writer = bcf::io::IndexedWriter::new(Vec::new());
+    /// writer.write_header(&vcf::Header::default())?;
+    ///
+    /// let index = writer.finish()?;
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn finish(&mut self) -> io::Result<csi::Index> {
+        self.inner.try_finish()?;
+
+        let indexer = mem::replace(&mut self.indexer, Indexer::new(self.min_shift, self.depth));
+
+        Ok(indexer.build(self.string_maps.contigs().len()))
+    }
+
+    fn write_indexed<R, F>(&mut self, header: &vcf::Header, record: &R, f: F) -> io::Result<()>
+    where
+        R: vcf::variant::Record + ?Sized,
+        F: FnOnce(&mut Writer<bgzf::Writer<W>>, &vcf::Header, &R) -> io::Result<()>,
+    {
+        let reference_sequence_name = record.reference_sequence_name(header)?;
+
+        let reference_sequence_id = self
+            .string_maps
+            .contigs()
+            .get_index_of(reference_sequence_name)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("undefined contig: {reference_sequence_name}"),
+                )
+            })?;
+
+        let start = record
+            .variant_start()
+            .transpose()?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing position"))?;
+
+        let end = record.variant_end(header)?;
+
+        let start_position = self.inner.get_ref().virtual_position();
+        f(&mut self.inner, header, record)?;
+        let end_position = self.inner.get_ref().virtual_position();
+
+        let chunk = Chunk::new(start_position, end_position);
+
+        self.indexer
+            .add_record(Some((reference_sequence_id, start, end, true)), chunk)
+    }
+}
+
+impl<W> vcf::variant::io::Write for IndexedWriter<W>
+where
+    W: Write,
+{
+    fn write_variant_header(&mut self, header: &vcf::Header) -> io::Result<()> {
+        self.write_header(header)
+    }
+
+    fn write_variant_record(
+        &mut self,
+        header: &vcf::Header,
+        record: &dyn vcf::variant::Record,
+    ) -> io::Result<()> {
+        self.write_indexed(header, record, |inner, header, record| {
+            inner.write_variant_record(header, record)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::{Position, Region};
+    use noodles_csi::BinningIndex;
+    use noodles_vcf::{
+        header::record::value::{map::Contig, Map},
+        variant::{io::Write as _, RecordBuf},
+    };
+
+    use super::*;
+    use crate::io::indexed_reader;
+
+    #[test]
+    fn test_write_variant_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header = vcf::Header::builder()
+            .add_contig("sq0", Map::<Contig>::new())
+            .add_contig("sq1", Map::<Contig>::new())
+            .add_contig("sq2", Map::<Contig>::new())
+            .build();
+
+        *header.string_maps_mut() = StringMaps::try_from(&header)?;
+
+        let mut writer = Builder::default()
+            .set_min_shift(10)
+            .set_depth(4)
+            .build_from_writer(Vec::new());
+
+        writer.write_header(&header)?;
+
+        for (name, start, reference_bases) in [
+            ("sq0", 8, "A"),
+            ("sq0", 13, "ACGT"),
+            ("sq0", 5000, "A"),
+            ("sq1", 21, "C"),
+        ] {
+            let record = RecordBuf::builder()
+                .set_reference_sequence_name(name)
+                .set_variant_start(Position::try_from(start)?)
+                .set_reference_bases(reference_bases)
+                .build();
+
+            writer.write_variant_record(&header, &record)?;
+        }
+
+        let index = writer.finish()?;
+
+        assert_eq!(index.min_shift(), 10);
+        assert_eq!(index.depth(), 4);
+        assert_eq!(index.reference_sequences().len(), 3);
+
+        let data = writer.get_ref().get_ref().clone();
+
+        let mut reader = indexed_reader::Builder::default()
+            .set_index(index)
+            .build_from_reader(io::Cursor::new(data))?;
+
+        let header = reader.read_header()?;
+
+        let region = Region::new("sq0", Position::try_from(10)?..=Position::try_from(20)?);
+        assert_eq!(reader.query(&header, &region)?.count(), 1);
+
+        let region = Region::new("sq1", ..);
+        assert_eq!(reader.query(&header, &region)?.count(), 1);
+
+        let region = Region::new("sq2", ..);
+        assert_eq!(reader.query(&header, &region)?.count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish_with_contig_string_map_gap() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = "##fileformat=VCFv4.5
+##contig=<ID=sq0,IDX=0>
+##contig=<ID=sq1,IDX=2>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+"
+        .parse()?;
+
+        *header.string_maps_mut() = StringMaps::try_from(&header)?;
+
+        let mut writer = IndexedWriter::new(io::sink());
+        writer.write_header(&header)?;
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq1")
+            .set_variant_start(Position::MIN)
+            .set_reference_bases("A")
+            .build();
+
+        writer.write_variant_record(&header, &record)?;
+
+        let index = writer.finish()?;
+        assert_eq!(index.reference_sequences().len(), 3);
+        assert!(!index.query(2, (..).into())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_variant_record_with_undefined_contig() -> io::Result<()> {
+        let mut writer = IndexedWriter::new(io::sink());
+
+        let header = vcf::Header::default();
+        writer.write_header(&header)?;
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::MIN)
+            .set_reference_bases("A")
+            .build();
+
+        assert!(matches!(
+            writer.write_variant_record(&header, &record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
+
+use noodles_bgzf as bgzf;
+
+use super::{IndexedWriter, DEFAULT_DEPTH, DEFAULT_MIN_SHIFT};
+use crate::io::Writer;
+
+/// An indexed BCF writer builder.
+#[derive(Debug)]
+pub struct Builder {
+    min_shift: u8,
+    depth: u8,
+}
+
+impl Builder {
+    /// Sets the min shift of the index.
+    ///
+    /// This is the number of bits for the minimal interval. By default, the min shift is 14.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::io::indexed_writer::Builder;
+    /// let builder = Builder::default().set_min_shift(12);
+    /// ```
+    pub fn set_min_shift(mut self, min_shift: u8) -> Self {
+        self.min_shift = min_shift;
+        self
+    }
+
+    /// Sets the depth of the index.
+    ///
+    /// This is the depth of the binning index. By default, the depth is 5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::io::indexed_writer::Builder;
+    /// let builder = Builder::default().set_depth(6);
+    /// ```
+    pub fn set_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Builds an indexed BCF writer from a path.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use noodles_bcf::io::indexed_writer::Builder;
+    /// let writer = Builder::default().build_from_path("out.bcf")?;
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn build_from_path<P>(self, dst: P) -> io::Result<IndexedWriter<File>>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::create(dst)?;
+        Ok(self.build_from_writer(file))
+    }
+
+    /// Builds an indexed BCF writer from a writer.
+    ///
+    /// The output is BGZF-compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf::io::indexed_writer::Builder;
+    /// let writer = Builder::default().build_from_writer(io::sink());
+    /// ```
+    pub fn build_from_writer<W>(self, writer: W) -> IndexedWriter<W>
+    where
+        W: Write,
+    {
+        let inner = Writer::from(bgzf::Writer::new(writer));
+        IndexedWriter::from_parts(inner, self.min_shift, self.depth)
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            min_shift: DEFAULT_MIN_SHIFT,
+            depth: DEFAULT_DEPTH,
+        }
+    }
+}
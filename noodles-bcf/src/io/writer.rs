@@ -12,6 +12,7 @@ use noodles_vcf::{self as vcf, header::StringMaps};
 
 pub use self::builder::Builder;
 use self::header::write_header;
+use self::record::write_raw_record;
 pub(crate) use self::record::write_record;
 use crate::Record;
 
@@ -123,6 +124,31 @@ where
     pub fn write_record(&mut self, header: &vcf::Header, record: &Record) -> io::Result<()> {
         write_record(&mut self.inner, header, &self.string_maps, record)
     }
+
+    /// Writes a record without re-encoding it.
+    ///
+    /// Unlike [`Self::write_record`], the encoded fields are copied as is. This is faster but
+    /// requires the string map indices of the record to be the same as those of the header that
+    /// was written, e.g., when the record was read from a BCF with the same header or a header that
+    /// only differs by sample names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut writer = bcf::io::Writer::new(io::sink());
+    /// writer.write_header(&vcf::Header::default())?;
+    ///
+    /// let record = bcf::Record::default();
+    /// writer.write_raw_record(&record)?;
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn write_raw_record(&mut self, record: &Record) -> io::Result<()> {
+        write_raw_record(&mut self.inner, record)
+    }
 }
 
 impl<W> Writer<bgzf::Writer<W>>
@@ -39,6 +39,26 @@ where
     Ok(())
 }
 
+pub fn write_raw_record<W>(writer: &mut W, record: &crate::Record) -> io::Result<()>
+where
+    W: Write,
+{
+    let fields = record.fields();
+
+    let l_shared = u32::try_from(fields.site_buf().len())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let l_indiv = u32::try_from(fields.samples_buf().len())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    writer.write_u32::<LittleEndian>(l_shared)?;
+    writer.write_u32::<LittleEndian>(l_indiv)?;
+    writer.write_all(fields.site_buf())?;
+    writer.write_all(fields.samples_buf())?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,10 +5,11 @@
 #[cfg(feature = "async")]
 pub mod r#async;
 
+mod indexer;
 pub mod io;
 pub mod record;
 
-pub use self::record::Record;
+pub use self::{indexer::index, record::Record};
 
 #[cfg(feature = "async")]
 pub use self::r#async::io::{Reader as AsyncReader, Writer as AsyncWriter};
@@ -6,6 +6,7 @@ mod fields;
 mod filters;
 mod ids;
 mod info;
+pub mod raw_value;
 mod reference_bases;
 pub mod samples;
 mod value;
@@ -18,7 +19,7 @@ use noodles_vcf::{self as vcf, header::StringMaps};
 use self::fields::Fields;
 pub(crate) use self::value::Value;
 pub use self::{
-    alternate_bases::AlternateBases, filters::Filters, ids::Ids, info::Info,
+    alternate_bases::AlternateBases, filters::Filters, ids::Ids, info::Info, raw_value::RawValue,
     reference_bases::ReferenceBases, samples::Samples,
 };
 
@@ -27,6 +28,10 @@ pub use self::{
 pub struct Record(Fields);
 
 impl Record {
+    pub(crate) fn fields(&self) -> &Fields {
+        &self.0
+    }
+
     pub(crate) fn fields_mut(&mut self) -> &mut Fields {
         &mut self.0
     }
@@ -235,6 +240,31 @@ impl Record {
     pub fn samples(&self) -> io::Result<Samples<'_>> {
         self.0.samples()
     }
+
+    /// Keeps only the samples at the given indices, in the given order.
+    ///
+    /// The encoded per-sample values of each series are sliced without being decoded, and the
+    /// sample count is updated. If no samples are selected, all series are removed.
+    ///
+    /// The sample names in the header must be changed accordingly, e.g., using
+    /// [`samples::Subset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf as bcf;
+    /// use noodles_vcf::variant::record::Samples;
+    ///
+    /// let mut record = bcf::Record::default();
+    /// record.select_samples(&[])?;
+    /// assert!(record.samples()?.is_empty());
+    ///
+    /// assert!(record.select_samples(&[0]).is_err());
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn select_samples(&mut self, sample_indices: &[usize]) -> io::Result<()> {
+        self.0.select_samples(sample_indices)
+    }
 }
 
 impl fmt::Debug for Record {
@@ -13,10 +13,18 @@ pub(crate) struct Fields {
 }
 
 impl Fields {
+    pub(crate) fn site_buf(&self) -> &[u8] {
+        &self.site_buf
+    }
+
     pub(crate) fn site_buf_mut(&mut self) -> &mut Vec<u8> {
         &mut self.site_buf
     }
 
+    pub(crate) fn samples_buf(&self) -> &[u8] {
+        &self.samples_buf
+    }
+
     pub(crate) fn samples_buf_mut(&mut self) -> &mut Vec<u8> {
         &mut self.samples_buf
     }
@@ -118,11 +126,96 @@ impl Fields {
         })
     }
 
+    pub(super) fn select_samples(&mut self, sample_indices: &[usize]) -> io::Result<()> {
+        const MAX_SAMPLE_COUNT: usize = (1 << 24) - 1;
+
+        let sample_count = self.sample_count()?;
+
+        if let Some(i) = sample_indices.iter().find(|&&i| i >= sample_count) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid sample index: {i}"),
+            ));
+        }
+
+        if sample_indices.len() > MAX_SAMPLE_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid sample count",
+            ));
+        }
+
+        let mut dst = Vec::with_capacity(self.samples_buf.len());
+
+        if sample_indices.is_empty() {
+            // Records without samples have no series.
+            self.site_buf[bounds::FORMAT_KEY_COUNT_INDEX] = 0;
+        } else {
+            select_series_samples(&self.samples_buf, sample_count, sample_indices, &mut dst)?;
+        }
+
+        self.samples_buf = dst;
+
+        // SAFETY: `sample_indices.len()` <= `MAX_SAMPLE_COUNT`.
+        let n = u32::try_from(sample_indices.len()).unwrap();
+        self.site_buf[bounds::SAMPLE_COUNT_RANGE].copy_from_slice(&n.to_le_bytes()[..3]);
+
+        Ok(())
+    }
+
     pub(crate) fn index(&mut self) -> io::Result<()> {
         index(&self.site_buf, &mut self.bounds)
     }
 }
 
+// Copies each series with only the values of the selected samples.
+//
+// The values of a series are fixed-size per sample, so they are sliced without being decoded.
+fn select_series_samples(
+    mut src: &[u8],
+    sample_count: usize,
+    sample_indices: &[usize],
+    dst: &mut Vec<u8>,
+) -> io::Result<()> {
+    use super::value::{read_type, read_value, Type};
+
+    while !src.is_empty() {
+        let prev_src = src;
+
+        read_value(&mut src)?;
+        let ty = read_type(&mut src)?;
+
+        let header_len = prev_src.len() - src.len();
+        dst.extend_from_slice(&prev_src[..header_len]);
+
+        let value_size = match ty {
+            None => 0,
+            Some(Type::Int8(n)) => mem::size_of::<i8>() * n,
+            Some(Type::Int16(n)) => mem::size_of::<i16>() * n,
+            Some(Type::Int32(n)) => mem::size_of::<i32>() * n,
+            Some(Type::Float(n)) => mem::size_of::<f32>() * n,
+            Some(Type::String(n)) => mem::size_of::<u8>() * n,
+        };
+
+        let len = value_size * sample_count;
+
+        if src.len() < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        let (buf, rest) = src.split_at(len);
+
+        for &i in sample_indices {
+            let start = value_size * i;
+            dst.extend_from_slice(&buf[start..start + value_size]);
+        }
+
+        src = rest;
+    }
+
+    Ok(())
+}
+
 fn index(buf: &[u8], bounds: &mut Bounds) -> io::Result<()> {
     use super::value::{read_type, Type};
 
@@ -8,6 +8,7 @@ use noodles_vcf::{
 };
 
 use self::field::read_field;
+use super::{raw_value::read_info_field, RawValue};
 
 /// BCF record info.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -42,6 +43,34 @@ impl<'r> Info<'r> {
     }
 }
 
+impl<'r> Info<'r> {
+    /// Returns the raw value of the field with the given string map index.
+    ///
+    /// This does not decode the value or allocate. `None` is returned if the field is not present,
+    /// and the inner value is `None` if the field does not have a value, e.g., a flag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::Info;
+    /// let info = Info::default();
+    /// assert!(info.get_raw(1).is_none());
+    /// ```
+    pub fn get_raw(&self, id: usize) -> Option<io::Result<Option<RawValue<'r>>>> {
+        let mut src = self.src;
+
+        for _ in 0..self.field_count {
+            match read_info_field(&mut src) {
+                Ok((i, value)) if i == id => return Some(Ok(value)),
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        None
+    }
+}
+
 impl<'r> AsRef<[u8]> for Info<'r> {
     fn as_ref(&self) -> &[u8] {
         self.src
@@ -0,0 +1,325 @@
+//! Raw BCF record value.
+
+use std::{io, str};
+
+use super::{
+    codec::value::{Float, Int16, Int32, Int8},
+    samples::series::read_string_map_index,
+    value::{read_type, split_to, Type},
+};
+
+/// A raw BCF record value.
+///
+/// This is an encoded typed value of an INFO field or a FORMAT field of a single sample. Its
+/// values are decoded on iteration without allocating.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RawValue<'r> {
+    /// 8-bit integers.
+    Int8(&'r [u8]),
+    /// 16-bit integers.
+    Int16(&'r [u8]),
+    /// 32-bit integers.
+    Int32(&'r [u8]),
+    /// Single-precision floating-point numbers.
+    Float(&'r [u8]),
+    /// A string, which may be padded with NUL characters.
+    String(&'r [u8]),
+}
+
+impl<'r> RawValue<'r> {
+    pub(super) fn new(ty: Type, src: &'r [u8]) -> Self {
+        match ty {
+            Type::Int8(_) => Self::Int8(src),
+            Type::Int16(_) => Self::Int16(src),
+            Type::Int32(_) => Self::Int32(src),
+            Type::Float(_) => Self::Float(src),
+            Type::String(_) => Self::String(src),
+        }
+    }
+
+    /// Returns an iterator over integer values.
+    ///
+    /// This returns `None` if the value is not an integer type. Missing values are `None`, and
+    /// iteration ends at the first end-of-vector value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::RawValue;
+    ///
+    /// let value = RawValue::Int8(&[0x05, 0x80, 0x81]);
+    /// let values: Vec<_> = value.integers().unwrap().collect::<std::io::Result<_>>()?;
+    /// assert_eq!(values, [Some(5), None]);
+    ///
+    /// assert!(RawValue::String(b"ndls").integers().is_none());
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn integers(&self) -> Option<Integers<'r>> {
+        match *self {
+            Self::Int8(src) => Some(Integers::new(IntegerType::Int8, src)),
+            Self::Int16(src) => Some(Integers::new(IntegerType::Int16, src)),
+            Self::Int32(src) => Some(Integers::new(IntegerType::Int32, src)),
+            Self::Float(_) | Self::String(_) => None,
+        }
+    }
+
+    /// Returns an iterator over float values.
+    ///
+    /// This returns `None` if the value is not a float type. Missing values are `None`, and
+    /// iteration ends at the first end-of-vector value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::RawValue;
+    ///
+    /// let value = RawValue::Float(&[0x00, 0x00, 0xc0, 0x3f, 0x01, 0x00, 0x80, 0x7f]);
+    /// let values: Vec<_> = value.floats().unwrap().collect::<std::io::Result<_>>()?;
+    /// assert_eq!(values, [Some(1.5), None]);
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn floats(&self) -> Option<Floats<'r>> {
+        match *self {
+            Self::Float(src) => Some(Floats { src }),
+            _ => None,
+        }
+    }
+
+    /// Returns the string value, without NUL padding.
+    ///
+    /// This returns `None` if the value is not a string type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::RawValue;
+    /// let value = RawValue::String(b"nd\x00\x00");
+    /// assert_eq!(value.as_str().transpose()?, Some("nd"));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn as_str(&self) -> Option<io::Result<&'r str>> {
+        match *self {
+            Self::String(src) => {
+                let len = src.iter().position(|&b| b == 0).unwrap_or(src.len());
+
+                Some(
+                    str::from_utf8(&src[..len])
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An iterator over the integer values of a raw BCF record value.
+pub struct Integers<'r> {
+    ty: IntegerType,
+    src: &'r [u8],
+}
+
+#[derive(Clone, Copy)]
+enum IntegerType {
+    Int8,
+    Int16,
+    Int32,
+}
+
+impl<'r> Integers<'r> {
+    fn new(ty: IntegerType, src: &'r [u8]) -> Self {
+        Self { ty, src }
+    }
+}
+
+impl<'r> Iterator for Integers<'r> {
+    type Item = io::Result<Option<i32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let result = match self.ty {
+            IntegerType::Int8 => {
+                read_array(&mut self.src).map(|buf| match Int8::from(i8::from_le_bytes(buf)) {
+                    Int8::Value(n) => Element::Value(i32::from(n)),
+                    Int8::Missing => Element::Missing,
+                    Int8::EndOfVector => Element::EndOfVector,
+                    _ => Element::Reserved,
+                })
+            }
+            IntegerType::Int16 => {
+                read_array(&mut self.src).map(|buf| match Int16::from(i16::from_le_bytes(buf)) {
+                    Int16::Value(n) => Element::Value(i32::from(n)),
+                    Int16::Missing => Element::Missing,
+                    Int16::EndOfVector => Element::EndOfVector,
+                    _ => Element::Reserved,
+                })
+            }
+            IntegerType::Int32 => {
+                read_array(&mut self.src).map(|buf| match Int32::from(i32::from_le_bytes(buf)) {
+                    Int32::Value(n) => Element::Value(n),
+                    Int32::Missing => Element::Missing,
+                    Int32::EndOfVector => Element::EndOfVector,
+                    _ => Element::Reserved,
+                })
+            }
+        };
+
+        next(&mut self.src, result)
+    }
+}
+
+/// An iterator over the float values of a raw BCF record value.
+pub struct Floats<'r> {
+    src: &'r [u8],
+}
+
+impl<'r> Iterator for Floats<'r> {
+    type Item = io::Result<Option<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let result =
+            read_array(&mut self.src).map(|buf| match Float::from(f32::from_le_bytes(buf)) {
+                Float::Value(n) => Element::Value(n),
+                Float::Missing => Element::Missing,
+                Float::EndOfVector => Element::EndOfVector,
+                _ => Element::Reserved,
+            });
+
+        next(&mut self.src, result)
+    }
+}
+
+// Reads an INFO field as a string map index and, if not a flag, a raw value.
+pub(super) fn read_info_field<'r>(src: &mut &'r [u8]) -> io::Result<(usize, Option<RawValue<'r>>)> {
+    let id = read_string_map_index(src)?;
+
+    let Some(ty) = read_type(src)? else {
+        return Ok((id, None));
+    };
+
+    let len = ty.size().ok_or_else(size_overflow_error)?;
+    let buf = split_to(src, len)?;
+
+    Ok((id, Some(RawValue::new(ty, buf))))
+}
+
+pub(super) fn size_overflow_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "value size overflow")
+}
+
+enum Element<T> {
+    Value(T),
+    Missing,
+    EndOfVector,
+    Reserved,
+}
+
+// Iteration ends at an end-of-vector value or an error.
+fn next<T>(src: &mut &[u8], result: io::Result<Element<T>>) -> Option<io::Result<Option<T>>> {
+    let item = match result {
+        Ok(Element::Value(n)) => return Some(Ok(Some(n))),
+        Ok(Element::Missing) => return Some(Ok(None)),
+        Ok(Element::EndOfVector) => None,
+        Ok(Element::Reserved) => Some(Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "reserved value",
+        ))),
+        Err(e) => Some(Err(e)),
+    };
+
+    *src = &[];
+
+    item
+}
+
+fn read_array<const N: usize>(src: &mut &[u8]) -> io::Result<[u8; N]> {
+    let buf = split_to(src, N)?;
+    // SAFETY: `buf.len() == N`.
+    Ok(buf.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers() -> io::Result<()> {
+        fn t(value: RawValue<'_>, expected: &[Option<i32>]) -> io::Result<()> {
+            let actual: Vec<_> = value.integers().unwrap().collect::<io::Result<_>>()?;
+            assert_eq!(actual, expected);
+            Ok(())
+        }
+
+        t(RawValue::Int8(&[0x05, 0x80, 0x81, 0x08]), &[Some(5), None])?;
+        t(
+            RawValue::Int16(&[0x00, 0x01, 0x00, 0x80]),
+            &[Some(256), None],
+        )?;
+        t(
+            RawValue::Int32(&[0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x80]),
+            &[Some(65536)],
+        )?;
+
+        let mut values = RawValue::Int8(&[0x82, 0x05]).integers().unwrap();
+        assert!(matches!(values.next(), Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData));
+        assert!(values.next().is_none());
+
+        let mut values = RawValue::Int16(&[0x00]).integers().unwrap();
+        assert!(matches!(values.next(), Some(Err(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+        assert!(RawValue::Float(&[]).integers().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_floats() -> io::Result<()> {
+        let value = RawValue::Float(&[
+            0x00, 0x00, 0xc0, 0x3f, // Some(1.5)
+            0x01, 0x00, 0x80, 0x7f, // None
+            0x02, 0x00, 0x80, 0x7f, // end of vector
+        ]);
+
+        let actual: Vec<_> = value.floats().unwrap().collect::<io::Result<_>>()?;
+        assert_eq!(actual, [Some(1.5), None]);
+
+        assert!(RawValue::Int8(&[]).floats().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_info_field() -> io::Result<()> {
+        let mut src = &[
+            0x11, 0x01, 0x11, 0x08, // 1 = 8
+            0x11, 0x02, 0x00, // 2 (flag)
+            0x11, 0x03, 0x27, b'n', 0x00, // 3 = n
+        ][..];
+
+        assert_eq!(
+            read_info_field(&mut src)?,
+            (1, Some(RawValue::Int8(&[0x08])))
+        );
+        assert_eq!(read_info_field(&mut src)?, (2, None));
+
+        let (id, value) = read_info_field(&mut src)?;
+        assert_eq!(id, 3);
+        assert_eq!(value.and_then(|v| v.as_str()).transpose()?, Some("n"));
+
+        assert!(src.is_empty());
+
+        let mut src = &[0x11, 0x01, 0x23, 0x08][..];
+        assert!(matches!(
+            read_info_field(&mut src),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+
+        Ok(())
+    }
+}
@@ -2,13 +2,16 @@
 
 mod sample;
 pub mod series;
+mod subset;
 
 use std::{io, iter};
 
 use noodles_vcf as vcf;
 
+use super::RawValue;
+
 use self::series::read_series;
-pub use self::{sample::Sample, series::Series};
+pub use self::{sample::Sample, series::Series, subset::Subset};
 
 /// BCF record genotypes.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -79,6 +82,35 @@ impl<'r> Samples<'r> {
         None
     }
 
+    /// Returns the raw value of a series for the sample at the given index.
+    ///
+    /// The series is identified by its index in the header string map. This does not decode the
+    /// value or allocate. `None` is returned if the series or sample is not present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::Samples;
+    /// let samples = Samples::default();
+    /// assert!(samples.get_raw(1, 0).is_none());
+    /// ```
+    pub fn get_raw(&self, id: usize, sample_index: usize) -> Option<io::Result<RawValue<'r>>> {
+        let mut src = self.src;
+
+        while !src.is_empty() {
+            let series = match read_series(&mut src, self.sample_count) {
+                Ok(series) => series,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if series.id() == id {
+                return series.raw_value(sample_index).map(Ok);
+            }
+        }
+
+        None
+    }
+
     /// Returns an iterator over series.
     pub fn series(&'r self) -> impl Iterator<Item = io::Result<Series<'r>>> + 'r {
         let mut src = self.src;
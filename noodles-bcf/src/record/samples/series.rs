@@ -13,7 +13,11 @@ use noodles_vcf::{
     },
 };
 
-use crate::record::value::{array::Values, read_type, read_value, Type};
+use crate::record::{
+    raw_value::size_overflow_error,
+    value::{array::Values, read_type, read_value, split_to, Type},
+    RawValue,
+};
 
 /// A BCF record samples series.
 pub struct Series<'r> {
@@ -32,6 +36,20 @@ impl<'r> Series<'r> {
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid string map ID"))
     }
 
+    pub(crate) fn id(&self) -> usize {
+        self.id
+    }
+
+    // Returns the raw value of the sample at the given index.
+    pub(crate) fn raw_value(&self, i: usize) -> Option<RawValue<'r>> {
+        let size = self.ty.size()?;
+        let start = i.checked_mul(size)?;
+        let end = start.checked_add(size)?;
+        self.src
+            .get(start..end)
+            .map(|buf| RawValue::new(self.ty, buf))
+    }
+
     fn len(&self) -> usize {
         match self.ty {
             Type::Int8(len) => len,
@@ -143,28 +161,22 @@ impl<'r> vcf::variant::record::samples::Series for Series<'r> {
 }
 
 pub(super) fn read_series<'a>(src: &mut &'a [u8], sample_count: usize) -> io::Result<Series<'a>> {
-    fn size_of(ty: Type) -> usize {
-        match ty {
-            Type::Int8(n) => mem::size_of::<i8>() * n,
-            Type::Int16(n) => mem::size_of::<i16>() * n,
-            Type::Int32(n) => mem::size_of::<i32>() * n,
-            Type::Float(n) => mem::size_of::<f32>() * n,
-            Type::String(n) => mem::size_of::<u8>() * n,
-        }
-    }
-
     let id = read_string_map_index(src)?;
-    let ty = read_type(src)?.expect("invalid type");
 
-    let len = size_of(ty) * sample_count;
-    let (buf, rest) = src.split_at(len);
+    let ty = read_type(src)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid type"))?;
+
+    let len = ty
+        .size()
+        .and_then(|size| size.checked_mul(sample_count))
+        .ok_or_else(size_overflow_error)?;
 
-    *src = rest;
+    let buf = split_to(src, len)?;
 
     Ok(Series { id, ty, src: buf })
 }
 
-fn read_string_map_index(src: &mut &[u8]) -> io::Result<usize> {
+pub(crate) fn read_string_map_index(src: &mut &[u8]) -> io::Result<usize> {
     match read_value(src)?.and_then(|v| v.as_int()) {
         Some(i) => usize::try_from(i).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
         None => Err(io::Error::new(
@@ -349,6 +361,34 @@ mod tests {
         header
     }
 
+    #[test]
+    fn test_read_series() -> io::Result<()> {
+        let mut src = &[
+            0x11, 0x01, 0x21, 0x05, 0x81, 0x80, 0x81, // 1 = 5;.
+        ][..];
+
+        let series = read_series(&mut src, 2)?;
+        assert_eq!(series.id(), 1);
+        assert_eq!(series.raw_value(0), Some(RawValue::Int8(&[0x05, 0x81])));
+        assert_eq!(series.raw_value(1), Some(RawValue::Int8(&[0x80, 0x81])));
+        assert!(series.raw_value(2).is_none());
+        assert!(src.is_empty());
+
+        let mut src = &[0x11, 0x01, 0x21, 0x05, 0x81][..];
+        assert!(matches!(
+            read_series(&mut src, 2),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+
+        let mut src = &[0x11, 0x01, 0xf3, 0x13, 0xff, 0xff, 0xff, 0x7f][..];
+        assert!(matches!(
+            read_series(&mut src, usize::MAX),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_with_int8_values() {
         fn t(series: &Series<'_>, header: &vcf::Header, i: usize, expected: Option<i32>) {
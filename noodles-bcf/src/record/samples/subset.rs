@@ -0,0 +1,202 @@
+use std::io;
+
+use noodles_vcf as vcf;
+
+use crate::Record;
+
+/// A subset of samples.
+///
+/// A subset selects samples by name from a header and applies the selection to both the header
+/// and records. Records are subset without decoding their sample values (see
+/// [`Record::select_samples`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subset {
+    sample_names: vcf::header::SampleNames,
+    sample_indices: Vec<usize>,
+}
+
+impl Subset {
+    /// Creates a subset of the given sample names in the given header.
+    ///
+    /// The subset keeps the order of the given sample names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::samples::Subset;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .add_sample_name("sample2")
+    ///     .build();
+    ///
+    /// let subset = Subset::new(&header, ["sample2", "sample0"])?;
+    /// assert_eq!(subset.sample_indices(), [2, 0]);
+    ///
+    /// assert!(Subset::new(&header, ["sample3"]).is_err());
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn new<I, S>(header: &vcf::Header, sample_names: I) -> io::Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut names = vcf::header::SampleNames::new();
+        let mut sample_indices = Vec::new();
+
+        for sample_name in sample_names {
+            let sample_name = sample_name.as_ref();
+
+            let i = header
+                .sample_names()
+                .get_index_of(sample_name)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("sample not in header: {sample_name}"),
+                    )
+                })?;
+
+            if !names.insert(sample_name.into()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate sample name: {sample_name}"),
+                ));
+            }
+
+            sample_indices.push(i);
+        }
+
+        Ok(Self {
+            sample_names: names,
+            sample_indices,
+        })
+    }
+
+    /// Returns the indices of the selected samples in the source header.
+    pub fn sample_indices(&self) -> &[usize] {
+        &self.sample_indices
+    }
+
+    /// Replaces the sample names of the given header with the selected sample names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_bcf::record::samples::Subset;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .build();
+    ///
+    /// let subset = Subset::new(&header, ["sample1"])?;
+    /// subset.apply_to_header(&mut header);
+    ///
+    /// assert_eq!(header.sample_names().len(), 1);
+    /// assert!(header.sample_names().contains("sample1"));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn apply_to_header(&self, header: &mut vcf::Header) {
+        *header.sample_names_mut() = self.sample_names.clone();
+    }
+
+    /// Keeps only the selected samples of the given record.
+    ///
+    /// The record must have been read with the source header.
+    pub fn apply(&self, record: &mut Record) -> io::Result<()> {
+        record.select_samples(&self.sample_indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::variant::{
+        record::{samples::Sample as _, Samples as _},
+        record_buf::samples::sample::Value,
+    };
+
+    use super::*;
+    use crate::io::reader::record::{
+        read_record,
+        tests::{DATA, RAW_HEADER},
+    };
+
+    fn sample_values(header: &vcf::Header, record: &Record) -> io::Result<Vec<Vec<Option<Value>>>> {
+        record
+            .samples()?
+            .iter()
+            .map(|sample| {
+                sample
+                    .iter(header)
+                    .map(|result| {
+                        result.and_then(|(_, value)| value.map(|v| v.try_into()).transpose())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        *header.string_maps_mut() = RAW_HEADER.parse()?;
+
+        let mut record = Record::default();
+        read_record(&mut &DATA[..], &mut record)?;
+        let values = sample_values(&header, &record)?;
+
+        let subset = Subset::new(&header, ["sample2", "sample1"])?;
+        subset.apply(&mut record)?;
+        subset.apply_to_header(&mut header);
+
+        assert_eq!(record.samples()?.len(), 2);
+        assert_eq!(
+            sample_values(&header, &record)?,
+            [values[2].clone(), values[1].clone()]
+        );
+
+        let mut writer = crate::io::Writer::from(Vec::new());
+        writer.write_raw_record(&record)?;
+        let data = writer.into_inner();
+
+        let mut actual = Record::default();
+        read_record(&mut &data[..], &mut actual)?;
+        assert_eq!(actual, record);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_with_no_samples() -> Result<(), Box<dyn std::error::Error>> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+
+        let mut record = Record::default();
+        read_record(&mut &DATA[..], &mut record)?;
+
+        let subset = Subset::new(&header, [] as [&str; 0])?;
+        subset.apply(&mut record)?;
+
+        let samples = record.samples()?;
+        assert!(samples.is_empty());
+        assert_eq!(samples.format_count(), 0);
+        assert!(samples.as_ref().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_duplicate_sample_names() -> Result<(), Box<dyn std::error::Error>> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+
+        assert!(matches!(
+            Subset::new(&header, ["sample0", "sample0"]),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -116,7 +116,7 @@ fn read_f32(src: &mut &[u8]) -> io::Result<f32> {
     Ok(f32::from_le_bytes(buf.try_into().unwrap()))
 }
 
-fn split_to<'a>(src: &mut &'a [u8], i: usize) -> io::Result<&'a [u8]> {
+pub(super) fn split_to<'a>(src: &mut &'a [u8], i: usize) -> io::Result<&'a [u8]> {
     if src.len() < i {
         return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
     }
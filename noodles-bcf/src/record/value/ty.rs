@@ -1,4 +1,4 @@
-use std::io;
+use std::{io, mem};
 
 use super::read_value;
 
@@ -11,6 +11,21 @@ pub(crate) enum Type {
     String(usize),
 }
 
+impl Type {
+    /// Returns the number of bytes of the encoded values.
+    ///
+    /// This returns `None` if the size overflows.
+    pub(crate) fn size(self) -> Option<usize> {
+        match self {
+            Self::Int8(n) => mem::size_of::<i8>().checked_mul(n),
+            Self::Int16(n) => mem::size_of::<i16>().checked_mul(n),
+            Self::Int32(n) => mem::size_of::<i32>().checked_mul(n),
+            Self::Float(n) => mem::size_of::<f32>().checked_mul(n),
+            Self::String(n) => mem::size_of::<u8>().checked_mul(n),
+        }
+    }
+}
+
 const MAX_TYPE_LEN: usize = 0x0f;
 
 pub(crate) fn read_type(src: &mut &[u8]) -> io::Result<Option<Type>> {
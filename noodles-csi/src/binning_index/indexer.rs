@@ -132,6 +132,8 @@ where
     pub fn build(mut self, reference_sequence_count: usize) -> Index<I> {
         if reference_sequence_count == 0 {
             return Index::builder()
+                .set_min_shift(self.min_shift)
+                .set_depth(self.depth)
                 .set_unplaced_unmapped_record_count(self.unplaced_unmapped_record_count)
                 .build();
         }
@@ -140,6 +142,8 @@ where
         self.add_reference_sequences_until(reference_sequence_count - 1);
 
         let mut builder = Index::builder()
+            .set_min_shift(self.min_shift)
+            .set_depth(self.depth)
             .set_reference_sequences(self.reference_sequences)
             .set_unplaced_unmapped_record_count(self.unplaced_unmapped_record_count);
 
@@ -280,4 +284,17 @@ mod tests {
         let index = Indexer::<LinearIndex>::default().build(2);
         assert_eq!(index.reference_sequences().len(), 2);
     }
+
+    #[test]
+    fn test_build_with_min_shift_and_depth() {
+        use crate::{binning_index::index::reference_sequence::index::BinnedIndex, BinningIndex};
+
+        let index = Indexer::<BinnedIndex>::new(10, 4).build(0);
+        assert_eq!(index.min_shift(), 10);
+        assert_eq!(index.depth(), 4);
+
+        let index = Indexer::<BinnedIndex>::new(10, 4).build(1);
+        assert_eq!(index.min_shift(), 10);
+        assert_eq!(index.depth(), 4);
+    }
 }
@@ -0,0 +1,33 @@
+//! Extracts fields from a variant file using a query format.
+//!
+//! The result is similar to the output of `bcftools query -f <format> <src>`, e.g.,
+//! `util_variant_extract <src> '%CHROM\t%POS\t%REF\t%ALT[\t%SAMPLE=%GT]\n'`.
+
+use std::{
+    env,
+    io::{self, BufWriter},
+};
+
+use noodles_util::variant::{self, query_format::QueryFormat};
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    let src = args.next().expect("missing src");
+    let raw_format = args.next().expect("missing format");
+
+    let mut reader = variant::io::reader::Builder::default().build_from_path(src)?;
+    let header = reader.read_header()?;
+
+    let format = QueryFormat::parse(&header, &raw_format)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let stdout = io::stdout().lock();
+    let mut writer = BufWriter::new(stdout);
+
+    for result in reader.records(&header) {
+        let record = result?;
+        format.write(&mut writer, &header, record.as_ref())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Sorts a variant file by contig order and position.
+//!
+//! The output format is determined from the extension of the destination, e.g., `.vcf.gz` or
+//! `.bcf`, which can then be indexed.
+
+use std::{env, io};
+
+use noodles_util::variant::{self, sort::Sorter};
+
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+
+    let src = args.next().expect("missing src");
+    let dst = args.next().expect("missing dst");
+
+    let mut reader = variant::io::reader::Builder::default().build_from_path(src)?;
+    let header = reader.read_header()?;
+
+    let mut sorter = Sorter::new(header.clone());
+
+    for result in reader.records(&header) {
+        let record = result?;
+        sorter.push(record.as_ref())?;
+    }
+
+    let mut writer = variant::io::writer::Builder::default().build_from_path(dst)?;
+    writer.write_header(&header)?;
+
+    for result in sorter.finish()? {
+        let record = result?;
+        writer.write_record(&header, &record)?;
+    }
+
+    Ok(())
+}
@@ -3,5 +3,12 @@
 #[cfg(feature = "async")]
 pub mod r#async;
 
+pub mod clip;
+pub mod consensus;
+pub mod filter;
 pub mod io;
 pub mod iter;
+pub mod rename;
+pub mod stats;
+
+pub use self::filter::Filter;
@@ -0,0 +1,551 @@
+//! Alignment record primer clipping.
+//!
+//! This clips primer sequences from amplicon alignments, similar to `samtools ampliconclip` and
+//! `ivar trim`. Primer intervals are typically read from a BED file into [`Primers`]. A
+//! [`Clipper`] then rewrites the CIGAR operations and alignment start of each record, soft or
+//! hard clipping read bases that align to a primer.
+
+mod builder;
+mod cigar;
+mod primers;
+
+use std::io;
+
+use noodles_bed::feature::record::Strand;
+use noodles_core::Position;
+use noodles_sam::{
+    self as sam,
+    alignment::{
+        record::{data::field::Tag, Flags},
+        record_buf::{data::field::Value, Cigar},
+        RecordBuf,
+    },
+};
+
+pub use self::{
+    builder::Builder,
+    primers::{Primer, Primers},
+};
+
+/// A clip mode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// Clipped bases are soft clipped (`S`) and kept in the sequence.
+    #[default]
+    Soft,
+    /// Clipped bases are hard clipped (`H`) and removed from the sequence and quality scores.
+    ///
+    /// Existing soft clips at a clipped end are converted to hard clips.
+    Hard,
+}
+
+/// The result of clipping a record.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The record is unmapped or does not start or end in a primer.
+    Unchanged,
+    /// The record was clipped.
+    Clipped {
+        /// The number of read bases clipped from the start of the alignment.
+        start: usize,
+        /// The number of read bases clipped from the end of the alignment.
+        end: usize,
+    },
+    /// The record aligns entirely within primers.
+    ///
+    /// The record is not modified.
+    Excluded,
+}
+
+/// An amplicon primer clipper.
+#[derive(Debug)]
+pub struct Clipper {
+    primers: Primers,
+    mode: Mode,
+    both_ends: bool,
+    strand_aware: bool,
+    tolerance: usize,
+    tag: Option<Tag>,
+}
+
+impl Clipper {
+    /// Creates a clipper with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::{Clipper, Primers};
+    /// let clipper = Clipper::new(Primers::default());
+    /// ```
+    pub fn new(primers: Primers) -> Self {
+        Builder::default().build(primers)
+    }
+
+    /// Returns a builder to create a clipper.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::Clipper;
+    /// let builder = Clipper::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the primers.
+    pub fn primers(&self) -> &Primers {
+        &self.primers
+    }
+
+    /// Clips primers from the given record.
+    ///
+    /// A primer is clipped from an end of the alignment if the alignment starts (or ends) within
+    /// the primer or within the tolerance before (or after) it. When multiple primers overlap an
+    /// end, e.g., for reads that span multiple amplicons, the farthest extent is clipped.
+    ///
+    /// The mate fields and template length are not updated, and soft clipping the start of an
+    /// alignment may change the sort order of coordinate-sorted records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use noodles_core::Position;
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     alignment::{
+    ///         record::{cigar::{op::Kind, Op}, Flags},
+    ///         record_buf::Cigar,
+    ///         RecordBuf,
+    ///     },
+    ///     header::record::value::{map::ReferenceSequence, Map},
+    /// };
+    /// use noodles_util::alignment::clip::{Clipper, Outcome, Primer, Primers};
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence(
+    ///         "sq0",
+    ///         Map::<ReferenceSequence>::new(NonZeroUsize::try_from(1000)?),
+    ///     )
+    ///     .build();
+    ///
+    /// let mut primers = Primers::default();
+    /// primers.insert(b"sq0", Primer::new(Position::try_from(8)?, Position::try_from(13)?, None));
+    ///
+    /// let clipper = Clipper::new(primers);
+    ///
+    /// let mut record = RecordBuf::builder()
+    ///     .set_flags(Flags::empty())
+    ///     .set_reference_sequence_id(0)
+    ///     .set_alignment_start(Position::try_from(8)?)
+    ///     .set_cigar([Op::new(Kind::Match, 20)].into_iter().collect())
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     clipper.clip(&header, &mut record)?,
+    ///     Outcome::Clipped { start: 6, end: 0 }
+    /// );
+    ///
+    /// assert_eq!(record.alignment_start(), Some(Position::try_from(14)?));
+    ///
+    /// let expected: Cigar = [Op::new(Kind::SoftClip, 6), Op::new(Kind::Match, 14)]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(record.cigar(), &expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn clip(&self, header: &sam::Header, record: &mut RecordBuf) -> io::Result<Outcome> {
+        let flags = record.flags();
+
+        if flags.is_unmapped() {
+            return Ok(Outcome::Unchanged);
+        }
+
+        let (Some(alignment_start), Some(alignment_end)) =
+            (record.alignment_start(), record.alignment_end())
+        else {
+            return Ok(Outcome::Unchanged);
+        };
+
+        let Some((reference_sequence_name, _)) = record.reference_sequence(header).transpose()?
+        else {
+            return Ok(Outcome::Unchanged);
+        };
+
+        let (clip_start, clip_end) = self.clip_ends(flags);
+        let start = usize::from(alignment_start);
+        let end = usize::from(alignment_end);
+
+        let start_len = if clip_start {
+            self.start_clip_len(reference_sequence_name, start)
+        } else {
+            0
+        };
+
+        let end_len = if clip_end {
+            self.end_clip_len(reference_sequence_name, end)
+        } else {
+            0
+        };
+
+        if start_len == 0 && end_len == 0 {
+            return Ok(Outcome::Unchanged);
+        }
+
+        if start_len + end_len > end - start {
+            return Ok(Outcome::Excluded);
+        }
+
+        let mut ops: Vec<_> = record.cigar().as_ref().to_vec();
+        let mut new_start = alignment_start;
+        let mut start_read_len = 0;
+        let mut end_read_len = 0;
+        let mut start_trim_len = 0;
+        let mut end_trim_len = 0;
+
+        if start_len > 0 {
+            let Some(clip) = cigar::clip_start(&ops, start_len, self.mode) else {
+                return Ok(Outcome::Excluded);
+            };
+
+            new_start = Position::new(start + clip.reference_len).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid alignment start")
+            })?;
+
+            ops = clip.ops;
+            start_read_len = clip.read_len;
+            start_trim_len = clip.trim_len;
+        }
+
+        if end_len > 0 {
+            let Some(clip) = cigar::clip_end(&ops, end_len, self.mode) else {
+                return Ok(Outcome::Excluded);
+            };
+
+            ops = clip.ops;
+            end_read_len = clip.read_len;
+            end_trim_len = clip.trim_len;
+        }
+
+        *record.alignment_start_mut() = Some(new_start);
+        *record.cigar_mut() = Cigar::from(ops);
+
+        if start_trim_len > 0 || end_trim_len > 0 {
+            trim(record.sequence_mut().as_mut(), start_trim_len, end_trim_len);
+            trim(
+                record.quality_scores_mut().as_mut(),
+                start_trim_len,
+                end_trim_len,
+            );
+        }
+
+        if let Some(tag) = self.tag {
+            let n = i32::try_from(start_read_len + end_read_len)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+            record.data_mut().insert(tag, Value::from(n));
+        }
+
+        Ok(Outcome::Clipped {
+            start: start_read_len,
+            end: end_read_len,
+        })
+    }
+
+    // Returns whether to clip the start and end of the alignment, respectively.
+    fn clip_ends(&self, flags: Flags) -> (bool, bool) {
+        if self.both_ends {
+            (true, true)
+        } else if flags.is_reverse_complemented() {
+            (false, true)
+        } else {
+            (true, false)
+        }
+    }
+
+    fn start_clip_len(&self, reference_sequence_name: &[u8], start: usize) -> usize {
+        self.primers
+            .overlapping(
+                reference_sequence_name,
+                start,
+                start.saturating_add(self.tolerance),
+            )
+            .filter(|primer| !self.strand_aware || primer.strand() != Some(Strand::Reverse))
+            .map(|primer| usize::from(primer.end()))
+            .filter(|&primer_end| primer_end >= start)
+            .max()
+            .map(|primer_end| primer_end - start + 1)
+            .unwrap_or_default()
+    }
+
+    fn end_clip_len(&self, reference_sequence_name: &[u8], end: usize) -> usize {
+        self.primers
+            .overlapping(
+                reference_sequence_name,
+                end.saturating_sub(self.tolerance),
+                end,
+            )
+            .filter(|primer| !self.strand_aware || primer.strand() != Some(Strand::Forward))
+            .map(|primer| usize::from(primer.start()))
+            .filter(|&primer_start| primer_start <= end)
+            .min()
+            .map(|primer_start| end - primer_start + 1)
+            .unwrap_or_default()
+    }
+}
+
+fn trim(buf: &mut Vec<u8>, start: usize, end: usize) {
+    if buf.is_empty() {
+        return;
+    }
+
+    buf.truncate(buf.len().saturating_sub(end));
+    buf.drain(..start.min(buf.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use noodles_sam::{
+        alignment::{
+            record::cigar::{op::Kind, Op},
+            record_buf::{QualityScores, Sequence},
+        },
+        header::record::value::{map::ReferenceSequence, Map},
+    };
+
+    use super::*;
+
+    const SQ0_LN: NonZeroUsize = match NonZeroUsize::new(1000) {
+        Some(length) => length,
+        None => unreachable!(),
+    };
+
+    #[test]
+    fn test_clip() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ0_LN))
+            .build();
+
+        let mut primers = Primers::default();
+        primers.insert(
+            b"sq0",
+            Primer::new(
+                Position::try_from(11)?,
+                Position::try_from(20)?,
+                Some(Strand::Forward),
+            ),
+        );
+        primers.insert(
+            b"sq0",
+            Primer::new(
+                Position::try_from(91)?,
+                Position::try_from(100)?,
+                Some(Strand::Reverse),
+            ),
+        );
+
+        let clipper = Clipper::new(primers);
+
+        // forward read starting in a primer
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(13)?)
+            .set_cigar([Op::new(Kind::Match, 50)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 50]))
+            .build();
+
+        assert_eq!(
+            clipper.clip(&header, &mut record)?,
+            Outcome::Clipped { start: 8, end: 0 }
+        );
+        assert_eq!(record.alignment_start(), Position::new(21));
+
+        // reverse read ending in a primer
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::REVERSE_COMPLEMENTED)
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(51)?)
+            .set_cigar([Op::new(Kind::Match, 45)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 45]))
+            .build();
+
+        assert_eq!(
+            clipper.clip(&header, &mut record)?,
+            Outcome::Clipped { start: 0, end: 5 }
+        );
+        assert_eq!(record.alignment_start(), Position::new(51));
+        assert_eq!(record.alignment_end(), Position::new(90));
+
+        // forward read not starting in a primer
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(31)?)
+            .set_cigar([Op::new(Kind::Match, 50)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 50]))
+            .build();
+
+        assert_eq!(clipper.clip(&header, &mut record)?, Outcome::Unchanged);
+
+        // unmapped read
+        let mut record = RecordBuf::default();
+        assert_eq!(clipper.clip(&header, &mut record)?, Outcome::Unchanged);
+
+        // read entirely within a primer
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(12)?)
+            .set_cigar([Op::new(Kind::Match, 5)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 5]))
+            .build();
+
+        assert_eq!(clipper.clip(&header, &mut record)?, Outcome::Excluded);
+        assert_eq!(record.alignment_start(), Position::new(12));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clip_with_both_ends_and_hard_mode() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ0_LN))
+            .build();
+
+        let mut primers = Primers::default();
+
+        for (start, end, strand) in [
+            (11, 20, Strand::Forward),
+            (91, 100, Strand::Reverse),
+            (81, 90, Strand::Forward),
+            (171, 180, Strand::Reverse),
+        ] {
+            primers.insert(
+                b"sq0",
+                Primer::new(
+                    Position::try_from(start)?,
+                    Position::try_from(end)?,
+                    Some(strand),
+                ),
+            );
+        }
+
+        let clipper = Clipper::builder()
+            .set_mode(Mode::Hard)
+            .set_both_ends(true)
+            .set_tag(Tag::from(*b"ZC"))
+            .build(primers);
+
+        // A read spanning two amplicons (11-100 and 81-180) is clipped at both of its ends.
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(15)?)
+            .set_cigar([Op::new(Kind::Match, 161)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 161]))
+            .set_quality_scores(QualityScores::from(vec![30; 161]))
+            .build();
+
+        assert_eq!(
+            clipper.clip(&header, &mut record)?,
+            Outcome::Clipped { start: 6, end: 5 }
+        );
+
+        assert_eq!(record.alignment_start(), Position::new(21));
+        assert_eq!(record.alignment_end(), Position::new(170));
+        assert_eq!(
+            record.cigar(),
+            &[
+                Op::new(Kind::HardClip, 6),
+                Op::new(Kind::Match, 150),
+                Op::new(Kind::HardClip, 5),
+            ]
+            .into_iter()
+            .collect::<Cigar>()
+        );
+        assert_eq!(record.sequence().len(), 150);
+        assert_eq!(record.quality_scores().as_ref().len(), 150);
+        assert_eq!(
+            record.data().get(&Tag::from(*b"ZC")),
+            Some(&Value::from(11))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clip_with_strand_awareness_and_tolerance() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ0_LN))
+            .build();
+
+        let reverse_primer = Primer::new(
+            Position::try_from(91)?,
+            Position::try_from(100)?,
+            Some(Strand::Reverse),
+        );
+
+        // The read starts in a reverse primer, which is ignored when strand aware.
+        let record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(95)?)
+            .set_cigar([Op::new(Kind::Match, 50)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 50]))
+            .build();
+
+        let mut primers = Primers::default();
+        primers.insert(b"sq0", reverse_primer);
+        let clipper = Clipper::builder().set_strand_aware(true).build(primers);
+        assert_eq!(
+            clipper.clip(&header, &mut record.clone())?,
+            Outcome::Unchanged
+        );
+
+        let mut primers = Primers::default();
+        primers.insert(b"sq0", reverse_primer);
+        let clipper = Clipper::new(primers);
+        assert_eq!(
+            clipper.clip(&header, &mut record.clone())?,
+            Outcome::Clipped { start: 6, end: 0 }
+        );
+
+        // The read starts just before a primer.
+        let forward_primer = Primer::new(
+            Position::try_from(11)?,
+            Position::try_from(20)?,
+            Some(Strand::Forward),
+        );
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(8)?)
+            .set_cigar([Op::new(Kind::Match, 50)].into_iter().collect())
+            .set_sequence(Sequence::from(vec![b'A'; 50]))
+            .build();
+
+        let mut primers = Primers::default();
+        primers.insert(b"sq0", forward_primer);
+        let clipper = Clipper::new(primers);
+        assert_eq!(
+            clipper.clip(&header, &mut record.clone())?,
+            Outcome::Unchanged
+        );
+
+        let mut primers = Primers::default();
+        primers.insert(b"sq0", forward_primer);
+        let clipper = Clipper::builder().set_tolerance(5).build(primers);
+        assert_eq!(
+            clipper.clip(&header, &mut record.clone())?,
+            Outcome::Clipped { start: 13, end: 0 }
+        );
+
+        Ok(())
+    }
+}
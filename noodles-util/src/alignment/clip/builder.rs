@@ -0,0 +1,114 @@
+use noodles_sam::alignment::record::data::field::Tag;
+
+use super::{Clipper, Mode, Primers};
+
+/// An amplicon primer clipper builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    mode: Mode,
+    both_ends: bool,
+    strand_aware: bool,
+    tolerance: usize,
+    tag: Option<Tag>,
+}
+
+impl Builder {
+    /// Sets the clip mode.
+    ///
+    /// By default, primers are soft clipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::{Clipper, Mode};
+    /// let builder = Clipper::builder().set_mode(Mode::Hard);
+    /// ```
+    pub fn set_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether to clip both ends of an alignment.
+    ///
+    /// By default, only the 5' end of the read is clipped, i.e., the start of the alignment for
+    /// forward reads and the end of the alignment for reverse reads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::Clipper;
+    /// let builder = Clipper::builder().set_both_ends(true);
+    /// ```
+    pub fn set_both_ends(mut self, both_ends: bool) -> Self {
+        self.both_ends = both_ends;
+        self
+    }
+
+    /// Sets whether to use primer strands.
+    ///
+    /// When enabled, only forward strand primers are clipped from the start of an alignment and
+    /// only reverse strand primers are clipped from the end. Primers without a strand are always
+    /// considered. This is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::Clipper;
+    /// let builder = Clipper::builder().set_strand_aware(true);
+    /// ```
+    pub fn set_strand_aware(mut self, strand_aware: bool) -> Self {
+        self.strand_aware = strand_aware;
+        self
+    }
+
+    /// Sets the number of bases an alignment may start before or end after a primer and still
+    /// match it.
+    ///
+    /// By default, the tolerance is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::Clipper;
+    /// let builder = Clipper::builder().set_tolerance(5);
+    /// ```
+    pub fn set_tolerance(mut self, tolerance: usize) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets a data field tag to add to clipped records.
+    ///
+    /// The value of the field is the number of clipped read bases. By default, no tag is added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alignment::record::data::field::Tag;
+    /// use noodles_util::alignment::clip::Clipper;
+    /// let builder = Clipper::builder().set_tag(Tag::from(*b"ZC"));
+    /// ```
+    pub fn set_tag(mut self, tag: Tag) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Builds a clipper using the given primers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::clip::{Clipper, Primers};
+    /// let clipper = Clipper::builder().build(Primers::default());
+    /// ```
+    pub fn build(self, primers: Primers) -> Clipper {
+        Clipper {
+            primers,
+            mode: self.mode,
+            both_ends: self.both_ends,
+            strand_aware: self.strand_aware,
+            tolerance: self.tolerance,
+            tag: self.tag,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use noodles_sam::alignment::record::cigar::{op::Kind, Op};
+
+use super::Mode;
+
+#[derive(Debug, Eq, PartialEq)]
+pub(super) struct Clip {
+    pub ops: Vec<Op>,
+    // The number of reference bases the alignment start moves by.
+    pub reference_len: usize,
+    // The number of read bases that are newly clipped.
+    pub read_len: usize,
+    // The number of read bases to remove from the sequence and quality scores.
+    pub trim_len: usize,
+}
+
+/// Clips `n` reference bases from the start of an alignment.
+///
+/// Insertions and deletions that end up adjacent to the new clip are clipped, too, so that the
+/// alignment starts with an aligned base. This returns `None` if no aligned bases remain.
+pub(super) fn clip_start(ops: &[Op], n: usize, mode: Mode) -> Option<Clip> {
+    let mut hard_clip_len = 0;
+    let mut soft_clip_len = 0;
+    let mut i = 0;
+
+    while let Some(op) = ops.get(i) {
+        match op.kind() {
+            Kind::HardClip => hard_clip_len += op.len(),
+            Kind::SoftClip => soft_clip_len += op.len(),
+            _ => break,
+        }
+
+        i += 1;
+    }
+
+    let mut remaining = n;
+    let mut reference_len = 0;
+    let mut read_len = 0;
+    let mut rest = Vec::with_capacity(ops.len() - i);
+
+    for (j, op) in ops[i..].iter().enumerate() {
+        let kind = op.kind();
+
+        if remaining == 0 {
+            if matches!(
+                kind,
+                Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch
+            ) {
+                rest.extend_from_slice(&ops[i + j..]);
+                break;
+            }
+        } else if matches!(
+            kind,
+            Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch
+        ) {
+            let len = op.len().min(remaining);
+
+            reference_len += len;
+            read_len += len;
+            remaining -= len;
+
+            if len < op.len() {
+                rest.push(Op::new(kind, op.len() - len));
+                rest.extend_from_slice(&ops[i + j + 1..]);
+                break;
+            }
+
+            continue;
+        }
+
+        match kind {
+            Kind::Deletion | Kind::Skip => {
+                reference_len += op.len();
+                remaining = remaining.saturating_sub(op.len());
+            }
+            Kind::Insertion => read_len += op.len(),
+            Kind::Pad => {}
+            // A trailing clip was reached before any aligned bases.
+            Kind::SoftClip | Kind::HardClip => return None,
+            _ => unreachable!(),
+        }
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut clipped_ops = Vec::with_capacity(rest.len() + 2);
+
+    let trim_len = match mode {
+        Mode::Soft => {
+            if hard_clip_len > 0 {
+                clipped_ops.push(Op::new(Kind::HardClip, hard_clip_len));
+            }
+
+            clipped_ops.push(Op::new(Kind::SoftClip, soft_clip_len + read_len));
+
+            0
+        }
+        Mode::Hard => {
+            let trim_len = soft_clip_len + read_len;
+            clipped_ops.push(Op::new(Kind::HardClip, hard_clip_len + trim_len));
+            trim_len
+        }
+    };
+
+    clipped_ops.extend(rest);
+
+    Some(Clip {
+        ops: clipped_ops,
+        reference_len,
+        read_len,
+        trim_len,
+    })
+}
+
+/// Clips `n` reference bases from the end of an alignment.
+///
+/// This is the mirror of [`clip_start`].
+pub(super) fn clip_end(ops: &[Op], n: usize, mode: Mode) -> Option<Clip> {
+    let reversed_ops: Vec<_> = ops.iter().rev().copied().collect();
+
+    clip_start(&reversed_ops, n, mode).map(|mut clip| {
+        clip.ops.reverse();
+        clip
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_start() {
+        let ops = [
+            Op::new(Kind::SoftClip, 2),
+            Op::new(Kind::Match, 4),
+            Op::new(Kind::Insertion, 1),
+            Op::new(Kind::Match, 3),
+            Op::new(Kind::Deletion, 2),
+            Op::new(Kind::Match, 5),
+        ];
+
+        assert_eq!(
+            clip_start(&ops, 2, Mode::Soft),
+            Some(Clip {
+                ops: vec![
+                    Op::new(Kind::SoftClip, 4),
+                    Op::new(Kind::Match, 2),
+                    Op::new(Kind::Insertion, 1),
+                    Op::new(Kind::Match, 3),
+                    Op::new(Kind::Deletion, 2),
+                    Op::new(Kind::Match, 5),
+                ],
+                reference_len: 2,
+                read_len: 2,
+                trim_len: 0,
+            })
+        );
+
+        // The insertion adjacent to the new clip is clipped.
+        assert_eq!(
+            clip_start(&ops, 4, Mode::Soft),
+            Some(Clip {
+                ops: vec![
+                    Op::new(Kind::SoftClip, 7),
+                    Op::new(Kind::Match, 3),
+                    Op::new(Kind::Deletion, 2),
+                    Op::new(Kind::Match, 5),
+                ],
+                reference_len: 4,
+                read_len: 5,
+                trim_len: 0,
+            })
+        );
+
+        // The deletion adjacent to the new clip is removed.
+        assert_eq!(
+            clip_start(&ops, 8, Mode::Soft),
+            Some(Clip {
+                ops: vec![Op::new(Kind::SoftClip, 10), Op::new(Kind::Match, 5)],
+                reference_len: 9,
+                read_len: 8,
+                trim_len: 0,
+            })
+        );
+
+        assert_eq!(
+            clip_start(&ops, 2, Mode::Hard),
+            Some(Clip {
+                ops: vec![
+                    Op::new(Kind::HardClip, 4),
+                    Op::new(Kind::Match, 2),
+                    Op::new(Kind::Insertion, 1),
+                    Op::new(Kind::Match, 3),
+                    Op::new(Kind::Deletion, 2),
+                    Op::new(Kind::Match, 5),
+                ],
+                reference_len: 2,
+                read_len: 2,
+                trim_len: 4,
+            })
+        );
+
+        assert!(clip_start(&ops, 14, Mode::Soft).is_none());
+    }
+
+    #[test]
+    fn test_clip_end() {
+        let ops = [
+            Op::new(Kind::HardClip, 1),
+            Op::new(Kind::Match, 8),
+            Op::new(Kind::SoftClip, 2),
+        ];
+
+        assert_eq!(
+            clip_end(&ops, 3, Mode::Soft),
+            Some(Clip {
+                ops: vec![
+                    Op::new(Kind::HardClip, 1),
+                    Op::new(Kind::Match, 5),
+                    Op::new(Kind::SoftClip, 5),
+                ],
+                reference_len: 3,
+                read_len: 3,
+                trim_len: 0,
+            })
+        );
+
+        assert!(clip_end(&ops, 8, Mode::Soft).is_none());
+    }
+}
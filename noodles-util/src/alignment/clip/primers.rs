@@ -0,0 +1,200 @@
+use std::{collections::HashMap, io};
+
+use noodles_bed::{self as bed, feature::record::Strand};
+use noodles_core::Position;
+
+/// A primer interval.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Primer {
+    start: Position,
+    end: Position,
+    strand: Option<Strand>,
+}
+
+impl Primer {
+    /// Creates a primer.
+    ///
+    /// The start and end positions are 1-based, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_util::alignment::clip::Primer;
+    ///
+    /// let primer = Primer::new(Position::try_from(8)?, Position::try_from(13)?, None);
+    /// # Ok::<_, noodles_core::position::TryFromIntError>(())
+    /// ```
+    pub fn new(start: Position, end: Position, strand: Option<Strand>) -> Self {
+        Self { start, end, strand }
+    }
+
+    /// Returns the start position.
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the end position.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns the strand the primer binds to, if known.
+    pub fn strand(&self) -> Option<Strand> {
+        self.strand
+    }
+
+    fn len(&self) -> usize {
+        usize::from(self.end) + 1 - usize::from(self.start)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReferenceSequencePrimers {
+    primers: Vec<Primer>,
+    max_len: usize,
+}
+
+/// A set of primer intervals grouped by reference sequence name.
+#[derive(Debug, Default)]
+pub struct Primers(HashMap<Vec<u8>, ReferenceSequencePrimers>);
+
+impl Primers {
+    /// Adds a primer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_util::alignment::clip::{Primer, Primers};
+    ///
+    /// let mut primers = Primers::default();
+    /// primers.insert(b"sq0", Primer::new(Position::try_from(8)?, Position::try_from(13)?, None));
+    ///
+    /// assert_eq!(primers.len(), 1);
+    /// # Ok::<_, noodles_core::position::TryFromIntError>(())
+    /// ```
+    pub fn insert<N>(&mut self, reference_sequence_name: N, primer: Primer)
+    where
+        N: Into<Vec<u8>>,
+    {
+        let entry = self.0.entry(reference_sequence_name.into()).or_default();
+
+        let i = entry
+            .primers
+            .partition_point(|p| p.start() <= primer.start());
+
+        entry.primers.insert(i, primer);
+        entry.max_len = entry.max_len.max(primer.len());
+    }
+
+    /// Adds a primer from a BED record.
+    ///
+    /// The name and strand are used if present. Records without an end position are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bed as bed;
+    /// use noodles_util::alignment::clip::Primers;
+    ///
+    /// let data = b"sq0\t7\t13\tp0_LEFT\t1\t+\nsq0\t92\t100\tp0_RIGHT\t1\t-\n";
+    /// let mut reader = bed::io::Reader::<6, _>::new(&data[..]);
+    ///
+    /// let mut primers = Primers::default();
+    /// let mut record = bed::Record::<6>::default();
+    ///
+    /// while reader.read_record(&mut record)? != 0 {
+    ///     primers.insert_from_bed_record(&record)?;
+    /// }
+    ///
+    /// assert_eq!(primers.len(), 2);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn insert_from_bed_record<const N: usize, R>(&mut self, record: &R) -> io::Result<()>
+    where
+        R: bed::feature::Record<N>,
+    {
+        let start = record.feature_start()?;
+
+        let end = record
+            .feature_end()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing feature end"))??;
+
+        let strand = record.strand().transpose()?.flatten();
+
+        self.insert(
+            record.reference_sequence_name().to_vec(),
+            Primer::new(start, end, strand),
+        );
+
+        Ok(())
+    }
+
+    /// Returns the number of primers.
+    pub fn len(&self) -> usize {
+        self.0.values().map(|entry| entry.primers.len()).sum()
+    }
+
+    /// Returns whether there are any primers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over primers on the given reference sequence that overlap the given
+    /// position.
+    pub(super) fn overlapping(
+        &self,
+        reference_sequence_name: &[u8],
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = &Primer> {
+        let (primers, max_len) = self
+            .0
+            .get(reference_sequence_name)
+            .map(|entry| (&entry.primers[..], entry.max_len))
+            .unwrap_or_default();
+
+        let i = primers.partition_point(|p| usize::from(p.start()) <= end);
+
+        primers[..i]
+            .iter()
+            .rev()
+            .take_while(move |p| usize::from(p.start()) + max_len > start)
+            .filter(move |p| usize::from(p.end()) >= start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping() -> Result<(), noodles_core::position::TryFromIntError> {
+        let p = |start, end| -> Result<Primer, noodles_core::position::TryFromIntError> {
+            Ok(Primer::new(
+                Position::try_from(start)?,
+                Position::try_from(end)?,
+                None,
+            ))
+        };
+
+        let mut primers = Primers::default();
+        primers.insert(b"sq0", p(30, 40)?);
+        primers.insert(b"sq0", p(1, 20)?);
+        primers.insert(b"sq0", p(15, 18)?);
+        primers.insert(b"sq1", p(1, 100)?);
+
+        let actual: Vec<_> = primers.overlapping(b"sq0", 19, 19).copied().collect();
+        assert_eq!(actual, [p(1, 20)?]);
+
+        let actual: Vec<_> = primers.overlapping(b"sq0", 16, 35).copied().collect();
+        assert_eq!(actual, [p(30, 40)?, p(15, 18)?, p(1, 20)?]);
+
+        assert_eq!(primers.overlapping(b"sq0", 21, 29).count(), 0);
+        assert_eq!(primers.overlapping(b"sq2", 1, 100).count(), 0);
+
+        Ok(())
+    }
+}
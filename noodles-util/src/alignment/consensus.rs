@@ -0,0 +1,631 @@
+//! Alignment consensus sequence calling.
+//!
+//! This calls a consensus sequence per reference sequence from coordinate-sorted alignment
+//! records, similar to the simple mode of `samtools consensus`. Each position is called by base
+//! frequency, optionally using IUPAC ambiguity codes for mixed positions, and low coverage
+//! positions can be masked, filled from the reference sequence, or omitted.
+
+mod builder;
+mod column;
+
+use std::{collections::VecDeque, io};
+
+use noodles_fasta as fasta;
+use noodles_sam::{
+    self as sam,
+    alignment::{record::cigar::op::Kind, Record},
+};
+
+pub use self::builder::Builder;
+use self::column::{Column, DELETION};
+
+const BASES: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
+// Indexed by a bitmask of A (1), C (2), G (4), and T (8).
+const AMBIGUITY_CODES: [u8; 16] = *b"NACMGRSVTWYHKDBN";
+
+/// How low coverage positions are called.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LowCoverage {
+    /// Low coverage positions are masked with `N`.
+    #[default]
+    Mask,
+    /// Low coverage positions are filled with the reference sequence base.
+    ///
+    /// This falls back to `N` when the reference sequence is not available.
+    Reference,
+    /// Low coverage positions are omitted from the consensus sequence.
+    Omit,
+}
+
+#[derive(Debug)]
+struct State {
+    reference_sequence_id: usize,
+    name: Vec<u8>,
+    reference_sequence_length: usize,
+    reference_sequence: Option<fasta::record::Sequence>,
+    // The 1-based position of the first column in the window, i.e., the next position to call.
+    position: usize,
+    window: VecDeque<Column>,
+    last_alignment_start: usize,
+    end: usize,
+    sequence: Vec<u8>,
+}
+
+/// An alignment consensus sequence caller.
+///
+/// Unmapped, secondary, QC failed, and duplicate records are ignored. Reference sequences without
+/// any aligned records do not have a consensus sequence.
+#[derive(Debug)]
+pub struct Caller {
+    min_depth: u32,
+    min_base_quality: u8,
+    call_fraction: f64,
+    het_fraction: f64,
+    use_ambiguity_codes: bool,
+    insertion_fraction: f64,
+    low_coverage: LowCoverage,
+    all_positions: bool,
+    reference_sequence_repository: Option<fasta::Repository>,
+    state: Option<State>,
+}
+
+impl Caller {
+    /// Creates a consensus caller with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let caller = Caller::new();
+    /// ```
+    pub fn new() -> Self {
+        Builder::default().build()
+    }
+
+    /// Returns a builder to create a consensus caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Adds an alignment record.
+    ///
+    /// Records must be coordinate-sorted. When the record is on a different reference sequence
+    /// than the previous record, the consensus sequence of the previous reference sequence is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use noodles_core::Position;
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     alignment::{
+    ///         record::{cigar::{op::Kind, Op}, Flags},
+    ///         record_buf::{Cigar, Sequence},
+    ///         RecordBuf,
+    ///     },
+    ///     header::record::value::{map::ReferenceSequence, Map},
+    /// };
+    /// use noodles_util::alignment::consensus::Caller;
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(NonZeroUsize::try_from(8)?))
+    ///     .build();
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_flags(Flags::empty())
+    ///     .set_reference_sequence_id(0)
+    ///     .set_alignment_start(Position::try_from(3)?)
+    ///     .set_cigar(Cigar::from(vec![Op::new(Kind::Match, 4)]))
+    ///     .set_sequence(Sequence::from(b"ACGT".to_vec()))
+    ///     .build();
+    ///
+    /// let mut caller = Caller::new();
+    /// assert!(caller.add(&header, &record)?.is_none());
+    ///
+    /// let consensus = caller.finish().expect("missing consensus");
+    /// assert_eq!(consensus.name(), b"sq0");
+    /// assert_eq!(consensus.sequence().as_ref(), b"ACGT");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add<R>(&mut self, header: &sam::Header, record: &R) -> io::Result<Option<fasta::Record>>
+    where
+        R: Record + ?Sized,
+    {
+        let flags = record.flags()?;
+
+        if flags.is_unmapped() || flags.is_secondary() || flags.is_qc_fail() || flags.is_duplicate()
+        {
+            return Ok(None);
+        }
+
+        let reference_sequence_id = record
+            .reference_sequence_id(header)
+            .transpose()?
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "missing reference sequence ID")
+            })?;
+
+        let alignment_start = record
+            .alignment_start()
+            .transpose()?
+            .map(usize::from)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing alignment start"))?;
+
+        let mut consensus = None;
+
+        let mut state = match self.state.take() {
+            Some(state) if state.reference_sequence_id == reference_sequence_id => {
+                if alignment_start < state.last_alignment_start {
+                    self.state = Some(state);
+                    return Err(unsorted_error());
+                }
+
+                state
+            }
+            Some(state) => {
+                if reference_sequence_id < state.reference_sequence_id {
+                    self.state = Some(state);
+                    return Err(unsorted_error());
+                }
+
+                consensus = Some(self.finish_state(state));
+                self.new_state(header, record, reference_sequence_id, alignment_start)?
+            }
+            None => self.new_state(header, record, reference_sequence_id, alignment_start)?,
+        };
+
+        state.last_alignment_start = alignment_start;
+        self.call_until(&mut state, alignment_start);
+        self.pileup(&mut state, record, alignment_start)?;
+
+        self.state = Some(state);
+
+        Ok(consensus)
+    }
+
+    /// Finishes calling and returns the consensus sequence of the last reference sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let mut caller = Caller::new();
+    /// assert!(caller.finish().is_none());
+    /// ```
+    pub fn finish(&mut self) -> Option<fasta::Record> {
+        self.state.take().map(|state| self.finish_state(state))
+    }
+
+    fn new_state<R>(
+        &self,
+        header: &sam::Header,
+        record: &R,
+        reference_sequence_id: usize,
+        alignment_start: usize,
+    ) -> io::Result<State>
+    where
+        R: Record + ?Sized,
+    {
+        let (name, reference_sequence) = record
+            .reference_sequence(header)
+            .transpose()?
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "missing reference sequence")
+            })?;
+
+        let sequence = match (self.low_coverage, &self.reference_sequence_repository) {
+            (LowCoverage::Reference, Some(repository)) => repository.get(name).transpose()?,
+            _ => None,
+        };
+
+        let position = if self.all_positions {
+            1
+        } else {
+            alignment_start
+        };
+
+        Ok(State {
+            reference_sequence_id,
+            name: name.to_vec(),
+            reference_sequence_length: reference_sequence.length().get(),
+            reference_sequence: sequence,
+            position,
+            window: VecDeque::new(),
+            last_alignment_start: alignment_start,
+            end: alignment_start,
+            sequence: Vec::new(),
+        })
+    }
+
+    fn finish_state(&self, mut state: State) -> fasta::Record {
+        let end = if self.all_positions {
+            state.reference_sequence_length.max(state.end)
+        } else {
+            state.end
+        };
+
+        self.call_until(&mut state, end + 1);
+
+        fasta::Record::new(
+            fasta::record::Definition::new(state.name, None),
+            fasta::record::Sequence::from(state.sequence),
+        )
+    }
+
+    fn pileup<R>(&self, state: &mut State, record: &R, alignment_start: usize) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let sequence: Vec<u8> = record.sequence().iter().collect();
+        let quality_scores: Vec<u8> = record.quality_scores().iter().collect();
+
+        let is_callable = |i: usize| {
+            quality_scores
+                .get(i)
+                .map(|&score| score >= self.min_base_quality)
+                .unwrap_or(true)
+        };
+
+        let mut reference_position = alignment_start;
+        let mut read_position = 0;
+
+        for result in record.cigar().iter() {
+            let op = result?;
+            let len = op.len();
+
+            match op.kind() {
+                Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch => {
+                    for i in 0..len {
+                        let j = read_position + i;
+
+                        if let Some(&base) = sequence.get(j) {
+                            if is_callable(j) {
+                                column_mut(state, reference_position + i).add_base(base);
+                            }
+                        }
+                    }
+
+                    reference_position += len;
+                    read_position += len;
+                }
+                Kind::Deletion => {
+                    for i in 0..len {
+                        column_mut(state, reference_position + i).add_deletion();
+                    }
+
+                    reference_position += len;
+                }
+                Kind::Skip => reference_position += len,
+                Kind::Insertion => {
+                    let end = read_position + len;
+
+                    if reference_position > alignment_start {
+                        if let Some(bases) = sequence.get(read_position..end) {
+                            let bases = bases.to_ascii_uppercase();
+                            column_mut(state, reference_position - 1).add_insertion(&bases);
+                        }
+                    }
+
+                    read_position = end;
+                }
+                Kind::SoftClip => read_position += len,
+                Kind::HardClip | Kind::Pad => {}
+            }
+        }
+
+        state.end = state.end.max(reference_position - 1);
+
+        Ok(())
+    }
+
+    /// Calls all positions before the given position.
+    fn call_until(&self, state: &mut State, position: usize) {
+        while state.position < position {
+            let column = state.window.pop_front();
+
+            let reference_base = state
+                .reference_sequence
+                .as_ref()
+                .and_then(|sequence| sequence.as_ref().get(state.position - 1))
+                .copied();
+
+            self.call(column.as_ref(), reference_base, &mut state.sequence);
+
+            state.position += 1;
+        }
+    }
+
+    fn call(&self, column: Option<&Column>, reference_base: Option<u8>, dst: &mut Vec<u8>) {
+        let Some(column) = column.filter(|c| c.depth() >= self.min_depth) else {
+            match self.low_coverage {
+                LowCoverage::Mask => dst.push(b'N'),
+                LowCoverage::Reference => {
+                    dst.push(reference_base.map_or(b'N', |b| b.to_ascii_uppercase()));
+                }
+                LowCoverage::Omit => {}
+            }
+
+            return;
+        };
+
+        let depth = f64::from(column.depth());
+
+        let (i, max) =
+            column.counts.iter().enumerate().fold(
+                (0, 0),
+                |(i, max), (j, &n)| if n > max { (j, n) } else { (i, max) },
+            );
+
+        if f64::from(max) >= self.call_fraction * depth {
+            if i != DELETION {
+                dst.push(BASES[i]);
+            }
+        } else if self.use_ambiguity_codes {
+            let threshold = self.het_fraction * f64::from(max);
+
+            let mask = column.counts[..BASES.len()]
+                .iter()
+                .enumerate()
+                .filter(|(_, &n)| n > 0 && f64::from(n) >= threshold)
+                .fold(0, |mask, (j, _)| mask | (1 << j));
+
+            dst.push(AMBIGUITY_CODES[mask]);
+        } else {
+            dst.push(b'N');
+        }
+
+        if let Some((bases, n)) = column.top_insertion() {
+            if f64::from(n) >= self.insertion_fraction * depth {
+                dst.extend(bases);
+            }
+        }
+    }
+}
+
+impl Default for Caller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn column_mut(state: &mut State, position: usize) -> &mut Column {
+    let i = position - state.position;
+
+    if i >= state.window.len() {
+        state.window.resize(i + 1, Column::default());
+    }
+
+    &mut state.window[i]
+}
+
+fn unsorted_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "records are not coordinate-sorted",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use noodles_core::Position;
+    use noodles_sam::{
+        alignment::{
+            record::{cigar::Op, Flags},
+            record_buf::{Cigar, Sequence},
+            RecordBuf,
+        },
+        header::record::value::{map::ReferenceSequence, Map},
+    };
+
+    use super::*;
+
+    const SQ_LENGTH: NonZeroUsize = match NonZeroUsize::new(10) {
+        Some(length) => length,
+        None => unreachable!(),
+    };
+
+    #[test]
+    fn test_add() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .add_reference_sequence("sq1", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .build();
+
+        let records = [
+            (0, 2, &b"ACGT"[..]),
+            (0, 2, b"ACCT"),
+            (0, 3, b"CG"),
+            (0, 8, b"GG"),
+            (1, 1, b"TT"),
+        ]
+        .into_iter()
+        .map(|(reference_sequence_id, alignment_start, sequence)| {
+            Ok(RecordBuf::builder()
+                .set_flags(Flags::empty())
+                .set_reference_sequence_id(reference_sequence_id)
+                .set_alignment_start(Position::try_from(alignment_start)?)
+                .set_cigar(Cigar::from(vec![Op::new(Kind::Match, sequence.len())]))
+                .set_sequence(Sequence::from(sequence.to_vec()))
+                .build())
+        })
+        .collect::<Result<Vec<_>, noodles_core::position::TryFromIntError>>()?;
+
+        let mut caller = Caller::new();
+        let mut actual = Vec::new();
+
+        for record in &records {
+            actual.extend(caller.add(&header, record)?);
+        }
+
+        actual.extend(caller.finish());
+
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].name(), b"sq0");
+        assert_eq!(actual[0].sequence().as_ref(), b"ACNTNNGG");
+        assert_eq!(actual[1].name(), b"sq1");
+        assert_eq!(actual[1].sequence().as_ref(), b"TT");
+
+        let mut caller = Caller::builder()
+            .set_use_ambiguity_codes(true)
+            .set_min_depth(2)
+            .set_low_coverage(LowCoverage::Omit)
+            .build();
+
+        let mut actual = Vec::new();
+
+        for record in &records[..2] {
+            actual.extend(caller.add(&header, record)?);
+        }
+
+        actual.extend(caller.finish());
+
+        assert_eq!(actual[0].sequence().as_ref(), b"ACST");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_indels() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .build();
+
+        let cigar = Cigar::from(vec![
+            Op::new(Kind::Match, 2),
+            Op::new(Kind::Insertion, 2),
+            Op::new(Kind::Match, 1),
+            Op::new(Kind::Deletion, 1),
+            Op::new(Kind::Match, 1),
+        ]);
+
+        let records = [
+            (cigar.clone(), &b"ACttGT"[..]),
+            (cigar, b"ACTTGT"),
+            (Cigar::from(vec![Op::new(Kind::Match, 5)]), b"ACGAT"),
+        ]
+        .into_iter()
+        .map(|(cigar, sequence)| {
+            Ok(RecordBuf::builder()
+                .set_flags(Flags::empty())
+                .set_reference_sequence_id(0)
+                .set_alignment_start(Position::try_from(1)?)
+                .set_cigar(cigar)
+                .set_sequence(Sequence::from(sequence.to_vec()))
+                .build())
+        })
+        .collect::<Result<Vec<_>, noodles_core::position::TryFromIntError>>()?;
+
+        let mut caller = Caller::builder().set_call_fraction(0.6).build();
+        let mut actual = Vec::new();
+
+        for record in &records {
+            actual.extend(caller.add(&header, record)?);
+        }
+
+        actual.extend(caller.finish());
+
+        assert_eq!(actual[0].sequence().as_ref(), b"ACTTGT");
+
+        let mut caller = Caller::builder()
+            .set_call_fraction(0.6)
+            .set_insertion_fraction(0.7)
+            .set_all_positions(true)
+            .build();
+
+        let mut actual = Vec::new();
+
+        for record in &records {
+            actual.extend(caller.add(&header, record)?);
+        }
+
+        actual.extend(caller.finish());
+
+        assert_eq!(actual[0].sequence().as_ref(), b"ACGTNNNNN");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_reference_sequence() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .build();
+
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq0", None),
+            fasta::record::Sequence::from(b"aaccggttaa".to_vec()),
+        )]);
+
+        let mut caller = Caller::builder()
+            .set_low_coverage(LowCoverage::Reference)
+            .set_reference_sequence_repository(repository)
+            .set_all_positions(true)
+            .build();
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(3)?)
+            .set_cigar(Cigar::from(vec![Op::new(Kind::Match, 2)]))
+            .set_sequence(Sequence::from(b"TT".to_vec()))
+            .build();
+
+        assert!(caller.add(&header, &record)?.is_none());
+
+        let actual = caller.finish();
+        assert_eq!(
+            actual.as_ref().map(|record| record.sequence().as_ref()),
+            Some(&b"AATTGGTTAA"[..])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_unsorted_records() -> Result<(), noodles_core::position::TryFromIntError> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .add_reference_sequence("sq1", Map::<ReferenceSequence>::new(SQ_LENGTH))
+            .build();
+
+        let mut caller = Caller::new();
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(1)
+            .set_alignment_start(Position::MIN)
+            .set_cigar(Cigar::from(vec![Op::new(Kind::Match, 1)]))
+            .set_sequence(Sequence::from(b"A".to_vec()))
+            .build();
+
+        assert!(caller.add(&header, &record).is_ok());
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::empty())
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::MIN)
+            .set_cigar(Cigar::from(vec![Op::new(Kind::Match, 1)]))
+            .set_sequence(Sequence::from(b"A".to_vec()))
+            .build();
+
+        assert!(matches!(
+            caller.add(&header, &record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use noodles_fasta as fasta;
+
+use super::{Caller, LowCoverage};
+
+/// A consensus caller builder.
+#[derive(Debug)]
+pub struct Builder {
+    min_depth: u32,
+    min_base_quality: u8,
+    call_fraction: f64,
+    het_fraction: f64,
+    use_ambiguity_codes: bool,
+    insertion_fraction: f64,
+    low_coverage: LowCoverage,
+    all_positions: bool,
+    reference_sequence_repository: Option<fasta::Repository>,
+}
+
+impl Builder {
+    /// Sets the minimum depth to call a position.
+    ///
+    /// Positions with fewer bases (including deletions) are low coverage. By default, the
+    /// minimum depth is 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_min_depth(10);
+    /// ```
+    pub fn set_min_depth(mut self, min_depth: u32) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    /// Sets the minimum base quality score for a base to be counted.
+    ///
+    /// Records without quality scores are always counted. By default, all bases are counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_min_base_quality(20);
+    /// ```
+    pub fn set_min_base_quality(mut self, min_base_quality: u8) -> Self {
+        self.min_base_quality = min_base_quality;
+        self
+    }
+
+    /// Sets the minimum fraction of the depth the most common base (or deletion) must reach to
+    /// be called.
+    ///
+    /// By default, the call fraction is 0.75.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_call_fraction(0.6);
+    /// ```
+    pub fn set_call_fraction(mut self, call_fraction: f64) -> Self {
+        self.call_fraction = call_fraction;
+        self
+    }
+
+    /// Sets the minimum count of a base, relative to the most common base, for it to be included
+    /// in an ambiguity code.
+    ///
+    /// This is only used when ambiguity codes are enabled. By default, the heterozygous fraction
+    /// is 0.5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_het_fraction(0.3);
+    /// ```
+    pub fn set_het_fraction(mut self, het_fraction: f64) -> Self {
+        self.het_fraction = het_fraction;
+        self
+    }
+
+    /// Sets whether to call IUPAC ambiguity codes at positions without a dominant base.
+    ///
+    /// When disabled, these positions are called as `N`. This is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_use_ambiguity_codes(true);
+    /// ```
+    pub fn set_use_ambiguity_codes(mut self, use_ambiguity_codes: bool) -> Self {
+        self.use_ambiguity_codes = use_ambiguity_codes;
+        self
+    }
+
+    /// Sets the minimum fraction of the depth an inserted sequence must reach to be called.
+    ///
+    /// By default, the insertion fraction is 0.5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_insertion_fraction(0.75);
+    /// ```
+    pub fn set_insertion_fraction(mut self, insertion_fraction: f64) -> Self {
+        self.insertion_fraction = insertion_fraction;
+        self
+    }
+
+    /// Sets how low coverage positions are called.
+    ///
+    /// By default, low coverage positions are masked with `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::{Caller, LowCoverage};
+    /// let builder = Caller::builder().set_low_coverage(LowCoverage::Omit);
+    /// ```
+    pub fn set_low_coverage(mut self, low_coverage: LowCoverage) -> Self {
+        self.low_coverage = low_coverage;
+        self
+    }
+
+    /// Sets whether to call all positions of a reference sequence.
+    ///
+    /// When disabled, the consensus spans from the first to the last aligned position of a
+    /// reference sequence. When enabled, uncovered positions before and after are included as
+    /// low coverage positions. This is disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let builder = Caller::builder().set_all_positions(true);
+    /// ```
+    pub fn set_all_positions(mut self, all_positions: bool) -> Self {
+        self.all_positions = all_positions;
+        self
+    }
+
+    /// Sets the reference sequence repository.
+    ///
+    /// The reference sequence is only used to fill low coverage positions when using
+    /// [`LowCoverage::Reference`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::alignment::consensus::Caller;
+    ///
+    /// let builder = Caller::builder()
+    ///     .set_reference_sequence_repository(fasta::Repository::default());
+    /// ```
+    pub fn set_reference_sequence_repository(
+        mut self,
+        reference_sequence_repository: fasta::Repository,
+    ) -> Self {
+        self.reference_sequence_repository = Some(reference_sequence_repository);
+        self
+    }
+
+    /// Builds a consensus caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::consensus::Caller;
+    /// let caller = Caller::builder().build();
+    /// ```
+    pub fn build(self) -> Caller {
+        Caller {
+            min_depth: self.min_depth.max(1),
+            min_base_quality: self.min_base_quality,
+            call_fraction: self.call_fraction,
+            het_fraction: self.het_fraction,
+            use_ambiguity_codes: self.use_ambiguity_codes,
+            insertion_fraction: self.insertion_fraction,
+            low_coverage: self.low_coverage,
+            all_positions: self.all_positions,
+            reference_sequence_repository: self.reference_sequence_repository,
+            state: None,
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            min_depth: 1,
+            min_base_quality: 0,
+            call_fraction: 0.75,
+            het_fraction: 0.5,
+            use_ambiguity_codes: false,
+            insertion_fraction: 0.5,
+            low_coverage: LowCoverage::default(),
+            all_positions: false,
+            reference_sequence_repository: None,
+        }
+    }
+}
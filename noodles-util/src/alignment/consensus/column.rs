@@ -0,0 +1,83 @@
+/// The index of deletions in the column counts.
+pub(super) const DELETION: usize = 4;
+
+/// The pileup of a reference sequence position.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) struct Column {
+    /// The counts of A, C, G, T, and deletions, respectively.
+    pub counts: [u32; 5],
+    /// Sequences inserted after this position and their counts.
+    pub insertions: Vec<(Vec<u8>, u32)>,
+}
+
+impl Column {
+    pub fn depth(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    /// Adds a read base.
+    ///
+    /// Ambiguous bases are ignored.
+    pub fn add_base(&mut self, base: u8) {
+        if let Some(i) = base_index(base) {
+            self.counts[i] += 1;
+        }
+    }
+
+    pub fn add_deletion(&mut self) {
+        self.counts[DELETION] += 1;
+    }
+
+    pub fn add_insertion(&mut self, bases: &[u8]) {
+        if let Some((_, n)) = self
+            .insertions
+            .iter_mut()
+            .find(|(sequence, _)| sequence == bases)
+        {
+            *n += 1;
+        } else {
+            self.insertions.push((bases.to_vec(), 1));
+        }
+    }
+
+    /// Returns the most common inserted sequence and its count.
+    ///
+    /// Ties are broken by first occurrence.
+    pub fn top_insertion(&self) -> Option<(&[u8], u32)> {
+        self.insertions
+            .iter()
+            .fold(None, |top, (sequence, n)| match top {
+                Some((_, m)) if m >= *n => top,
+                _ => Some((&sequence[..], *n)),
+            })
+    }
+}
+
+fn base_index(base: u8) -> Option<usize> {
+    match base.to_ascii_uppercase() {
+        b'A' => Some(0),
+        b'C' => Some(1),
+        b'G' => Some(2),
+        b'T' => Some(3),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_insertion() {
+        let mut column = Column::default();
+        assert!(column.top_insertion().is_none());
+
+        column.add_insertion(b"AC");
+        column.add_insertion(b"G");
+        column.add_insertion(b"G");
+        column.add_insertion(b"AC");
+        column.add_insertion(b"G");
+
+        assert_eq!(column.top_insertion(), Some((&b"G"[..], 3)));
+    }
+}
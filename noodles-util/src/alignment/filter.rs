@@ -0,0 +1,191 @@
+//! Alignment record filtering.
+//!
+//! A [`Filter`] combines an optional filter [`Expression`] with an optional deterministic
+//! [`Subsample`]. It can be applied to any alignment record iterator, e.g., the records from an
+//! [`crate::alignment::io::Reader`].
+
+mod builder;
+pub mod expression;
+mod subsample;
+
+use std::io;
+
+use noodles_sam::{self as sam, alignment::Record};
+
+pub use self::{builder::Builder, expression::Expression, subsample::Subsample};
+
+/// An alignment record filter.
+#[derive(Debug, Default)]
+pub struct Filter {
+    expression: Option<Expression>,
+    subsample: Option<Subsample>,
+}
+
+impl Filter {
+    /// Returns a builder to create a filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::Filter;
+    /// let builder = Filter::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the filter expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::Filter;
+    /// let filter = Filter::default();
+    /// assert!(filter.expression().is_none());
+    /// ```
+    pub fn expression(&self) -> Option<&Expression> {
+        self.expression.as_ref()
+    }
+
+    /// Returns the subsample.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::Filter;
+    /// let filter = Filter::default();
+    /// assert!(filter.subsample().is_none());
+    /// ```
+    pub fn subsample(&self) -> Option<&Subsample> {
+        self.subsample.as_ref()
+    }
+
+    /// Returns whether the given record passes the filter.
+    ///
+    /// The subsample is checked before the expression is evaluated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, alignment::{record::MappingQuality, RecordBuf}};
+    /// use noodles_util::alignment::{filter::Expression, Filter};
+    ///
+    /// let expression: Expression = "mapq >= 30".parse()?;
+    /// let filter = Filter::builder().set_expression(expression).build();
+    ///
+    /// let header = sam::Header::default();
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_mapping_quality(MappingQuality::try_from(34)?)
+    ///     .build();
+    /// assert!(filter.matches(&header, &record)?);
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_mapping_quality(MappingQuality::try_from(8)?)
+    ///     .build();
+    /// assert!(!filter.matches(&header, &record)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn matches<R>(&self, header: &sam::Header, record: &R) -> io::Result<bool>
+    where
+        R: Record + ?Sized,
+    {
+        if let Some(subsample) = self.subsample() {
+            if !subsample.matches(record.name().map(|name| name.as_ref())) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(expression) = self.expression() {
+            expression.evaluate(header, record)
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Returns an iterator over records that pass the filter.
+    ///
+    /// Errors from the input iterator are passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Cursor};
+    /// use noodles_util::alignment::{self, Filter};
+    ///
+    /// let data = Cursor::new(b"@HD\tVN:1.6
+    /// r0\t4\t*\t0\t255\t*\t*\t0\t0\t*\t*
+    /// r1\t0\t*\t0\t8\t*\t*\t0\t0\t*\t*
+    /// ");
+    ///
+    /// let mut reader = alignment::io::reader::Builder::default().build_from_reader(data)?;
+    /// let header = reader.read_header()?;
+    ///
+    /// let filter = Filter::builder()
+    ///     .set_expression("!flag.unmap".parse().unwrap())
+    ///     .build();
+    ///
+    /// let mut records = filter.records(&header, reader.records(&header));
+    ///
+    /// let record = records.next().transpose()?.expect("missing record");
+    /// assert_eq!(record.name().map(|name| name.as_ref()), Some(&b"r1"[..]));
+    /// assert!(records.next().is_none());
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn records<'a, I, R>(
+        &'a self,
+        header: &'a sam::Header,
+        records: I,
+    ) -> impl Iterator<Item = io::Result<R>> + 'a
+    where
+        I: IntoIterator<Item = io::Result<R>>,
+        I::IntoIter: 'a,
+        R: Record,
+    {
+        records.into_iter().filter_map(move |result| {
+            let record = match result {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.matches(header, &record) {
+                Ok(true) => Some(Ok(record)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::alignment::{record::Flags, RecordBuf};
+
+    use super::*;
+
+    #[test]
+    fn test_records() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::default();
+
+        let records = [
+            RecordBuf::builder().set_name("r0").build(),
+            RecordBuf::builder()
+                .set_name("r1")
+                .set_flags(Flags::empty())
+                .build(),
+        ];
+
+        let filter = Filter::builder()
+            .set_expression("!flag.unmap".parse()?)
+            .build();
+
+        let actual: Vec<_> = filter
+            .records(&header, records.into_iter().map(Ok))
+            .collect::<io::Result<_>>()?;
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].name().map(|name| name.as_ref()), Some(&b"r1"[..]));
+
+        Ok(())
+    }
+}
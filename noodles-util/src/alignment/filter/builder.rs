@@ -0,0 +1,60 @@
+use super::{Expression, Filter, Subsample};
+
+/// An alignment record filter builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    expression: Option<Expression>,
+    subsample: Option<Subsample>,
+}
+
+impl Builder {
+    /// Sets the filter expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::{filter::Expression, Filter};
+    ///
+    /// let expression: Expression = "mapq >= 30".parse()?;
+    /// let filter = Filter::builder().set_expression(expression.clone()).build();
+    ///
+    /// assert_eq!(filter.expression(), Some(&expression));
+    /// # Ok::<_, noodles_util::alignment::filter::expression::ParseError>(())
+    /// ```
+    pub fn set_expression(mut self, expression: Expression) -> Self {
+        self.expression = Some(expression);
+        self
+    }
+
+    /// Sets the subsample.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::{filter::Subsample, Filter};
+    ///
+    /// let subsample = Subsample::new(0, 0.5);
+    /// let filter = Filter::builder().set_subsample(subsample).build();
+    ///
+    /// assert_eq!(filter.subsample(), Some(&subsample));
+    /// ```
+    pub fn set_subsample(mut self, subsample: Subsample) -> Self {
+        self.subsample = Some(subsample);
+        self
+    }
+
+    /// Builds an alignment record filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::Filter;
+    /// let filter = Filter::builder().build();
+    /// ```
+    pub fn build(self) -> Filter {
+        Filter {
+            expression: self.expression,
+            subsample: self.subsample,
+        }
+    }
+}
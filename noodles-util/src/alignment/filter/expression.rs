@@ -0,0 +1,395 @@
+//! Alignment record filter expression.
+
+mod parser;
+
+use std::{io, str::FromStr};
+
+use noodles_sam::{
+    self as sam,
+    alignment::{
+        record::{cigar::op::Kind, data::field::Value as FieldValue, Flags},
+        Record,
+    },
+};
+
+use crate::expression::{
+    evaluate_binary, evaluate_match, evaluate_unary, length_value, BinaryOperator, Regex,
+    UnaryOperator, Value,
+};
+
+pub use crate::expression::ParseError;
+
+/// An alignment record filter expression.
+///
+/// The expression language is modeled after the one used by `samtools view -e`. An expression is
+/// built from the following:
+///
+///   * literals: integers (decimal, `0x` hexadecimal, or `0` octal), floats, and double-quoted
+///     strings;
+///   * record fields: `qname`, `flag`, `rname`, `pos`, `endpos`, `mapq`, `rnext`, `pnext`,
+///     `tlen`, `seq`, `qlen`, `rlen`, `ncigar`, `sclen`, and `hclen`;
+///   * flag tests: `flag.paired`, `flag.proper_pair`, `flag.unmap`, `flag.munmap`,
+///     `flag.reverse`, `flag.mreverse`, `flag.read1`, `flag.read2`, `flag.secondary`,
+///     `flag.qcfail`, `flag.dup`, and `flag.supplementary`;
+///   * data field values, e.g., `[NM]`;
+///   * operators, from lowest to highest precedence: `||`; `&&`; `|`; `^`; `&`; `==`, `!=`;
+///     `<`, `<=`, `>`, `>=`; `=~`, `!~`; `+`, `-`; `*`, `/`, `%`; and the unary `!`, `-`, `~`.
+///
+/// Missing values (e.g., a data field that is not present or an unmapped position) are null.
+/// Null is false, and any comparison or arithmetic with a null is null, with the exception of
+/// `!`, which makes a null true. This allows, e.g., `![XA]` to select records without an `XA`
+/// data field.
+///
+/// The right-hand side of a regular expression match (`=~`, `!~`) must be a string literal. It is
+/// compiled once when the expression is parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression(Node);
+
+impl Expression {
+    /// Evaluates the expression for the given record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::{self as sam, alignment::{record::Flags, RecordBuf}};
+    /// use noodles_util::alignment::filter::Expression;
+    ///
+    /// let header = sam::Header::default();
+    /// let record = RecordBuf::builder()
+    ///     .set_name("r0")
+    ///     .set_flags(Flags::SEGMENTED | Flags::FIRST_SEGMENT)
+    ///     .build();
+    ///
+    /// let expression: Expression = r#"flag.read1 && qname =~ "^r[0-9]+$""#.parse()?;
+    /// assert!(expression.evaluate(&header, &record)?);
+    ///
+    /// let expression: Expression = "flag.read2".parse()?;
+    /// assert!(!expression.evaluate(&header, &record)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn evaluate<R>(&self, header: &sam::Header, record: &R) -> io::Result<bool>
+    where
+        R: Record + ?Sized,
+    {
+        let context = Context { header, record };
+        self.0.evaluate(&context).map(|value| value.is_truthy())
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse(s).map(Self)
+    }
+}
+
+struct Context<'a, R: ?Sized> {
+    header: &'a sam::Header,
+    record: &'a R,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Literal(Value),
+    Field(Field),
+    FlagTest(Flags),
+    Tag([u8; 2]),
+    Unary(UnaryOperator, Box<Node>),
+    Binary(BinaryOperator, Box<Node>, Box<Node>),
+    Match(Box<Node>, Regex, bool),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Field {
+    Name,
+    Flags,
+    ReferenceSequenceName,
+    AlignmentStart,
+    AlignmentEnd,
+    MappingQuality,
+    MateReferenceSequenceName,
+    MateAlignmentStart,
+    TemplateLength,
+    Sequence,
+    SequenceLength,
+    AlignmentSpan,
+    CigarOpCount,
+    SoftClipLength,
+    HardClipLength,
+}
+
+impl Node {
+    fn evaluate<R>(&self, context: &Context<'_, R>) -> io::Result<Value>
+    where
+        R: Record + ?Sized,
+    {
+        match self {
+            Self::Literal(value) => Ok(value.clone()),
+            Self::Field(field) => evaluate_field(context, *field),
+            Self::FlagTest(flag) => {
+                let flags = context.record.flags()?;
+                Ok(Value::Integer(i64::from(flags.intersects(*flag))))
+            }
+            Self::Tag(tag) => evaluate_tag(context.record, *tag),
+            Self::Unary(op, node) => node
+                .evaluate(context)
+                .map(|value| evaluate_unary(*op, &value)),
+            Self::Binary(BinaryOperator::Or, lhs, rhs) => {
+                if lhs.evaluate(context)?.is_truthy() {
+                    Ok(Value::Integer(1))
+                } else {
+                    let value = rhs.evaluate(context)?;
+                    Ok(Value::Integer(i64::from(value.is_truthy())))
+                }
+            }
+            Self::Binary(BinaryOperator::And, lhs, rhs) => {
+                if lhs.evaluate(context)?.is_truthy() {
+                    let value = rhs.evaluate(context)?;
+                    Ok(Value::Integer(i64::from(value.is_truthy())))
+                } else {
+                    Ok(Value::Integer(0))
+                }
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let lhs = lhs.evaluate(context)?;
+                let rhs = rhs.evaluate(context)?;
+                Ok(evaluate_binary(*op, &lhs, &rhs))
+            }
+            Self::Match(node, regex, is_negated) => node
+                .evaluate(context)
+                .map(|value| evaluate_match(regex, &value, *is_negated)),
+        }
+    }
+}
+
+fn evaluate_field<R>(context: &Context<'_, R>, field: Field) -> io::Result<Value>
+where
+    R: Record + ?Sized,
+{
+    let record = context.record;
+
+    match field {
+        Field::Name => Ok(record
+            .name()
+            .map(|name| Value::String(name.to_vec()))
+            .unwrap_or(Value::Null)),
+        Field::Flags => record
+            .flags()
+            .map(|flags| Value::Integer(i64::from(flags.bits()))),
+        Field::ReferenceSequenceName => {
+            record
+                .reference_sequence(context.header)
+                .transpose()
+                .map(|entry| {
+                    entry
+                        .map(|(name, _)| Value::String(name.to_vec()))
+                        .unwrap_or(Value::Null)
+                })
+        }
+        Field::AlignmentStart => position_value(record.alignment_start()),
+        Field::AlignmentEnd => position_value(record.alignment_end()),
+        Field::MappingQuality => record.mapping_quality().transpose().map(|mapping_quality| {
+            let n = mapping_quality.map(u8::from).unwrap_or(255);
+            Value::Integer(i64::from(n))
+        }),
+        Field::MateReferenceSequenceName => record
+            .mate_reference_sequence(context.header)
+            .transpose()
+            .map(|entry| {
+                entry
+                    .map(|(name, _)| Value::String(name.to_vec()))
+                    .unwrap_or(Value::Null)
+            }),
+        Field::MateAlignmentStart => position_value(record.mate_alignment_start()),
+        Field::TemplateLength => record
+            .template_length()
+            .map(|n| Value::Integer(i64::from(n))),
+        Field::Sequence => {
+            let sequence = record.sequence();
+
+            if sequence.is_empty() {
+                Ok(Value::Null)
+            } else {
+                Ok(Value::String(sequence.iter().collect()))
+            }
+        }
+        Field::SequenceLength => Ok(length_value(record.sequence().len())),
+        Field::AlignmentSpan => record.cigar().alignment_span().map(length_value),
+        Field::CigarOpCount => Ok(length_value(record.cigar().len())),
+        Field::SoftClipLength => clip_length(record, Kind::SoftClip).map(length_value),
+        Field::HardClipLength => clip_length(record, Kind::HardClip).map(length_value),
+    }
+}
+
+fn position_value(position: Option<io::Result<noodles_core::Position>>) -> io::Result<Value> {
+    position.transpose().map(|position| {
+        position
+            .map(|position| length_value(usize::from(position)))
+            .unwrap_or(Value::Null)
+    })
+}
+
+fn clip_length<R>(record: &R, kind: Kind) -> io::Result<usize>
+where
+    R: Record + ?Sized,
+{
+    let mut len = 0;
+
+    for result in record.cigar().iter() {
+        let op = result?;
+
+        if op.kind() == kind {
+            len += op.len();
+        }
+    }
+
+    Ok(len)
+}
+
+fn evaluate_tag<R>(record: &R, tag: [u8; 2]) -> io::Result<Value>
+where
+    R: Record + ?Sized,
+{
+    let data = record.data();
+
+    let Some(value) = data.get(&tag.into()).transpose()? else {
+        return Ok(Value::Null);
+    };
+
+    let value = match value {
+        FieldValue::Character(c) => Value::String(vec![c]),
+        FieldValue::Float(n) => Value::Float(f64::from(n)),
+        FieldValue::String(s) | FieldValue::Hex(s) => Value::String(s.to_vec()),
+        FieldValue::Array(array) => length_value(array_len(&array)?),
+        value => value.as_int().map(Value::Integer).unwrap_or(Value::Null),
+    };
+
+    Ok(value)
+}
+
+fn array_len(array: &sam::alignment::record::data::field::value::Array<'_>) -> io::Result<usize> {
+    use sam::alignment::record::data::field::value::Array;
+
+    fn count<I, T>(iter: I) -> io::Result<usize>
+    where
+        I: Iterator<Item = io::Result<T>>,
+    {
+        let mut n = 0;
+
+        for result in iter {
+            result?;
+            n += 1;
+        }
+
+        Ok(n)
+    }
+
+    match array {
+        Array::Int8(values) => count(values.iter()),
+        Array::UInt8(values) => count(values.iter()),
+        Array::Int16(values) => count(values.iter()),
+        Array::UInt16(values) => count(values.iter()),
+        Array::Int32(values) => count(values.iter()),
+        Array::UInt32(values) => count(values.iter()),
+        Array::Float(values) => count(values.iter()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use noodles_core::Position;
+    use noodles_sam::{
+        alignment::{
+            record::{cigar::Op, data::field::Tag, MappingQuality},
+            record_buf::{data::field::Value as ValueBuf, Cigar, Sequence},
+            RecordBuf,
+        },
+        header::record::value::{map::ReferenceSequence, Map},
+    };
+
+    use super::*;
+
+    fn evaluate(header: &sam::Header, record: &RecordBuf, s: &str) -> bool {
+        let expression: Expression = s.parse().unwrap();
+        expression.evaluate(header, record).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() -> Result<(), Box<dyn std::error::Error>> {
+        const SQ0_LN: NonZeroUsize = match NonZeroUsize::new(131072) {
+            Some(length) => length,
+            None => unreachable!(),
+        };
+
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ0_LN))
+            .build();
+
+        let record = RecordBuf::builder()
+            .set_name("r0")
+            .set_flags(Flags::SEGMENTED | Flags::REVERSE_COMPLEMENTED | Flags::LAST_SEGMENT)
+            .set_reference_sequence_id(0)
+            .set_alignment_start(Position::try_from(8)?)
+            .set_mapping_quality(MappingQuality::try_from(13)?)
+            .set_cigar(Cigar::from(vec![
+                Op::new(Kind::SoftClip, 2),
+                Op::new(Kind::Match, 4),
+                Op::new(Kind::HardClip, 5),
+            ]))
+            .set_template_length(-21)
+            .set_sequence(Sequence::from(b"NNACGT".to_vec()))
+            .set_data(
+                [
+                    (Tag::from(*b"NM"), ValueBuf::from(3u8)),
+                    (Tag::from(*b"RG"), ValueBuf::from("rg0")),
+                    (Tag::from(*b"XS"), ValueBuf::from(1.5f32)),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .build();
+
+        assert!(evaluate(&header, &record, "flag.paired && flag.reverse"));
+        assert!(evaluate(&header, &record, "flag.read2 && !flag.read1"));
+        assert!(evaluate(&header, &record, "flag & 0x10"));
+        assert!(evaluate(&header, &record, "flag == 145"));
+        assert!(evaluate(&header, &record, r#"rname == "sq0""#));
+        assert!(evaluate(&header, &record, "pos == 8 && endpos == 11"));
+        assert!(evaluate(&header, &record, "mapq >= 10 && mapq < 20"));
+        assert!(evaluate(&header, &record, "tlen < 0 && -tlen > 20"));
+        assert!(evaluate(&header, &record, r#"seq =~ "ACGT$""#));
+        assert!(evaluate(&header, &record, r#"qname !~ "^r1""#));
+        assert!(evaluate(&header, &record, "qlen == 6 && rlen == 4"));
+        assert!(evaluate(&header, &record, "ncigar == 3"));
+        assert!(evaluate(&header, &record, "sclen == 2 && hclen == 5"));
+        assert!(evaluate(&header, &record, "[NM] / qlen > 0.4"));
+        assert!(evaluate(&header, &record, "[NM] * 2 + 1 == 7"));
+        assert!(evaluate(&header, &record, "[NM] % 2 == 1"));
+        assert!(evaluate(&header, &record, r#"[RG] == "rg0""#));
+        assert!(evaluate(&header, &record, "[XS] > 1 && [XS] < 2"));
+        assert!(evaluate(&header, &record, "![XA] && [NM]"));
+        assert!(evaluate(&header, &record, "!([XA] == 0)"));
+        assert!(evaluate(&header, &record, "!rnext && !pnext"));
+        assert!(evaluate(&header, &record, "mapq > 50 || [NM] == 3"));
+        assert!(!evaluate(&header, &record, "flag.dup || flag.qcfail"));
+        assert!(!evaluate(&header, &record, "[NM] > 1 && [NM] < 3"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_with_unmapped_record() {
+        let header = sam::Header::default();
+        let record = RecordBuf::default();
+
+        assert!(evaluate(&header, &record, "flag.unmap"));
+        assert!(evaluate(&header, &record, "!rname && !pos && !endpos"));
+        assert!(!evaluate(&header, &record, "pos == 0"));
+        assert!(!evaluate(&header, &record, "pos >= 0 || pnext >= 0"));
+        assert!(evaluate(&header, &record, "mapq == 255"));
+        assert!(evaluate(&header, &record, "!qname && !seq"));
+    }
+}
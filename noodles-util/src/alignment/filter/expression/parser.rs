@@ -0,0 +1,259 @@
+use noodles_sam::alignment::record::Flags;
+
+use super::{Field, Node};
+use crate::expression::{
+    parser::{self, Parser, Syntax, Token},
+    BinaryOperator, ParseError, Regex, UnaryOperator, Value,
+};
+
+pub(super) fn parse(s: &str) -> Result<Node, ParseError> {
+    parser::parse::<Dialect>(s)
+}
+
+// The `samtools view -e` dialect.
+struct Dialect;
+
+impl Syntax for Dialect {
+    type Node = Node;
+
+    const BINARY_OPERATORS: &'static [&'static [(Token, BinaryOperator)]] = &[
+        &[(Token::Or, BinaryOperator::Or)],
+        &[(Token::And, BinaryOperator::And)],
+        &[(Token::Pipe, BinaryOperator::BitwiseOr)],
+        &[(Token::Caret, BinaryOperator::BitwiseXor)],
+        &[(Token::Ampersand, BinaryOperator::BitwiseAnd)],
+        &[
+            (Token::Eq, BinaryOperator::Eq),
+            (Token::Ne, BinaryOperator::Ne),
+        ],
+        &[
+            (Token::Lt, BinaryOperator::Lt),
+            (Token::Le, BinaryOperator::Le),
+            (Token::Gt, BinaryOperator::Gt),
+            (Token::Ge, BinaryOperator::Ge),
+        ],
+        &[],
+        &[
+            (Token::Plus, BinaryOperator::Add),
+            (Token::Minus, BinaryOperator::Sub),
+        ],
+        &[
+            (Token::Star, BinaryOperator::Mul),
+            (Token::Slash, BinaryOperator::Div),
+            (Token::Percent, BinaryOperator::Rem),
+        ],
+    ];
+
+    const MATCH_OPERATORS: &'static [(Token, bool)] =
+        &[(Token::Match, false), (Token::NotMatch, true)];
+
+    const MATCH_PRECEDENCE: usize = 7;
+
+    const UNARY_OPERATORS: &'static [(Token, UnaryOperator)] = &[
+        (Token::Bang, UnaryOperator::Not),
+        (Token::Minus, UnaryOperator::Negate),
+        (Token::Tilde, UnaryOperator::BitwiseNot),
+    ];
+
+    // Integers can be decimal, hexadecimal (`0x`), or octal (`0`).
+    fn parse_integer(s: &str) -> Option<i64> {
+        if let Some(digits) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            i64::from_str_radix(digits, 16).ok()
+        } else if s.len() > 1 && s.starts_with('0') {
+            i64::from_str_radix(&s[1..], 8).ok()
+        } else {
+            s.parse().ok()
+        }
+    }
+
+    fn literal(value: Value) -> Node {
+        Node::Literal(value)
+    }
+
+    fn unary(op: UnaryOperator, node: Node) -> Node {
+        Node::Unary(op, Box::new(node))
+    }
+
+    fn binary(op: BinaryOperator, lhs: Node, rhs: Node) -> Node {
+        Node::Binary(op, Box::new(lhs), Box::new(rhs))
+    }
+
+    fn regex_match(node: Node, regex: Regex, is_negated: bool) -> Node {
+        Node::Match(Box::new(node), regex, is_negated)
+    }
+
+    fn parse_term(_: &mut Parser<Self>, offset: usize, token: Token) -> Result<Node, ParseError> {
+        match token {
+            Token::Bracket(s) => parse_tag(&s).map(Node::Tag),
+            Token::Identifier(s) => parse_identifier(&s),
+            token => Err(ParseError::UnexpectedToken(offset, token.to_string())),
+        }
+    }
+}
+
+fn parse_tag(s: &str) -> Result<[u8; 2], ParseError> {
+    match s.as_bytes() {
+        [b'[', a, b, b']'] if a.is_ascii_alphabetic() && b.is_ascii_alphanumeric() => Ok([*a, *b]),
+        _ => Err(ParseError::InvalidTag(s.into())),
+    }
+}
+
+fn parse_identifier(s: &str) -> Result<Node, ParseError> {
+    if let Some(name) = s.strip_prefix("flag.") {
+        return parse_flag(name)
+            .map(Node::FlagTest)
+            .ok_or_else(|| ParseError::UnknownIdentifier(s.into()));
+    }
+
+    let field = match s {
+        "qname" => Field::Name,
+        "flag" => Field::Flags,
+        "rname" => Field::ReferenceSequenceName,
+        "pos" => Field::AlignmentStart,
+        "endpos" => Field::AlignmentEnd,
+        "mapq" => Field::MappingQuality,
+        "rnext" => Field::MateReferenceSequenceName,
+        "pnext" => Field::MateAlignmentStart,
+        "tlen" => Field::TemplateLength,
+        "seq" => Field::Sequence,
+        "qlen" => Field::SequenceLength,
+        "rlen" => Field::AlignmentSpan,
+        "ncigar" => Field::CigarOpCount,
+        "sclen" => Field::SoftClipLength,
+        "hclen" => Field::HardClipLength,
+        _ => return Err(ParseError::UnknownIdentifier(s.into())),
+    };
+
+    Ok(Node::Field(field))
+}
+
+fn parse_flag(s: &str) -> Option<Flags> {
+    match s {
+        "paired" => Some(Flags::SEGMENTED),
+        "proper_pair" => Some(Flags::PROPERLY_SEGMENTED),
+        "unmap" => Some(Flags::UNMAPPED),
+        "munmap" => Some(Flags::MATE_UNMAPPED),
+        "reverse" => Some(Flags::REVERSE_COMPLEMENTED),
+        "mreverse" => Some(Flags::MATE_REVERSE_COMPLEMENTED),
+        "read1" => Some(Flags::FIRST_SEGMENT),
+        "read2" => Some(Flags::LAST_SEGMENT),
+        "secondary" => Some(Flags::SECONDARY),
+        "qcfail" => Some(Flags::QC_FAIL),
+        "dup" => Some(Flags::DUPLICATE),
+        "supplementary" => Some(Flags::SUPPLEMENTARY),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("mapq >= 30 && !flag.dup"),
+            Ok(Node::Binary(
+                BinaryOperator::And,
+                Box::new(Node::Binary(
+                    BinaryOperator::Ge,
+                    Box::new(Node::Field(Field::MappingQuality)),
+                    Box::new(Node::Literal(Value::Integer(30))),
+                )),
+                Box::new(Node::Unary(
+                    UnaryOperator::Not,
+                    Box::new(Node::FlagTest(Flags::DUPLICATE))
+                )),
+            ))
+        );
+
+        assert_eq!(
+            parse("1 + 2 * 3"),
+            Ok(Node::Binary(
+                BinaryOperator::Add,
+                Box::new(Node::Literal(Value::Integer(1))),
+                Box::new(Node::Binary(
+                    BinaryOperator::Mul,
+                    Box::new(Node::Literal(Value::Integer(2))),
+                    Box::new(Node::Literal(Value::Integer(3))),
+                )),
+            ))
+        );
+
+        assert_eq!(
+            parse("mapq > 1e-3"),
+            Ok(Node::Binary(
+                BinaryOperator::Gt,
+                Box::new(Node::Field(Field::MappingQuality)),
+                Box::new(Node::Literal(Value::Float(0.001))),
+            ))
+        );
+
+        assert_eq!(parse("2.5E+2"), Ok(Node::Literal(Value::Float(250.0))));
+
+        assert_eq!(
+            parse("0x1e-3"),
+            Ok(Node::Binary(
+                BinaryOperator::Sub,
+                Box::new(Node::Literal(Value::Integer(0x1e))),
+                Box::new(Node::Literal(Value::Integer(3))),
+            ))
+        );
+
+        assert_eq!(parse("([NM])"), Ok(Node::Tag([b'N', b'M'])),);
+
+        assert_eq!(parse(""), Err(ParseError::Empty));
+        assert_eq!(parse("mapq >="), Err(ParseError::UnexpectedEof));
+        assert_eq!(
+            parse("mapq = 30"),
+            Err(ParseError::UnexpectedToken(5, String::from("=")))
+        );
+        assert_eq!(
+            parse("mapq # 30"),
+            Err(ParseError::UnexpectedCharacter(5, '#'))
+        );
+        assert_eq!(parse("(mapq"), Err(ParseError::UnexpectedEof));
+        assert_eq!(
+            parse("mapq 30"),
+            Err(ParseError::UnexpectedToken(5, String::from("30")))
+        );
+        assert_eq!(
+            parse("qual > 0"),
+            Err(ParseError::UnknownIdentifier(String::from("qual")))
+        );
+        assert_eq!(
+            parse("flag.ndr"),
+            Err(ParseError::UnknownIdentifier(String::from("flag.ndr")))
+        );
+        assert_eq!(
+            parse("[NMX] > 0"),
+            Err(ParseError::InvalidTag(String::from("[NMX]")))
+        );
+        assert_eq!(
+            parse("[NM"),
+            Err(ParseError::InvalidTag(String::from("[NM")))
+        );
+        assert_eq!(
+            parse("08"),
+            Err(ParseError::InvalidNumber(String::from("08")))
+        );
+        assert_eq!(
+            parse(r#"qname == "r0"#),
+            Err(ParseError::UnterminatedString)
+        );
+        assert_eq!(parse("qname =~ 0"), Err(ParseError::ExpectedPattern));
+        assert!(matches!(
+            parse(r#"qname =~ "(""#),
+            Err(ParseError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        assert_eq!(Dialect::parse_integer("13"), Some(13));
+        assert_eq!(Dialect::parse_integer("0x10"), Some(16));
+        assert_eq!(Dialect::parse_integer("010"), Some(8));
+        assert_eq!(Dialect::parse_integer("08"), None);
+        assert_eq!(Dialect::parse_integer("0.5"), None);
+    }
+}
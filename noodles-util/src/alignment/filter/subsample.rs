@@ -0,0 +1,128 @@
+/// A deterministic subsample of records by read name.
+///
+/// Whether a record is kept only depends on its read name and the seed, so mates and other
+/// segments of the same template are either all kept or all discarded.
+///
+/// The hash is compatible with `samtools view --subsample`, i.e., given the same seed and
+/// fraction, the same set of templates is selected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Subsample {
+    seed: u32,
+    fraction: f64,
+}
+
+impl Subsample {
+    /// Creates a subsample.
+    ///
+    /// `fraction` is the proportion of templates to keep, in the range [0.0, 1.0].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::filter::Subsample;
+    /// let subsample = Subsample::new(0, 0.5);
+    /// ```
+    pub fn new(seed: u32, fraction: f64) -> Self {
+        Self { seed, fraction }
+    }
+
+    /// Returns the seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::filter::Subsample;
+    /// let subsample = Subsample::new(5, 0.5);
+    /// assert_eq!(subsample.seed(), 5);
+    /// ```
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns the fraction of templates to keep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::filter::Subsample;
+    /// let subsample = Subsample::new(5, 0.5);
+    /// assert_eq!(subsample.fraction(), 0.5);
+    /// ```
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// Returns whether a record with the given name is kept.
+    ///
+    /// Records without names are treated as having an empty name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::filter::Subsample;
+    ///
+    /// assert!(Subsample::new(0, 1.0).matches(Some(b"r0")));
+    /// assert!(!Subsample::new(0, 0.0).matches(Some(b"r0")));
+    /// ```
+    pub fn matches(&self, name: Option<&[u8]>) -> bool {
+        let name = name.unwrap_or_default();
+        let k = wang_hash(x31_hash(name) ^ self.seed);
+        let p = f64::from(k & 0xffffff) / f64::from(0x1000000);
+        p < self.fraction
+    }
+}
+
+fn x31_hash(s: &[u8]) -> u32 {
+    let mut iter = s.iter().map(|&b| b as i8 as u32);
+
+    let Some(mut h) = iter.next() else {
+        return 0;
+    };
+
+    for c in iter {
+        h = (h << 5).wrapping_sub(h).wrapping_add(c);
+    }
+
+    h
+}
+
+fn wang_hash(mut key: u32) -> u32 {
+    key = key.wrapping_add(!(key << 15));
+    key ^= key >> 10;
+    key = key.wrapping_add(key << 3);
+    key ^= key >> 6;
+    key = key.wrapping_add(!(key << 11));
+    key ^= key >> 16;
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let names: Vec<_> = (0..1000).map(|i| format!("r{i}")).collect();
+
+        let subsample = Subsample::new(13, 0.25);
+        let n = names
+            .iter()
+            .filter(|name| subsample.matches(Some(name.as_bytes())))
+            .count();
+        assert!((200..300).contains(&n));
+
+        // Smaller fractions select subsets of larger fractions.
+        let larger = Subsample::new(13, 0.5);
+        assert!(names
+            .iter()
+            .filter(|name| subsample.matches(Some(name.as_bytes())))
+            .all(|name| larger.matches(Some(name.as_bytes()))));
+    }
+
+    #[test]
+    fn test_x31_hash() {
+        assert_eq!(x31_hash(b""), 0);
+        assert_eq!(x31_hash(b"a"), 0x61);
+        assert_eq!(x31_hash(b"ab"), 0x61 * 31 + 0x62);
+    }
+}
@@ -0,0 +1,268 @@
+//! Alignment reference sequence renaming.
+
+use std::io;
+
+use noodles_sam::{
+    self as sam,
+    alignment::{record::Flags, RecordBuf},
+    header::ReferenceSequences,
+};
+
+use crate::contig_map::ContigMap;
+
+/// An alignment reference sequence renamer.
+///
+/// This renames the reference sequences (`@SQ`) of a header using a [`ContigMap`] and remaps the
+/// reference sequence IDs of records from the source header to the renamed header. Dropped
+/// reference sequences are removed from the renamed header, which changes the IDs of the
+/// reference sequences that follow.
+#[derive(Debug)]
+pub struct Renamer {
+    header: sam::Header,
+    reference_sequence_ids: Vec<Option<usize>>,
+}
+
+impl Renamer {
+    /// Creates an alignment reference sequence renamer for the given source header.
+    ///
+    /// This returns an error if a reference sequence is unmapped and unmapped names are errors,
+    /// or if multiple reference sequences are renamed to the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{io, num::NonZeroUsize};
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     header::record::value::{map::ReferenceSequence, Map},
+    /// };
+    /// use noodles_util::{alignment::rename::Renamer, contig_map::ContigMap};
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence(
+    ///         "chr1",
+    ///         Map::<ReferenceSequence>::new(NonZeroUsize::try_from(8)?),
+    ///     )
+    ///     .build();
+    ///
+    /// let mut contig_map = ContigMap::new();
+    /// contig_map.insert("chr1", "1");
+    ///
+    /// let renamer = Renamer::new(&contig_map, &header)?;
+    /// assert!(renamer.header().reference_sequences().contains_key(&b"1"[..]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(contig_map: &ContigMap, header: &sam::Header) -> io::Result<Self> {
+        let mut reference_sequences = ReferenceSequences::default();
+        let mut reference_sequence_ids = Vec::with_capacity(header.reference_sequences().len());
+
+        for (name, reference_sequence) in header.reference_sequences() {
+            let name = std::str::from_utf8(name)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let Some(dst) = contig_map.resolve(name)? else {
+                reference_sequence_ids.push(None);
+                continue;
+            };
+
+            let (id, previous) =
+                reference_sequences.insert_full(dst.into(), reference_sequence.clone());
+
+            if previous.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate reference sequence name: {dst}"),
+                ));
+            }
+
+            reference_sequence_ids.push(Some(id));
+        }
+
+        let mut renamed_header = header.clone();
+        *renamed_header.reference_sequences_mut() = reference_sequences;
+
+        Ok(Self {
+            header: renamed_header,
+            reference_sequence_ids,
+        })
+    }
+
+    /// Returns the renamed header.
+    pub fn header(&self) -> &sam::Header {
+        &self.header
+    }
+
+    /// Remaps the reference sequence IDs of a record.
+    ///
+    /// This returns `false` if the record is placed on a dropped reference sequence, i.e., the
+    /// record should be dropped. If only the mate is placed on a dropped reference sequence, the
+    /// mate is marked as unmapped, and its position and the template length are cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{io, num::NonZeroUsize};
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     alignment::RecordBuf,
+    ///     header::record::value::{map::ReferenceSequence, Map},
+    /// };
+    /// use noodles_util::{
+    ///     alignment::rename::Renamer,
+    ///     contig_map::{ContigMap, Unmapped},
+    /// };
+    ///
+    /// let header = sam::Header::builder()
+    ///     .add_reference_sequence(
+    ///         "chrUn",
+    ///         Map::<ReferenceSequence>::new(NonZeroUsize::try_from(8)?),
+    ///     )
+    ///     .add_reference_sequence(
+    ///         "chr1",
+    ///         Map::<ReferenceSequence>::new(NonZeroUsize::try_from(13)?),
+    ///     )
+    ///     .build();
+    ///
+    /// let mut contig_map = ContigMap::new().with_unmapped(Unmapped::Drop);
+    /// contig_map.insert("chr1", "1");
+    ///
+    /// let renamer = Renamer::new(&contig_map, &header)?;
+    ///
+    /// let mut record = RecordBuf::builder().set_reference_sequence_id(1).build();
+    /// assert!(renamer.rename_record(&mut record)?);
+    /// assert_eq!(record.reference_sequence_id(), Some(0));
+    ///
+    /// let mut record = RecordBuf::builder().set_reference_sequence_id(0).build();
+    /// assert!(!renamer.rename_record(&mut record)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn rename_record(&self, record: &mut RecordBuf) -> io::Result<bool> {
+        if let Some(id) = record.reference_sequence_id() {
+            match self.remap(id)? {
+                Some(new_id) => *record.reference_sequence_id_mut() = Some(new_id),
+                None => return Ok(false),
+            }
+        }
+
+        if let Some(id) = record.mate_reference_sequence_id() {
+            match self.remap(id)? {
+                Some(new_id) => *record.mate_reference_sequence_id_mut() = Some(new_id),
+                None => {
+                    *record.mate_reference_sequence_id_mut() = None;
+                    *record.mate_alignment_start_mut() = None;
+                    *record.template_length_mut() = 0;
+                    record.flags_mut().insert(Flags::MATE_UNMAPPED);
+                    record.flags_mut().remove(Flags::PROPERLY_SEGMENTED);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn remap(&self, id: usize) -> io::Result<Option<usize>> {
+        self.reference_sequence_ids.get(id).copied().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid reference sequence ID: {id}"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use noodles_core::Position;
+    use sam::header::record::value::{map::ReferenceSequence, Map};
+
+    use super::*;
+    use crate::contig_map::Unmapped;
+
+    #[test]
+    fn test_new() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(
+                "chr1",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(8)?),
+            )
+            .add_reference_sequence(
+                "chrUn",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(13)?),
+            )
+            .add_reference_sequence(
+                "chr2",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(21)?),
+            )
+            .build();
+
+        let mut contig_map = ContigMap::new();
+        contig_map.insert("chr1", "1");
+        contig_map.insert("chr2", "2");
+
+        let renamer = Renamer::new(&contig_map.clone().with_unmapped(Unmapped::Keep), &header)?;
+        let names: Vec<_> = renamer.header().reference_sequences().keys().collect();
+        assert_eq!(names, ["1", "chrUn", "2"]);
+
+        let renamer = Renamer::new(&contig_map.clone().with_unmapped(Unmapped::Drop), &header)?;
+        let names: Vec<_> = renamer.header().reference_sequences().keys().collect();
+        assert_eq!(names, ["1", "2"]);
+        assert_eq!(renamer.reference_sequence_ids, [Some(0), None, Some(1)]);
+
+        assert!(Renamer::new(&contig_map.clone().with_unmapped(Unmapped::Error), &header).is_err());
+
+        contig_map.insert("chrUn", "1");
+        assert!(Renamer::new(&contig_map, &header).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_record() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence(
+                "chr1",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(8)?),
+            )
+            .add_reference_sequence(
+                "chrUn",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(13)?),
+            )
+            .add_reference_sequence(
+                "chr2",
+                Map::<ReferenceSequence>::new(NonZeroUsize::try_from(21)?),
+            )
+            .build();
+
+        let mut contig_map = ContigMap::new().with_unmapped(Unmapped::Drop);
+        contig_map.insert("chr1", "1");
+        contig_map.insert("chr2", "2");
+
+        let renamer = Renamer::new(&contig_map, &header)?;
+
+        let mut record = RecordBuf::builder()
+            .set_flags(Flags::SEGMENTED | Flags::PROPERLY_SEGMENTED)
+            .set_reference_sequence_id(2)
+            .set_alignment_start(Position::MIN)
+            .set_mate_reference_sequence_id(1)
+            .set_mate_alignment_start(Position::MIN)
+            .set_template_length(8)
+            .build();
+
+        assert!(renamer.rename_record(&mut record)?);
+        assert_eq!(record.reference_sequence_id(), Some(1));
+        assert_eq!(record.flags(), Flags::SEGMENTED | Flags::MATE_UNMAPPED);
+        assert!(record.mate_reference_sequence_id().is_none());
+        assert!(record.mate_alignment_start().is_none());
+        assert_eq!(record.template_length(), 0);
+
+        let mut record = RecordBuf::default();
+        assert!(renamer.rename_record(&mut record)?);
+
+        let mut record = RecordBuf::builder().set_reference_sequence_id(3).build();
+        assert!(renamer.rename_record(&mut record).is_err());
+
+        Ok(())
+    }
+}
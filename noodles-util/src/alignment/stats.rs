@@ -0,0 +1,686 @@
+//! Alignment record statistics.
+//!
+//! A [`Collector`] accumulates statistics over any stream of alignment records. The resulting
+//! [`Stats`] can be merged, e.g., when collecting over shards in parallel, and written in the
+//! text formats of `samtools stats`, `samtools flagstat`, and `samtools idxstats`.
+
+mod collector;
+mod coverage;
+mod flag_stats;
+
+use std::{collections::BTreeMap, fmt};
+
+use noodles_sam as sam;
+
+pub use self::{
+    collector::Collector,
+    flag_stats::{FlagCounts, FlagStats},
+};
+
+/// The number of base composition classes per cycle: A, C, G, T, N, and other.
+const BASE_CLASS_COUNT: usize = 6;
+
+/// Summary counts of primary alignment records.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    /// The number of records.
+    pub sequences: u64,
+    /// The number of first fragments (first segments or unpaired records).
+    pub first_fragments: u64,
+    /// The number of last fragments.
+    pub last_fragments: u64,
+    /// The number of mapped records.
+    pub reads_mapped: u64,
+    /// The number of mapped records where the mate is also mapped.
+    pub reads_mapped_and_paired: u64,
+    /// The number of unmapped records.
+    pub reads_unmapped: u64,
+    /// The number of properly paired records.
+    pub reads_properly_paired: u64,
+    /// The number of paired records.
+    pub reads_paired: u64,
+    /// The number of duplicate records.
+    pub reads_duplicated: u64,
+    /// The number of mapped records with a mapping quality of 0.
+    pub reads_mq0: u64,
+    /// The number of records that failed quality checks.
+    pub reads_qc_failed: u64,
+    /// The number of secondary and supplementary records.
+    ///
+    /// These are not included in any other statistics.
+    pub non_primary_alignments: u64,
+    /// The number of supplementary records.
+    pub supplementary_alignments: u64,
+    /// The total sequence length.
+    pub total_length: u64,
+    /// The total sequence length of first fragments.
+    pub total_first_fragment_length: u64,
+    /// The total sequence length of last fragments.
+    pub total_last_fragment_length: u64,
+    /// The total sequence length of mapped records.
+    pub bases_mapped: u64,
+    /// The number of bases aligned to the reference sequence (`M`, `=`, and `X` operations).
+    pub bases_mapped_cigar: u64,
+    /// The total sequence length of duplicate records.
+    pub bases_duplicated: u64,
+    /// The number of mismatched bases.
+    ///
+    /// When a reference sequence is available, this is calculated by comparing aligned bases.
+    /// Otherwise, it is the sum of the edit distances (`NM`).
+    pub mismatches: u64,
+    /// The maximum sequence length.
+    pub maximum_length: u64,
+    /// The maximum sequence length of first fragments.
+    pub maximum_first_fragment_length: u64,
+    /// The maximum sequence length of last fragments.
+    pub maximum_last_fragment_length: u64,
+    /// The sum of all quality scores.
+    pub quality_sum: u64,
+    /// The number of mapped pairs with mates on different reference sequences.
+    pub pairs_on_different_chromosomes: u64,
+}
+
+impl Summary {
+    fn merge(&mut self, other: &Self) {
+        self.sequences += other.sequences;
+        self.first_fragments += other.first_fragments;
+        self.last_fragments += other.last_fragments;
+        self.reads_mapped += other.reads_mapped;
+        self.reads_mapped_and_paired += other.reads_mapped_and_paired;
+        self.reads_unmapped += other.reads_unmapped;
+        self.reads_properly_paired += other.reads_properly_paired;
+        self.reads_paired += other.reads_paired;
+        self.reads_duplicated += other.reads_duplicated;
+        self.reads_mq0 += other.reads_mq0;
+        self.reads_qc_failed += other.reads_qc_failed;
+        self.non_primary_alignments += other.non_primary_alignments;
+        self.supplementary_alignments += other.supplementary_alignments;
+        self.total_length += other.total_length;
+        self.total_first_fragment_length += other.total_first_fragment_length;
+        self.total_last_fragment_length += other.total_last_fragment_length;
+        self.bases_mapped += other.bases_mapped;
+        self.bases_mapped_cigar += other.bases_mapped_cigar;
+        self.bases_duplicated += other.bases_duplicated;
+        self.mismatches += other.mismatches;
+        self.maximum_length = self.maximum_length.max(other.maximum_length);
+        self.maximum_first_fragment_length = self
+            .maximum_first_fragment_length
+            .max(other.maximum_first_fragment_length);
+        self.maximum_last_fragment_length = self
+            .maximum_last_fragment_length
+            .max(other.maximum_last_fragment_length);
+        self.quality_sum += other.quality_sum;
+        self.pairs_on_different_chromosomes += other.pairs_on_different_chromosomes;
+    }
+}
+
+/// Insert size counts by pair orientation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InsertSizeCounts {
+    /// The number of inward oriented pairs (FR).
+    pub inward: u64,
+    /// The number of outward oriented pairs (RF).
+    pub outward: u64,
+    /// The number of pairs with another orientation (FF or RR).
+    pub other: u64,
+}
+
+impl InsertSizeCounts {
+    /// Returns the total number of pairs.
+    pub fn total(&self) -> u64 {
+        self.inward + self.outward + self.other
+    }
+}
+
+/// Record counts for a reference sequence.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReferenceSequenceCounts {
+    /// The number of mapped records.
+    pub mapped: u64,
+    /// The number of unmapped records placed on the reference sequence.
+    pub unmapped: u64,
+}
+
+/// Alignment record statistics.
+///
+/// The [`fmt::Display`] implementation writes the statistics in the text format of
+/// `samtools stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    flag_stats: FlagStats,
+    reference_sequence_counts: Vec<ReferenceSequenceCounts>,
+    unplaced_unmapped_count: u64,
+    summary: Summary,
+    is_sorted: bool,
+    mapping_qualities: Vec<u64>,
+    insert_sizes: BTreeMap<u64, InsertSizeCounts>,
+    read_lengths: BTreeMap<usize, u64>,
+    base_composition: Vec<[u64; BASE_CLASS_COUNT]>,
+    first_fragment_qualities: Vec<Vec<u64>>,
+    last_fragment_qualities: Vec<Vec<u64>>,
+    coverage: BTreeMap<u64, u64>,
+}
+
+impl Stats {
+    /// Returns the flag statistics.
+    ///
+    /// Unlike the other statistics, these include secondary and supplementary records.
+    pub fn flag_stats(&self) -> &FlagStats {
+        &self.flag_stats
+    }
+
+    /// Returns the record counts per reference sequence, indexed by reference sequence ID.
+    pub fn reference_sequence_counts(&self) -> &[ReferenceSequenceCounts] {
+        &self.reference_sequence_counts
+    }
+
+    /// Returns the number of unmapped records not placed on a reference sequence.
+    pub fn unplaced_unmapped_count(&self) -> u64 {
+        self.unplaced_unmapped_count
+    }
+
+    /// Returns the summary counts.
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    /// Returns whether the input records were coordinate-sorted.
+    ///
+    /// Coverage is only calculated for coordinate-sorted input.
+    pub fn is_sorted(&self) -> bool {
+        self.is_sorted
+    }
+
+    /// Returns the number of mapped records per mapping quality.
+    ///
+    /// This is indexed by mapping quality. Records with a missing mapping quality are counted at
+    /// 255.
+    pub fn mapping_qualities(&self) -> &[u64] {
+        &self.mapping_qualities
+    }
+
+    /// Returns the insert size histogram.
+    ///
+    /// Each pair is counted once, by the record with a positive template length.
+    pub fn insert_sizes(&self) -> &BTreeMap<u64, InsertSizeCounts> {
+        &self.insert_sizes
+    }
+
+    /// Returns the read length histogram.
+    pub fn read_lengths(&self) -> &BTreeMap<usize, u64> {
+        &self.read_lengths
+    }
+
+    /// Returns the base counts per cycle.
+    ///
+    /// Cycles are in sequencing order, i.e., reverse complemented records are reverted. Each
+    /// entry holds the counts of A, C, G, T, N, and other bases, respectively.
+    pub fn base_composition(&self) -> &[[u64; 6]] {
+        &self.base_composition
+    }
+
+    /// Returns the quality score histograms per cycle of first fragments.
+    pub fn first_fragment_qualities(&self) -> &[Vec<u64>] {
+        &self.first_fragment_qualities
+    }
+
+    /// Returns the quality score histograms per cycle of last fragments.
+    pub fn last_fragment_qualities(&self) -> &[Vec<u64>] {
+        &self.last_fragment_qualities
+    }
+
+    /// Returns the coverage distribution, i.e., the number of reference sequence positions per
+    /// depth.
+    ///
+    /// Uncovered positions are not counted.
+    pub fn coverage(&self) -> &BTreeMap<u64, u64> {
+        &self.coverage
+    }
+
+    /// Returns the error rate, i.e., mismatches per aligned base.
+    pub fn error_rate(&self) -> f64 {
+        ratio(self.summary.mismatches, self.summary.bases_mapped_cigar)
+    }
+
+    /// Merges another set of statistics into this one.
+    ///
+    /// Counts and histograms are summed. Coverage is summed per depth, so positions covered by
+    /// records in both sets are not recombined into a deeper depth. Merged statistics are
+    /// considered sorted if both are, regardless of the order across the two sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::stats::Stats;
+    /// let mut stats = Stats::default();
+    /// stats.merge(&Stats::default());
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        self.flag_stats.merge(&other.flag_stats);
+
+        merge_vecs(
+            &mut self.reference_sequence_counts,
+            &other.reference_sequence_counts,
+            |a, b| {
+                a.mapped += b.mapped;
+                a.unmapped += b.unmapped;
+            },
+        );
+
+        self.unplaced_unmapped_count += other.unplaced_unmapped_count;
+        self.summary.merge(&other.summary);
+        self.is_sorted = self.is_sorted && other.is_sorted;
+
+        merge_vecs(
+            &mut self.mapping_qualities,
+            &other.mapping_qualities,
+            |a, b| *a += b,
+        );
+
+        for (&insert_size, counts) in &other.insert_sizes {
+            let entry = self.insert_sizes.entry(insert_size).or_default();
+            entry.inward += counts.inward;
+            entry.outward += counts.outward;
+            entry.other += counts.other;
+        }
+
+        merge_histograms(&mut self.read_lengths, &other.read_lengths);
+
+        merge_vecs(
+            &mut self.base_composition,
+            &other.base_composition,
+            |a, b| {
+                for (x, y) in a.iter_mut().zip(b) {
+                    *x += y;
+                }
+            },
+        );
+
+        for (dst, src) in [
+            (
+                &mut self.first_fragment_qualities,
+                &other.first_fragment_qualities,
+            ),
+            (
+                &mut self.last_fragment_qualities,
+                &other.last_fragment_qualities,
+            ),
+        ] {
+            merge_vecs(dst, src, |a, b| merge_vecs(a, b, |x, y| *x += y));
+        }
+
+        merge_histograms(&mut self.coverage, &other.coverage);
+    }
+
+    /// Returns a displayable view of the per reference sequence counts.
+    ///
+    /// The output matches that of `samtools idxstats`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam as sam;
+    /// use noodles_util::alignment::stats::Stats;
+    ///
+    /// let header = sam::Header::default();
+    /// let stats = Stats::default();
+    ///
+    /// assert_eq!(stats.idxstats(&header).to_string(), "*\t0\t0\t0\n");
+    /// ```
+    pub fn idxstats<'a>(&'a self, header: &'a sam::Header) -> impl fmt::Display + 'a {
+        IdxStats {
+            stats: self,
+            header,
+        }
+    }
+}
+
+fn merge_vecs<T, F>(dst: &mut Vec<T>, src: &[T], f: F)
+where
+    T: Clone + Default,
+    F: Fn(&mut T, &T),
+{
+    if dst.len() < src.len() {
+        dst.resize(src.len(), T::default());
+    }
+
+    for (a, b) in dst.iter_mut().zip(src) {
+        f(a, b);
+    }
+}
+
+fn merge_histograms<K>(dst: &mut BTreeMap<K, u64>, src: &BTreeMap<K, u64>)
+where
+    K: Copy + Ord,
+{
+    for (&k, &n) in src {
+        *dst.entry(k).or_default() += n;
+    }
+}
+
+fn ratio(a: u64, b: u64) -> f64 {
+    if b == 0 {
+        0.0
+    } else {
+        a as f64 / b as f64
+    }
+}
+
+struct IdxStats<'a> {
+    stats: &'a Stats,
+    header: &'a sam::Header,
+}
+
+impl fmt::Display for IdxStats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, reference_sequence)) in self.header.reference_sequences().iter().enumerate()
+        {
+            let counts = self
+                .stats
+                .reference_sequence_counts
+                .get(i)
+                .copied()
+                .unwrap_or_default();
+
+            writeln!(
+                f,
+                "{name}\t{}\t{}\t{}",
+                reference_sequence.length(),
+                counts.mapped,
+                counts.unmapped
+            )?;
+        }
+
+        writeln!(f, "*\t0\t0\t{}", self.stats.unplaced_unmapped_count)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = &self.summary;
+
+        let (insert_size_sum, insert_size_squares_sum, pair_count) = self.insert_sizes.iter().fold(
+            (0.0, 0.0, 0),
+            |(sum, squares_sum, n), (&size, counts)| {
+                let m = counts.total();
+                let size = size as f64;
+                let k = m as f64;
+                (sum + size * k, squares_sum + size * size * k, n + m)
+            },
+        );
+
+        let insert_size_mean = ratio_f64(insert_size_sum, pair_count);
+        let insert_size_sd = if pair_count > 0 {
+            (ratio_f64(insert_size_squares_sum, pair_count) - insert_size_mean * insert_size_mean)
+                .max(0.0)
+                .sqrt()
+        } else {
+            0.0
+        };
+
+        let (inward, outward, other) =
+            self.insert_sizes
+                .values()
+                .fold((0, 0, 0), |(inward, outward, other), counts| {
+                    (
+                        inward + counts.inward,
+                        outward + counts.outward,
+                        other + counts.other,
+                    )
+                });
+
+        writeln!(
+            f,
+            "# Summary Numbers. Use `grep ^SN | cut -f 2-` to extract this part."
+        )?;
+        writeln!(
+            f,
+            "SN\traw total sequences:\t{}",
+            s.sequences + s.reads_qc_failed
+        )?;
+        writeln!(f, "SN\tsequences:\t{}", s.sequences)?;
+        writeln!(f, "SN\tis sorted:\t{}", u8::from(self.is_sorted))?;
+        writeln!(f, "SN\t1st fragments:\t{}", s.first_fragments)?;
+        writeln!(f, "SN\tlast fragments:\t{}", s.last_fragments)?;
+        writeln!(f, "SN\treads mapped:\t{}", s.reads_mapped)?;
+        writeln!(
+            f,
+            "SN\treads mapped and paired:\t{}\t# paired-end technology bit set + both mates mapped",
+            s.reads_mapped_and_paired
+        )?;
+        writeln!(f, "SN\treads unmapped:\t{}", s.reads_unmapped)?;
+        writeln!(
+            f,
+            "SN\treads properly paired:\t{}\t# proper-pair bit set",
+            s.reads_properly_paired
+        )?;
+        writeln!(
+            f,
+            "SN\treads paired:\t{}\t# paired-end technology bit set",
+            s.reads_paired
+        )?;
+        writeln!(
+            f,
+            "SN\treads duplicated:\t{}\t# PCR or optical duplicate bit set",
+            s.reads_duplicated
+        )?;
+        writeln!(f, "SN\treads MQ0:\t{}\t# mapped and MQ=0", s.reads_mq0)?;
+        writeln!(f, "SN\treads QC failed:\t{}", s.reads_qc_failed)?;
+        writeln!(
+            f,
+            "SN\tnon-primary alignments:\t{}",
+            s.non_primary_alignments
+        )?;
+        writeln!(
+            f,
+            "SN\tsupplementary alignments:\t{}",
+            s.supplementary_alignments
+        )?;
+        writeln!(
+            f,
+            "SN\ttotal length:\t{}\t# ignores clipping",
+            s.total_length
+        )?;
+        writeln!(
+            f,
+            "SN\ttotal first fragment length:\t{}\t# ignores clipping",
+            s.total_first_fragment_length
+        )?;
+        writeln!(
+            f,
+            "SN\ttotal last fragment length:\t{}\t# ignores clipping",
+            s.total_last_fragment_length
+        )?;
+        writeln!(
+            f,
+            "SN\tbases mapped:\t{}\t# ignores clipping",
+            s.bases_mapped
+        )?;
+        writeln!(
+            f,
+            "SN\tbases mapped (cigar):\t{}\t# more accurate",
+            s.bases_mapped_cigar
+        )?;
+        writeln!(f, "SN\tbases trimmed:\t0")?;
+        writeln!(f, "SN\tbases duplicated:\t{}", s.bases_duplicated)?;
+        writeln!(f, "SN\tmismatches:\t{}", s.mismatches)?;
+        writeln!(
+            f,
+            "SN\terror rate:\t{:e}\t# mismatches / bases mapped (cigar)",
+            self.error_rate()
+        )?;
+        writeln!(
+            f,
+            "SN\taverage length:\t{}",
+            ratio(s.total_length, s.sequences).round()
+        )?;
+        writeln!(
+            f,
+            "SN\taverage first fragment length:\t{}",
+            ratio(s.total_first_fragment_length, s.first_fragments).round()
+        )?;
+        writeln!(
+            f,
+            "SN\taverage last fragment length:\t{}",
+            ratio(s.total_last_fragment_length, s.last_fragments).round()
+        )?;
+        writeln!(f, "SN\tmaximum length:\t{}", s.maximum_length)?;
+        writeln!(
+            f,
+            "SN\tmaximum first fragment length:\t{}",
+            s.maximum_first_fragment_length
+        )?;
+        writeln!(
+            f,
+            "SN\tmaximum last fragment length:\t{}",
+            s.maximum_last_fragment_length
+        )?;
+        writeln!(
+            f,
+            "SN\taverage quality:\t{:.1}",
+            ratio(s.quality_sum, s.total_length)
+        )?;
+        writeln!(f, "SN\tinsert size average:\t{insert_size_mean:.1}")?;
+        writeln!(
+            f,
+            "SN\tinsert size standard deviation:\t{insert_size_sd:.1}"
+        )?;
+        writeln!(f, "SN\tinward oriented pairs:\t{inward}")?;
+        writeln!(f, "SN\toutward oriented pairs:\t{outward}")?;
+        writeln!(f, "SN\tpairs with other orientation:\t{other}")?;
+        writeln!(
+            f,
+            "SN\tpairs on different chromosomes:\t{}",
+            s.pairs_on_different_chromosomes
+        )?;
+        writeln!(
+            f,
+            "SN\tpercentage of properly paired reads (%):\t{:.1}",
+            ratio(s.reads_properly_paired, s.sequences) * 100.0
+        )?;
+
+        writeln!(
+            f,
+            "# First Fragment Qualities. Use `grep ^FFQ | cut -f 2-` to extract this part."
+        )?;
+        write_qualities(f, "FFQ", &self.first_fragment_qualities)?;
+
+        writeln!(
+            f,
+            "# Last Fragment Qualities. Use `grep ^LFQ | cut -f 2-` to extract this part."
+        )?;
+        write_qualities(f, "LFQ", &self.last_fragment_qualities)?;
+
+        writeln!(f, "# ACGT content per cycle. Use `grep ^GCC | cut -f 2-` to extract this part. The columns are: cycle; A,C,G,T base counts as a percentage of all A/C/G/T bases [%]; and N and O counts as a percentage of all A/C/G/T bases [%]")?;
+        for (i, counts) in self.base_composition.iter().enumerate() {
+            let acgt_count: u64 = counts[..4].iter().sum();
+            write!(f, "GCC\t{}", i + 1)?;
+
+            for &n in counts {
+                write!(f, "\t{:.2}", ratio(n, acgt_count) * 100.0)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        writeln!(f, "# Insert sizes. Use `grep ^IS | cut -f 2-` to extract this part. The columns are: insert size, pairs total, inward oriented pairs, outward oriented pairs, other pairs")?;
+        for (insert_size, counts) in &self.insert_sizes {
+            writeln!(
+                f,
+                "IS\t{insert_size}\t{}\t{}\t{}\t{}",
+                counts.total(),
+                counts.inward,
+                counts.outward,
+                counts.other
+            )?;
+        }
+
+        writeln!(f, "# Read lengths. Use `grep ^RL | cut -f 2-` to extract this part. The columns are: read length, count")?;
+        for (len, n) in &self.read_lengths {
+            writeln!(f, "RL\t{len}\t{n}")?;
+        }
+
+        writeln!(f, "# Mapping qualities. Use `grep ^MAPQ | cut -f 2-` to extract this part. The columns are: mapq, count")?;
+        for (mapq, &n) in self.mapping_qualities.iter().enumerate() {
+            if n > 0 {
+                writeln!(f, "MAPQ\t{mapq}\t{n}")?;
+            }
+        }
+
+        writeln!(
+            f,
+            "# Coverage distribution. Use `grep ^COV | cut -f 2-` to extract this part."
+        )?;
+        for (depth, n) in &self.coverage {
+            writeln!(f, "COV\t[{depth}-{depth}]\t{depth}\t{n}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn ratio_f64(a: f64, b: u64) -> f64 {
+    if b == 0 {
+        0.0
+    } else {
+        a / b as f64
+    }
+}
+
+fn write_qualities(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    qualities: &[Vec<u64>],
+) -> fmt::Result {
+    let width = qualities
+        .iter()
+        .map(|counts| counts.len())
+        .max()
+        .unwrap_or(0);
+
+    for (i, counts) in qualities.iter().enumerate() {
+        write!(f, "{prefix}\t{}", i + 1)?;
+
+        for j in 0..width {
+            write!(f, "\t{}", counts.get(j).copied().unwrap_or(0))?;
+        }
+
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = Stats {
+            is_sorted: true,
+            mapping_qualities: vec![0, 1],
+            read_lengths: [(100, 2)].into_iter().collect(),
+            first_fragment_qualities: vec![vec![0, 1]],
+            ..Default::default()
+        };
+
+        let b = Stats {
+            is_sorted: true,
+            mapping_qualities: vec![0, 2, 3],
+            read_lengths: [(100, 1), (150, 1)].into_iter().collect(),
+            first_fragment_qualities: vec![vec![1], vec![0, 0, 5]],
+            ..Default::default()
+        };
+
+        a.merge(&b);
+
+        assert!(a.is_sorted());
+        assert_eq!(a.mapping_qualities(), [0, 3, 3]);
+        assert_eq!(
+            a.read_lengths(),
+            &[(100, 3), (150, 1)].into_iter().collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(a.first_fragment_qualities(), [vec![1, 1], vec![0, 0, 5]]);
+    }
+}
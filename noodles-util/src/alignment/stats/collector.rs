@@ -0,0 +1,634 @@
+use std::io;
+
+use noodles_fasta as fasta;
+use noodles_sam::{
+    self as sam,
+    alignment::{
+        record::{cigar::op::Kind, data::field::Tag, Flags},
+        Record,
+    },
+};
+
+use super::{coverage::Coverage, InsertSizeCounts, Stats, BASE_CLASS_COUNT};
+
+const MISSING_MAPPING_QUALITY: usize = 255;
+
+/// An alignment record statistics collector.
+///
+/// Secondary and supplementary records are only counted in the flag statistics, per reference
+/// sequence counts, and non-primary summary counts. Records that fail quality checks are only
+/// counted in the flag statistics, per reference sequence counts, and QC failed summary count.
+#[derive(Debug, Default)]
+pub struct Collector {
+    stats: Stats,
+    coverage: Coverage,
+    reference_sequence_repository: Option<fasta::Repository>,
+    reference_sequence: Option<(usize, fasta::record::Sequence)>,
+}
+
+impl Collector {
+    /// Creates an alignment record statistics collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::stats::Collector;
+    /// let collector = Collector::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the reference sequence repository used to count mismatches.
+    ///
+    /// Without a reference sequence repository, mismatches are counted using the edit distance
+    /// (`NM`) data field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::alignment::stats::Collector;
+    /// let collector = Collector::new()
+    ///     .with_reference_sequence_repository(fasta::Repository::default());
+    /// ```
+    pub fn with_reference_sequence_repository(
+        mut self,
+        reference_sequence_repository: fasta::Repository,
+    ) -> Self {
+        self.reference_sequence_repository = Some(reference_sequence_repository);
+        self
+    }
+
+    /// Adds an alignment record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_sam::{self as sam, alignment::RecordBuf};
+    /// use noodles_util::alignment::stats::Collector;
+    ///
+    /// let header = sam::Header::default();
+    ///
+    /// let mut collector = Collector::new();
+    /// collector.add(&header, &RecordBuf::default())?;
+    ///
+    /// let stats = collector.finish();
+    /// assert_eq!(stats.summary().reads_unmapped, 1);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn add<R>(&mut self, header: &sam::Header, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let flags = record.flags()?;
+
+        self.stats.flag_stats.add(header, record)?;
+
+        let reference_sequence_id = record.reference_sequence_id(header).transpose()?;
+        self.add_reference_sequence_counts(flags, reference_sequence_id);
+
+        let summary = &mut self.stats.summary;
+
+        if flags.is_secondary() || flags.is_supplementary() {
+            summary.non_primary_alignments += 1;
+
+            if flags.is_supplementary() {
+                summary.supplementary_alignments += 1;
+            }
+
+            return Ok(());
+        }
+
+        if flags.is_qc_fail() {
+            summary.reads_qc_failed += 1;
+            return Ok(());
+        }
+
+        summary.sequences += 1;
+
+        let sequence = record.sequence();
+        let len = sequence.len();
+        let is_last_fragment =
+            flags.is_segmented() && flags.is_last_segment() && !flags.is_first_segment();
+
+        summary.total_length += len as u64;
+        summary.maximum_length = summary.maximum_length.max(len as u64);
+
+        if is_last_fragment {
+            summary.last_fragments += 1;
+            summary.total_last_fragment_length += len as u64;
+            summary.maximum_last_fragment_length =
+                summary.maximum_last_fragment_length.max(len as u64);
+        } else {
+            summary.first_fragments += 1;
+            summary.total_first_fragment_length += len as u64;
+            summary.maximum_first_fragment_length =
+                summary.maximum_first_fragment_length.max(len as u64);
+        }
+
+        *self.stats.read_lengths.entry(len).or_default() += 1;
+
+        let is_reverse_complemented = flags.is_reverse_complemented();
+        let cycle = |len: usize, i: usize| {
+            if is_reverse_complemented {
+                len - 1 - i
+            } else {
+                i
+            }
+        };
+
+        if self.stats.base_composition.len() < len {
+            self.stats
+                .base_composition
+                .resize(len, [0; BASE_CLASS_COUNT]);
+        }
+
+        for (i, base) in sequence.iter().enumerate() {
+            let base = if is_reverse_complemented {
+                complement(base)
+            } else {
+                base
+            };
+
+            self.stats.base_composition[cycle(len, i)][base_class(base)] += 1;
+        }
+
+        let quality_scores = record.quality_scores();
+
+        if !quality_scores.is_empty() {
+            let qualities = if is_last_fragment {
+                &mut self.stats.last_fragment_qualities
+            } else {
+                &mut self.stats.first_fragment_qualities
+            };
+
+            if qualities.len() < quality_scores.len() {
+                qualities.resize(quality_scores.len(), Vec::new());
+            }
+
+            let len = quality_scores.len();
+
+            for (i, score) in quality_scores.iter().enumerate() {
+                let counts = &mut qualities[cycle(len, i)];
+                let score = usize::from(score);
+
+                if counts.len() <= score {
+                    counts.resize(score + 1, 0);
+                }
+
+                counts[score] += 1;
+                self.stats.summary.quality_sum += score as u64;
+            }
+        }
+
+        let summary = &mut self.stats.summary;
+
+        if flags.is_segmented() {
+            summary.reads_paired += 1;
+
+            if flags.is_properly_segmented() {
+                summary.reads_properly_paired += 1;
+            }
+        }
+
+        if flags.is_duplicate() {
+            summary.reads_duplicated += 1;
+            summary.bases_duplicated += len as u64;
+        }
+
+        if flags.is_unmapped() {
+            summary.reads_unmapped += 1;
+            return Ok(());
+        }
+
+        summary.reads_mapped += 1;
+        summary.bases_mapped += len as u64;
+
+        let mapping_quality = record
+            .mapping_quality()
+            .transpose()?
+            .map(|mapq| usize::from(mapq.get()))
+            .unwrap_or(MISSING_MAPPING_QUALITY);
+
+        if mapping_quality == 0 {
+            summary.reads_mq0 += 1;
+        }
+
+        if self.stats.mapping_qualities.len() <= mapping_quality {
+            self.stats.mapping_qualities.resize(mapping_quality + 1, 0);
+        }
+
+        self.stats.mapping_qualities[mapping_quality] += 1;
+
+        if flags.is_segmented() && !flags.is_mate_unmapped() {
+            self.add_pair(header, record, flags, reference_sequence_id)?;
+        }
+
+        let Some(alignment_start) = record.alignment_start().transpose()? else {
+            return Ok(());
+        };
+
+        let blocks = aligned_blocks(record, usize::from(alignment_start))?;
+        let bases_mapped_cigar: usize = blocks.iter().map(|(_, _, len)| len).sum();
+        self.stats.summary.bases_mapped_cigar += bases_mapped_cigar as u64;
+
+        self.stats.summary.mismatches += match reference_sequence_id {
+            Some(id) if self.reference_sequence_repository.is_some() => {
+                self.count_mismatches(header, record, id, &blocks)?
+            }
+            _ => edit_distance(record)?,
+        };
+
+        if let Some(id) = reference_sequence_id {
+            let blocks: Vec<_> = blocks
+                .iter()
+                .map(|&(reference_start, _, len)| (reference_start, len))
+                .collect();
+
+            self.coverage.add(
+                &mut self.stats.coverage,
+                id,
+                usize::from(alignment_start),
+                &blocks,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Finishes collecting and returns the statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::alignment::stats::Collector;
+    /// let stats = Collector::new().finish();
+    /// assert_eq!(stats.summary().sequences, 0);
+    /// ```
+    pub fn finish(mut self) -> Stats {
+        self.coverage.flush(&mut self.stats.coverage, usize::MAX);
+        self.stats.is_sorted = self.coverage.is_sorted();
+        self.stats
+    }
+
+    fn add_reference_sequence_counts(
+        &mut self,
+        flags: Flags,
+        reference_sequence_id: Option<usize>,
+    ) {
+        let Some(id) = reference_sequence_id else {
+            self.stats.unplaced_unmapped_count += 1;
+            return;
+        };
+
+        let counts = &mut self.stats.reference_sequence_counts;
+
+        if counts.len() <= id {
+            counts.resize(id + 1, Default::default());
+        }
+
+        if flags.is_unmapped() {
+            counts[id].unmapped += 1;
+        } else {
+            counts[id].mapped += 1;
+        }
+    }
+
+    fn add_pair<R>(
+        &mut self,
+        header: &sam::Header,
+        record: &R,
+        flags: Flags,
+        reference_sequence_id: Option<usize>,
+    ) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let summary = &mut self.stats.summary;
+        summary.reads_mapped_and_paired += 1;
+
+        let mate_reference_sequence_id = record.mate_reference_sequence_id(header).transpose()?;
+
+        if mate_reference_sequence_id != reference_sequence_id {
+            if flags.is_first_segment() {
+                summary.pairs_on_different_chromosomes += 1;
+            }
+
+            return Ok(());
+        }
+
+        let template_length = record.template_length()?;
+
+        if template_length <= 0 {
+            return Ok(());
+        }
+
+        let start = record.alignment_start().transpose()?;
+        let mate_start = record.mate_alignment_start().transpose()?;
+
+        let (left_is_reverse, right_is_reverse) = if start <= mate_start {
+            (
+                flags.is_reverse_complemented(),
+                flags.is_mate_reverse_complemented(),
+            )
+        } else {
+            (
+                flags.is_mate_reverse_complemented(),
+                flags.is_reverse_complemented(),
+            )
+        };
+
+        let counts: &mut InsertSizeCounts = self
+            .stats
+            .insert_sizes
+            .entry(u64::from(template_length.unsigned_abs()))
+            .or_default();
+
+        match (left_is_reverse, right_is_reverse) {
+            (false, true) => counts.inward += 1,
+            (true, false) => counts.outward += 1,
+            _ => counts.other += 1,
+        }
+
+        Ok(())
+    }
+
+    fn count_mismatches<R>(
+        &mut self,
+        header: &sam::Header,
+        record: &R,
+        reference_sequence_id: usize,
+        blocks: &[(usize, usize, usize)],
+    ) -> io::Result<u64>
+    where
+        R: Record + ?Sized,
+    {
+        let Some(reference_sequence) = self.reference_sequence(header, reference_sequence_id)?
+        else {
+            return edit_distance(record);
+        };
+
+        let reference_sequence = reference_sequence.as_ref();
+        let sequence: Vec<u8> = record.sequence().iter().collect();
+        let mut mismatches = 0;
+
+        for &(reference_start, read_start, len) in blocks {
+            let reference_bases = reference_sequence
+                .get(reference_start - 1..)
+                .unwrap_or_default()
+                .iter();
+
+            let read_bases = sequence.get(read_start..).unwrap_or_default().iter();
+
+            for (a, b) in reference_bases.zip(read_bases).take(len) {
+                let (a, b) = (a.to_ascii_uppercase(), b.to_ascii_uppercase());
+
+                if a != b && a != b'N' && b != b'N' {
+                    mismatches += 1;
+                }
+            }
+        }
+
+        Ok(mismatches)
+    }
+
+    fn reference_sequence(
+        &mut self,
+        header: &sam::Header,
+        reference_sequence_id: usize,
+    ) -> io::Result<Option<&fasta::record::Sequence>> {
+        let is_cached = self
+            .reference_sequence
+            .as_ref()
+            .map(|(id, _)| *id == reference_sequence_id)
+            .unwrap_or(false);
+
+        if !is_cached {
+            let Some(repository) = self.reference_sequence_repository.as_ref() else {
+                return Ok(None);
+            };
+
+            let Some((name, _)) = header
+                .reference_sequences()
+                .get_index(reference_sequence_id)
+            else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid reference sequence ID",
+                ));
+            };
+
+            let Some(sequence) = repository.get(name).transpose()? else {
+                return Ok(None);
+            };
+
+            self.reference_sequence = Some((reference_sequence_id, sequence));
+        }
+
+        Ok(self
+            .reference_sequence
+            .as_ref()
+            .map(|(_, sequence)| sequence))
+    }
+}
+
+/// Returns the aligned blocks of an alignment as (reference start, read start, length) triples.
+///
+/// The reference start is 1-based, and the read start is 0-based.
+fn aligned_blocks<R>(record: &R, alignment_start: usize) -> io::Result<Vec<(usize, usize, usize)>>
+where
+    R: Record + ?Sized,
+{
+    let mut blocks = Vec::new();
+    let mut reference_position = alignment_start;
+    let mut read_position = 0;
+
+    for result in record.cigar().iter() {
+        let op = result?;
+        let len = op.len();
+
+        if matches!(
+            op.kind(),
+            Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch
+        ) {
+            blocks.push((reference_position, read_position, len));
+        }
+
+        if op.kind().consumes_reference() {
+            reference_position += len;
+        }
+
+        if op.kind().consumes_read() {
+            read_position += len;
+        }
+    }
+
+    Ok(blocks)
+}
+
+fn edit_distance<R>(record: &R) -> io::Result<u64>
+where
+    R: Record + ?Sized,
+{
+    let data = record.data();
+
+    let n = match data.get(&Tag::EDIT_DISTANCE).transpose()? {
+        Some(value) => value
+            .as_int()
+            .and_then(|n| u64::try_from(n).ok())
+            .unwrap_or_default(),
+        None => 0,
+    };
+
+    Ok(n)
+}
+
+fn base_class(base: u8) -> usize {
+    match base.to_ascii_uppercase() {
+        b'A' => 0,
+        b'C' => 1,
+        b'G' => 2,
+        b'T' => 3,
+        b'N' => 4,
+        _ => 5,
+    }
+}
+
+fn complement(base: u8) -> u8 {
+    match base.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'T' => b'A',
+        b => b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use noodles_core::Position;
+    use noodles_sam::{
+        alignment::{
+            record::{cigar::Op, MappingQuality},
+            record_buf::{Cigar, QualityScores, Sequence},
+            RecordBuf,
+        },
+        header::record::value::{map::ReferenceSequence, Map},
+    };
+
+    use super::*;
+
+    const SQ0_LENGTH: NonZeroUsize = match NonZeroUsize::new(16) {
+        Some(length) => length,
+        None => unreachable!(),
+    };
+
+    #[test]
+    fn test_add() -> Result<(), Box<dyn std::error::Error>> {
+        let header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(SQ0_LENGTH))
+            .build();
+
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq0", None),
+            fasta::record::Sequence::from(b"ACGTACGTACGTACGT".to_vec()),
+        )]);
+
+        let mut collector = Collector::new().with_reference_sequence_repository(repository);
+
+        let build_record = |start, mate_start, flags, template_length| {
+            RecordBuf::builder()
+                .set_flags(flags)
+                .set_reference_sequence_id(0)
+                .set_alignment_start(Position::try_from(start).unwrap())
+                .set_mapping_quality(MappingQuality::new(30).unwrap())
+                .set_cigar(Cigar::from(vec![
+                    Op::new(Kind::SoftClip, 1),
+                    Op::new(Kind::Match, 3),
+                ]))
+                .set_mate_reference_sequence_id(0)
+                .set_mate_alignment_start(Position::try_from(mate_start).unwrap())
+                .set_template_length(template_length)
+                .set_sequence(Sequence::from(b"NACG".to_vec()))
+                .build()
+        };
+
+        let record = build_record(
+            1,
+            5,
+            Flags::SEGMENTED | Flags::FIRST_SEGMENT | Flags::MATE_REVERSE_COMPLEMENTED,
+            7,
+        );
+        collector.add(&header, &record)?;
+
+        let record = build_record(
+            5,
+            1,
+            Flags::SEGMENTED | Flags::LAST_SEGMENT | Flags::REVERSE_COMPLEMENTED,
+            -7,
+        );
+        collector.add(&header, &record)?;
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::UNMAPPED | Flags::SECONDARY)
+            .build();
+        collector.add(&header, &record)?;
+
+        let stats = collector.finish();
+
+        assert_eq!(stats.flag_stats().qc_pass().read, 3);
+        assert_eq!(stats.reference_sequence_counts()[0].mapped, 2);
+        assert_eq!(stats.unplaced_unmapped_count(), 1);
+
+        let summary = stats.summary();
+        assert_eq!(summary.sequences, 2);
+        assert_eq!(summary.first_fragments, 1);
+        assert_eq!(summary.last_fragments, 1);
+        assert_eq!(summary.reads_mapped_and_paired, 2);
+        assert_eq!(summary.non_primary_alignments, 1);
+        assert_eq!(summary.bases_mapped_cigar, 6);
+        // sq0:1-3 = ACG vs ACG; sq0:5-7 = ACG vs ACG
+        assert_eq!(summary.mismatches, 0);
+
+        assert!(stats.is_sorted());
+        assert_eq!(stats.mapping_qualities()[30], 2);
+        assert_eq!(
+            stats.insert_sizes().get(&7),
+            Some(&InsertSizeCounts {
+                inward: 1,
+                ..Default::default()
+            })
+        );
+        assert_eq!(stats.coverage(), &[(1, 6)].into_iter().collect());
+
+        // The reverse complemented record is reverted: NACG -> CGTN.
+        assert_eq!(stats.base_composition()[0], [0, 1, 0, 0, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_mismatched_quality_scores_length() -> io::Result<()> {
+        let header = sam::Header::default();
+        let mut collector = Collector::new();
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::UNMAPPED | Flags::REVERSE_COMPLEMENTED)
+            .set_sequence(Sequence::from(b"ACGT".to_vec()))
+            .set_quality_scores(QualityScores::from(vec![30, 40]))
+            .build();
+
+        collector.add(&header, &record)?;
+
+        let stats = collector.finish();
+        assert_eq!(stats.base_composition().len(), 4);
+        assert_eq!(stats.first_fragment_qualities().len(), 2);
+        assert_eq!(stats.first_fragment_qualities()[0][40], 1);
+
+        Ok(())
+    }
+}
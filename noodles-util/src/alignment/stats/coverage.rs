@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, VecDeque};
+
+/// A coverage tracker over coordinate-sorted alignments.
+///
+/// Depths are accumulated in a window that starts at the start of the last added alignment.
+/// Positions before the window are final and are moved into the depth histogram.
+#[derive(Debug, Default)]
+pub(super) struct Coverage {
+    reference_sequence_id: Option<usize>,
+    window_start: usize,
+    window: VecDeque<u64>,
+    is_sorted: bool,
+    has_records: bool,
+}
+
+impl Coverage {
+    pub fn is_sorted(&self) -> bool {
+        !self.has_records || self.is_sorted
+    }
+
+    /// Adds the aligned blocks of an alignment.
+    ///
+    /// Blocks are (start, length) pairs, where start is 1-based. `start` is the alignment start.
+    /// If the alignment is out of order, coverage tracking is stopped.
+    pub fn add(
+        &mut self,
+        histogram: &mut BTreeMap<u64, u64>,
+        reference_sequence_id: usize,
+        start: usize,
+        blocks: &[(usize, usize)],
+    ) {
+        if !self.has_records {
+            self.has_records = true;
+            self.is_sorted = true;
+        } else if !self.is_sorted {
+            return;
+        }
+
+        if self.reference_sequence_id != Some(reference_sequence_id) {
+            if self
+                .reference_sequence_id
+                .map(|id| reference_sequence_id < id)
+                .unwrap_or(false)
+            {
+                self.stop();
+                return;
+            }
+
+            self.flush(histogram, usize::MAX);
+            self.reference_sequence_id = Some(reference_sequence_id);
+            self.window_start = start;
+        } else if start < self.window_start {
+            self.stop();
+            return;
+        }
+
+        self.flush(histogram, start);
+
+        for &(block_start, len) in blocks {
+            let offset = block_start - self.window_start;
+            let end = offset + len;
+
+            if end > self.window.len() {
+                self.window.resize(end, 0);
+            }
+
+            for depth in self.window.range_mut(offset..end) {
+                *depth += 1;
+            }
+        }
+    }
+
+    /// Moves depths of positions before the given position into the histogram.
+    pub fn flush(&mut self, histogram: &mut BTreeMap<u64, u64>, position: usize) {
+        while self.window_start < position {
+            let Some(depth) = self.window.pop_front() else {
+                self.window_start = position;
+                break;
+            };
+
+            if depth > 0 {
+                *histogram.entry(depth).or_default() += 1;
+            }
+
+            self.window_start += 1;
+        }
+    }
+
+    fn stop(&mut self) {
+        self.is_sorted = false;
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut histogram = BTreeMap::new();
+        let mut coverage = Coverage::default();
+
+        coverage.add(&mut histogram, 0, 1, &[(1, 4)]);
+        coverage.add(&mut histogram, 0, 3, &[(3, 2), (8, 1)]);
+        coverage.add(&mut histogram, 1, 1, &[(1, 2)]);
+        coverage.flush(&mut histogram, usize::MAX);
+
+        // sq0: 1 1 2 2 0 0 0 1; sq1: 1 1
+        assert_eq!(histogram, [(1, 5), (2, 2)].into_iter().collect());
+        assert!(coverage.is_sorted());
+
+        coverage.add(&mut histogram, 0, 1, &[(1, 1)]);
+        assert!(!coverage.is_sorted());
+    }
+}
@@ -0,0 +1,323 @@
+use std::{fmt, io};
+
+use noodles_sam::{
+    self as sam,
+    alignment::{record::MappingQuality, Record},
+};
+
+const MIN_HQ_MAPPING_QUALITY: MappingQuality = match MappingQuality::new(5) {
+    Some(mapping_quality) => mapping_quality,
+    None => unreachable!(),
+};
+
+/// Alignment record flag category counts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlagCounts {
+    /// The number of records.
+    pub read: u64,
+    /// The number of primary records.
+    pub primary: u64,
+    /// The number of secondary records.
+    pub secondary: u64,
+    /// The number of supplementary records.
+    pub supplementary: u64,
+    /// The number of duplicate records.
+    pub duplicate: u64,
+    /// The number of primary duplicate records.
+    pub primary_duplicate: u64,
+    /// The number of mapped records.
+    pub mapped: u64,
+    /// The number of primary mapped records.
+    pub primary_mapped: u64,
+    /// The number of primary paired records.
+    pub paired: u64,
+    /// The number of primary first segments.
+    pub read_1: u64,
+    /// The number of primary last segments.
+    pub read_2: u64,
+    /// The number of primary mapped, properly paired records.
+    pub proper_pair: u64,
+    /// The number of primary records where both the record and its mate are mapped.
+    pub mate_mapped: u64,
+    /// The number of primary mapped records with an unmapped mate.
+    pub singleton: u64,
+    /// The number of primary records with a mate mapped to a different reference sequence.
+    pub mate_reference_sequence_id_mismatch: u64,
+    /// The number of primary records with a mate mapped to a different reference sequence and a
+    /// mapping quality of at least 5.
+    pub mate_reference_sequence_id_mismatch_hq: u64,
+}
+
+impl FlagCounts {
+    fn add<R>(&mut self, header: &sam::Header, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let flags = record.flags()?;
+
+        self.read += 1;
+
+        if !flags.is_unmapped() {
+            self.mapped += 1;
+        }
+
+        if flags.is_duplicate() {
+            self.duplicate += 1;
+        }
+
+        if flags.is_secondary() {
+            self.secondary += 1;
+        } else if flags.is_supplementary() {
+            self.supplementary += 1;
+        } else {
+            self.primary += 1;
+
+            if !flags.is_unmapped() {
+                self.primary_mapped += 1;
+            }
+
+            if flags.is_duplicate() {
+                self.primary_duplicate += 1;
+            }
+
+            if flags.is_segmented() {
+                self.paired += 1;
+
+                if flags.is_first_segment() {
+                    self.read_1 += 1;
+                }
+
+                if flags.is_last_segment() {
+                    self.read_2 += 1;
+                }
+
+                if !flags.is_unmapped() {
+                    if flags.is_properly_segmented() {
+                        self.proper_pair += 1;
+                    }
+
+                    if flags.is_mate_unmapped() {
+                        self.singleton += 1;
+                    } else {
+                        self.mate_mapped += 1;
+
+                        let reference_sequence_id =
+                            record.reference_sequence_id(header).transpose()?;
+                        let mate_reference_sequence_id =
+                            record.mate_reference_sequence_id(header).transpose()?;
+
+                        if mate_reference_sequence_id != reference_sequence_id {
+                            self.mate_reference_sequence_id_mismatch += 1;
+
+                            if record
+                                .mapping_quality()
+                                .transpose()?
+                                .map(|mapq| mapq >= MIN_HQ_MAPPING_QUALITY)
+                                .unwrap_or(true)
+                            {
+                                self.mate_reference_sequence_id_mismatch_hq += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.read += other.read;
+        self.primary += other.primary;
+        self.secondary += other.secondary;
+        self.supplementary += other.supplementary;
+        self.duplicate += other.duplicate;
+        self.primary_duplicate += other.primary_duplicate;
+        self.mapped += other.mapped;
+        self.primary_mapped += other.primary_mapped;
+        self.paired += other.paired;
+        self.read_1 += other.read_1;
+        self.read_2 += other.read_2;
+        self.proper_pair += other.proper_pair;
+        self.mate_mapped += other.mate_mapped;
+        self.singleton += other.singleton;
+        self.mate_reference_sequence_id_mismatch += other.mate_reference_sequence_id_mismatch;
+        self.mate_reference_sequence_id_mismatch_hq += other.mate_reference_sequence_id_mismatch_hq;
+    }
+}
+
+/// Alignment record flag statistics.
+///
+/// The [`fmt::Display`] implementation matches the output of `samtools flagstat`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlagStats {
+    qc_pass: FlagCounts,
+    qc_fail: FlagCounts,
+}
+
+impl FlagStats {
+    /// Returns the counts of records that pass quality checks.
+    pub fn qc_pass(&self) -> &FlagCounts {
+        &self.qc_pass
+    }
+
+    /// Returns the counts of records that fail quality checks.
+    pub fn qc_fail(&self) -> &FlagCounts {
+        &self.qc_fail
+    }
+
+    pub(super) fn add<R>(&mut self, header: &sam::Header, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        if record.flags()?.is_qc_fail() {
+            self.qc_fail.add(header, record)
+        } else {
+            self.qc_pass.add(header, record)
+        }
+    }
+
+    pub(super) fn merge(&mut self, other: &Self) {
+        self.qc_pass.merge(&other.qc_pass);
+        self.qc_fail.merge(&other.qc_fail);
+    }
+}
+
+struct PercentageFormat(u64, u64);
+
+impl fmt::Display for PercentageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.1 == 0 {
+            f.write_str("N/A")
+        } else {
+            let (a, b) = (self.0 as f64, self.1 as f64);
+            write!(f, "{:.2}%", a / b * 100.0)
+        }
+    }
+}
+
+impl fmt::Display for FlagStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (pass, fail) = (&self.qc_pass, &self.qc_fail);
+
+        writeln!(
+            f,
+            "{} + {} in total (QC-passed reads + QC-failed reads)",
+            pass.read, fail.read
+        )?;
+        writeln!(f, "{} + {} primary", pass.primary, fail.primary)?;
+        writeln!(f, "{} + {} secondary", pass.secondary, fail.secondary)?;
+        writeln!(
+            f,
+            "{} + {} supplementary",
+            pass.supplementary, fail.supplementary
+        )?;
+        writeln!(f, "{} + {} duplicates", pass.duplicate, fail.duplicate)?;
+        writeln!(
+            f,
+            "{} + {} primary duplicates",
+            pass.primary_duplicate, fail.primary_duplicate
+        )?;
+        writeln!(
+            f,
+            "{} + {} mapped ({} : {})",
+            pass.mapped,
+            fail.mapped,
+            PercentageFormat(pass.mapped, pass.read),
+            PercentageFormat(fail.mapped, fail.read)
+        )?;
+        writeln!(
+            f,
+            "{} + {} primary mapped ({} : {})",
+            pass.primary_mapped,
+            fail.primary_mapped,
+            PercentageFormat(pass.primary_mapped, pass.primary),
+            PercentageFormat(fail.primary_mapped, fail.primary)
+        )?;
+        writeln!(f, "{} + {} paired in sequencing", pass.paired, fail.paired)?;
+        writeln!(f, "{} + {} read1", pass.read_1, fail.read_1)?;
+        writeln!(f, "{} + {} read2", pass.read_2, fail.read_2)?;
+        writeln!(
+            f,
+            "{} + {} properly paired ({} : {})",
+            pass.proper_pair,
+            fail.proper_pair,
+            PercentageFormat(pass.proper_pair, pass.paired),
+            PercentageFormat(fail.proper_pair, fail.paired)
+        )?;
+        writeln!(
+            f,
+            "{} + {} with itself and mate mapped",
+            pass.mate_mapped, fail.mate_mapped
+        )?;
+        writeln!(
+            f,
+            "{} + {} singletons ({} : {})",
+            pass.singleton,
+            fail.singleton,
+            PercentageFormat(pass.singleton, pass.paired),
+            PercentageFormat(fail.singleton, fail.paired)
+        )?;
+        writeln!(
+            f,
+            "{} + {} with mate mapped to a different chr",
+            pass.mate_reference_sequence_id_mismatch, fail.mate_reference_sequence_id_mismatch
+        )?;
+        writeln!(
+            f,
+            "{} + {} with mate mapped to a different chr (mapQ>={})",
+            pass.mate_reference_sequence_id_mismatch_hq,
+            fail.mate_reference_sequence_id_mismatch_hq,
+            MIN_HQ_MAPPING_QUALITY.get()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::alignment::{record::Flags, RecordBuf};
+
+    use super::*;
+
+    #[test]
+    fn test_add() -> io::Result<()> {
+        let header = sam::Header::default();
+        let mut flag_stats = FlagStats::default();
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::SEGMENTED | Flags::FIRST_SEGMENT | Flags::MATE_UNMAPPED)
+            .build();
+        flag_stats.add(&header, &record)?;
+
+        let record = RecordBuf::builder()
+            .set_flags(Flags::SECONDARY | Flags::QC_FAIL)
+            .build();
+        flag_stats.add(&header, &record)?;
+
+        assert_eq!(
+            flag_stats.qc_pass(),
+            &FlagCounts {
+                read: 1,
+                primary: 1,
+                mapped: 1,
+                primary_mapped: 1,
+                paired: 1,
+                read_1: 1,
+                singleton: 1,
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            flag_stats.qc_fail(),
+            &FlagCounts {
+                read: 1,
+                secondary: 1,
+                mapped: 1,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+}
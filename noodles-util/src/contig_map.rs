@@ -0,0 +1,213 @@
+//! Contig name mapping.
+//!
+//! A [`ContigMap`] translates reference sequence names between naming conventions, e.g., UCSC
+//! (`chr1`) and Ensembl (`1`). It is used to rename the reference sequences of alignment headers
+//! and records (`alignment::rename`) and the contigs of variant headers and records
+//! (`variant::rename`).
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+};
+
+/// The handling of contig names without a mapping.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Unmapped {
+    /// The name is kept as is.
+    #[default]
+    Keep,
+    /// The contig and its records are dropped.
+    Drop,
+    /// An error is returned.
+    Error,
+}
+
+/// A contig name mapping.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContigMap {
+    names: HashMap<String, String>,
+    unmapped: Unmapped,
+}
+
+impl ContigMap {
+    /// Creates an empty contig name mapping.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::contig_map::ContigMap;
+    /// let contig_map = ContigMap::new();
+    /// assert!(contig_map.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a contig name mapping from a two-column table.
+    ///
+    /// Each line has a source name and a destination name separated by whitespace. Empty lines
+    /// and lines starting with `#` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::contig_map::ContigMap;
+    ///
+    /// let data = b"# UCSC to Ensembl\nchr1\t1\nchrM MT\n";
+    /// let contig_map = ContigMap::read(&data[..])?;
+    ///
+    /// assert_eq!(contig_map.len(), 2);
+    /// assert_eq!(contig_map.get("chrM"), Some("MT"));
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn read<R>(reader: R) -> io::Result<Self>
+    where
+        R: BufRead,
+    {
+        let mut contig_map = Self::new();
+
+        for (i, result) in reader.lines().enumerate() {
+            let line = result?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+
+            let (Some(src), Some(dst), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid contig mapping at line {}: {line}", i + 1),
+                ));
+            };
+
+            if contig_map.insert(src, dst).is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("duplicate contig mapping at line {}: {src}", i + 1),
+                ));
+            }
+        }
+
+        Ok(contig_map)
+    }
+
+    /// Sets the handling of contig names without a mapping.
+    ///
+    /// By default, these names are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::contig_map::{ContigMap, Unmapped};
+    /// let contig_map = ContigMap::new().with_unmapped(Unmapped::Drop);
+    /// assert_eq!(contig_map.unmapped(), Unmapped::Drop);
+    /// ```
+    pub fn with_unmapped(mut self, unmapped: Unmapped) -> Self {
+        self.unmapped = unmapped;
+        self
+    }
+
+    /// Returns the handling of contig names without a mapping.
+    pub fn unmapped(&self) -> Unmapped {
+        self.unmapped
+    }
+
+    /// Returns the number of mappings.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns whether there are any mappings.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Adds a mapping from a source name to a destination name.
+    ///
+    /// This returns the previous destination name of the source name, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::contig_map::ContigMap;
+    /// let mut contig_map = ContigMap::new();
+    /// assert!(contig_map.insert("chr1", "1").is_none());
+    /// ```
+    pub fn insert<S, D>(&mut self, src: S, dst: D) -> Option<String>
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.names.insert(src.into(), dst.into())
+    }
+
+    /// Returns the destination name of the given source name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.names.get(name).map(|s| s.as_str())
+    }
+
+    /// Returns the name to use for the given contig name.
+    ///
+    /// Without a mapping, this returns the name itself if unmapped names are kept, `None` if they
+    /// are dropped, and an error otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::contig_map::{ContigMap, Unmapped};
+    ///
+    /// let mut contig_map = ContigMap::new();
+    /// contig_map.insert("chr1", "1");
+    ///
+    /// assert_eq!(contig_map.resolve("chr1")?, Some("1"));
+    /// assert_eq!(contig_map.resolve("chr2")?, Some("chr2"));
+    ///
+    /// let contig_map = contig_map.with_unmapped(Unmapped::Drop);
+    /// assert_eq!(contig_map.resolve("chr2")?, None);
+    ///
+    /// let contig_map = contig_map.with_unmapped(Unmapped::Error);
+    /// assert!(contig_map.resolve("chr2").is_err());
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn resolve<'a>(&'a self, name: &'a str) -> io::Result<Option<&'a str>> {
+        if let Some(dst) = self.get(name) {
+            return Ok(Some(dst));
+        }
+
+        match self.unmapped {
+            Unmapped::Keep => Ok(Some(name)),
+            Unmapped::Drop => Ok(None),
+            Unmapped::Error => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unmapped contig: {name}"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        let data = b"chr1\t1\n\n# comment\n  chr2   2  \n";
+        let contig_map = ContigMap::read(&data[..])?;
+
+        assert_eq!(contig_map.len(), 2);
+        assert_eq!(contig_map.get("chr1"), Some("1"));
+        assert_eq!(contig_map.get("chr2"), Some("2"));
+        assert!(contig_map.get("chr3").is_none());
+
+        assert!(ContigMap::read(&b"chr1\n"[..]).is_err());
+        assert!(ContigMap::read(&b"chr1\t1\tx\n"[..]).is_err());
+        assert!(ContigMap::read(&b"chr1\t1\nchr1\t2\n"[..]).is_err());
+
+        Ok(())
+    }
+}
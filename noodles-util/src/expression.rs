@@ -0,0 +1,235 @@
+//! Record filter expression core.
+//!
+//! This is shared by the alignment and variant record filter expressions. It defines the
+//! tokenizer, the operator precedence parser, and the evaluation of operators on values. Each
+//! filter defines its own syntax tree and the resolution of record fields.
+
+pub(crate) mod parser;
+
+use std::{cmp::Ordering, fmt};
+
+pub use self::parser::ParseError;
+
+// The bitwise operators are only used by alignment record filter expressions.
+#[cfg_attr(not(feature = "alignment"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum UnaryOperator {
+    Not,
+    Negate,
+    BitwiseNot,
+}
+
+// The bitwise and remainder operators are only used by alignment record filter expressions.
+#[cfg_attr(not(feature = "alignment"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum BinaryOperator {
+    Or,
+    And,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Regex(regex::bytes::Regex);
+
+impl PartialEq for Regex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Integer(i64),
+    Float(f64),
+    String(Vec<u8>),
+}
+
+impl Value {
+    pub(crate) fn is_truthy(&self) -> bool {
+        match self {
+            Self::Null => false,
+            Self::Integer(n) => *n != 0,
+            Self::Float(n) => *n != 0.0,
+            Self::String(s) => !s.is_empty(),
+        }
+    }
+
+    pub(crate) fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Integer(n) => Some(*n as f64),
+            Self::Float(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Integer(i64::from(b))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n}"),
+            Self::String(s) => write!(f, "{}", String::from_utf8_lossy(s)),
+        }
+    }
+}
+
+pub(crate) fn length_value(n: usize) -> Value {
+    i64::try_from(n).map(Value::Integer).unwrap_or(Value::Null)
+}
+
+pub(crate) fn evaluate_unary(op: UnaryOperator, value: &Value) -> Value {
+    match op {
+        UnaryOperator::Not => Value::from(!value.is_truthy()),
+        UnaryOperator::Negate => match value {
+            Value::Integer(n) => Value::Integer(n.wrapping_neg()),
+            Value::Float(n) => Value::Float(-n),
+            _ => Value::Null,
+        },
+        UnaryOperator::BitwiseNot => match value {
+            Value::Integer(n) => Value::Integer(!n),
+            _ => Value::Null,
+        },
+    }
+}
+
+pub(crate) fn evaluate_binary(op: BinaryOperator, lhs: &Value, rhs: &Value) -> Value {
+    use BinaryOperator as Op;
+
+    match op {
+        Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+            let Some(ordering) = compare(lhs, rhs) else {
+                return Value::Null;
+            };
+
+            let result = match op {
+                Op::Eq => ordering.is_eq(),
+                Op::Ne => ordering.is_ne(),
+                Op::Lt => ordering.is_lt(),
+                Op::Le => ordering.is_le(),
+                Op::Gt => ordering.is_gt(),
+                Op::Ge => ordering.is_ge(),
+                _ => unreachable!(),
+            };
+
+            Value::from(result)
+        }
+        Op::BitwiseOr | Op::BitwiseXor | Op::BitwiseAnd => match (lhs, rhs) {
+            (Value::Integer(a), Value::Integer(b)) => Value::Integer(match op {
+                Op::BitwiseOr => a | b,
+                Op::BitwiseXor => a ^ b,
+                _ => a & b,
+            }),
+            _ => Value::Null,
+        },
+        Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Rem => match (lhs, rhs) {
+            (Value::Integer(a), Value::Integer(b)) if op != Op::Div => match op {
+                Op::Add => Value::Integer(a.wrapping_add(*b)),
+                Op::Sub => Value::Integer(a.wrapping_sub(*b)),
+                Op::Mul => Value::Integer(a.wrapping_mul(*b)),
+                _ => a.checked_rem(*b).map(Value::Integer).unwrap_or(Value::Null),
+            },
+            (lhs, rhs) => match (lhs.as_float(), rhs.as_float()) {
+                (Some(a), Some(b)) => Value::Float(match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    _ => a % b,
+                }),
+                _ => Value::Null,
+            },
+        },
+        Op::Or | Op::And => unreachable!("short-circuiting operators are evaluated separately"),
+    }
+}
+
+/// Matches the value, or its string representation if it is not a string, with a regular
+/// expression.
+pub(crate) fn evaluate_match(regex: &Regex, value: &Value, is_negated: bool) -> Value {
+    let is_match = match value {
+        Value::Null => return Value::Null,
+        Value::String(s) => regex.0.is_match(s),
+        value => regex.0.is_match(value.to_string().as_bytes()),
+    };
+
+    Value::from(is_match != is_negated)
+}
+
+pub(crate) fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => lhs.as_float()?.partial_cmp(&rhs.as_float()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_binary() {
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Div, &Value::Integer(7), &Value::Integer(2)),
+            Value::Float(3.5)
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Rem, &Value::Integer(7), &Value::Integer(0)),
+            Value::Null
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Lt, &Value::Integer(1), &Value::Float(1.5)),
+            Value::Integer(1)
+        );
+        assert_eq!(
+            evaluate_binary(BinaryOperator::Eq, &Value::Null, &Value::Null),
+            Value::Null
+        );
+        assert_eq!(
+            evaluate_binary(
+                BinaryOperator::Eq,
+                &Value::String(b"a".to_vec()),
+                &Value::Integer(0)
+            ),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_evaluate_match() -> Result<(), regex::Error> {
+        let regex = Regex(regex::bytes::Regex::new("^1")?);
+
+        assert_eq!(
+            evaluate_match(&regex, &Value::String(b"13".to_vec()), false),
+            Value::Integer(1)
+        );
+        assert_eq!(
+            evaluate_match(&regex, &Value::Integer(13), true),
+            Value::Integer(0)
+        );
+        assert_eq!(evaluate_match(&regex, &Value::Null, false), Value::Null);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,466 @@
+use std::{error, fmt, iter::Peekable, marker::PhantomData, str::CharIndices};
+
+use super::{BinaryOperator, Regex, UnaryOperator, Value};
+
+/// An error returned when a record filter expression fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The input ended unexpectedly.
+    UnexpectedEof,
+    /// An unexpected character was found at the given byte offset.
+    UnexpectedCharacter(usize, char),
+    /// An unexpected token was found at the given byte offset.
+    UnexpectedToken(usize, String),
+    /// A number literal is invalid.
+    InvalidNumber(String),
+    /// A string literal is not terminated.
+    UnterminatedString,
+    /// A data field tag or an INFO or FORMAT field key is invalid.
+    InvalidTag(String),
+    /// A subscript is invalid.
+    InvalidSubscript(String),
+    /// An identifier is not a known field or function.
+    UnknownIdentifier(String),
+    /// The right-hand side of a regular expression match is not a string literal.
+    ExpectedPattern,
+    /// A regular expression is invalid.
+    InvalidPattern(String),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnexpectedCharacter(i, c) => {
+                write!(f, "unexpected character at offset {i}: {c:?}")
+            }
+            Self::UnexpectedToken(i, s) => write!(f, "unexpected token at offset {i}: {s}"),
+            Self::InvalidNumber(s) => write!(f, "invalid number: {s}"),
+            Self::UnterminatedString => f.write_str("unterminated string"),
+            Self::InvalidTag(s) => write!(f, "invalid tag: {s}"),
+            Self::InvalidSubscript(s) => write!(f, "invalid subscript: {s}"),
+            Self::UnknownIdentifier(s) => write!(f, "unknown identifier: {s}"),
+            Self::ExpectedPattern => f.write_str("expected pattern"),
+            Self::InvalidPattern(s) => write!(f, "invalid pattern: {s}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Identifier(String),
+    /// `[...]`, including the brackets. This is not terminated if the input ends first.
+    Bracket(String),
+    LeftParen,
+    RightParen,
+    Or,
+    And,
+    Pipe,
+    Caret,
+    Ampersand,
+    Eq,
+    Assign,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Bang,
+    Tilde,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n}"),
+            Self::String(s) => write!(f, "{s:?}"),
+            Self::Identifier(s) | Self::Bracket(s) => f.write_str(s),
+            Self::LeftParen => f.write_str("("),
+            Self::RightParen => f.write_str(")"),
+            Self::Or => f.write_str("||"),
+            Self::And => f.write_str("&&"),
+            Self::Pipe => f.write_str("|"),
+            Self::Caret => f.write_str("^"),
+            Self::Ampersand => f.write_str("&"),
+            Self::Eq => f.write_str("=="),
+            Self::Assign => f.write_str("="),
+            Self::Ne => f.write_str("!="),
+            Self::Lt => f.write_str("<"),
+            Self::Le => f.write_str("<="),
+            Self::Gt => f.write_str(">"),
+            Self::Ge => f.write_str(">="),
+            Self::Match => f.write_str("=~"),
+            Self::NotMatch => f.write_str("!~"),
+            Self::Plus => f.write_str("+"),
+            Self::Minus => f.write_str("-"),
+            Self::Star => f.write_str("*"),
+            Self::Slash => f.write_str("/"),
+            Self::Percent => f.write_str("%"),
+            Self::Bang => f.write_str("!"),
+            Self::Tilde => f.write_str("~"),
+        }
+    }
+}
+
+/// The syntax of a filter expression language.
+///
+/// This defines which tokens are operators and builds the syntax tree of the language.
+pub(crate) trait Syntax: Sized {
+    type Node;
+
+    /// Binary operators, grouped by ascending precedence.
+    const BINARY_OPERATORS: &'static [&'static [(Token, BinaryOperator)]];
+
+    /// Regular expression match operators and whether they are negated.
+    const MATCH_OPERATORS: &'static [(Token, bool)];
+
+    /// The index into `BINARY_OPERATORS` of the regular expression match operators.
+    const MATCH_PRECEDENCE: usize;
+
+    const UNARY_OPERATORS: &'static [(Token, UnaryOperator)];
+
+    /// Identifiers that are prefixes of identifiers when followed by `/`.
+    const IDENTIFIER_PREFIXES: &'static [&'static str] = &[];
+
+    fn parse_integer(s: &str) -> Option<i64> {
+        s.parse().ok()
+    }
+
+    fn literal(value: Value) -> Self::Node;
+
+    fn unary(op: UnaryOperator, node: Self::Node) -> Self::Node;
+
+    fn binary(op: BinaryOperator, lhs: Self::Node, rhs: Self::Node) -> Self::Node;
+
+    fn regex_match(node: Self::Node, regex: Regex, is_negated: bool) -> Self::Node;
+
+    /// Parses a primary expression that is not a literal or parenthesized expression.
+    fn parse_term(
+        parser: &mut Parser<Self>,
+        offset: usize,
+        token: Token,
+    ) -> Result<Self::Node, ParseError>;
+}
+
+pub(crate) fn parse<S>(s: &str) -> Result<S::Node, ParseError>
+where
+    S: Syntax,
+{
+    let tokens = tokenize::<S>(s)?;
+
+    if tokens.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut parser = Parser::<S> {
+        tokens,
+        i: 0,
+        syntax: PhantomData,
+    };
+
+    let node = parser.parse_binary(0)?;
+
+    match parser.tokens.get(parser.i) {
+        Some((offset, token)) => Err(ParseError::UnexpectedToken(*offset, token.to_string())),
+        None => Ok(node),
+    }
+}
+
+fn tokenize<S>(s: &str) -> Result<Vec<(usize, Token)>, ParseError>
+where
+    S: Syntax,
+{
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '~' => Token::Tilde,
+            '|' => next_if_eq(&mut chars, '|', Token::Or, Token::Pipe),
+            '&' => next_if_eq(&mut chars, '&', Token::And, Token::Ampersand),
+            '<' => next_if_eq(&mut chars, '=', Token::Le, Token::Lt),
+            '>' => next_if_eq(&mut chars, '=', Token::Ge, Token::Gt),
+            '=' => match chars.peek() {
+                Some((_, '=')) => {
+                    chars.next();
+                    Token::Eq
+                }
+                Some((_, '~')) => {
+                    chars.next();
+                    Token::Match
+                }
+                _ => Token::Assign,
+            },
+            '!' => match chars.peek() {
+                Some((_, '=')) => {
+                    chars.next();
+                    Token::Ne
+                }
+                Some((_, '~')) => {
+                    chars.next();
+                    Token::NotMatch
+                }
+                _ => Token::Bang,
+            },
+            '"' => tokenize_string(&mut chars)?,
+            '[' => {
+                let end = take_while(&mut chars, |c| c != ']')
+                    .map(|i| i + 1)
+                    .unwrap_or(s.len());
+
+                chars.next();
+
+                Token::Bracket(s[i..end].into())
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                tokenize_number(s, i, &mut chars, S::parse_integer)?
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                tokenize_identifier(s, i, &mut chars, S::IDENTIFIER_PREFIXES)
+            }
+            _ => return Err(ParseError::UnexpectedCharacter(i, c)),
+        };
+
+        tokens.push((i, token));
+    }
+
+    Ok(tokens)
+}
+
+fn next_if_eq(chars: &mut Peekable<CharIndices<'_>>, c: char, t: Token, u: Token) -> Token {
+    if chars.next_if(|(_, d)| *d == c).is_some() {
+        t
+    } else {
+        u
+    }
+}
+
+// Advances while the predicate holds and returns the offset of the first unmatched character.
+fn take_while<F>(chars: &mut Peekable<CharIndices<'_>>, f: F) -> Option<usize>
+where
+    F: Fn(char) -> bool,
+{
+    while chars.next_if(|(_, c)| f(*c)).is_some() {}
+    chars.peek().map(|(i, _)| *i)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+fn tokenize_identifier(
+    s: &str,
+    start: usize,
+    chars: &mut Peekable<CharIndices<'_>>,
+    prefixes: &[&str],
+) -> Token {
+    let mut end = take_while(chars, is_identifier_char).unwrap_or(s.len());
+
+    // A prefix (e.g., `INFO/`) is part of the identifier. Otherwise, `/` is division.
+    if prefixes.contains(&&s[start..end]) && chars.next_if(|(_, c)| *c == '/').is_some() {
+        end = take_while(chars, is_identifier_char).unwrap_or(s.len());
+    }
+
+    Token::Identifier(s[start..end].into())
+}
+
+fn tokenize_string(chars: &mut Peekable<CharIndices<'_>>) -> Result<Token, ParseError> {
+    let mut buf = String::new();
+
+    loop {
+        match chars.next() {
+            Some((_, '"')) => return Ok(Token::String(buf)),
+            Some((_, '\\')) => match chars.next() {
+                Some((_, 'n')) => buf.push('\n'),
+                Some((_, 't')) => buf.push('\t'),
+                Some((_, '"')) => buf.push('"'),
+                Some((_, '\\')) => buf.push('\\'),
+                // Keep unknown escapes (e.g., `\d`) so that regular expressions can be written
+                // naturally.
+                Some((_, c)) => {
+                    buf.push('\\');
+                    buf.push(c);
+                }
+                None => return Err(ParseError::UnterminatedString),
+            },
+            Some((_, c)) => buf.push(c),
+            None => return Err(ParseError::UnterminatedString),
+        }
+    }
+}
+
+fn tokenize_number(
+    s: &str,
+    start: usize,
+    chars: &mut Peekable<CharIndices<'_>>,
+    parse_integer: fn(&str) -> Option<i64>,
+) -> Result<Token, ParseError> {
+    // A sign directly after an exponent marker is part of a decimal float literal, e.g., `1e-3`.
+    let is_hex = s[start..].starts_with("0x") || s[start..].starts_with("0X");
+    let mut prev = None;
+
+    while let Some((_, c)) = chars.next_if(|&(_, c)| {
+        c.is_ascii_alphanumeric()
+            || c == '.'
+            || (!is_hex && matches!(c, '+' | '-') && matches!(prev, Some('e' | 'E')))
+    }) {
+        prev = Some(c);
+    }
+
+    let end = chars.peek().map(|(i, _)| *i).unwrap_or(s.len());
+    let raw = &s[start..end];
+
+    if let Some(n) = parse_integer(raw) {
+        Ok(Token::Integer(n))
+    } else if raw.contains(['.', 'e', 'E']) {
+        raw.parse()
+            .map(Token::Float)
+            .map_err(|_| ParseError::InvalidNumber(raw.into()))
+    } else {
+        Err(ParseError::InvalidNumber(raw.into()))
+    }
+}
+
+pub(crate) struct Parser<S> {
+    tokens: Vec<(usize, Token)>,
+    i: usize,
+    syntax: PhantomData<S>,
+}
+
+impl<S> Parser<S>
+where
+    S: Syntax,
+{
+    pub(crate) fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.i).map(|(_, token)| token)
+    }
+
+    pub(crate) fn next(&mut self) -> Result<(usize, Token), ParseError> {
+        let entry = self.tokens.get(self.i).cloned();
+        self.i += 1;
+        entry.ok_or(ParseError::UnexpectedEof)
+    }
+
+    fn parse_binary(&mut self, precedence: usize) -> Result<S::Node, ParseError> {
+        if precedence == S::BINARY_OPERATORS.len() {
+            return self.parse_unary();
+        }
+
+        let mut lhs = self.parse_binary(precedence + 1)?;
+
+        if precedence == S::MATCH_PRECEDENCE {
+            while let Some(is_negated) = self.peek().and_then(|token| {
+                S::MATCH_OPERATORS
+                    .iter()
+                    .find(|(t, _)| t == token)
+                    .map(|(_, is_negated)| *is_negated)
+            }) {
+                self.i += 1;
+
+                let regex = match self.next()? {
+                    (_, Token::String(pattern)) => regex::bytes::Regex::new(&pattern)
+                        .map(Regex)
+                        .map_err(|e| ParseError::InvalidPattern(e.to_string()))?,
+                    _ => return Err(ParseError::ExpectedPattern),
+                };
+
+                lhs = S::regex_match(lhs, regex, is_negated);
+            }
+
+            return Ok(lhs);
+        }
+
+        while let Some(op) = self.peek().and_then(|token| {
+            S::BINARY_OPERATORS[precedence]
+                .iter()
+                .find(|(t, _)| t == token)
+                .map(|(_, op)| *op)
+        }) {
+            self.i += 1;
+            let rhs = self.parse_binary(precedence + 1)?;
+            lhs = S::binary(op, lhs, rhs);
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<S::Node, ParseError> {
+        let Some(op) = self.peek().and_then(|token| {
+            S::UNARY_OPERATORS
+                .iter()
+                .find(|(t, _)| t == token)
+                .map(|(_, op)| *op)
+        }) else {
+            return self.parse_primary();
+        };
+
+        self.i += 1;
+
+        let node = self.parse_unary()?;
+        Ok(S::unary(op, node))
+    }
+
+    pub(crate) fn parse_primary(&mut self) -> Result<S::Node, ParseError> {
+        match self.next()? {
+            (_, Token::Integer(n)) => Ok(S::literal(Value::Integer(n))),
+            (_, Token::Float(n)) => Ok(S::literal(Value::Float(n))),
+            (_, Token::String(s)) => Ok(S::literal(Value::String(s.into_bytes()))),
+            (_, Token::LeftParen) => {
+                let node = self.parse_binary(0)?;
+
+                match self.next()? {
+                    (_, Token::RightParen) => Ok(node),
+                    (i, token) => Err(ParseError::UnexpectedToken(i, token.to_string())),
+                }
+            }
+            (i, token) => S::parse_term(self, i, token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_number() {
+        fn t(s: &str) -> Result<Token, ParseError> {
+            let mut chars = s.char_indices().peekable();
+            chars.next();
+            tokenize_number(s, 0, &mut chars, |s| s.parse().ok())
+        }
+
+        assert_eq!(t("0"), Ok(Token::Integer(0)));
+        assert_eq!(t("13"), Ok(Token::Integer(13)));
+        assert_eq!(t("0.5"), Ok(Token::Float(0.5)));
+        assert_eq!(t("1e3"), Ok(Token::Float(1000.0)));
+        assert_eq!(t("1e-3"), Ok(Token::Float(0.001)));
+        assert_eq!(t("2.5E+2"), Ok(Token::Float(250.0)));
+        assert_eq!(t("1x"), Err(ParseError::InvalidNumber(String::from("1x"))));
+    }
+}
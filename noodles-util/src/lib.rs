@@ -6,5 +6,11 @@
 #[cfg(feature = "alignment")]
 pub mod alignment;
 
+#[cfg(any(feature = "alignment", feature = "variant"))]
+pub mod contig_map;
+
+#[cfg(any(feature = "alignment", feature = "variant"))]
+mod expression;
+
 #[cfg(feature = "variant")]
 pub mod variant;
@@ -3,4 +3,27 @@
 #[cfg(feature = "async")]
 pub mod r#async;
 
+pub mod accessor;
+
+#[cfg(feature = "alignment")]
+pub mod allele_counts;
+pub mod annotation;
+pub mod concat;
+pub mod consensus;
+pub mod fill_tags;
+pub mod filter;
+pub mod gvcf;
 pub mod io;
+pub mod local_alleles;
+pub mod merge;
+pub mod multiallelic;
+pub mod normalize;
+pub mod pedigree;
+pub mod query_format;
+pub mod rename;
+pub mod samples;
+pub mod sort;
+pub mod stats;
+pub mod validate;
+
+pub use self::filter::Filter;
@@ -0,0 +1,712 @@
+//! Typed INFO and FORMAT field accessors.
+//!
+//! An [`InfoField`] or [`FormatField`] is resolved against a header once, including its BCF
+//! dictionary index, and then reads typed values directly from the raw INFO and FORMAT fields of
+//! lazy VCF and BCF records. Unlike [`vcf::variant::record::Info::get`], this does not look up the
+//! header definition, parse other fields, or allocate on each call.
+//!
+//! The sources are the INFO field (`record.info()`) or the samples (`record.samples()`) of a
+//! [`vcf::Record`] or [`noodles_bcf::Record`]. VCF strings are returned as is, i.e.,
+//! percent-encoded characters are not decoded.
+
+use std::{io, str};
+
+use noodles_bcf as bcf;
+use noodles_vcf::{
+    self as vcf,
+    header::record::value::map::{format, info},
+    variant::record::samples::keys::key,
+};
+
+const VCF_INFO_DELIMITER: char = ';';
+const VCF_SAMPLES_DELIMITER: char = '\t';
+const VCF_SAMPLE_DELIMITER: char = ':';
+const VCF_VALUE_DELIMITER: char = ',';
+const MISSING: &str = ".";
+
+/// Raw record INFO fields.
+///
+/// This is created from the INFO field of a [`vcf::Record`] or [`noodles_bcf::Record`].
+#[derive(Clone, Copy, Debug)]
+pub struct RawInfo<'a>(RawInfoInner<'a>);
+
+#[derive(Clone, Copy, Debug)]
+enum RawInfoInner<'a> {
+    Vcf(&'a str),
+    Bcf(&'a bcf::record::Info<'a>),
+}
+
+impl<'a, 'r> From<&'a vcf::record::Info<'r>> for RawInfo<'a> {
+    fn from(info: &'a vcf::record::Info<'r>) -> Self {
+        Self(RawInfoInner::Vcf(info.as_ref()))
+    }
+}
+
+impl<'a, 'r: 'a> From<&'a bcf::record::Info<'r>> for RawInfo<'a> {
+    fn from(info: &'a bcf::record::Info<'r>) -> Self {
+        Self(RawInfoInner::Bcf(info))
+    }
+}
+
+/// Raw record samples.
+///
+/// This is created from the samples of a [`vcf::Record`] or [`noodles_bcf::Record`].
+#[derive(Clone, Copy, Debug)]
+pub struct RawSamples<'a>(RawSamplesInner<'a>);
+
+#[derive(Clone, Copy, Debug)]
+enum RawSamplesInner<'a> {
+    Vcf(&'a str),
+    Bcf(&'a bcf::record::Samples<'a>),
+}
+
+impl<'a, 'r> From<&'a vcf::record::Samples<'r>> for RawSamples<'a> {
+    fn from(samples: &'a vcf::record::Samples<'r>) -> Self {
+        Self(RawSamplesInner::Vcf(samples.as_ref()))
+    }
+}
+
+impl<'a, 'r: 'a> From<&'a bcf::record::Samples<'r>> for RawSamples<'a> {
+    fn from(samples: &'a bcf::record::Samples<'r>) -> Self {
+        Self(RawSamplesInner::Bcf(samples))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum RawValue<'a> {
+    Vcf(&'a str),
+    // A BCF INFO flag does not have a value.
+    Bcf(Option<bcf::record::RawValue<'a>>),
+}
+
+/// An INFO field accessor.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InfoField {
+    key: String,
+    id: Option<usize>,
+    ty: info::Type,
+}
+
+impl InfoField {
+    /// Resolves an INFO field accessor against a header.
+    ///
+    /// This returns an error if the key is not defined in the header. To read BCF records, the
+    /// header must have string maps, e.g., a header read by a BCF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::accessor::InfoField;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Info, Map},
+    ///     variant::record::info::field::key,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_info(key::TOTAL_DEPTH, Map::<Info>::from(key::TOTAL_DEPTH))
+    ///     .build();
+    ///
+    /// let depth = InfoField::new(&header, key::TOTAL_DEPTH)?;
+    /// assert_eq!(depth.key(), key::TOTAL_DEPTH);
+    ///
+    /// assert!(InfoField::new(&header, "NDP").is_err());
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn new(header: &vcf::Header, key: &str) -> io::Result<Self> {
+        let ty = header
+            .infos()
+            .get(key)
+            .map(|info| info.ty())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("missing INFO header definition: {key}"),
+                )
+            })?;
+
+        Ok(Self {
+            key: key.into(),
+            id: header.string_maps().strings().get_index_of(key),
+            ty,
+        })
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns whether the field is present, e.g., whether a flag is set.
+    pub fn is_set<'a, I>(&self, info: I) -> io::Result<bool>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        self.find(info.into()).map(|value| value.is_some())
+    }
+
+    /// Returns the first value of an integer field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::accessor::InfoField;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Info, Map},
+    ///     variant::record::info::field::key,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_info(key::TOTAL_DEPTH, Map::<Info>::from(key::TOTAL_DEPTH))
+    ///     .build();
+    ///
+    /// let depth = InfoField::new(&header, key::TOTAL_DEPTH)?;
+    ///
+    /// let record = vcf::Record::try_from(&b"sq0\t1\t.\tA\t.\t.\tPASS\tDP=13"[..])?;
+    /// let info = record.info();
+    /// assert_eq!(depth.integer(&info)?, Some(13));
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn integer<'a, I>(&self, info: I) -> io::Result<Option<i32>>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        first(self.integers(info)?)
+    }
+
+    /// Returns the values of an integer field.
+    pub fn integers<'a, I>(&self, info: I) -> io::Result<Option<Integers<'a>>>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        expect_type(self.ty == info::Type::Integer, &self.key)?;
+        self.find(info.into())?.map(Integers::new).transpose()
+    }
+
+    /// Returns the first value of a float field.
+    pub fn float<'a, I>(&self, info: I) -> io::Result<Option<f32>>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        first(self.floats(info)?)
+    }
+
+    /// Returns the values of a float field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::accessor::InfoField;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Info, Map},
+    ///     variant::record::info::field::key,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_info(key::ALLELE_FREQUENCIES, Map::<Info>::from(key::ALLELE_FREQUENCIES))
+    ///     .build();
+    ///
+    /// let allele_frequencies = InfoField::new(&header, key::ALLELE_FREQUENCIES)?;
+    ///
+    /// let record = vcf::Record::try_from(&b"sq0\t1\t.\tA\tC,G\t.\tPASS\tAF=0.5,."[..])?;
+    /// let info = record.info();
+    ///
+    /// let values: Vec<_> = allele_frequencies
+    ///     .floats(&info)?
+    ///     .into_iter()
+    ///     .flatten()
+    ///     .collect::<io::Result<_>>()?;
+    ///
+    /// assert_eq!(values, [Some(0.5), None]);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn floats<'a, I>(&self, info: I) -> io::Result<Option<Floats<'a>>>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        expect_type(self.ty == info::Type::Float, &self.key)?;
+        self.find(info.into())?.map(Floats::new).transpose()
+    }
+
+    /// Returns the value of a string or character field.
+    pub fn string<'a, I>(&self, info: I) -> io::Result<Option<&'a str>>
+    where
+        I: Into<RawInfo<'a>>,
+    {
+        expect_type(
+            matches!(self.ty, info::Type::String | info::Type::Character),
+            &self.key,
+        )?;
+
+        self.find(info.into())?
+            .map(string)
+            .transpose()
+            .map(Option::flatten)
+    }
+
+    fn find<'a>(&self, info: RawInfo<'a>) -> io::Result<Option<RawValue<'a>>> {
+        match info.0 {
+            RawInfoInner::Vcf(src) => {
+                if src == MISSING {
+                    return Ok(None);
+                }
+
+                let value =
+                    src.split(VCF_INFO_DELIMITER)
+                        .find_map(|field| match field.split_once('=') {
+                            Some((k, v)) => (k == self.key).then_some(v),
+                            None => (field == self.key).then_some(MISSING),
+                        });
+
+                Ok(value.map(RawValue::Vcf))
+            }
+            RawInfoInner::Bcf(info) => {
+                let id = self.id.ok_or_else(|| missing_string_map_entry(&self.key))?;
+                info.get_raw(id)
+                    .transpose()
+                    .map(|value| value.map(RawValue::Bcf))
+            }
+        }
+    }
+}
+
+/// A FORMAT field accessor.
+///
+/// Genotypes (`GT`) are encoded as integers in BCF and cannot be read with a FORMAT field
+/// accessor.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FormatField {
+    key: String,
+    id: Option<usize>,
+    ty: format::Type,
+}
+
+impl FormatField {
+    /// Resolves a FORMAT field accessor against a header.
+    ///
+    /// This returns an error if the key is not defined in the header or is `GT`. To read BCF
+    /// records, the header must have string maps, e.g., a header read by a BCF reader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::accessor::FormatField;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Format, Map},
+    ///     variant::record::samples::keys::key,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_format(key::READ_DEPTH, Map::<Format>::from(key::READ_DEPTH))
+    ///     .build();
+    ///
+    /// let read_depth = FormatField::new(&header, key::READ_DEPTH)?;
+    /// assert_eq!(read_depth.key(), key::READ_DEPTH);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn new(header: &vcf::Header, key: &str) -> io::Result<Self> {
+        if key == key::GENOTYPE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "genotypes cannot be read with a FORMAT field accessor",
+            ));
+        }
+
+        let ty = header
+            .formats()
+            .get(key)
+            .map(|format| format.ty())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("missing FORMAT header definition: {key}"),
+                )
+            })?;
+
+        Ok(Self {
+            key: key.into(),
+            id: header.string_maps().strings().get_index_of(key),
+            ty,
+        })
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the first value of an integer field of the sample at the given index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::accessor::FormatField;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Format, Map},
+    ///     variant::record::samples::keys::key,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_format(key::READ_DEPTH, Map::<Format>::from(key::READ_DEPTH))
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .build();
+    ///
+    /// let read_depth = FormatField::new(&header, key::READ_DEPTH)?;
+    ///
+    /// let record = vcf::Record::try_from(&b"sq0\t1\t.\tA\t.\t.\tPASS\t.\tGT:DP\t0|0:8\t0/1"[..])?;
+    /// let samples = record.samples();
+    ///
+    /// assert_eq!(read_depth.integer(&samples, 0)?, Some(8));
+    /// assert_eq!(read_depth.integer(&samples, 1)?, None);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn integer<'a, S>(&self, samples: S, sample_index: usize) -> io::Result<Option<i32>>
+    where
+        S: Into<RawSamples<'a>>,
+    {
+        first(self.integers(samples, sample_index)?)
+    }
+
+    /// Returns the values of an integer field of the sample at the given index.
+    pub fn integers<'a, S>(
+        &self,
+        samples: S,
+        sample_index: usize,
+    ) -> io::Result<Option<Integers<'a>>>
+    where
+        S: Into<RawSamples<'a>>,
+    {
+        expect_type(self.ty == format::Type::Integer, &self.key)?;
+
+        self.find(samples.into(), sample_index)?
+            .map(Integers::new)
+            .transpose()
+    }
+
+    /// Returns the first value of a float field of the sample at the given index.
+    pub fn float<'a, S>(&self, samples: S, sample_index: usize) -> io::Result<Option<f32>>
+    where
+        S: Into<RawSamples<'a>>,
+    {
+        first(self.floats(samples, sample_index)?)
+    }
+
+    /// Returns the values of a float field of the sample at the given index.
+    pub fn floats<'a, S>(&self, samples: S, sample_index: usize) -> io::Result<Option<Floats<'a>>>
+    where
+        S: Into<RawSamples<'a>>,
+    {
+        expect_type(self.ty == format::Type::Float, &self.key)?;
+
+        self.find(samples.into(), sample_index)?
+            .map(Floats::new)
+            .transpose()
+    }
+
+    /// Returns the value of a string or character field of the sample at the given index.
+    pub fn string<'a, S>(&self, samples: S, sample_index: usize) -> io::Result<Option<&'a str>>
+    where
+        S: Into<RawSamples<'a>>,
+    {
+        expect_type(
+            matches!(self.ty, format::Type::String | format::Type::Character),
+            &self.key,
+        )?;
+
+        self.find(samples.into(), sample_index)?
+            .map(string)
+            .transpose()
+            .map(Option::flatten)
+    }
+
+    fn find<'a>(
+        &self,
+        samples: RawSamples<'a>,
+        sample_index: usize,
+    ) -> io::Result<Option<RawValue<'a>>> {
+        match samples.0 {
+            RawSamplesInner::Vcf(src) => {
+                let Some((keys, src)) = src.split_once(VCF_SAMPLES_DELIMITER) else {
+                    return Ok(None);
+                };
+
+                let Some(i) = keys
+                    .split(VCF_SAMPLE_DELIMITER)
+                    .position(|key| key == self.key)
+                else {
+                    return Ok(None);
+                };
+
+                let value = src
+                    .split(VCF_SAMPLES_DELIMITER)
+                    .nth(sample_index)
+                    .map(|sample| sample.split(VCF_SAMPLE_DELIMITER).nth(i).unwrap_or(MISSING));
+
+                Ok(value.map(RawValue::Vcf))
+            }
+            RawSamplesInner::Bcf(samples) => {
+                let id = self.id.ok_or_else(|| missing_string_map_entry(&self.key))?;
+
+                samples
+                    .get_raw(id, sample_index)
+                    .transpose()
+                    .map(|value| value.map(|v| RawValue::Bcf(Some(v))))
+            }
+        }
+    }
+}
+
+/// An iterator over the values of an integer field.
+///
+/// Missing values are `None`.
+pub struct Integers<'a>(Values<'a, bcf::record::raw_value::Integers<'a>>);
+
+impl<'a> Integers<'a> {
+    fn new(value: RawValue<'a>) -> io::Result<Self> {
+        Values::new(value, |value| value.integers()).map(Self)
+    }
+}
+
+impl Iterator for Integers<'_> {
+    type Item = io::Result<Option<i32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next(parse_integer)
+    }
+}
+
+/// An iterator over the values of a float field.
+///
+/// Missing values are `None`.
+pub struct Floats<'a>(Values<'a, bcf::record::raw_value::Floats<'a>>);
+
+impl<'a> Floats<'a> {
+    fn new(value: RawValue<'a>) -> io::Result<Self> {
+        Values::new(value, |value| value.floats()).map(Self)
+    }
+}
+
+impl Iterator for Floats<'_> {
+    type Item = io::Result<Option<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next(parse_float)
+    }
+}
+
+enum Values<'a, I> {
+    Vcf(str::Split<'a, char>),
+    Bcf(I),
+    Done,
+}
+
+impl<'a, I> Values<'a, I> {
+    fn new<F>(value: RawValue<'a>, f: F) -> io::Result<Self>
+    where
+        F: FnOnce(&bcf::record::RawValue<'a>) -> Option<I>,
+    {
+        match value {
+            RawValue::Vcf(src) => Ok(Self::Vcf(src.split(VCF_VALUE_DELIMITER))),
+            RawValue::Bcf(None) => Ok(Self::Done),
+            RawValue::Bcf(Some(value)) => f(&value).map(Self::Bcf).ok_or_else(bcf_type_mismatch),
+        }
+    }
+
+    fn next<T, P>(&mut self, parse: P) -> Option<io::Result<Option<T>>>
+    where
+        I: Iterator<Item = io::Result<Option<T>>>,
+        P: Fn(&str) -> io::Result<T>,
+    {
+        match self {
+            Self::Vcf(values) => {
+                let result = match values.next()? {
+                    MISSING => Ok(None),
+                    s => parse(s).map(Some),
+                };
+
+                if result.is_err() {
+                    *self = Self::Done;
+                }
+
+                Some(result)
+            }
+            Self::Bcf(values) => values.next(),
+            Self::Done => None,
+        }
+    }
+}
+
+fn parse_integer(s: &str) -> io::Result<i32> {
+    s.parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn parse_float(s: &str) -> io::Result<f32> {
+    s.parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn string(value: RawValue<'_>) -> io::Result<Option<&str>> {
+    let s = match value {
+        RawValue::Vcf(s) => s,
+        RawValue::Bcf(None) => return Ok(None),
+        RawValue::Bcf(Some(value)) => value.as_str().ok_or_else(bcf_type_mismatch)??,
+    };
+
+    match s {
+        "" | MISSING => Ok(None),
+        _ => Ok(Some(s)),
+    }
+}
+
+fn first<I, T>(values: Option<I>) -> io::Result<Option<T>>
+where
+    I: Iterator<Item = io::Result<Option<T>>>,
+{
+    values
+        .and_then(|mut values| values.next())
+        .transpose()
+        .map(Option::flatten)
+}
+
+fn expect_type(is_valid: bool, key: &str) -> io::Result<()> {
+    if is_valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("type mismatch: {key}"),
+        ))
+    }
+}
+
+fn bcf_type_mismatch() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "unexpected BCF value type")
+}
+
+fn missing_string_map_entry(key: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("missing string map entry: {key}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::variant::io::Write as _;
+
+    use super::*;
+
+    const DATA: &str = "##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##INFO=<ID=AF,Number=A,Type=Float,Description=\"Allele frequency\">
+##INFO=<ID=DB,Number=0,Type=Flag,Description=\"dbSNP membership\">
+##INFO=<ID=AA,Number=1,Type=String,Description=\"Ancestral allele\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"Read depth for each allele\">
+##FORMAT=<ID=FT,Number=1,Type=String,Description=\"Filter\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\tsample1
+sq0\t1\t.\tA\tC,G\t.\t.\tDP=13;AF=0.5,.;AA=C\tGT:AD:FT\t0/1:5,8:PASS\t1/2:0,.,21:.
+";
+
+    fn check<'a, I, S>(
+        header: &vcf::Header,
+        info: I,
+        samples: S,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        I: Into<RawInfo<'a>>,
+        S: Into<RawSamples<'a>>,
+    {
+        let info = info.into();
+        let samples = samples.into();
+
+        let depth = InfoField::new(header, "DP")?;
+        assert_eq!(depth.integer(info)?, Some(13));
+        assert!(depth.float(info).is_err());
+
+        let allele_frequencies = InfoField::new(header, "AF")?;
+        let values: Vec<_> = allele_frequencies
+            .floats(info)?
+            .unwrap()
+            .collect::<io::Result<_>>()?;
+        assert_eq!(values, [Some(0.5), None]);
+
+        let db = InfoField::new(header, "DB")?;
+        assert!(!db.is_set(info)?);
+
+        let ancestral_allele = InfoField::new(header, "AA")?;
+        assert_eq!(ancestral_allele.string(info)?, Some("C"));
+
+        let read_depths = FormatField::new(header, "AD")?;
+        let values: Vec<_> = read_depths
+            .integers(samples, 0)?
+            .unwrap()
+            .collect::<io::Result<_>>()?;
+        assert_eq!(values, [Some(5), Some(8)]);
+        let values: Vec<_> = read_depths
+            .integers(samples, 1)?
+            .unwrap()
+            .collect::<io::Result<_>>()?;
+        assert_eq!(values, [Some(0), None, Some(21)]);
+        assert!(read_depths.integers(samples, 2)?.is_none());
+
+        let filter = FormatField::new(header, "FT")?;
+        assert_eq!(filter.string(samples, 0)?, Some("PASS"));
+        assert_eq!(filter.string(samples, 1)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vcf_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let mut record = vcf::Record::default();
+        reader.read_record(&mut record)?;
+
+        check(&header, &record.info(), &record.samples())?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bcf_record() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let mut record = vcf::Record::default();
+        reader.read_record(&mut record)?;
+
+        let mut writer = noodles_bcf::io::Writer::from(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_variant_record(&header, &record)?;
+
+        let mut reader = noodles_bcf::io::Reader::from(&writer.get_ref()[..]);
+        let header = reader.read_header()?;
+        let mut record = noodles_bcf::Record::default();
+        reader.read_record(&mut record)?;
+
+        check(&header, &record.info(), &record.samples()?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_field_new() -> io::Result<()> {
+        let header = vcf::io::Reader::new(DATA.as_bytes()).read_header()?;
+        assert!(FormatField::new(&header, key::GENOTYPE).is_err());
+        assert!(FormatField::new(&header, "DP").is_err());
+        Ok(())
+    }
+}
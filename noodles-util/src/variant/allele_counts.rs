@@ -0,0 +1,716 @@
+//! Allele counts from alignments at variant sites.
+//!
+//! This genotypes known sites by counting the alignment records that support each allele of a
+//! variant record. The resulting counts can be written to a record as allele depths (`AD`) and
+//! read depths (`DP`).
+
+mod builder;
+
+use std::io::{self, Read, Seek};
+
+use noodles_core::{Position, Region};
+use noodles_sam::{
+    self as sam,
+    alignment::record::{cigar::op::Kind, Flags, MappingQuality},
+};
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::samples::keys::key,
+        record_buf::{samples::sample::Value, Samples},
+        RecordBuf,
+    },
+};
+
+pub use self::builder::Builder;
+use crate::alignment;
+
+/// Observation counts of an allele.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Count {
+    /// The number of observations on forward strand records.
+    pub forward: u64,
+    /// The number of observations on reverse strand records.
+    pub reverse: u64,
+    /// The sum of the base qualities of the observations.
+    pub quality_sum: u64,
+}
+
+impl Count {
+    /// Returns the total number of observations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::Count;
+    /// let count = Count { forward: 3, reverse: 5, quality_sum: 240 };
+    /// assert_eq!(count.total(), 8);
+    /// ```
+    pub fn total(&self) -> u64 {
+        self.forward + self.reverse
+    }
+
+    fn add(&mut self, is_reverse: bool, quality: u8) {
+        if is_reverse {
+            self.reverse += 1;
+        } else {
+            self.forward += 1;
+        }
+
+        self.quality_sum += u64::from(quality);
+    }
+}
+
+/// Allele observation counts at a variant site.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AlleleCounts {
+    alleles: Vec<Count>,
+    other: Count,
+}
+
+impl AlleleCounts {
+    /// Returns the counts of each allele.
+    ///
+    /// The first allele is the reference allele, followed by the alternate alleles.
+    pub fn alleles(&self) -> &[Count] {
+        &self.alleles
+    }
+
+    /// Returns the counts of observations that do not match any allele.
+    pub fn other(&self) -> &Count {
+        &self.other
+    }
+
+    /// Returns the total number of observations, including those that do not match any allele.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::AlleleCounts;
+    /// assert_eq!(AlleleCounts::default().depth(), 0);
+    /// ```
+    pub fn depth(&self) -> u64 {
+        self.alleles.iter().map(Count::total).sum::<u64>() + self.other.total()
+    }
+}
+
+/// An allele counter.
+///
+/// An alignment record supports an allele when its read bases aligned to the reference allele
+/// span, including insertions after any position in the span, equal the allele bases. Records
+/// that do not fully span the reference allele are not counted. Symbolic alleles are never
+/// matched.
+#[derive(Debug)]
+pub struct Counter {
+    min_mapping_quality: Option<MappingQuality>,
+    min_base_quality: u8,
+    excluded_flags: Flags,
+}
+
+impl Counter {
+    /// Creates an allele counter with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::Counter;
+    /// let counter = Counter::new();
+    /// ```
+    pub fn new() -> Self {
+        Builder::default().build()
+    }
+
+    /// Returns a builder to create an allele counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::Counter;
+    /// let builder = Counter::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Counts allele observations at a variant site using an indexed alignment reader.
+    ///
+    /// Reference sequences missing from the alignment header have no observations.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use noodles_util::{alignment, variant::allele_counts::{self, Counter}};
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut alignment_reader = alignment::io::indexed_reader::Builder::default()
+    ///     .build_from_path("sample.bam")?;
+    /// let alignment_header = alignment_reader.read_header()?;
+    ///
+    /// let mut variant_reader = vcf::io::reader::Builder::default().build_from_path("sites.vcf")?;
+    /// let variant_header = variant_reader.read_header()?;
+    ///
+    /// let counter = Counter::new();
+    ///
+    /// for result in variant_reader.record_bufs(&variant_header) {
+    ///     let mut record = result?;
+    ///
+    ///     let counts = counter.count(
+    ///         &mut alignment_reader,
+    ///         &alignment_header,
+    ///         &variant_header,
+    ///         &record,
+    ///     )?;
+    ///
+    ///     allele_counts::set_read_depths(&mut record, &[counts]);
+    ///     // ...
+    /// }
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn count<R, V>(
+        &self,
+        reader: &mut alignment::io::IndexedReader<R>,
+        alignment_header: &sam::Header,
+        variant_header: &vcf::Header,
+        record: &V,
+    ) -> io::Result<AlleleCounts>
+    where
+        R: Read + Seek,
+        V: vcf::variant::Record + ?Sized,
+    {
+        let site = Site::try_from_record(variant_header, record)?;
+
+        if !alignment_header
+            .reference_sequences()
+            .contains_key(site.reference_sequence_name.as_bytes())
+        {
+            return Ok(site.empty_counts());
+        }
+
+        let region = Region::new(
+            site.reference_sequence_name.as_str(),
+            site.start_position()?..=site.end_position()?,
+        );
+
+        let query = reader.query(alignment_header, &region)?;
+        self.count_site(&site, query)
+    }
+
+    /// Counts allele observations at a variant site from the given alignment records.
+    ///
+    /// The alignment records are typically those that intersect the variant site. Records on
+    /// other reference sequences or positions are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_sam::{
+    ///     self as sam,
+    ///     alignment::{
+    ///         record::{cigar::{op::Kind, Op}, Flags},
+    ///         record_buf::{Cigar, Sequence},
+    ///         RecordBuf,
+    ///     },
+    ///     header::record::value::{map::ReferenceSequence, Map},
+    /// };
+    /// use noodles_vcf::{self as vcf, variant::record_buf::AlternateBases};
+    /// use noodles_util::variant::allele_counts::Counter;
+    ///
+    /// let alignment_header = sam::Header::builder()
+    ///     .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(8.try_into()?))
+    ///     .build();
+    ///
+    /// let alignment_record = RecordBuf::builder()
+    ///     .set_flags(Flags::empty())
+    ///     .set_reference_sequence_id(0)
+    ///     .set_alignment_start(Position::MIN)
+    ///     .set_cigar(Cigar::from(vec![Op::new(Kind::Match, 4)]))
+    ///     .set_sequence(Sequence::from(b"ACGT".to_vec()))
+    ///     .build();
+    ///
+    /// let variant_header = vcf::Header::default();
+    ///
+    /// let variant_record = vcf::variant::RecordBuf::builder()
+    ///     .set_reference_sequence_name("sq0")
+    ///     .set_variant_start(Position::try_from(3)?)
+    ///     .set_reference_bases("C")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+    ///     .build();
+    ///
+    /// let counter = Counter::new();
+    /// let counts = counter.count_from_records(
+    ///     &alignment_header,
+    ///     &variant_header,
+    ///     &variant_record,
+    ///     [Ok(alignment_record)],
+    /// )?;
+    ///
+    /// assert_eq!(counts.alleles()[1].forward, 1);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn count_from_records<V, I, A>(
+        &self,
+        alignment_header: &sam::Header,
+        variant_header: &vcf::Header,
+        record: &V,
+        alignment_records: I,
+    ) -> io::Result<AlleleCounts>
+    where
+        V: vcf::variant::Record + ?Sized,
+        I: IntoIterator<Item = io::Result<A>>,
+        A: sam::alignment::Record,
+    {
+        let site = Site::try_from_record(variant_header, record)?;
+
+        let reference_sequence_id = alignment_header
+            .reference_sequences()
+            .get_index_of(site.reference_sequence_name.as_bytes());
+
+        let Some(reference_sequence_id) = reference_sequence_id else {
+            return Ok(site.empty_counts());
+        };
+
+        let records = alignment_records.into_iter().filter_map(|result| {
+            result
+                .and_then(|record| {
+                    let id = record.reference_sequence_id(alignment_header).transpose()?;
+                    Ok((id == Some(reference_sequence_id)).then_some(record))
+                })
+                .transpose()
+        });
+
+        self.count_site(&site, records)
+    }
+
+    fn count_site<I, A>(&self, site: &Site, alignment_records: I) -> io::Result<AlleleCounts>
+    where
+        I: IntoIterator<Item = io::Result<A>>,
+        A: sam::alignment::Record,
+    {
+        let mut counts = site.empty_counts();
+
+        for result in alignment_records {
+            let record = result?;
+            let flags = record.flags()?;
+
+            if flags.intersects(self.excluded_flags) {
+                continue;
+            }
+
+            if let Some(min_mapping_quality) = self.min_mapping_quality {
+                match record.mapping_quality().transpose()? {
+                    Some(mapping_quality) if mapping_quality >= min_mapping_quality => {}
+                    _ => continue,
+                }
+            }
+
+            let Some((bases, quality)) = observe(&record, site.start, site.end)? else {
+                continue;
+            };
+
+            if quality < self.min_base_quality {
+                continue;
+            }
+
+            let is_reverse = flags.is_reverse_complemented();
+
+            let count = site
+                .alleles
+                .iter()
+                .position(|allele| allele.as_deref() == Some(&bases[..]))
+                .map(|i| &mut counts.alleles[i])
+                .unwrap_or(&mut counts.other);
+
+            count.add(is_reverse, quality);
+        }
+
+        Ok(counts)
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sets the allele depths (`AD`) and read depths (`DP`) of the samples in a variant record.
+///
+/// The allele counts are given in sample order. If the record has fewer samples than allele
+/// counts, samples are added. The header is not modified and should define both fields.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{self as vcf, variant::record::samples::keys::key};
+/// use noodles_util::variant::allele_counts::{self, AlleleCounts};
+///
+/// let mut record = vcf::variant::RecordBuf::default();
+/// allele_counts::set_read_depths(&mut record, &[AlleleCounts::default()]);
+///
+/// assert!(record.format().as_ref().contains(key::READ_DEPTHS));
+/// assert!(record.format().as_ref().contains(key::READ_DEPTH));
+/// ```
+pub fn set_read_depths(record: &mut RecordBuf, sample_counts: &[AlleleCounts]) {
+    let samples = record.samples();
+    let mut keys = samples.keys().clone();
+
+    let (ad_index, _) = keys.as_mut().insert_full(String::from(key::READ_DEPTHS));
+    let (dp_index, _) = keys.as_mut().insert_full(String::from(key::READ_DEPTH));
+
+    let mut values: Vec<Vec<Option<Value>>> = samples
+        .values()
+        .map(|sample| sample.values().to_vec())
+        .collect();
+
+    if values.len() < sample_counts.len() {
+        values.resize(sample_counts.len(), Vec::new());
+    }
+
+    for (sample_values, counts) in values.iter_mut().zip(sample_counts) {
+        sample_values.resize(keys.as_ref().len(), None);
+
+        let allele_depths: Vec<_> = counts
+            .alleles()
+            .iter()
+            .map(|count| Some(saturating_i32(count.total())))
+            .collect();
+
+        sample_values[ad_index] = Some(Value::from(allele_depths));
+        sample_values[dp_index] = Some(Value::from(saturating_i32(counts.depth())));
+    }
+
+    *record.samples_mut() = Samples::new(keys, values);
+}
+
+fn saturating_i32(n: u64) -> i32 {
+    i32::try_from(n).unwrap_or(i32::MAX)
+}
+
+struct Site {
+    reference_sequence_name: String,
+    start: usize,
+    end: usize,
+    alleles: Vec<Option<Vec<u8>>>,
+}
+
+impl Site {
+    fn try_from_record<V>(header: &vcf::Header, record: &V) -> io::Result<Self>
+    where
+        V: vcf::variant::Record + ?Sized,
+    {
+        let reference_sequence_name = record.reference_sequence_name(header)?.into();
+
+        let start = record
+            .variant_start()
+            .transpose()?
+            .map(usize::from)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing variant start"))?;
+
+        let reference_bases = record
+            .reference_bases()
+            .iter()
+            .map(|result| result.map(|b| b.to_ascii_uppercase()))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        if reference_bases.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing reference bases",
+            ));
+        }
+
+        let end = start + reference_bases.len() - 1;
+
+        let mut alleles = vec![Some(reference_bases)];
+
+        for result in record.alternate_bases().iter() {
+            let allele = result?;
+            alleles.push(is_sequence(allele).then(|| allele.to_ascii_uppercase().into_bytes()));
+        }
+
+        Ok(Self {
+            reference_sequence_name,
+            start,
+            end,
+            alleles,
+        })
+    }
+
+    fn start_position(&self) -> io::Result<Position> {
+        Position::try_from(self.start).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn end_position(&self) -> io::Result<Position> {
+        Position::try_from(self.end).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn empty_counts(&self) -> AlleleCounts {
+        AlleleCounts {
+            alleles: vec![Count::default(); self.alleles.len()],
+            other: Count::default(),
+        }
+    }
+}
+
+fn is_sequence(allele: &str) -> bool {
+    !allele.is_empty()
+        && allele
+            .bytes()
+            .all(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'))
+}
+
+/// Returns the read bases and base quality of an alignment record over the given reference
+/// span.
+///
+/// The base quality is the lowest quality score of the read bases or, for an empty observation,
+/// the quality score of the last aligned base before the span.
+fn observe<R>(record: &R, start: usize, end: usize) -> io::Result<Option<(Vec<u8>, u8)>>
+where
+    R: sam::alignment::Record,
+{
+    let (Some(alignment_start), Some(alignment_end)) = (
+        record.alignment_start().transpose()?,
+        record.alignment_end().transpose()?,
+    ) else {
+        return Ok(None);
+    };
+
+    if usize::from(alignment_start) > start || usize::from(alignment_end) < end {
+        return Ok(None);
+    }
+
+    let sequence: Vec<u8> = record.sequence().iter().collect();
+    let quality_scores: Vec<u8> = record.quality_scores().iter().collect();
+    let quality_score = |i: usize| quality_scores.get(i).copied().unwrap_or(u8::MAX);
+
+    let mut bases = Vec::new();
+    let mut min_quality: Option<u8> = None;
+    let mut anchor_quality = u8::MAX;
+
+    let mut reference_position = usize::from(alignment_start);
+    let mut read_position = 0;
+
+    let mut push = |bases: &mut Vec<u8>, i: usize| {
+        if let Some(&base) = sequence.get(i) {
+            bases.push(base.to_ascii_uppercase());
+            let q = quality_score(i);
+            min_quality = Some(min_quality.map_or(q, |m| m.min(q)));
+        }
+    };
+
+    for result in record.cigar().iter() {
+        let op = result?;
+        let len = op.len();
+
+        match op.kind() {
+            Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch => {
+                for i in 0..len {
+                    let position = reference_position + i;
+
+                    if (start..=end).contains(&position) {
+                        push(&mut bases, read_position + i);
+                    } else if position < start {
+                        anchor_quality = quality_score(read_position + i);
+                    }
+                }
+
+                reference_position += len;
+                read_position += len;
+            }
+            Kind::Insertion => {
+                if reference_position > start && reference_position - 1 <= end {
+                    for i in 0..len {
+                        push(&mut bases, read_position + i);
+                    }
+                }
+
+                read_position += len;
+            }
+            Kind::Deletion => reference_position += len,
+            Kind::Skip => {
+                let skip_end = reference_position + len - 1;
+
+                if reference_position <= end && skip_end >= start {
+                    return Ok(None);
+                }
+
+                reference_position += len;
+            }
+            Kind::SoftClip => read_position += len,
+            Kind::HardClip | Kind::Pad => {}
+        }
+
+        if reference_position > end + 1 {
+            break;
+        }
+    }
+
+    Ok(Some((bases, min_quality.unwrap_or(anchor_quality))))
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_sam::alignment::{
+        record::cigar::Op,
+        record_buf::{Cigar, QualityScores, Sequence},
+        RecordBuf as AlignmentRecordBuf,
+    };
+    use noodles_vcf::variant::record_buf::AlternateBases;
+
+    use super::*;
+
+    #[test]
+    fn test_count_from_records() -> Result<(), Box<dyn std::error::Error>> {
+        use noodles_sam::header::record::value::{map::ReferenceSequence, Map};
+
+        let alignment_header = sam::Header::builder()
+            .add_reference_sequence("sq0", Map::<ReferenceSequence>::new(16.try_into()?))
+            .build();
+
+        let variant_header = vcf::Header::default();
+
+        let m = |len| Op::new(Kind::Match, len);
+
+        let alignment_records: Vec<_> = [
+            // ref
+            (vec![m(6)], &b"ACGTAC"[..], Flags::empty()),
+            // ins
+            (
+                vec![m(2), Op::new(Kind::Insertion, 2), m(4)],
+                b"ACttGTAC",
+                Flags::REVERSE_COMPLEMENTED,
+            ),
+            // del
+            (
+                vec![m(2), Op::new(Kind::Deletion, 1), m(3)],
+                b"ACTAC",
+                Flags::empty(),
+            ),
+            // snv
+            (vec![m(6)], b"ACATAC", Flags::empty()),
+            // does not span the site
+            (vec![m(1)], b"A", Flags::empty()),
+            // duplicate
+            (vec![m(6)], b"ACGTAC", Flags::DUPLICATE),
+        ]
+        .into_iter()
+        .map(|(ops, sequence, flags)| {
+            AlignmentRecordBuf::builder()
+                .set_flags(flags)
+                .set_reference_sequence_id(0)
+                .set_alignment_start(Position::MIN)
+                .set_cigar(Cigar::from(ops))
+                .set_sequence(Sequence::from(sequence.to_vec()))
+                .set_quality_scores(QualityScores::from(vec![30; sequence.len()]))
+                .build()
+        })
+        .collect();
+
+        let counter = Counter::new();
+
+        let variant_record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("C")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("CTT")]))
+            .build();
+
+        let counts = counter.count_from_records(
+            &alignment_header,
+            &variant_header,
+            &variant_record,
+            alignment_records.iter().cloned().map(Ok),
+        )?;
+
+        assert_eq!(counts.alleles()[0].total(), 3);
+        assert_eq!(
+            counts.alleles()[1],
+            Count {
+                forward: 0,
+                reverse: 1,
+                quality_sum: 30,
+            }
+        );
+        assert_eq!(counts.other().total(), 0);
+
+        let variant_record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("CG")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("CA"),
+            ]))
+            .build();
+
+        let counts = counter.count_from_records(
+            &alignment_header,
+            &variant_header,
+            &variant_record,
+            alignment_records.iter().cloned().map(Ok),
+        )?;
+
+        let totals: Vec<_> = counts.alleles().iter().map(Count::total).collect();
+        assert_eq!(totals, [1, 1, 1]);
+        assert_eq!(counts.other().total(), 1);
+        assert_eq!(counts.depth(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_read_depths() {
+        use noodles_vcf::variant::record_buf::samples::Keys;
+
+        let keys: Keys = [String::from(key::GENOTYPE)].into_iter().collect();
+        let samples = Samples::new(keys, vec![vec![Some(Value::from("0/1"))]]);
+
+        let mut record = RecordBuf::builder().set_samples(samples).build();
+
+        let counts = AlleleCounts {
+            alleles: vec![
+                Count {
+                    forward: 1,
+                    reverse: 2,
+                    quality_sum: 0,
+                },
+                Count {
+                    forward: 4,
+                    reverse: 0,
+                    quality_sum: 0,
+                },
+            ],
+            other: Count {
+                forward: 1,
+                reverse: 0,
+                quality_sum: 0,
+            },
+        };
+
+        set_read_depths(&mut record, &[counts.clone(), counts]);
+
+        let expected_keys: Keys = [key::GENOTYPE, key::READ_DEPTHS, key::READ_DEPTH]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let expected = Samples::new(
+            expected_keys,
+            vec![
+                vec![
+                    Some(Value::from("0/1")),
+                    Some(Value::from(vec![Some(3), Some(4)])),
+                    Some(Value::from(8)),
+                ],
+                vec![
+                    None,
+                    Some(Value::from(vec![Some(3), Some(4)])),
+                    Some(Value::from(8)),
+                ],
+            ],
+        );
+
+        assert_eq!(record.samples(), &expected);
+    }
+}
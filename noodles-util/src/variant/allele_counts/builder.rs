@@ -0,0 +1,89 @@
+use noodles_sam::alignment::record::{Flags, MappingQuality};
+
+use super::Counter;
+
+/// An allele counter builder.
+#[derive(Debug)]
+pub struct Builder {
+    min_mapping_quality: Option<MappingQuality>,
+    min_base_quality: u8,
+    excluded_flags: Flags,
+}
+
+impl Builder {
+    /// Sets the minimum mapping quality of counted alignment records.
+    ///
+    /// By default, records are not filtered by mapping quality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alignment::record::MappingQuality;
+    /// use noodles_util::variant::allele_counts::Counter;
+    ///
+    /// let builder = Counter::builder()
+    ///     .set_min_mapping_quality(MappingQuality::new(20).unwrap());
+    /// ```
+    pub fn set_min_mapping_quality(mut self, min_mapping_quality: MappingQuality) -> Self {
+        self.min_mapping_quality = Some(min_mapping_quality);
+        self
+    }
+
+    /// Sets the minimum base quality of counted observations.
+    ///
+    /// The base quality of an observation is the lowest quality score of the read bases in the
+    /// allele. By default, observations are not filtered by base quality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::Counter;
+    /// let builder = Counter::builder().set_min_base_quality(13);
+    /// ```
+    pub fn set_min_base_quality(mut self, min_base_quality: u8) -> Self {
+        self.min_base_quality = min_base_quality;
+        self
+    }
+
+    /// Sets the flags of alignment records that are not counted.
+    ///
+    /// By default, unmapped, secondary, QC failed, and duplicate records are excluded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_sam::alignment::record::Flags;
+    /// use noodles_util::variant::allele_counts::Counter;
+    /// let builder = Counter::builder().set_excluded_flags(Flags::UNMAPPED);
+    /// ```
+    pub fn set_excluded_flags(mut self, excluded_flags: Flags) -> Self {
+        self.excluded_flags = excluded_flags;
+        self
+    }
+
+    /// Builds an allele counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::allele_counts::Counter;
+    /// let counter = Counter::builder().build();
+    /// ```
+    pub fn build(self) -> Counter {
+        Counter {
+            min_mapping_quality: self.min_mapping_quality,
+            min_base_quality: self.min_base_quality,
+            excluded_flags: self.excluded_flags,
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            min_mapping_quality: None,
+            min_base_quality: 0,
+            excluded_flags: Flags::UNMAPPED | Flags::SECONDARY | Flags::QC_FAIL | Flags::DUPLICATE,
+        }
+    }
+}
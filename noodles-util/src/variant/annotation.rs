@@ -0,0 +1,226 @@
+//! Variant consequence annotations.
+//!
+//! Annotators such as Ensembl VEP (`CSQ`), SnpEff (`ANN`), and bcftools csq (`BCSQ`) write one
+//! pipe-delimited annotation per allele and feature to an INFO field. The field names are only
+//! listed in the description of the INFO header record. A [`Layout`] reads these names and parses
+//! annotations into [`Annotation`] records.
+
+mod impact;
+pub mod key;
+mod layout;
+
+use std::io;
+
+pub use self::{impact::Impact, layout::Layout};
+
+const CONSEQUENCE_DELIMITER: char = '&';
+
+/// A consequence annotation.
+///
+/// This is typically an annotation of one allele on one feature, e.g., a transcript.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Annotation<'l> {
+    layout: &'l Layout,
+    values: Vec<Option<String>>,
+}
+
+impl<'l> Annotation<'l> {
+    fn new(layout: &'l Layout, values: Vec<Option<String>>) -> Self {
+        Self { layout, values }
+    }
+
+    /// Returns the layout.
+    pub fn layout(&self) -> &'l Layout {
+        self.layout
+    }
+
+    /// Returns the value of a field.
+    ///
+    /// This returns `None` if the field is not in the layout or its value is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::Layout;
+    /// let layout = Layout::new("CSQ", ["Allele", "Consequence", "EXON"]);
+    /// let annotation = layout.parse("C|missense_variant|")?;
+    /// assert_eq!(annotation.get("Allele"), Some("C"));
+    /// assert!(annotation.get("EXON").is_none());
+    /// assert!(annotation.get("INTRON").is_none());
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn get(&self, field_name: &str) -> Option<&str> {
+        self.layout
+            .index_of(field_name)
+            .and_then(|i| self.get_index(i))
+    }
+
+    /// Returns the value of a field at the given index.
+    pub fn get_index(&self, i: usize) -> Option<&str> {
+        self.values.get(i).and_then(|value| value.as_deref())
+    }
+
+    /// Returns an iterator over field name-value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.layout
+            .field_names()
+            .iter()
+            .map(String::as_str)
+            .zip(self.values.iter().map(|value| value.as_deref()))
+    }
+
+    /// Returns the allele (`Allele`).
+    pub fn allele(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().allele)
+    }
+
+    /// Returns an iterator over the consequence terms (`Consequence`, `Annotation`).
+    ///
+    /// Multiple terms are separated by `&`, e.g., `splice_region_variant&intron_variant`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::Layout;
+    /// let layout = Layout::new("CSQ", ["Allele", "Consequence"]);
+    /// let annotation = layout.parse("C|splice_region_variant&intron_variant")?;
+    /// assert_eq!(
+    ///     annotation.consequences().collect::<Vec<_>>(),
+    ///     ["splice_region_variant", "intron_variant"]
+    /// );
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn consequences(&self) -> impl Iterator<Item = &str> {
+        self.get_known(self.layout.indices().consequence)
+            .into_iter()
+            .flat_map(|s| s.split(CONSEQUENCE_DELIMITER))
+    }
+
+    /// Returns the impact (`IMPACT`, `Annotation_Impact`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::{Impact, Layout};
+    /// let layout = Layout::new("ANN", ["Allele", "Annotation", "Annotation_Impact"]);
+    /// let annotation = layout.parse("C|stop_gained|HIGH")?;
+    /// assert_eq!(annotation.impact()?, Some(Impact::High));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn impact(&self) -> io::Result<Option<Impact>> {
+        self.get_known(self.layout.indices().impact)
+            .map(|s| {
+                s.parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .transpose()
+    }
+
+    /// Returns the gene symbol (`SYMBOL`, `Gene_Name`, `gene`).
+    pub fn gene_symbol(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().gene_symbol)
+    }
+
+    /// Returns the gene ID (`Gene`, `Gene_ID`).
+    pub fn gene_id(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().gene_id)
+    }
+
+    /// Returns the feature ID, e.g., the transcript ID (`Feature`, `Feature_ID`, `transcript`).
+    pub fn feature_id(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().feature_id)
+    }
+
+    /// Returns the feature biotype (`BIOTYPE`, `Transcript_BioType`, `biotype`).
+    pub fn biotype(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().biotype)
+    }
+
+    /// Returns the HGVS coding sequence notation (`HGVSc`, `HGVS.c`, `dna_change`).
+    pub fn hgvs_c(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().hgvs_c)
+    }
+
+    /// Returns the HGVS protein sequence notation (`HGVSp`, `HGVS.p`, `amino_acid_change`).
+    pub fn hgvs_p(&self) -> Option<&str> {
+        self.get_known(self.layout.indices().hgvs_p)
+    }
+
+    fn get_known(&self, i: Option<usize>) -> Option<&str> {
+        i.and_then(|i| self.get_index(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::{
+        self as vcf,
+        header::record::value::{map::Info, Map},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_annotations_from_vcf_record() -> io::Result<()> {
+        let src = b"##fileformat=VCFv4.5
+##INFO=<ID=ANN,Number=.,Type=String,Description=\"Functional annotations: 'Allele | Annotation | Annotation_Impact | Gene_Name | Gene_ID | Feature_Type | Feature_ID | Transcript_BioType | Rank | HGVS.c | HGVS.p'\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t8\t.\tA\tC\t.\tPASS\tANN=C|missense_variant&splice_region_variant|MODERATE|NDLS|NDLS_ID|transcript|NDLS_T1|protein_coding|2/5|c.8A>C|p.Lys3Thr,C|upstream_gene_variant|MODIFIER|NDLS2|NDLS2_ID|transcript|NDLS2_T1|lncRNA||n.-8A%3DC|
+";
+
+        let mut reader = vcf::io::Reader::new(&src[..]);
+        let header = reader.read_header()?;
+
+        let mut record = vcf::Record::default();
+        reader.read_record(&mut record)?;
+
+        let layout = Layout::from_header(&header, key::SNPEFF_ANNOTATIONS)?;
+        let annotations = layout.annotations(&header, &record)?;
+
+        assert_eq!(annotations.len(), 2);
+
+        let annotation = &annotations[0];
+        assert_eq!(annotation.allele(), Some("C"));
+        assert_eq!(
+            annotation.consequences().collect::<Vec<_>>(),
+            ["missense_variant", "splice_region_variant"]
+        );
+        assert_eq!(annotation.impact()?, Some(Impact::Moderate));
+        assert_eq!(annotation.gene_symbol(), Some("NDLS"));
+        assert_eq!(annotation.gene_id(), Some("NDLS_ID"));
+        assert_eq!(annotation.feature_id(), Some("NDLS_T1"));
+        assert_eq!(annotation.biotype(), Some("protein_coding"));
+        assert_eq!(annotation.hgvs_c(), Some("c.8A>C"));
+        assert_eq!(annotation.hgvs_p(), Some("p.Lys3Thr"));
+
+        let annotation = &annotations[1];
+        assert_eq!(annotation.impact()?, Some(Impact::Modifier));
+        assert_eq!(annotation.get("Rank"), None);
+        assert_eq!(annotation.hgvs_c(), Some("n.-8A=C"));
+        assert_eq!(annotation.hgvs_p(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations_without_field() -> io::Result<()> {
+        let header = vcf::Header::builder()
+            .add_info(
+                key::VEP_CONSEQUENCES,
+                Map::<Info>::new(
+                    vcf::header::record::value::map::info::Number::Unknown,
+                    vcf::header::record::value::map::info::Type::String,
+                    "Consequence annotations from Ensembl VEP. Format: Allele|Consequence",
+                ),
+            )
+            .build();
+
+        let layout = Layout::from_header(&header, key::VEP_CONSEQUENCES)?;
+        let record = vcf::variant::RecordBuf::default();
+
+        assert!(layout.annotations(&header, &record)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::{error, fmt, str::FromStr};
+
+/// The putative impact of a consequence.
+///
+/// Impacts are ordered by severity, i.e., [`Impact::Modifier`] is the least severe and
+/// [`Impact::High`], the most.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Impact {
+    /// Usually noncoding variants or variants affecting noncoding genes (`MODIFIER`).
+    Modifier,
+    /// Mostly harmless variants (`LOW`).
+    Low,
+    /// Nondisruptive variants that might change protein effectiveness (`MODERATE`).
+    Moderate,
+    /// Variants assumed to have a disruptive effect on the protein (`HIGH`).
+    High,
+}
+
+impl AsRef<str> for Impact {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Modifier => "MODIFIER",
+            Self::Low => "LOW",
+            Self::Moderate => "MODERATE",
+            Self::High => "HIGH",
+        }
+    }
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// An error returned when a raw impact fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The input is invalid.
+    Invalid,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::Invalid => f.write_str("invalid input"),
+        }
+    }
+}
+
+impl FromStr for Impact {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(ParseError::Empty),
+            "MODIFIER" => Ok(Self::Modifier),
+            "LOW" => Ok(Self::Low),
+            "MODERATE" => Ok(Self::Moderate),
+            "HIGH" => Ok(Self::High),
+            _ => Err(ParseError::Invalid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("MODIFIER".parse(), Ok(Impact::Modifier));
+        assert_eq!("LOW".parse(), Ok(Impact::Low));
+        assert_eq!("MODERATE".parse(), Ok(Impact::Moderate));
+        assert_eq!("HIGH".parse(), Ok(Impact::High));
+
+        assert_eq!("".parse::<Impact>(), Err(ParseError::Empty));
+        assert_eq!("high".parse::<Impact>(), Err(ParseError::Invalid));
+    }
+
+    #[test]
+    fn test_ord() {
+        assert!(Impact::High > Impact::Moderate);
+        assert!(Impact::Moderate > Impact::Low);
+        assert!(Impact::Low > Impact::Modifier);
+    }
+}
@@ -0,0 +1,10 @@
+//! Annotation INFO keys.
+
+/// Ensembl VEP consequence annotations (`CSQ`).
+pub const VEP_CONSEQUENCES: &str = "CSQ";
+
+/// SnpEff functional annotations (`ANN`).
+pub const SNPEFF_ANNOTATIONS: &str = "ANN";
+
+/// bcftools csq haplotype-aware consequence annotations (`BCSQ`).
+pub const BCFTOOLS_CONSEQUENCES: &str = "BCSQ";
@@ -0,0 +1,427 @@
+use std::io;
+
+use noodles_vcf::{
+    self as vcf,
+    header::record::value::map::info,
+    variant::{
+        record::info::field::{value::Array, Value},
+        Record,
+    },
+};
+use percent_encoding::percent_decode_str;
+
+use super::Annotation;
+
+const DELIMITER: char = '|';
+
+// Field names of Ensembl VEP (`CSQ`), SnpEff (`ANN`), and bcftools csq (`BCSQ`), respectively.
+const ALLELE: &[&str] = &["Allele"];
+const CONSEQUENCE: &[&str] = &["Consequence", "Annotation"];
+const IMPACT: &[&str] = &["IMPACT", "Annotation_Impact"];
+const GENE_SYMBOL: &[&str] = &["SYMBOL", "Gene_Name", "gene"];
+const GENE_ID: &[&str] = &["Gene", "Gene_ID"];
+const FEATURE_ID: &[&str] = &["Feature", "Feature_ID", "transcript"];
+const BIOTYPE: &[&str] = &["BIOTYPE", "Transcript_BioType", "biotype"];
+const HGVS_C: &[&str] = &["HGVSc", "HGVS.c", "dna_change"];
+const HGVS_P: &[&str] = &["HGVSp", "HGVS.p", "amino_acid_change"];
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(super) struct Indices {
+    pub(super) allele: Option<usize>,
+    pub(super) consequence: Option<usize>,
+    pub(super) impact: Option<usize>,
+    pub(super) gene_symbol: Option<usize>,
+    pub(super) gene_id: Option<usize>,
+    pub(super) feature_id: Option<usize>,
+    pub(super) biotype: Option<usize>,
+    pub(super) hgvs_c: Option<usize>,
+    pub(super) hgvs_p: Option<usize>,
+}
+
+/// The layout of pipe-delimited annotations in an INFO field.
+///
+/// The field names are taken from the `Description` of the INFO header record, e.g.,
+/// `Consequence annotations from Ensembl VEP. Format: Allele|Consequence|IMPACT|SYMBOL`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Layout {
+    key: String,
+    field_names: Vec<String>,
+    indices: Indices,
+}
+
+impl Layout {
+    /// Creates a layout from field names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::Layout;
+    /// let layout = Layout::new("CSQ", ["Allele", "Consequence"]);
+    /// assert_eq!(layout.field_names(), ["Allele", "Consequence"]);
+    /// ```
+    pub fn new<K, I, N>(key: K, field_names: I) -> Self
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        let field_names: Vec<_> = field_names.into_iter().map(Into::into).collect();
+
+        let position = |names: &[&str]| {
+            field_names
+                .iter()
+                .position(|field_name| names.contains(&field_name.as_str()))
+        };
+
+        let indices = Indices {
+            allele: position(ALLELE),
+            consequence: position(CONSEQUENCE),
+            impact: position(IMPACT),
+            gene_symbol: position(GENE_SYMBOL),
+            gene_id: position(GENE_ID),
+            feature_id: position(FEATURE_ID),
+            biotype: position(BIOTYPE),
+            hgvs_c: position(HGVS_C),
+            hgvs_p: position(HGVS_P),
+        };
+
+        Self {
+            key: key.into(),
+            field_names,
+            indices,
+        }
+    }
+
+    /// Derives the layout of an INFO field from its header definition.
+    ///
+    /// This returns an error if the INFO field is not defined or its description does not list
+    /// field names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::{key, Layout};
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::{info, Info}, Map},
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_info(key::VEP_CONSEQUENCES, Map::<Info>::new(
+    ///         info::Number::Unknown,
+    ///         info::Type::String,
+    ///         "Consequence annotations from Ensembl VEP. Format: Allele|Consequence|IMPACT|SYMBOL",
+    ///     ))
+    ///     .build();
+    ///
+    /// let layout = Layout::from_header(&header, key::VEP_CONSEQUENCES)?;
+    /// assert_eq!(layout.field_names(), ["Allele", "Consequence", "IMPACT", "SYMBOL"]);
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn from_header(header: &vcf::Header, key: &str) -> io::Result<Self> {
+        let definition = header.infos().get(key).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("missing INFO definition: {key}"),
+            )
+        })?;
+
+        if definition.ty() != info::Type::String {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid INFO {key} type: expected String"),
+            ));
+        }
+
+        let field_names = parse_field_names(definition.description()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("missing field names in INFO {key} description"),
+            )
+        })?;
+
+        Ok(Self::new(key, field_names))
+    }
+
+    /// Returns the INFO key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the field names.
+    pub fn field_names(&self) -> &[String] {
+        &self.field_names
+    }
+
+    /// Returns the index of a field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::Layout;
+    /// let layout = Layout::new("CSQ", ["Allele", "Consequence"]);
+    /// assert_eq!(layout.index_of("Consequence"), Some(1));
+    /// assert!(layout.index_of("SYMBOL").is_none());
+    /// ```
+    pub fn index_of(&self, field_name: &str) -> Option<usize> {
+        self.field_names.iter().position(|name| name == field_name)
+    }
+
+    pub(super) fn indices(&self) -> &Indices {
+        &self.indices
+    }
+
+    /// Parses a raw annotation.
+    ///
+    /// Field values are percent-decoded. Use this for annotations that were not decoded by a
+    /// record, e.g., taken from raw text. Missing trailing fields are set to missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::Layout;
+    ///
+    /// let layout = Layout::new("CSQ", ["Allele", "Consequence", "HGVSc"]);
+    /// let annotation = layout.parse("C|missense_variant|ENST00000000001.1%3Ac.8A>C")?;
+    ///
+    /// assert_eq!(annotation.hgvs_c(), Some("ENST00000000001.1:c.8A>C"));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn parse(&self, s: &str) -> io::Result<Annotation<'_>> {
+        self.parse_fields(s, |t| {
+            percent_decode_str(t)
+                .decode_utf8()
+                .map(String::from)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn parse_fields<F>(&self, s: &str, f: F) -> io::Result<Annotation<'_>>
+    where
+        F: Fn(&str) -> io::Result<String>,
+    {
+        let mut values = Vec::with_capacity(self.field_names.len());
+
+        for raw_value in s.split(DELIMITER) {
+            if values.len() == self.field_names.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "invalid INFO {} annotation: expected {} fields",
+                        self.key,
+                        self.field_names.len()
+                    ),
+                ));
+            }
+
+            let value = if raw_value.is_empty() {
+                None
+            } else {
+                Some(f(raw_value)?)
+            };
+
+            values.push(value);
+        }
+
+        values.resize(self.field_names.len(), None);
+
+        Ok(Annotation::new(self, values))
+    }
+
+    /// Returns the annotations of a record.
+    ///
+    /// Values read from records are already percent-decoded. This returns an empty list if the
+    /// record does not have the INFO field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::annotation::{Impact, Layout};
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     variant::{record_buf::info::field::{value::Array, Value}, RecordBuf},
+    /// };
+    ///
+    /// let header = vcf::Header::default();
+    /// let layout = Layout::new("ANN", ["Allele", "Annotation", "Annotation_Impact", "Gene_Name"]);
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_info(
+    ///         [(
+    ///             String::from("ANN"),
+    ///             Some(Value::Array(Array::String(vec![
+    ///                 Some(String::from("C|missense_variant|MODERATE|NDLS")),
+    ///                 Some(String::from("C|upstream_gene_variant|MODIFIER|NDLS2")),
+    ///             ]))),
+    ///         )]
+    ///         .into_iter()
+    ///         .collect(),
+    ///     )
+    ///     .build();
+    ///
+    /// let annotations = layout.annotations(&header, &record)?;
+    /// assert_eq!(annotations.len(), 2);
+    /// assert_eq!(annotations[0].impact()?, Some(Impact::Moderate));
+    /// assert_eq!(annotations[1].gene_symbol(), Some("NDLS2"));
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn annotations<R>(
+        &self,
+        header: &vcf::Header,
+        record: &R,
+    ) -> io::Result<Vec<Annotation<'_>>>
+    where
+        R: Record + ?Sized,
+    {
+        let info = record.info();
+
+        let value = match info.get(header, &self.key).transpose()?.flatten() {
+            Some(value) => value,
+            None => return Ok(Vec::new()),
+        };
+
+        let decoded = |t: &str| Ok(String::from(t));
+
+        match value {
+            Value::String(s) => self
+                .parse_fields(&s, decoded)
+                .map(|annotation| vec![annotation]),
+            Value::Array(Array::String(values)) => {
+                let mut annotations = Vec::with_capacity(values.len());
+
+                for result in values.iter() {
+                    if let Some(s) = result? {
+                        annotations.push(self.parse_fields(&s, decoded)?);
+                    }
+                }
+
+                Ok(annotations)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid INFO {} value", self.key),
+            )),
+        }
+    }
+}
+
+/// Parses the field names listed in a description.
+///
+/// The list starts after the last colon before the first pipe and may be quoted, e.g.,
+/// `Functional annotations: 'Allele | Annotation | Annotation_Impact'`.
+fn parse_field_names(description: &str) -> Option<Vec<String>> {
+    let i = description.find(DELIMITER)?;
+    let start = description[..i].rfind(':').map(|j| j + 1).unwrap_or(0);
+
+    let raw_field_names = description[start..]
+        .trim()
+        .trim_matches(|c| matches!(c, '\'' | '"'))
+        .trim();
+
+    let field_names: Vec<_> = raw_field_names
+        .split(DELIMITER)
+        .map(|name| String::from(name.trim()))
+        .collect();
+
+    if field_names.iter().any(|name| name.is_empty()) {
+        None
+    } else {
+        Some(field_names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_field_names() {
+        assert_eq!(
+            parse_field_names(
+                "Consequence annotations from Ensembl VEP. Format: Allele|Consequence|IMPACT"
+            ),
+            Some(vec![
+                String::from("Allele"),
+                String::from("Consequence"),
+                String::from("IMPACT")
+            ])
+        );
+
+        assert_eq!(
+            parse_field_names(
+                "Functional annotations: 'Allele | Annotation | Annotation_Impact | Gene_Name '"
+            ),
+            Some(vec![
+                String::from("Allele"),
+                String::from("Annotation"),
+                String::from("Annotation_Impact"),
+                String::from("Gene_Name")
+            ])
+        );
+
+        assert_eq!(
+            parse_field_names(
+                "Haplotype-aware consequence annotation from BCFtools/csq, see http://samtools.github.io/bcftools/howtos/csq-calling.html for details. Format: Consequence|gene|transcript"
+            ),
+            Some(vec![
+                String::from("Consequence"),
+                String::from("gene"),
+                String::from("transcript")
+            ])
+        );
+
+        assert!(parse_field_names("Combined depth across samples").is_none());
+        assert!(parse_field_names("Format: Allele||IMPACT").is_none());
+    }
+
+    #[test]
+    fn test_parse() -> io::Result<()> {
+        let layout = Layout::new("CSQ", ["Allele", "Consequence", "IMPACT", "SYMBOL"]);
+
+        let annotation = layout.parse("C|missense_variant||NDLS%3B1")?;
+        assert_eq!(annotation.get("Allele"), Some("C"));
+        assert_eq!(annotation.get("IMPACT"), None);
+        assert_eq!(annotation.gene_symbol(), Some("NDLS;1"));
+
+        let annotation = layout.parse("C|missense_variant")?;
+        assert_eq!(annotation.gene_symbol(), None);
+
+        assert!(matches!(
+            layout.parse("C|missense_variant|MODERATE|NDLS|ENSG00000000001"),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_header() {
+        use vcf::header::record::value::{map::Info, Map};
+
+        let header = vcf::Header::builder()
+            .add_info(
+                "CSQ",
+                Map::<Info>::new(
+                    info::Number::Unknown,
+                    info::Type::String,
+                    "Consequence annotations from Ensembl VEP. Format: Allele|Gene|SYMBOL",
+                ),
+            )
+            .add_info("DP", Map::<Info>::from("DP"))
+            .build();
+
+        let layout = Layout::from_header(&header, "CSQ").unwrap();
+        assert_eq!(layout.indices().gene_id, Some(1));
+        assert_eq!(layout.indices().gene_symbol, Some(2));
+
+        assert!(matches!(
+            Layout::from_header(&header, "ANN"),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        assert!(matches!(
+            Layout::from_header(&header, "DP"),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+//! Variant file concatenation.
+//!
+//! This is similar to `bcftools concat`. Inputs are typically shards of the same call set, e.g.,
+//! per region, in coordinate order.
+//!
+//! [`concat`] decodes and reencodes records and allows compatible headers. [`concat_naive`]
+//! copies BGZF blocks without decoding them and requires identical headers. In both cases, the
+//! output index can be rebuilt with [`index`].
+
+mod naive;
+
+pub use self::naive::concat_naive;
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use noodles_bcf as bcf;
+use noodles_core::Position;
+use noodles_csi as csi;
+use noodles_tabix as tabix;
+use noodles_vcf::{self as vcf, header::StringMaps, variant::RecordBuf};
+
+use super::{
+    io::{
+        reader::builder::{detect_compression_method, detect_format},
+        CompressionMethod, Format, Reader, Writer,
+    },
+    merge::merge_definitions,
+};
+
+/// Concatenates variant readers into a writer.
+///
+/// The headers of all readers are read and merged. They must have the same sample names in the
+/// same order, and their definitions must not conflict (see [`crate::variant::merge`]). The
+/// merged header is written, followed by the records of each reader.
+///
+/// Records must be in coordinate order across all readers, i.e., shards must be given in order
+/// and not overlap. This returns the merged header.
+///
+/// # Examples
+///
+/// ```
+/// use std::io;
+/// use noodles_util::variant::{self, concat::concat};
+///
+/// let data = b"##fileformat=VCFv4.5
+/// #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+/// sq0\t1\t.\tA\t.\t.\tPASS\t.
+/// ";
+///
+/// let mut readers = [
+///     variant::io::reader::Builder::default().build_from_reader(&data[..])?,
+/// ];
+///
+/// let mut writer = variant::io::writer::Builder::default().build_from_writer(io::sink());
+///
+/// let header = concat(&mut readers, &mut writer)?;
+/// assert!(header.sample_names().is_empty());
+/// # Ok::<_, io::Error>(())
+/// ```
+pub fn concat<R>(readers: &mut [Reader<R>], writer: &mut Writer) -> io::Result<vcf::Header>
+where
+    R: BufRead,
+{
+    let headers = readers
+        .iter_mut()
+        .map(|reader| reader.read_header())
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let header = merge_headers(&headers)?;
+    writer.write_header(&header)?;
+
+    let mut reference_sequence_ids: HashMap<String, usize> = header
+        .contigs()
+        .keys()
+        .enumerate()
+        .map(|(i, name)| (name.clone(), i))
+        .collect();
+
+    let mut last_key: Option<(usize, Option<Position>)> = None;
+
+    for (reader, src_header) in readers.iter_mut().zip(&headers) {
+        let is_same_header = *src_header == header;
+
+        for result in reader.records(src_header) {
+            let record = result?;
+
+            let name = record.reference_sequence_name(src_header)?;
+
+            let id = match reference_sequence_ids.get(name) {
+                Some(id) => *id,
+                None => {
+                    let id = reference_sequence_ids.len();
+                    reference_sequence_ids.insert(name.into(), id);
+                    id
+                }
+            };
+
+            let key = (id, record.variant_start().transpose()?);
+
+            if last_key.is_some_and(|last_key| key < last_key) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "input is not coordinate-sorted",
+                ));
+            }
+
+            last_key = Some(key);
+
+            if is_same_header {
+                writer.write_record(&header, record.as_ref())?;
+            } else {
+                let record = RecordBuf::try_from_variant_record(src_header, record.as_ref())?;
+                writer.write_record(&header, &record)?;
+            }
+        }
+    }
+
+    Ok(header)
+}
+
+fn merge_headers(headers: &[vcf::Header]) -> io::Result<vcf::Header> {
+    let Some((first, rest)) = headers.split_first() else {
+        return Ok(vcf::Header::default());
+    };
+
+    let mut header = first.clone();
+
+    for src in rest {
+        if src.sample_names() != first.sample_names() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "sample names differ",
+            ));
+        }
+
+        *header.file_format_mut() = header.file_format().max(src.file_format());
+        merge_definitions(&mut header, src)?;
+    }
+
+    *header.string_maps_mut() = StringMaps::try_from(&header)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    Ok(header)
+}
+
+/// Builds and writes an index for a BGZF-compressed variant file.
+///
+/// A BCF file is indexed as `<src>.csi`, and a bgzipped VCF file, as `<src>.tbi`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use noodles_util::variant::concat;
+/// concat::index("out.bcf")?;
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn index<P>(src: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let src = src.as_ref();
+
+    let mut reader = File::open(src).map(BufReader::new)?;
+    let compression_method = detect_compression_method(&mut reader)?;
+    let format = detect_format(&mut reader, compression_method)?;
+
+    match (format, compression_method) {
+        (Format::Bcf, Some(CompressionMethod::Bgzf)) => {
+            let index = bcf::index(src)?;
+            csi::write(push_ext(src, "csi"), &index)
+        }
+        (Format::Vcf, Some(CompressionMethod::Bgzf)) => {
+            let index = vcf::index(src)?;
+            tabix::write(push_ext(src, "tbi"), &index)
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input is not BGZF-compressed",
+        )),
+    }
+}
+
+fn push_ext(src: &Path, ext: &str) -> PathBuf {
+    let mut s = OsString::from(src);
+    s.push(".");
+    s.push(ext);
+    PathBuf::from(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io::Write, rc::Rc};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_concat() -> io::Result<()> {
+        const SRC_0: &[u8] = b"##fileformat=VCFv4.3
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t1\t.\tA\t.\t.\tPASS\t.
+sq0\t8\t.\tC\t.\t.\tPASS\t.
+";
+
+        const SRC_1: &[u8] = b"##fileformat=VCFv4.4
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##contig=<ID=sq0>
+##contig=<ID=sq1>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t13\t.\tG\t.\t.\tPASS\tDP=5
+sq1\t2\t.\tT\t.\t.\tPASS\t.
+";
+
+        let mut readers = [SRC_0, SRC_1]
+            .into_iter()
+            .map(|src| crate::variant::io::reader::Builder::default().build_from_reader(src))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let buf = SharedBuf::default();
+        let mut writer = crate::variant::io::writer::Builder::default()
+            .set_format(Format::Vcf)
+            .set_compression_method(None)
+            .build_from_writer(buf.clone());
+
+        concat(&mut readers, &mut writer)?;
+        drop(writer);
+
+        let expected = b"##fileformat=VCFv4.4
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##contig=<ID=sq0>
+##contig=<ID=sq1>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t1\t.\tA\t.\t.\tPASS\t.
+sq0\t8\t.\tC\t.\t.\tPASS\t.
+sq0\t13\t.\tG\t.\t.\tPASS\tDP=5
+sq1\t2\t.\tT\t.\t.\tPASS\t.
+";
+
+        assert_eq!(
+            String::from_utf8_lossy(&buf.0.borrow()),
+            String::from_utf8_lossy(expected)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concat_with_unsorted_shards() -> io::Result<()> {
+        const SRC_0: &[u8] = b"##fileformat=VCFv4.4
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t8\t.\tC\t.\t.\tPASS\t.
+";
+
+        const SRC_1: &[u8] = b"##fileformat=VCFv4.4
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t1\t.\tA\t.\t.\tPASS\t.
+";
+
+        let mut readers = [SRC_0, SRC_1]
+            .into_iter()
+            .map(|src| crate::variant::io::reader::Builder::default().build_from_reader(src))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut writer =
+            crate::variant::io::writer::Builder::default().build_from_writer(io::sink());
+
+        assert!(matches!(
+            concat(&mut readers, &mut writer),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concat_with_different_sample_names() -> io::Result<()> {
+        const SRC_0: &[u8] = b"##fileformat=VCFv4.4
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+";
+
+        const SRC_1: &[u8] = b"##fileformat=VCFv4.4
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample1
+";
+
+        let mut readers = [SRC_0, SRC_1]
+            .into_iter()
+            .map(|src| crate::variant::io::reader::Builder::default().build_from_reader(src))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut writer =
+            crate::variant::io::writer::Builder::default().build_from_writer(io::sink());
+
+        assert!(matches!(
+            concat(&mut readers, &mut writer),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use noodles_bcf as bcf;
+use noodles_bgzf as bgzf;
+use noodles_vcf as vcf;
+
+use crate::variant::io::{
+    reader::builder::{detect_compression_method, detect_format},
+    CompressionMethod, Format,
+};
+
+// § 4.1 "The BGZF compression format" (2023-05-24)
+const BGZF_HEADER_SIZE: usize = 18;
+const GZ_TRAILER_SIZE: usize = 8;
+
+/// Concatenates BGZF-compressed variant files by copying compressed blocks.
+///
+/// The inputs must be bgzipped VCF or BCF files of the same format with byte-identical headers.
+/// The header of the first input is written, followed by the records of each input. Records are
+/// not decoded, and only the block that contains the end of the header is recompressed.
+///
+/// Unlike [`super::concat`], the record order is not checked.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Write};
+/// use noodles_bgzf as bgzf;
+/// use noodles_util::variant::concat::concat_naive;
+///
+/// fn build_shard(records: &[u8]) -> io::Result<Vec<u8>> {
+///     let mut writer = bgzf::Writer::new(Vec::new());
+///     writer.write_all(b"##fileformat=VCFv4.5\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n")?;
+///     writer.write_all(records)?;
+///     writer.finish()
+/// }
+///
+/// let shards = [
+///     build_shard(b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n")?,
+///     build_shard(b"sq0\t8\t.\tC\t.\t.\tPASS\t.\n")?,
+/// ];
+///
+/// let mut dst = Vec::new();
+/// concat_naive(shards.iter().map(|shard| &shard[..]), &mut dst)?;
+/// # Ok::<_, io::Error>(())
+/// ```
+pub fn concat_naive<I, R, W>(readers: I, writer: &mut W) -> io::Result<()>
+where
+    I: IntoIterator<Item = R>,
+    R: Read,
+    W: Write,
+{
+    let mut first_header: Option<(Format, Vec<u8>)> = None;
+
+    for reader in readers {
+        let mut reader = BufReader::new(reader);
+
+        let compression_method = detect_compression_method(&mut reader)?;
+
+        if compression_method != Some(CompressionMethod::Bgzf) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "input is not BGZF-compressed",
+            ));
+        }
+
+        let format = detect_format(&mut reader, compression_method)?;
+
+        let mut reader = bgzf::Reader::new(reader);
+        let raw_header = read_raw_header(&mut reader, format)?;
+
+        match &first_header {
+            Some((first_format, first_raw_header)) => {
+                if format != *first_format || raw_header != *first_raw_header {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "headers differ",
+                    ));
+                }
+            }
+            None => {
+                write_block(writer, &raw_header)?;
+                first_header = Some((format, raw_header));
+            }
+        }
+
+        // The rest of the block that contains the end of the header.
+        let buf = reader.fill_buf()?;
+
+        if !buf.is_empty() {
+            write_block(writer, buf)?;
+        }
+
+        let mut inner = reader.into_inner();
+        copy_blocks(&mut inner, writer)?;
+    }
+
+    if first_header.is_some() {
+        bgzf::Writer::new(writer).finish()?;
+    }
+
+    Ok(())
+}
+
+fn read_raw_header<R>(reader: &mut R, format: Format) -> io::Result<Vec<u8>>
+where
+    R: BufRead,
+{
+    let mut recorder = Recorder {
+        inner: reader,
+        buf: Vec::new(),
+    };
+
+    match format {
+        Format::Vcf => {
+            vcf::io::Reader::new(&mut recorder).read_header()?;
+        }
+        Format::Bcf => {
+            bcf::io::Reader::from(&mut recorder).read_header()?;
+        }
+    }
+
+    Ok(recorder.buf)
+}
+
+/// A reader that records consumed data.
+struct Recorder<'r, R> {
+    inner: &'r mut R,
+    buf: Vec<u8>,
+}
+
+impl<R> Read for Recorder<'_, R>
+where
+    R: BufRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut src = self.fill_buf()?;
+        let amt = src.read(buf)?;
+        self.consume(amt);
+        Ok(amt)
+    }
+}
+
+impl<R> BufRead for Recorder<'_, R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The buffer was filled before being consumed, so this does not read from the underlying
+        // stream.
+        if let Ok(src) = self.inner.fill_buf() {
+            self.buf.extend(&src[..amt.min(src.len())]);
+        }
+
+        self.inner.consume(amt);
+    }
+}
+
+fn write_block<W>(writer: &mut W, buf: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut block_writer = bgzf::Writer::new(writer);
+    block_writer.write_all(buf)?;
+    block_writer.flush()?;
+
+    // Taking the inner writer skips writing an EOF block.
+    block_writer.into_inner();
+
+    Ok(())
+}
+
+/// Copies nonempty BGZF blocks.
+fn copy_blocks<R, W>(reader: &mut R, writer: &mut W) -> io::Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let mut header = [0; BGZF_HEADER_SIZE];
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+
+        if header[..4] != [0x1f, 0x8b, 0x08, 0x04] || header[12..14] != *b"BC" {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid BGZF block header",
+            ));
+        }
+
+        let block_size = usize::from(u16::from_le_bytes([header[16], header[17]])) + 1;
+
+        let len = block_size
+            .checked_sub(BGZF_HEADER_SIZE)
+            .filter(|&len| len >= GZ_TRAILER_SIZE)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid BGZF block size"))?;
+
+        buf.resize(len, 0);
+        reader.read_exact(&mut buf)?;
+
+        // ISIZE
+        let uncompressed_size = &buf[len - 4..];
+
+        if uncompressed_size != [0, 0, 0, 0] {
+            writer.write_all(&header)?;
+            writer.write_all(&buf)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &[u8] = b"##fileformat=VCFv4.5\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n";
+
+    #[test]
+    fn test_concat_naive() -> io::Result<()> {
+        let shards = [
+            (HEADER, &[&b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n"[..]][..]),
+            (HEADER, &[]),
+            (
+                HEADER,
+                &[
+                    b"sq0\t8\t.\tC\t.\t.\tPASS\t.\n",
+                    b"sq0\t13\t.\tG\t.\t.\tPASS\t.\n",
+                ],
+            ),
+        ]
+        .into_iter()
+        .map(|(header, records)| {
+            let mut writer = bgzf::Writer::new(Vec::new());
+            writer.write_all(header)?;
+
+            // Each record is in its own block, and the first shares the block of the header.
+            for record in records {
+                writer.write_all(record)?;
+                writer.flush()?;
+            }
+
+            writer.finish()
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+        let mut dst = Vec::new();
+        concat_naive(shards.iter().map(|shard| &shard[..]), &mut dst)?;
+
+        // The output has exactly one EOF block.
+        let eof = bgzf::Writer::new(Vec::new()).finish()?;
+        assert!(dst.ends_with(&eof));
+        assert!(!dst[..dst.len() - eof.len()].ends_with(&eof));
+
+        let mut data = Vec::new();
+        bgzf::Reader::new(&dst[..]).read_to_end(&mut data)?;
+
+        let mut expected = HEADER.to_vec();
+        expected.extend(b"sq0\t1\t.\tA\t.\t.\tPASS\t.\n");
+        expected.extend(b"sq0\t8\t.\tC\t.\t.\tPASS\t.\n");
+        expected.extend(b"sq0\t13\t.\tG\t.\t.\tPASS\t.\n");
+
+        assert_eq!(data, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concat_naive_with_different_headers() -> io::Result<()> {
+        let shards = [
+            HEADER,
+            b"##fileformat=VCFv4.4\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n",
+        ]
+        .into_iter()
+        .map(|header| {
+            let mut writer = bgzf::Writer::new(Vec::new());
+            writer.write_all(header)?;
+            writer.finish()
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+        let mut dst = Vec::new();
+
+        assert!(matches!(
+            concat_naive(shards.iter().map(|shard| &shard[..]), &mut dst),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concat_naive_with_uncompressed_input() {
+        let mut dst = Vec::new();
+
+        assert!(matches!(
+            concat_naive([HEADER], &mut dst),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+}
@@ -0,0 +1,697 @@
+//! Variant consensus sequences.
+//!
+//! This applies variants to a reference sequence to create a consensus or haplotype sequence,
+//! similar to `bcftools consensus`. Alleles are selected from the alternate alleles of a record or
+//! the genotype of a sample, single base substitutions can be applied as IUPAC ambiguity codes,
+//! and reference sequence intervals can be masked. The coordinate mapping between each reference
+//! sequence and its consensus sequence is given as a chain.
+
+mod builder;
+mod chain;
+mod mask;
+
+use std::{collections::HashSet, io};
+
+use noodles_fasta as fasta;
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{
+            alternate_bases::Allele,
+            samples::{keys::key, series::Value, Sample as _},
+            AlternateBases as _, Samples as _,
+        },
+        Record,
+    },
+};
+
+pub use self::{
+    builder::Builder,
+    chain::{Block, Chain},
+    mask::Mask,
+};
+
+// Indexed by a bitmask of A (1), C (2), G (4), and T (8).
+const AMBIGUITY_CODES: [u8; 16] = *b"NACMGRSVTWYHKDBN";
+
+/// A consensus sequence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Consensus {
+    record: fasta::Record,
+    chain: Chain,
+    skipped_record_count: usize,
+}
+
+impl Consensus {
+    /// Returns the consensus sequence record.
+    pub fn record(&self) -> &fasta::Record {
+        &self.record
+    }
+
+    /// Returns the coordinate mapping from the reference sequence to the consensus sequence.
+    pub fn chain(&self) -> &Chain {
+        &self.chain
+    }
+
+    /// Returns the number of records that were not applied.
+    ///
+    /// These are records that overlap a previously applied record or a masked interval or that
+    /// have an unsupported (e.g., symbolic or breakend) allele.
+    pub fn skipped_record_count(&self) -> usize {
+        self.skipped_record_count
+    }
+}
+
+impl From<Consensus> for fasta::Record {
+    fn from(consensus: Consensus) -> Self {
+        consensus.record
+    }
+}
+
+enum Selection {
+    Reference,
+    Bases(Vec<u8>),
+    Unsupported,
+}
+
+#[derive(Debug)]
+struct State {
+    name: String,
+    reference_sequence: Vec<u8>,
+    // The 1-based position of the next reference base to copy.
+    position: usize,
+    sequence: Vec<u8>,
+    chain: Chain,
+    skipped_record_count: usize,
+}
+
+/// A variant applier.
+///
+/// This creates a consensus sequence for each reference sequence with records. Reference
+/// sequences without records are unchanged.
+#[derive(Debug)]
+pub struct Applier {
+    reference_sequence_repository: fasta::Repository,
+    sample_name: Option<String>,
+    haplotype: Option<usize>,
+    use_ambiguity_codes: bool,
+    mask: Mask,
+    mask_base: u8,
+    finished_reference_sequence_names: HashSet<String>,
+    state: Option<State>,
+}
+
+impl Applier {
+    /// Creates a variant applier with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::consensus::Applier;
+    /// let applier = Applier::new(fasta::Repository::default());
+    /// ```
+    pub fn new(reference_sequence_repository: fasta::Repository) -> Self {
+        Builder::default().build(reference_sequence_repository)
+    }
+
+    /// Returns a builder to create a variant applier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::Applier;
+    /// let builder = Applier::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Adds a variant record.
+    ///
+    /// Records must be sorted. When the record is on a different reference sequence than the
+    /// previous record, the consensus sequence of the previous reference sequence is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::consensus::Applier;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let repository = fasta::Repository::new(vec![fasta::Record::new(
+    ///     fasta::record::Definition::new("sq0", None),
+    ///     fasta::record::Sequence::from(b"ACGTACGT".to_vec()),
+    /// )]);
+    ///
+    /// let header = vcf::Header::default();
+    /// let mut reader = vcf::io::Reader::new(&b"sq0\t3\t.\tG\tTT\t.\t.\t.\n"[..]);
+    /// let record = reader.records().next().transpose()?.unwrap();
+    ///
+    /// let mut applier = Applier::new(repository);
+    /// assert!(applier.add(&header, &record)?.is_none());
+    ///
+    /// let consensus = applier.finish().expect("missing consensus");
+    /// assert_eq!(consensus.record().sequence().as_ref(), b"ACTTTACGT");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add<R>(&mut self, header: &vcf::Header, record: &R) -> io::Result<Option<Consensus>>
+    where
+        R: Record + ?Sized,
+    {
+        let reference_sequence_name = record.reference_sequence_name(header)?;
+
+        let mut consensus = None;
+
+        let mut state = match self.state.take() {
+            Some(state) if state.name == reference_sequence_name => state,
+            Some(state) => {
+                consensus = Some(self.finish_state(state));
+                self.new_state(reference_sequence_name)?
+            }
+            None => self.new_state(reference_sequence_name)?,
+        };
+
+        let result = self.apply(header, &mut state, record);
+        self.state = Some(state);
+        result?;
+
+        Ok(consensus)
+    }
+
+    /// Finishes applying variants and returns the consensus sequence of the last reference
+    /// sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::consensus::Applier;
+    /// let mut applier = Applier::new(fasta::Repository::default());
+    /// assert!(applier.finish().is_none());
+    /// ```
+    pub fn finish(&mut self) -> Option<Consensus> {
+        self.state.take().map(|state| self.finish_state(state))
+    }
+
+    fn new_state(&mut self, reference_sequence_name: &str) -> io::Result<State> {
+        if self
+            .finished_reference_sequence_names
+            .contains(reference_sequence_name)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unsorted input",
+            ));
+        }
+
+        let sequence = self
+            .reference_sequence_repository
+            .get(reference_sequence_name.as_bytes())
+            .transpose()?
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("missing reference sequence: {reference_sequence_name}"),
+                )
+            })?;
+
+        let mut reference_sequence = sequence.as_ref().to_vec();
+
+        for &(start, end) in self.mask.intervals(reference_sequence_name.as_bytes()) {
+            let end = end.min(reference_sequence.len());
+
+            if let Some(bases) = reference_sequence.get_mut(start - 1..end) {
+                bases.fill(self.mask_base);
+            }
+        }
+
+        let id = self.finished_reference_sequence_names.len() + 1;
+
+        Ok(State {
+            name: reference_sequence_name.into(),
+            reference_sequence,
+            position: 1,
+            sequence: Vec::new(),
+            chain: Chain::new(id, reference_sequence_name.into()),
+            skipped_record_count: 0,
+        })
+    }
+
+    fn finish_state(&mut self, mut state: State) -> Consensus {
+        let bases = &state.reference_sequence[state.position - 1..];
+        state.sequence.extend_from_slice(bases);
+        state.chain.push_match(bases.len());
+
+        self.finished_reference_sequence_names
+            .insert(state.name.clone());
+
+        Consensus {
+            record: fasta::Record::new(
+                fasta::record::Definition::new(state.name, None),
+                fasta::record::Sequence::from(state.sequence),
+            ),
+            chain: state.chain,
+            skipped_record_count: state.skipped_record_count,
+        }
+    }
+
+    fn apply<R>(&self, header: &vcf::Header, state: &mut State, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let start = record
+            .variant_start()
+            .transpose()?
+            .map(usize::from)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing position"))?;
+
+        let reference_bases: Vec<u8> =
+            record.reference_bases().iter().collect::<io::Result<_>>()?;
+
+        if reference_bases.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing reference bases",
+            ));
+        }
+
+        let end = start + reference_bases.len() - 1;
+
+        let bases = match self.select(header, record, &reference_bases)? {
+            Selection::Reference => return Ok(()),
+            Selection::Bases(bases) => bases,
+            Selection::Unsupported => {
+                state.skipped_record_count += 1;
+                return Ok(());
+            }
+        };
+
+        if start < state.position || self.mask.overlaps(state.name.as_bytes(), start, end) {
+            state.skipped_record_count += 1;
+            return Ok(());
+        }
+
+        let actual_reference_bases =
+            state
+                .reference_sequence
+                .get(start - 1..end)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "variant out of reference sequence bounds",
+                    )
+                })?;
+
+        if !actual_reference_bases.eq_ignore_ascii_case(&reference_bases) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "reference bases mismatch at {}:{start}: expected {}, got {}",
+                    state.name,
+                    String::from_utf8_lossy(actual_reference_bases),
+                    String::from_utf8_lossy(&reference_bases),
+                ),
+            ));
+        }
+
+        let unchanged_bases = &state.reference_sequence[state.position - 1..start - 1];
+        state.sequence.extend_from_slice(unchanged_bases);
+        state.chain.push_match(unchanged_bases.len());
+
+        // The shared prefix of the reference and applied alleles is aligned.
+        let aligned_len = reference_bases.len().min(bases.len());
+        state.sequence.extend_from_slice(&bases);
+        state.chain.push_match(aligned_len);
+        state.chain.push_gap(
+            reference_bases.len() - aligned_len,
+            bases.len() - aligned_len,
+        );
+
+        state.position = end + 1;
+
+        Ok(())
+    }
+
+    fn select<R>(
+        &self,
+        header: &vcf::Header,
+        record: &R,
+        reference_bases: &[u8],
+    ) -> io::Result<Selection>
+    where
+        R: Record + ?Sized,
+    {
+        let alternate_bases = record.alternate_bases();
+        let alleles: Vec<_> = alternate_bases.alleles().collect::<io::Result<_>>()?;
+
+        let allele_indices: Vec<usize> = match &self.sample_name {
+            Some(sample_name) => {
+                let genotype = sample_genotype(header, record, sample_name)?;
+
+                match self.haplotype {
+                    Some(i) => match genotype.get(i).copied().flatten() {
+                        Some(j) => vec![j],
+                        None => return Ok(Selection::Reference),
+                    },
+                    None => {
+                        let mut indices: Vec<_> = genotype.into_iter().flatten().collect();
+                        indices.sort_unstable();
+                        indices.dedup();
+                        indices
+                    }
+                }
+            }
+            None if alleles.is_empty() => return Ok(Selection::Reference),
+            None if self.use_ambiguity_codes => (0..=alleles.len()).collect(),
+            None => vec![1],
+        };
+
+        if self.use_ambiguity_codes && self.haplotype.is_none() && allele_indices.len() > 1 {
+            if let Some(code) = ambiguity_code(reference_bases, &alleles, &allele_indices)? {
+                return Ok(Selection::Bases(vec![code]));
+            }
+        }
+
+        let Some(i) = allele_indices.into_iter().find(|&i| i > 0) else {
+            return Ok(Selection::Reference);
+        };
+
+        match get_allele(&alleles, i)? {
+            Some(Allele::Bases(bases)) => Ok(Selection::Bases(bases.as_bytes().to_vec())),
+            Some(Allele::OverlappingDeletion) => Ok(Selection::Reference),
+            Some(Allele::Symbol(_) | Allele::Breakend(_)) => Ok(Selection::Unsupported),
+            None => Ok(Selection::Reference),
+        }
+    }
+}
+
+fn sample_genotype<R>(
+    header: &vcf::Header,
+    record: &R,
+    sample_name: &str,
+) -> io::Result<Vec<Option<usize>>>
+where
+    R: Record + ?Sized,
+{
+    let i = header
+        .sample_names()
+        .get_index_of(sample_name)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("sample not in header: {sample_name}"),
+            )
+        })?;
+
+    let samples = record.samples()?;
+
+    let Some(sample) = samples.iter().nth(i) else {
+        return Ok(Vec::new());
+    };
+
+    let positions = match sample.get(header, key::GENOTYPE).transpose()? {
+        Some(Some(Value::Genotype(genotype))) => genotype
+            .iter()
+            .map(|result| result.map(|(position, _)| position))
+            .collect::<io::Result<_>>()?,
+        _ => Vec::new(),
+    };
+
+    Ok(positions)
+}
+
+fn get_allele<'a>(alleles: &[Allele<'a>], i: usize) -> io::Result<Option<Allele<'a>>> {
+    if i == 0 {
+        return Ok(None);
+    }
+
+    alleles
+        .get(i - 1)
+        .copied()
+        .map(Some)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid allele index"))
+}
+
+// Returns `None` if any allele is not a single base.
+fn ambiguity_code(
+    reference_bases: &[u8],
+    alleles: &[Allele<'_>],
+    allele_indices: &[usize],
+) -> io::Result<Option<u8>> {
+    let mut mask = 0;
+
+    for &i in allele_indices {
+        let base = match get_allele(alleles, i)? {
+            None => reference_bases,
+            Some(Allele::Bases(bases)) => bases.as_bytes(),
+            Some(_) => return Ok(None),
+        };
+
+        let [base] = base else {
+            return Ok(None);
+        };
+
+        mask |= match base.to_ascii_uppercase() {
+            b'A' => 1,
+            b'C' => 2,
+            b'G' => 4,
+            b'T' => 8,
+            _ => return Ok(None),
+        };
+    }
+
+    Ok(Some(AMBIGUITY_CODES[mask]))
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::Position;
+
+    use super::*;
+
+    const DATA: &str = "##fileformat=VCFv4.5
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+#CHROM	POS	ID	REF	ALT	QUAL	FILTER	INFO	FORMAT	sample0
+sq0	2	.	C	T	.	.	.	GT	0/1
+sq0	4	.	TAC	T	.	.	.	GT	1/1
+sq0	5	.	A	G	.	.	.	GT	1/1
+sq0	9	.	A	AGG	.	.	.	GT	0/0
+sq0	11	.	G	GTT	.	.	.	GT	1|0
+sq0	13	.	A	<DEL>	.	.	.	GT	1/1
+sq1	2	.	A	C,G	.	.	.	GT	2/1
+";
+
+    fn sequences(consensuses: &[Consensus]) -> Vec<&[u8]> {
+        consensuses
+            .iter()
+            .map(|consensus| consensus.record().sequence().as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_add() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = fasta::Repository::new(vec![
+            fasta::Record::new(
+                fasta::record::Definition::new("sq0", None),
+                fasta::record::Sequence::from(b"ACGTACGTACGTACGTACGT".to_vec()),
+            ),
+            fasta::Record::new(
+                fasta::record::Definition::new("sq1", None),
+                fasta::record::Sequence::from(b"aaaa".to_vec()),
+            ),
+        ]);
+
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let records: Vec<_> = reader.records().collect::<io::Result<_>>()?;
+
+        let mut applier = Applier::new(repository);
+        let mut consensuses = Vec::new();
+
+        for record in &records {
+            consensuses.extend(applier.add(&header, record)?);
+        }
+
+        consensuses.extend(applier.finish());
+
+        assert_eq!(
+            sequences(&consensuses),
+            [&b"ATGTGTAGGCGTTTACGTACGT"[..], b"aCaa"]
+        );
+
+        assert_eq!(consensuses[0].record().name(), b"sq0");
+        assert_eq!(consensuses[0].skipped_record_count(), 2);
+        assert_eq!(consensuses[1].skipped_record_count(), 0);
+
+        let chain = consensuses[0].chain();
+        assert_eq!(chain.reference_sequence_length(), 20);
+        assert_eq!(chain.query_sequence_length(), 22);
+
+        let map = |n| -> Result<Option<usize>, Box<dyn std::error::Error>> {
+            Ok(chain.map(Position::try_from(n)?).map(usize::from))
+        };
+
+        assert_eq!(map(4)?, Some(4));
+        assert_eq!(map(5)?, None);
+        assert_eq!(map(7)?, Some(5));
+        assert_eq!(map(10)?, Some(10));
+        assert_eq!(map(12)?, Some(14));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_sample() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = fasta::Repository::new(vec![
+            fasta::Record::new(
+                fasta::record::Definition::new("sq0", None),
+                fasta::record::Sequence::from(b"ACGTACGTACGTACGTACGT".to_vec()),
+            ),
+            fasta::Record::new(
+                fasta::record::Definition::new("sq1", None),
+                fasta::record::Sequence::from(b"aaaa".to_vec()),
+            ),
+        ]);
+
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let records: Vec<_> = reader.records().collect::<io::Result<_>>()?;
+
+        let builders = [
+            (
+                Applier::builder().set_sample_name("sample0"),
+                [&b"ATGTGTACGTTTACGTACGT"[..], b"aCaa"],
+            ),
+            (
+                Applier::builder()
+                    .set_sample_name("sample0")
+                    .set_haplotype(1),
+                [&b"ATGTGTACGTACGTACGT"[..], b"aCaa"],
+            ),
+            (
+                Applier::builder()
+                    .set_sample_name("sample0")
+                    .set_use_ambiguity_codes(true),
+                [&b"AYGTGTACGTTTACGTACGT"[..], b"aSaa"],
+            ),
+        ];
+
+        for (builder, expected) in builders {
+            let mut applier = builder.build(repository.clone());
+            let mut consensuses = Vec::new();
+
+            for record in &records {
+                consensuses.extend(applier.add(&header, record)?);
+            }
+
+            consensuses.extend(applier.finish());
+
+            assert_eq!(sequences(&consensuses), expected);
+        }
+
+        let mut applier = Applier::builder()
+            .set_sample_name("sample1")
+            .build(repository);
+        assert!(matches!(
+            applier.add(&header, &records[0]),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_mask() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = fasta::Repository::new(vec![
+            fasta::Record::new(
+                fasta::record::Definition::new("sq0", None),
+                fasta::record::Sequence::from(b"ACGTACGTACGTACGTACGT".to_vec()),
+            ),
+            fasta::Record::new(
+                fasta::record::Definition::new("sq1", None),
+                fasta::record::Sequence::from(b"aaaa".to_vec()),
+            ),
+        ]);
+
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let records: Vec<_> = reader.records().collect::<io::Result<_>>()?;
+
+        let mut mask = Mask::default();
+        mask.insert(b"sq0", Position::MIN, Position::try_from(2)?);
+        mask.insert(b"sq1", Position::try_from(4)?, Position::try_from(8)?);
+
+        let mut applier = Applier::builder()
+            .set_sample_name("sample0")
+            .set_mask(mask)
+            .build(repository);
+        let mut consensuses = Vec::new();
+
+        for record in &records {
+            consensuses.extend(applier.add(&header, record)?);
+        }
+
+        consensuses.extend(applier.finish());
+
+        assert_eq!(
+            sequences(&consensuses),
+            [&b"NNGTGTACGTTTACGTACGT"[..], b"aCaN"]
+        );
+        assert_eq!(consensuses[0].skipped_record_count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_monomorphic_record() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq1", None),
+            fasta::record::Sequence::from(b"aaaa".to_vec()),
+        )]);
+
+        let src = "##fileformat=VCFv4.5
+#CHROM	POS	ID	REF	ALT	QUAL	FILTER	INFO
+sq1	2	.	A	.	.	.	.
+";
+        let mut reader = vcf::io::Reader::new(src.as_bytes());
+        let header = reader.read_header()?;
+        let record = reader.records().next().transpose()?.unwrap();
+
+        let mut applier = Applier::new(repository);
+        assert!(applier.add(&header, &record)?.is_none());
+
+        let consensus = applier.finish().unwrap();
+        assert_eq!(consensus.record().sequence().as_ref(), b"aaaa");
+        assert_eq!(consensus.skipped_record_count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_reference_bases_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = fasta::Repository::new(vec![
+            fasta::Record::new(
+                fasta::record::Definition::new("sq0", None),
+                fasta::record::Sequence::from(b"ACGTACGTACGTACGTACGT".to_vec()),
+            ),
+            fasta::Record::new(
+                fasta::record::Definition::new("sq1", None),
+                fasta::record::Sequence::from(b"aaaa".to_vec()),
+            ),
+        ]);
+
+        let src = DATA.replace("sq0\t2\t.\tC", "sq0\t2\t.\tG");
+        let mut reader = vcf::io::Reader::new(src.as_bytes());
+        let header = reader.read_header()?;
+        let record = reader.records().next().transpose()?.unwrap();
+
+        let mut applier = Applier::new(repository);
+        assert!(matches!(
+            applier.add(&header, &record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
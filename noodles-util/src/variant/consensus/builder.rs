@@ -0,0 +1,132 @@
+use noodles_fasta as fasta;
+
+use super::{Applier, Mask};
+
+/// A variant applier builder.
+#[derive(Debug)]
+pub struct Builder {
+    sample_name: Option<String>,
+    haplotype: Option<usize>,
+    use_ambiguity_codes: bool,
+    mask: Mask,
+    mask_base: u8,
+}
+
+impl Builder {
+    /// Sets the sample whose genotypes select the applied alleles.
+    ///
+    /// By default, no sample is used, and the first alternate allele of each record is applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::Applier;
+    /// let builder = Applier::builder().set_sample_name("sample0");
+    /// ```
+    pub fn set_sample_name<S>(mut self, sample_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.sample_name = Some(sample_name.into());
+        self
+    }
+
+    /// Sets the 0-based index of the genotype allele to apply.
+    ///
+    /// This is only used with a sample. By default, the first non-reference allele in the
+    /// genotype is applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::Applier;
+    /// let builder = Applier::builder().set_sample_name("sample0").set_haplotype(1);
+    /// ```
+    pub fn set_haplotype(mut self, haplotype: usize) -> Self {
+        self.haplotype = Some(haplotype);
+        self
+    }
+
+    /// Sets whether to apply single base substitutions with multiple alleles as IUPAC ambiguity
+    /// codes.
+    ///
+    /// Without a sample, the reference and all alternate alleles are combined. With a sample, the
+    /// alleles in its genotype are combined. This is not used when a haplotype is set. By
+    /// default, ambiguity codes are not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::Applier;
+    /// let builder = Applier::builder().set_use_ambiguity_codes(true);
+    /// ```
+    pub fn set_use_ambiguity_codes(mut self, use_ambiguity_codes: bool) -> Self {
+        self.use_ambiguity_codes = use_ambiguity_codes;
+        self
+    }
+
+    /// Sets the masked reference sequence intervals.
+    ///
+    /// Masked positions are replaced with the mask base, and records that overlap a masked
+    /// position are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::{Applier, Mask};
+    /// let builder = Applier::builder().set_mask(Mask::default());
+    /// ```
+    pub fn set_mask(mut self, mask: Mask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets the base that replaces masked positions.
+    ///
+    /// By default, this is `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::consensus::Applier;
+    /// let builder = Applier::builder().set_mask_base(b'n');
+    /// ```
+    pub fn set_mask_base(mut self, mask_base: u8) -> Self {
+        self.mask_base = mask_base;
+        self
+    }
+
+    /// Builds a variant applier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::consensus::Applier;
+    /// let applier = Applier::builder().build(fasta::Repository::default());
+    /// ```
+    pub fn build(self, reference_sequence_repository: fasta::Repository) -> Applier {
+        Applier {
+            reference_sequence_repository,
+            sample_name: self.sample_name,
+            haplotype: self.haplotype,
+            use_ambiguity_codes: self.use_ambiguity_codes,
+            mask: self.mask,
+            mask_base: self.mask_base,
+            finished_reference_sequence_names: Default::default(),
+            state: None,
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            sample_name: None,
+            haplotype: None,
+            use_ambiguity_codes: false,
+            mask: Mask::default(),
+            mask_base: b'N',
+        }
+    }
+}
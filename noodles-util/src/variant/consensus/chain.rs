@@ -0,0 +1,259 @@
+use std::fmt;
+
+use noodles_core::Position;
+
+/// An ungapped alignment block of a chain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Block {
+    size: usize,
+    reference_gap: usize,
+    query_gap: usize,
+}
+
+impl Block {
+    /// Returns the length of the ungapped alignment.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of reference bases between this block and the next.
+    pub fn reference_gap(&self) -> usize {
+        self.reference_gap
+    }
+
+    /// Returns the number of query bases between this block and the next.
+    pub fn query_gap(&self) -> usize {
+        self.query_gap
+    }
+}
+
+/// A coordinate mapping between a reference sequence and its consensus sequence.
+///
+/// This is formatted as a [UCSC chain] with the reference sequence as the target and the
+/// consensus sequence as the query.
+///
+/// [UCSC chain]: https://genome.ucsc.edu/goldenPath/help/chain.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chain {
+    id: usize,
+    reference_sequence_name: String,
+    reference_sequence_length: usize,
+    query_sequence_name: String,
+    query_sequence_length: usize,
+    blocks: Vec<Block>,
+}
+
+impl Chain {
+    pub(super) fn new(id: usize, reference_sequence_name: String) -> Self {
+        Self {
+            id,
+            query_sequence_name: reference_sequence_name.clone(),
+            reference_sequence_name,
+            reference_sequence_length: 0,
+            query_sequence_length: 0,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Returns the chain ID.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the reference sequence name.
+    pub fn reference_sequence_name(&self) -> &str {
+        &self.reference_sequence_name
+    }
+
+    /// Returns the reference sequence length.
+    pub fn reference_sequence_length(&self) -> usize {
+        self.reference_sequence_length
+    }
+
+    /// Returns the query sequence name.
+    pub fn query_sequence_name(&self) -> &str {
+        &self.query_sequence_name
+    }
+
+    /// Returns the query sequence length.
+    pub fn query_sequence_length(&self) -> usize {
+        self.query_sequence_length
+    }
+
+    /// Returns the alignment blocks.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Maps a reference sequence position to a query sequence position.
+    ///
+    /// This returns `None` if the position is deleted in or out of bounds of the query sequence.
+    pub fn map(&self, position: Position) -> Option<Position> {
+        let position = usize::from(position);
+
+        let mut reference_start = 1;
+        let mut query_start = 1;
+
+        for block in &self.blocks {
+            if position < reference_start {
+                return None;
+            }
+
+            if position < reference_start + block.size {
+                return Position::new(query_start + (position - reference_start));
+            }
+
+            reference_start += block.size + block.reference_gap;
+            query_start += block.size + block.query_gap;
+        }
+
+        None
+    }
+
+    pub(super) fn push_match(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+
+        match self.blocks.last_mut() {
+            Some(block) if block.reference_gap == 0 && block.query_gap == 0 => block.size += len,
+            _ => self.blocks.push(Block {
+                size: len,
+                reference_gap: 0,
+                query_gap: 0,
+            }),
+        }
+
+        self.reference_sequence_length += len;
+        self.query_sequence_length += len;
+    }
+
+    pub(super) fn push_gap(&mut self, reference_len: usize, query_len: usize) {
+        if reference_len == 0 && query_len == 0 {
+            return;
+        }
+
+        match self.blocks.last_mut() {
+            Some(block) => {
+                block.reference_gap += reference_len;
+                block.query_gap += query_len;
+            }
+            // Gaps before the first ungapped block are kept in an empty block.
+            None => self.blocks.push(Block {
+                size: 0,
+                reference_gap: reference_len,
+                query_gap: query_len,
+            }),
+        }
+
+        self.reference_sequence_length += reference_len;
+        self.query_sequence_length += query_len;
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blocks = &self.blocks[..];
+
+        // Gaps before the first or after the last ungapped block are unaligned ends.
+        let (reference_start, query_start) = match blocks.split_first() {
+            Some((block, rest)) if block.size == 0 => {
+                blocks = rest;
+                (block.reference_gap, block.query_gap)
+            }
+            _ => (0, 0),
+        };
+
+        let (reference_end, query_end) = match blocks.last() {
+            Some(block) => (
+                self.reference_sequence_length - block.reference_gap,
+                self.query_sequence_length - block.query_gap,
+            ),
+            None => (reference_start, query_start),
+        };
+
+        writeln!(
+            f,
+            "chain 0 {} {} + {} {} {} {} + {} {} {}",
+            self.reference_sequence_name,
+            self.reference_sequence_length,
+            reference_start,
+            reference_end,
+            self.query_sequence_name,
+            self.query_sequence_length,
+            query_start,
+            query_end,
+            self.id,
+        )?;
+
+        if let Some((last_block, blocks)) = blocks.split_last() {
+            for block in blocks {
+                writeln!(
+                    f,
+                    "{}\t{}\t{}",
+                    block.size, block.reference_gap, block.query_gap
+                )?;
+            }
+
+            writeln!(f, "{}", last_block.size)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() -> Result<(), noodles_core::position::TryFromIntError> {
+        // reference: ACGT--ACGTAC
+        // query:     ACGTTTACGT-C
+        let mut chain = Chain::new(1, String::from("sq0"));
+        chain.push_match(4);
+        chain.push_gap(0, 2);
+        chain.push_match(4);
+        chain.push_gap(1, 0);
+        chain.push_match(1);
+
+        let map = |n| -> Result<Option<usize>, noodles_core::position::TryFromIntError> {
+            Ok(chain.map(Position::try_from(n)?).map(usize::from))
+        };
+
+        assert_eq!(map(1)?, Some(1));
+        assert_eq!(map(4)?, Some(4));
+        assert_eq!(map(5)?, Some(7));
+        assert_eq!(map(8)?, Some(10));
+        assert_eq!(map(9)?, None);
+        assert_eq!(map(10)?, Some(11));
+        assert_eq!(map(11)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fmt() {
+        // reference: ACGT--ACGTAC
+        // query:     ACGTTTACGT-C
+        let mut chain = Chain::new(1, String::from("sq0"));
+        chain.push_match(4);
+        chain.push_gap(0, 2);
+        chain.push_match(4);
+        chain.push_gap(1, 0);
+        chain.push_match(1);
+        assert_eq!(chain.reference_sequence_length(), 10);
+        assert_eq!(chain.query_sequence_length(), 11);
+
+        let expected = "chain 0 sq0 10 + 0 10 sq0 11 + 0 11 1\n4\t0\t2\n4\t1\t0\n1\n";
+        assert_eq!(chain.to_string(), expected);
+
+        let mut chain = Chain::new(2, String::from("sq1"));
+        chain.push_gap(2, 0);
+        chain.push_match(3);
+        chain.push_gap(0, 1);
+
+        let expected = "chain 0 sq1 5 + 2 5 sq1 4 + 0 3 2\n3\n";
+        assert_eq!(chain.to_string(), expected);
+    }
+}
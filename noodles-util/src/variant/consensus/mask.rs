@@ -0,0 +1,161 @@
+use std::{collections::HashMap, io};
+
+use noodles_bed as bed;
+use noodles_core::Position;
+
+/// A set of masked intervals grouped by reference sequence name.
+///
+/// Overlapping and adjacent intervals are merged.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Mask(HashMap<Vec<u8>, Vec<(usize, usize)>>);
+
+impl Mask {
+    /// Adds a masked interval.
+    ///
+    /// The start and end positions are 1-based, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_util::variant::consensus::Mask;
+    ///
+    /// let mut mask = Mask::default();
+    /// mask.insert(b"sq0", Position::try_from(8)?, Position::try_from(13)?);
+    /// mask.insert(b"sq0", Position::try_from(13)?, Position::try_from(21)?);
+    ///
+    /// assert_eq!(mask.len(), 1);
+    /// # Ok::<_, noodles_core::position::TryFromIntError>(())
+    /// ```
+    pub fn insert<N>(&mut self, reference_sequence_name: N, start: Position, end: Position)
+    where
+        N: Into<Vec<u8>>,
+    {
+        let (mut start, mut end) = (usize::from(start), usize::from(end));
+
+        if start > end {
+            return;
+        }
+
+        let intervals = self.0.entry(reference_sequence_name.into()).or_default();
+
+        let i = intervals.partition_point(|&(_, e)| e + 1 < start);
+        let j = intervals.partition_point(|&(s, _)| s <= end + 1);
+
+        if i < j {
+            start = start.min(intervals[i].0);
+            end = end.max(intervals[j - 1].1);
+        }
+
+        intervals.splice(i..j, [(start, end)]);
+    }
+
+    /// Adds a masked interval from a BED record.
+    ///
+    /// Records without an end position are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_bed as bed;
+    /// use noodles_util::variant::consensus::Mask;
+    ///
+    /// let data = b"sq0\t7\t13\nsq1\t92\t100\n";
+    /// let mut reader = bed::io::Reader::<3, _>::new(&data[..]);
+    ///
+    /// let mut mask = Mask::default();
+    /// let mut record = bed::Record::<3>::default();
+    ///
+    /// while reader.read_record(&mut record)? != 0 {
+    ///     mask.insert_from_bed_record(&record)?;
+    /// }
+    ///
+    /// assert_eq!(mask.len(), 2);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn insert_from_bed_record<const N: usize, R>(&mut self, record: &R) -> io::Result<()>
+    where
+        R: bed::feature::Record<N>,
+    {
+        let start = record.feature_start()?;
+
+        let end = record
+            .feature_end()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing feature end"))??;
+
+        self.insert(record.reference_sequence_name().to_vec(), start, end);
+
+        Ok(())
+    }
+
+    /// Returns the number of masked intervals.
+    pub fn len(&self) -> usize {
+        self.0.values().map(|intervals| intervals.len()).sum()
+    }
+
+    /// Returns whether there are any masked intervals.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the masked intervals on the given reference sequence.
+    pub(super) fn intervals(&self, reference_sequence_name: &[u8]) -> &[(usize, usize)] {
+        self.0
+            .get(reference_sequence_name)
+            .map(|intervals| &intervals[..])
+            .unwrap_or_default()
+    }
+
+    /// Returns whether any position in the given 1-based, inclusive interval is masked.
+    pub(super) fn overlaps(
+        &self,
+        reference_sequence_name: &[u8],
+        start: usize,
+        end: usize,
+    ) -> bool {
+        let intervals = self.intervals(reference_sequence_name);
+        let i = intervals.partition_point(|&(_, e)| e < start);
+        intervals.get(i).is_some_and(|&(s, _)| s <= end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() -> Result<(), noodles_core::position::TryFromIntError> {
+        let mut mask = Mask::default();
+
+        let mut insert = |start, end| -> Result<(), noodles_core::position::TryFromIntError> {
+            mask.insert(b"sq0", Position::try_from(start)?, Position::try_from(end)?);
+            Ok(())
+        };
+
+        insert(20, 30)?;
+        insert(1, 5)?;
+        insert(40, 50)?;
+        insert(6, 8)?;
+        insert(25, 45)?;
+
+        assert_eq!(mask.intervals(b"sq0"), [(1, 8), (20, 50)]);
+        assert!(mask.intervals(b"sq1").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlaps() -> Result<(), noodles_core::position::TryFromIntError> {
+        let mut mask = Mask::default();
+        mask.insert(b"sq0", Position::try_from(8)?, Position::try_from(13)?);
+
+        assert!(mask.overlaps(b"sq0", 13, 15));
+        assert!(mask.overlaps(b"sq0", 1, 8));
+        assert!(!mask.overlaps(b"sq0", 1, 7));
+        assert!(!mask.overlaps(b"sq0", 14, 20));
+        assert!(!mask.overlaps(b"sq1", 8, 13));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,599 @@
+//! Allele count and genotype summary tags.
+//!
+//! A [`Filler`] recomputes INFO fields such as `AC`, `AN`, and `AF` from the genotypes (`GT`) of
+//! a record. This is typically done after subsetting samples, which otherwise leaves these fields
+//! stale. Tags can also be computed over groups of samples, e.g., populations.
+
+mod builder;
+mod hwe;
+pub mod tag;
+
+use std::io;
+
+use noodles_vcf::{
+    self as vcf,
+    header::record::value::map::info,
+    variant::{
+        record::samples::keys::key,
+        record_buf::{info::field::Value, samples::sample::Value as SampleValue},
+        RecordBuf,
+    },
+};
+
+pub use self::{builder::Builder, tag::Tag};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Group {
+    name: String,
+    sample_names: Vec<String>,
+}
+
+/// A tag filler.
+#[derive(Debug)]
+pub struct Filler {
+    tags: Vec<Tag>,
+    groups: Vec<Group>,
+}
+
+impl Filler {
+    /// Creates a tag filler that computes all tags over all samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::Filler;
+    /// let filler = Filler::new();
+    /// ```
+    pub fn new() -> Self {
+        Builder::default().build()
+    }
+
+    /// Returns a builder to create a tag filler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::Filler;
+    /// let builder = Filler::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Adds or replaces the INFO header records of the computed tags.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::{Filler, Tag};
+    /// use noodles_vcf as vcf;
+    ///
+    /// let filler = Filler::builder()
+    ///     .set_tags([Tag::AlleleCount])
+    ///     .add_group("EUR", ["sample0"])
+    ///     .build();
+    ///
+    /// let mut header = vcf::Header::default();
+    /// filler.add_header_definitions(&mut header);
+    ///
+    /// assert!(header.infos().contains_key("AC"));
+    /// assert!(header.infos().contains_key("AC_EUR"));
+    /// ```
+    pub fn add_header_definitions(&self, header: &mut vcf::Header) {
+        let group_names = self.groups.iter().map(|group| Some(group.name.as_str()));
+
+        for group_name in [None].into_iter().chain(group_names) {
+            for tag in &self.tags {
+                header
+                    .infos_mut()
+                    .insert(tag_key(*tag, group_name), tag.definition(group_name));
+            }
+        }
+    }
+
+    /// Computes the tags from the genotypes of the given record and sets them as INFO fields.
+    ///
+    /// Existing values of the computed tags are replaced. Tags with a number of `A` are removed
+    /// from records without alternate alleles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::{Filler, Tag};
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     variant::{
+    ///         record_buf::{
+    ///             info::field::Value,
+    ///             samples::{sample::Value as SampleValue, Keys},
+    ///             AlternateBases, Samples,
+    ///         },
+    ///         RecordBuf,
+    ///     },
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .build();
+    ///
+    /// let mut record = RecordBuf::builder()
+    ///     .set_reference_bases("A")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+    ///     .set_samples(Samples::new(
+    ///         Keys::from_iter([String::from("GT")]),
+    ///         vec![
+    ///             vec![Some(SampleValue::Genotype("0/1".parse()?))],
+    ///             vec![Some(SampleValue::Genotype("1/1".parse()?))],
+    ///         ],
+    ///     ))
+    ///     .build();
+    ///
+    /// let filler = Filler::builder()
+    ///     .set_tags([Tag::AlleleCount, Tag::TotalAlleleCount])
+    ///     .build();
+    ///
+    /// filler.fill(&header, &mut record)?;
+    ///
+    /// assert_eq!(record.info().get("AC"), Some(Some(&Value::from(vec![Some(3)]))));
+    /// assert_eq!(record.info().get("AN"), Some(Some(&Value::from(4))));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fill(&self, header: &vcf::Header, record: &mut RecordBuf) -> io::Result<()> {
+        let allele_count = record.alternate_bases().as_ref().len() + 1;
+        let genotypes = read_genotypes(header, record)?;
+
+        let counts = Counts::from_genotypes(allele_count, genotypes.iter())?;
+        self.set_values(record, None, &counts)?;
+
+        for group in &self.groups {
+            let group_genotypes = group
+                .sample_names
+                .iter()
+                .map(|sample_name| {
+                    header
+                        .sample_names()
+                        .get_index_of(sample_name)
+                        .map(|i| &genotypes[i])
+                        .ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!(
+                                    "group {}: sample not in header: {sample_name}",
+                                    group.name
+                                ),
+                            )
+                        })
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+
+            let counts = Counts::from_genotypes(allele_count, group_genotypes.into_iter())?;
+            self.set_values(record, Some(&group.name), &counts)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_values(
+        &self,
+        record: &mut RecordBuf,
+        group_name: Option<&str>,
+        counts: &Counts,
+    ) -> io::Result<()> {
+        let alternate_allele_count = counts.allele_counts.len() - 1;
+
+        for tag in &self.tags {
+            let key = tag_key(*tag, group_name);
+
+            if tag.number() == info::Number::AlternateBases && alternate_allele_count == 0 {
+                record.info_mut().as_mut().shift_remove(&key);
+                continue;
+            }
+
+            let value = counts.value(*tag)?;
+            record.info_mut().insert(key, value);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Filler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn tag_key(tag: Tag, group_name: Option<&str>) -> String {
+    match group_name {
+        Some(group_name) => format!("{tag}_{group_name}"),
+        None => tag.to_string(),
+    }
+}
+
+type Genotype = Vec<Option<usize>>;
+
+fn read_genotypes(header: &vcf::Header, record: &RecordBuf) -> io::Result<Vec<Option<Genotype>>> {
+    let sample_count = header.sample_names().len();
+
+    let Some(series) = record.samples().select(key::GENOTYPE) else {
+        return Ok(vec![None; sample_count]);
+    };
+
+    (0..sample_count)
+        .map(|i| match series.get(i).flatten() {
+            Some(SampleValue::Genotype(genotype)) => Ok(Some(
+                genotype
+                    .as_ref()
+                    .iter()
+                    .map(|allele| allele.position())
+                    .collect(),
+            )),
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid GT value",
+            )),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Counts {
+    sample_count: u64,
+    called_sample_count: u64,
+    allele_counts: Vec<u64>,
+    homozygous_allele_counts: Vec<u64>,
+    heterozygous_allele_counts: Vec<u64>,
+    hemizygous_allele_counts: Vec<u64>,
+    // The number of called diploid genotypes and, for each allele, the number of these with
+    // exactly one and two copies of the allele.
+    diploid_genotype_count: u64,
+    diploid_copy_counts: Vec<(u64, u64)>,
+}
+
+impl Counts {
+    fn from_genotypes<'g, I>(allele_count: usize, genotypes: I) -> io::Result<Self>
+    where
+        I: Iterator<Item = &'g Option<Genotype>>,
+    {
+        let mut counts = Self {
+            allele_counts: vec![0; allele_count],
+            homozygous_allele_counts: vec![0; allele_count],
+            heterozygous_allele_counts: vec![0; allele_count],
+            hemizygous_allele_counts: vec![0; allele_count],
+            diploid_copy_counts: vec![(0, 0); allele_count],
+            ..Default::default()
+        };
+
+        for genotype in genotypes {
+            counts.sample_count += 1;
+
+            if let Some(positions) = genotype {
+                counts.add(positions)?;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    fn add(&mut self, positions: &[Option<usize>]) -> io::Result<()> {
+        let allele_count = self.allele_counts.len();
+        let mut is_called = false;
+
+        for &position in positions.iter().flatten() {
+            if position >= allele_count {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid GT allele index: {position}"),
+                ));
+            }
+
+            self.allele_counts[position] += 1;
+            is_called = true;
+        }
+
+        if is_called {
+            self.called_sample_count += 1;
+        }
+
+        // Zygosity is only defined for fully called genotypes.
+        let Some(alleles) = positions.iter().copied().collect::<Option<Vec<_>>>() else {
+            return Ok(());
+        };
+
+        match alleles[..] {
+            [] => {}
+            [a] => self.hemizygous_allele_counts[a] += 1,
+            [a, ..] if alleles.iter().all(|&b| b == a) => {
+                self.homozygous_allele_counts[a] += alleles.len() as u64;
+            }
+            _ => {
+                for &a in &alleles {
+                    self.heterozygous_allele_counts[a] += 1;
+                }
+            }
+        }
+
+        if let [a, b] = alleles[..] {
+            self.diploid_genotype_count += 1;
+
+            if a == b {
+                self.diploid_copy_counts[a].1 += 1;
+            } else {
+                self.diploid_copy_counts[a].0 += 1;
+                self.diploid_copy_counts[b].0 += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn total_allele_count(&self) -> u64 {
+        self.allele_counts.iter().sum()
+    }
+
+    fn value(&self, tag: Tag) -> io::Result<Option<Value>> {
+        let total_allele_count = self.total_allele_count();
+
+        let frequency = |n: u64| {
+            if total_allele_count == 0 {
+                None
+            } else {
+                Some((n as f64 / total_allele_count as f64) as f32)
+            }
+        };
+
+        let value = match tag {
+            Tag::AlleleCount => integers(&self.allele_counts[1..])?,
+            Tag::TotalAlleleCount => Value::from(to_i32(total_allele_count)?),
+            Tag::AlleleFrequencies => Value::from(
+                self.allele_counts[1..]
+                    .iter()
+                    .map(|&n| frequency(n))
+                    .collect::<Vec<_>>(),
+            ),
+            Tag::MinorAlleleFrequency => {
+                let mut allele_counts = self.allele_counts.clone();
+                allele_counts.sort_unstable_by(|a, b| b.cmp(a));
+
+                match frequency(allele_counts.get(1).copied().unwrap_or(0)) {
+                    Some(n) => Value::from(n),
+                    None => return Ok(None),
+                }
+            }
+            Tag::SamplesWithDataCount => Value::from(to_i32(self.called_sample_count)?),
+            Tag::HardyWeinbergEquilibrium => self.exact_tests(|(p_hwe, _)| p_hwe),
+            Tag::ExcessHeterozygosity => self.exact_tests(|(_, p_excess_het)| p_excess_het),
+            Tag::MissingFraction => {
+                if self.sample_count == 0 {
+                    return Ok(None);
+                }
+
+                let missing_sample_count = self.sample_count - self.called_sample_count;
+                Value::from((missing_sample_count as f64 / self.sample_count as f64) as f32)
+            }
+            Tag::HomozygousAlleleCount => integers(&self.homozygous_allele_counts[1..])?,
+            Tag::HeterozygousAlleleCount => integers(&self.heterozygous_allele_counts[1..])?,
+            Tag::HemizygousAlleleCount => integers(&self.hemizygous_allele_counts[1..])?,
+        };
+
+        Ok(Some(value))
+    }
+
+    // Tests each alternate allele against all other alleles using the called diploid genotypes.
+    fn exact_tests<F>(&self, f: F) -> Value
+    where
+        F: Fn((f64, f64)) -> f64,
+    {
+        let values = self.diploid_copy_counts[1..]
+            .iter()
+            .map(|&(het_count, hom_count)| {
+                if self.diploid_genotype_count == 0 {
+                    return None;
+                }
+
+                let other_hom_count = self.diploid_genotype_count - het_count - hom_count;
+                let p = f(hwe::exact_test(het_count, other_hom_count, hom_count));
+                Some(p as f32)
+            })
+            .collect::<Vec<_>>();
+
+        Value::from(values)
+    }
+}
+
+fn integers(counts: &[u64]) -> io::Result<Value> {
+    counts
+        .iter()
+        .map(|&n| to_i32(n).map(Some))
+        .collect::<io::Result<Vec<_>>>()
+        .map(Value::from)
+}
+
+fn to_i32(n: u64) -> io::Result<i32> {
+    i32::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::variant::record_buf::{samples::Keys, AlternateBases, Samples};
+
+    use super::*;
+
+    #[test]
+    fn test_fill() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .add_sample_name("sample2")
+            .add_sample_name("sample3")
+            .build();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_samples(Samples::new(
+                Keys::from_iter([String::from(key::GENOTYPE)]),
+                vec![
+                    vec![Some(SampleValue::Genotype("0/1".parse()?))],
+                    vec![Some(SampleValue::Genotype("1/1".parse()?))],
+                    vec![Some(SampleValue::Genotype("./.".parse()?))],
+                    vec![Some(SampleValue::Genotype("1".parse()?))],
+                ],
+            ))
+            .build();
+
+        let filler = Filler::builder()
+            .add_group("G0", ["sample0", "sample2"])
+            .build();
+
+        filler.fill(&header, &mut record)?;
+
+        let info = record.info();
+        let get = |key: &str| info.get(key).flatten().cloned();
+
+        assert_eq!(get("AC"), Some(Value::from(vec![Some(4)])));
+        assert_eq!(get("AN"), Some(Value::from(5)));
+        assert_eq!(get("AF"), Some(Value::from(vec![Some(0.8)])));
+        assert_eq!(get("MAF"), Some(Value::from(0.2)));
+        assert_eq!(get("NS"), Some(Value::from(3)));
+        assert_eq!(get("HWE"), Some(Value::from(vec![Some(1.0)])));
+        assert_eq!(get("ExcHet"), Some(Value::from(vec![Some(1.0)])));
+        assert_eq!(get("F_MISSING"), Some(Value::from(0.25)));
+        assert_eq!(get("AC_Hom"), Some(Value::from(vec![Some(2)])));
+        assert_eq!(get("AC_Het"), Some(Value::from(vec![Some(1)])));
+        assert_eq!(get("AC_Hemi"), Some(Value::from(vec![Some(1)])));
+
+        assert_eq!(get("AC_G0"), Some(Value::from(vec![Some(1)])));
+        assert_eq!(get("AN_G0"), Some(Value::from(2)));
+        assert_eq!(get("AF_G0"), Some(Value::from(vec![Some(0.5)])));
+        assert_eq!(get("NS_G0"), Some(Value::from(1)));
+        assert_eq!(get("F_MISSING_G0"), Some(Value::from(0.5)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_with_missing_genotypes() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .add_sample_name("sample2")
+            .add_sample_name("sample3")
+            .build();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_samples(Samples::new(
+                Keys::from_iter([String::from(key::GENOTYPE)]),
+                vec![
+                    vec![Some(SampleValue::Genotype("./.".parse()?))],
+                    vec![Some(SampleValue::Genotype("./.".parse()?))],
+                    vec![Some(SampleValue::Genotype("./.".parse()?))],
+                    vec![Some(SampleValue::Genotype("./.".parse()?))],
+                ],
+            ))
+            .build();
+
+        let filler = Filler::new();
+        filler.fill(&header, &mut record)?;
+
+        let info = record.info();
+        assert_eq!(info.get("AN"), Some(Some(&Value::from(0))));
+        assert_eq!(info.get("AF"), Some(Some(&Value::from(vec![None::<f32>]))));
+        assert_eq!(info.get("MAF"), Some(None));
+        assert_eq!(info.get("HWE"), Some(Some(&Value::from(vec![None::<f32>]))));
+        assert_eq!(info.get("F_MISSING"), Some(Some(&Value::from(1.0))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_without_alternate_bases() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .add_sample_name("sample2")
+            .add_sample_name("sample3")
+            .build();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_samples(Samples::new(
+                Keys::from_iter([String::from(key::GENOTYPE)]),
+                vec![
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                ],
+            ))
+            .build();
+
+        record
+            .info_mut()
+            .insert(String::from("AC"), Some(Value::from(vec![Some(1)])));
+
+        let filler = Filler::new();
+        filler.fill(&header, &mut record)?;
+
+        assert!(record.info().get("AC").is_none());
+        assert_eq!(record.info().get("AN"), Some(Some(&Value::from(8))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_with_invalid_inputs() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .add_sample_name("sample2")
+            .add_sample_name("sample3")
+            .build();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_samples(Samples::new(
+                Keys::from_iter([String::from(key::GENOTYPE)]),
+                vec![
+                    vec![Some(SampleValue::Genotype("0/2".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                ],
+            ))
+            .build();
+
+        assert!(matches!(
+            Filler::new().fill(&header, &mut record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_samples(Samples::new(
+                Keys::from_iter([String::from(key::GENOTYPE)]),
+                vec![
+                    vec![Some(SampleValue::Genotype("0/1".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                    vec![Some(SampleValue::Genotype("0/0".parse()?))],
+                ],
+            ))
+            .build();
+
+        let filler = Filler::builder().add_group("G0", ["sample4"]).build();
+
+        assert!(matches!(
+            filler.fill(&header, &mut record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use super::{Filler, Group, Tag};
+
+/// A tag filler builder.
+#[derive(Debug)]
+pub struct Builder {
+    tags: Vec<Tag>,
+    groups: Vec<Group>,
+}
+
+impl Builder {
+    /// Sets the tags to compute.
+    ///
+    /// By default, all tags are computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::{Filler, Tag};
+    /// let builder = Filler::builder().set_tags([Tag::AlleleCount, Tag::TotalAlleleCount]);
+    /// ```
+    pub fn set_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        self.tags.clear();
+
+        for tag in tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+
+        self
+    }
+
+    /// Adds a sample group, e.g., a population.
+    ///
+    /// Tags are also computed over the samples in each group and written with the group name as a
+    /// suffix, e.g., `AC_EUR`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::Filler;
+    /// let builder = Filler::builder().add_group("EUR", ["sample0", "sample1"]);
+    /// ```
+    pub fn add_group<N, I, S>(mut self, name: N, sample_names: I) -> Self
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.groups.push(Group {
+            name: name.into(),
+            sample_names: sample_names.into_iter().map(|s| s.into()).collect(),
+        });
+
+        self
+    }
+
+    /// Builds a tag filler.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::fill_tags::Filler;
+    /// let filler = Filler::builder().build();
+    /// ```
+    pub fn build(self) -> Filler {
+        Filler {
+            tags: self.tags,
+            groups: self.groups,
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            tags: Tag::ALL.to_vec(),
+            groups: Vec::new(),
+        }
+    }
+}
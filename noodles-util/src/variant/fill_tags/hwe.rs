@@ -0,0 +1,115 @@
+/// Returns the p-values of the Hardy-Weinberg equilibrium exact test and the test for excess
+/// heterozygosity.
+///
+/// This is the exact test described in Wigginton et al. (2005) "A Note on Exact Tests of
+/// Hardy-Weinberg Equilibrium" (PMID:15789306). The excess heterozygosity p-value is the
+/// probability of observing at least the given number of heterozygotes.
+pub(super) fn exact_test(het_count: u64, hom_count_1: u64, hom_count_2: u64) -> (f64, f64) {
+    let rare_hom_count = hom_count_1.min(hom_count_2);
+    let rare_allele_count = (2 * rare_hom_count + het_count) as usize;
+    let genotype_count = het_count + hom_count_1 + hom_count_2;
+
+    if genotype_count == 0 {
+        return (1.0, 1.0);
+    }
+
+    let n = genotype_count as f64;
+    let common_allele_count = (2 * genotype_count) as usize - rare_allele_count;
+
+    let mut probabilities = vec![0.0; rare_allele_count + 1];
+
+    // Start at the most likely number of heterozygotes, which has the same parity as the number
+    // of rare alleles.
+    let mut mid = ((rare_allele_count as f64) * (common_allele_count as f64) / (2.0 * n)) as usize;
+
+    if mid % 2 != rare_allele_count % 2 {
+        mid += 1;
+    }
+
+    mid = mid.min(rare_allele_count);
+
+    probabilities[mid] = 1.0;
+    let mut sum = 1.0;
+
+    let mut hets = mid;
+    let mut rare_homs = (rare_allele_count - mid) / 2;
+    let mut common_homs = genotype_count as usize - hets - rare_homs;
+
+    while hets >= 2 {
+        let p = probabilities[hets] * (hets as f64) * ((hets - 1) as f64)
+            / (4.0 * ((rare_homs + 1) as f64) * ((common_homs + 1) as f64));
+
+        probabilities[hets - 2] = p;
+        sum += p;
+
+        hets -= 2;
+        rare_homs += 1;
+        common_homs += 1;
+    }
+
+    let mut hets = mid;
+    let mut rare_homs = (rare_allele_count - mid) / 2;
+    let mut common_homs = genotype_count as usize - hets - rare_homs;
+
+    while hets + 2 <= rare_allele_count {
+        let p = probabilities[hets] * 4.0 * (rare_homs as f64) * (common_homs as f64)
+            / (((hets + 2) as f64) * ((hets + 1) as f64));
+
+        probabilities[hets + 2] = p;
+        sum += p;
+
+        hets += 2;
+        rare_homs -= 1;
+        common_homs -= 1;
+    }
+
+    let observed = probabilities[het_count as usize];
+
+    let p_hwe = probabilities
+        .iter()
+        .filter(|&&p| p <= observed)
+        .sum::<f64>()
+        / sum;
+
+    let p_excess_het = probabilities[het_count as usize..].iter().sum::<f64>() / sum;
+
+    (p_hwe.min(1.0), p_excess_het.min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_test() {
+        let (p_hwe, p_excess_het) = exact_test(0, 0, 0);
+        assert_eq!(p_hwe, 1.0);
+        assert_eq!(p_excess_het, 1.0);
+
+        let (p_hwe, p_excess_het) = exact_test(0, 8, 0);
+        assert_eq!(p_hwe, 1.0);
+        assert_eq!(p_excess_het, 1.0);
+
+        // 1 het, 0 rare homs, 1 common hom: the only configuration with 1 rare allele.
+        let (p_hwe, p_excess_het) = exact_test(1, 1, 0);
+        assert_eq!(p_hwe, 1.0);
+        assert_eq!(p_excess_het, 1.0);
+
+        // 2 genotypes with 2 rare alleles: P(het = 0) = 1/3 and P(het = 2) = 2/3.
+        let (p_hwe, p_excess_het) = exact_test(0, 1, 1);
+        assert!((p_hwe - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(p_excess_het, 1.0);
+
+        let (p_hwe, p_excess_het) = exact_test(2, 0, 0);
+        assert_eq!(p_hwe, 1.0);
+        assert!((p_excess_het - 2.0 / 3.0).abs() < 1e-9);
+
+        let (p_hwe, p_excess_het) = exact_test(0, 50, 50);
+        assert!(p_hwe < 1e-20);
+        assert_eq!(p_excess_het, 1.0);
+
+        let (p_hwe, p_excess_het) = exact_test(100, 0, 0);
+        assert!(p_hwe < 1e-20);
+        assert!(p_excess_het < 1e-20);
+    }
+}
@@ -0,0 +1,206 @@
+//! Computed INFO fields.
+
+use std::{error, fmt, str::FromStr};
+
+use noodles_vcf::header::record::value::{
+    map::{info, Info},
+    Map,
+};
+
+/// A computed INFO field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Tag {
+    /// Allele count for each alternate allele (`AC`).
+    AlleleCount,
+    /// Total number of alleles in called genotypes (`AN`).
+    TotalAlleleCount,
+    /// Allele frequency for each alternate allele (`AF`).
+    AlleleFrequencies,
+    /// Frequency of the second most common allele (`MAF`).
+    MinorAlleleFrequency,
+    /// Number of samples with data (`NS`).
+    SamplesWithDataCount,
+    /// Hardy-Weinberg equilibrium exact test p-value for each alternate allele (`HWE`).
+    HardyWeinbergEquilibrium,
+    /// Excess heterozygosity exact test p-value for each alternate allele (`ExcHet`).
+    ExcessHeterozygosity,
+    /// Fraction of samples with missing genotypes (`F_MISSING`).
+    MissingFraction,
+    /// Allele count in homozygous genotypes for each alternate allele (`AC_Hom`).
+    HomozygousAlleleCount,
+    /// Allele count in heterozygous genotypes for each alternate allele (`AC_Het`).
+    HeterozygousAlleleCount,
+    /// Allele count in hemizygous (haploid) genotypes for each alternate allele (`AC_Hemi`).
+    HemizygousAlleleCount,
+}
+
+impl Tag {
+    pub(super) const ALL: [Self; 11] = [
+        Self::AlleleCount,
+        Self::TotalAlleleCount,
+        Self::AlleleFrequencies,
+        Self::MinorAlleleFrequency,
+        Self::SamplesWithDataCount,
+        Self::HardyWeinbergEquilibrium,
+        Self::ExcessHeterozygosity,
+        Self::MissingFraction,
+        Self::HomozygousAlleleCount,
+        Self::HeterozygousAlleleCount,
+        Self::HemizygousAlleleCount,
+    ];
+
+    pub(super) fn number(&self) -> info::Number {
+        self.properties().0
+    }
+
+    pub(super) fn definition(&self, group: Option<&str>) -> Map<Info> {
+        let (number, ty, description) = self.properties();
+
+        match group {
+            Some(group) => Map::<Info>::new(number, ty, format!("{description} in {group}")),
+            None => Map::<Info>::new(number, ty, description),
+        }
+    }
+
+    fn properties(&self) -> (info::Number, info::Type, &'static str) {
+        match self {
+            Self::AlleleCount => (
+                info::Number::AlternateBases,
+                info::Type::Integer,
+                "Allele count in genotypes",
+            ),
+            Self::TotalAlleleCount => (
+                info::Number::Count(1),
+                info::Type::Integer,
+                "Total number of alleles in called genotypes",
+            ),
+            Self::AlleleFrequencies => (
+                info::Number::AlternateBases,
+                info::Type::Float,
+                "Allele frequency",
+            ),
+            Self::MinorAlleleFrequency => (
+                info::Number::Count(1),
+                info::Type::Float,
+                "Frequency of the second most common allele",
+            ),
+            Self::SamplesWithDataCount => (
+                info::Number::Count(1),
+                info::Type::Integer,
+                "Number of samples with data",
+            ),
+            Self::HardyWeinbergEquilibrium => (
+                info::Number::AlternateBases,
+                info::Type::Float,
+                "HWE test (PMID:15789306); 1=good, 0=bad",
+            ),
+            Self::ExcessHeterozygosity => (
+                info::Number::AlternateBases,
+                info::Type::Float,
+                "Test excess heterozygosity; 1=good, 0=bad",
+            ),
+            Self::MissingFraction => (
+                info::Number::Count(1),
+                info::Type::Float,
+                "Fraction of missing genotypes",
+            ),
+            Self::HomozygousAlleleCount => (
+                info::Number::AlternateBases,
+                info::Type::Integer,
+                "Allele counts in homozygous genotypes",
+            ),
+            Self::HeterozygousAlleleCount => (
+                info::Number::AlternateBases,
+                info::Type::Integer,
+                "Allele counts in heterozygous genotypes",
+            ),
+            Self::HemizygousAlleleCount => (
+                info::Number::AlternateBases,
+                info::Type::Integer,
+                "Allele counts in hemizygous genotypes",
+            ),
+        }
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::AlleleCount => "AC",
+            Self::TotalAlleleCount => "AN",
+            Self::AlleleFrequencies => "AF",
+            Self::MinorAlleleFrequency => "MAF",
+            Self::SamplesWithDataCount => "NS",
+            Self::HardyWeinbergEquilibrium => "HWE",
+            Self::ExcessHeterozygosity => "ExcHet",
+            Self::MissingFraction => "F_MISSING",
+            Self::HomozygousAlleleCount => "AC_Hom",
+            Self::HeterozygousAlleleCount => "AC_Het",
+            Self::HemizygousAlleleCount => "AC_Hemi",
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// An error returned when a raw tag fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The input is invalid.
+    Invalid,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::Invalid => f.write_str("invalid input"),
+        }
+    }
+}
+
+impl FromStr for Tag {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|tag| tag.as_ref() == s)
+            .ok_or(ParseError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        for tag in Tag::ALL {
+            assert_eq!(tag.to_string().parse(), Ok(tag));
+        }
+
+        assert_eq!("".parse::<Tag>(), Err(ParseError::Empty));
+        assert_eq!("ac".parse::<Tag>(), Err(ParseError::Invalid));
+    }
+
+    #[test]
+    fn test_definition() {
+        let definition = Tag::AlleleCount.definition(Some("EUR"));
+        assert_eq!(definition.number(), info::Number::AlternateBases);
+        assert_eq!(definition.ty(), info::Type::Integer);
+        assert_eq!(definition.description(), "Allele count in genotypes in EUR");
+    }
+}
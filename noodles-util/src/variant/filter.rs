@@ -0,0 +1,154 @@
+//! Variant record filtering.
+//!
+//! A [`Filter`] includes or excludes records matching a filter [`Expression`], like the `-i` and
+//! `-e` options of bcftools. Expressions are parsed once and evaluated with the generic
+//! [`vcf::variant::Record`] trait, so the same filter applies to both VCF and BCF records.
+
+pub mod expression;
+
+use std::io;
+
+use noodles_vcf::{self as vcf, variant::Record};
+
+pub use self::expression::Expression;
+
+/// A variant record filter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// Keep records that match the expression.
+    Include(Expression),
+    /// Keep records that do not match the expression.
+    Exclude(Expression),
+}
+
+impl Filter {
+    /// Returns the filter expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::{filter::Expression, Filter};
+    /// let expression: Expression = "QUAL > 10".parse()?;
+    /// let filter = Filter::Include(expression.clone());
+    /// assert_eq!(filter.expression(), &expression);
+    /// # Ok::<_, noodles_util::variant::filter::expression::ParseError>(())
+    /// ```
+    pub fn expression(&self) -> &Expression {
+        match self {
+            Self::Include(expression) | Self::Exclude(expression) => expression,
+        }
+    }
+
+    /// Returns whether the given record passes the filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::Filter;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let header = vcf::Header::default();
+    /// let filter = Filter::Exclude("QUAL < 10".parse()?);
+    ///
+    /// let record = RecordBuf::builder().set_quality_score(13.0).build();
+    /// assert!(filter.matches(&header, &record)?);
+    ///
+    /// let record = RecordBuf::builder().set_quality_score(8.0).build();
+    /// assert!(!filter.matches(&header, &record)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn matches<R>(&self, header: &vcf::Header, record: &R) -> io::Result<bool>
+    where
+        R: Record + ?Sized,
+    {
+        match self {
+            Self::Include(expression) => expression.evaluate(header, record),
+            Self::Exclude(expression) => expression.evaluate(header, record).map(|b| !b),
+        }
+    }
+
+    /// Returns an iterator over records that pass the filter.
+    ///
+    /// Errors from the input iterator are passed through.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::Filter;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let data = b"##fileformat=VCFv4.5
+    /// ###INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+    /// ##CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+    /// sq0\t1\t.\tA\t.\t.\tPASS\tDP=5
+    /// sq0\t2\t.\tC\t.\t.\tPASS\tDP=34
+    /// ";
+    ///
+    /// let mut reader = vcf::io::Reader::new(&data[..]);
+    /// let header = reader.read_header()?;
+    ///
+    /// let filter = Filter::Include("INFO/DP >= 10".parse().unwrap());
+    /// let mut records = filter.records(&header, reader.records());
+    ///
+    /// let record = records.next().transpose()?.expect("missing record");
+    /// assert_eq!(record.variant_start().transpose()?.map(usize::from), Some(2));
+    /// assert!(records.next().is_none());
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn records<'a, I, R>(
+        &'a self,
+        header: &'a vcf::Header,
+        records: I,
+    ) -> impl Iterator<Item = io::Result<R>> + 'a
+    where
+        I: IntoIterator<Item = io::Result<R>>,
+        I::IntoIter: 'a,
+        R: Record,
+    {
+        records.into_iter().filter_map(move |result| {
+            let record = match result {
+                Ok(record) => record,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match self.matches(header, &record) {
+                Ok(true) => Some(Ok(record)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::variant::RecordBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_records() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::default();
+
+        let records = [
+            RecordBuf::builder().set_quality_score(8.0).build(),
+            RecordBuf::builder().set_quality_score(13.0).build(),
+            RecordBuf::default(),
+        ];
+
+        let filter = Filter::Include("QUAL >= 10".parse()?);
+        let actual: Vec<_> = filter
+            .records(&header, records.iter().cloned().map(Ok))
+            .collect::<io::Result<_>>()?;
+        assert_eq!(actual, [records[1].clone()]);
+
+        let filter = Filter::Exclude("QUAL >= 10".parse()?);
+        let actual: Vec<_> = filter
+            .records(&header, records.iter().cloned().map(Ok))
+            .collect::<io::Result<_>>()?;
+        assert_eq!(actual, [records[0].clone(), records[2].clone()]);
+
+        Ok(())
+    }
+}
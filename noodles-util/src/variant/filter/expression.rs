@@ -0,0 +1,761 @@
+//! Variant record filter expression.
+
+mod parser;
+
+use std::{io, str::FromStr};
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{
+            info::field::{value::Array as InfoArray, Value as InfoValue},
+            samples::series::{
+                value::{genotype::Phasing, Array as SampleArray, Genotype},
+                Value as SampleValue,
+            },
+        },
+        Record,
+    },
+};
+
+use crate::expression::{
+    compare, evaluate_binary, evaluate_match, evaluate_unary, length_value, BinaryOperator, Regex,
+    UnaryOperator, Value,
+};
+
+pub use crate::expression::ParseError;
+
+/// A variant record filter expression.
+///
+/// The expression language is modeled after the one used by `bcftools view -i/-e`. An expression
+/// is built from the following:
+///
+///   * literals: integers, floats, and double-quoted strings;
+///   * record fields: `CHROM`, `POS`, `ID`, `REF`, `ALT`, `QUAL`, `FILTER`, `N_ALT`, and
+///     `N_SAMPLES`;
+///   * INFO and FORMAT fields, e.g., `INFO/DP` and `FMT/AD` (or `FORMAT/AD`). A bare key (e.g.,
+///     `DP`) is an INFO field if the header defines one and a FORMAT field otherwise;
+///   * subscripts: `INFO/AF[1]` selects the value at index 1; `FMT/DP[0]`, the value of the
+///     first sample; `FMT/AD[0:1]`, the value at index 1 of the first sample; and `FMT/AD[*:1]`,
+///     the value at index 1 of all samples;
+///   * genotype predicates: `GT == "het"` and `GT != "het"`, where the class is one of `het`,
+///     `hom`, `ref` (all alleles are the reference), `alt` (any allele is an alternate), `mis`
+///     (any allele is missing), or `hap` (haploid). These can be subscripted by sample, e.g.,
+///     `GT[0] == "het"`;
+///   * functions: `sum`, `min`, `max`, `avg`, `strlen`, and `abs`;
+///   * operators, from lowest to highest precedence: `||` (or `|`); `&&` (or `&`); `==` (or `=`),
+///     `!=`; `<`, `<=`, `>`, `>=`; `~` (or `=~`), `!~`; `+`, `-`; `*`, `/`; and the unary `!` and
+///     `-`.
+///
+/// Fields with multiple values (e.g., per-sample or per-allele values) evaluate to vectors.
+/// Comparisons and arithmetic are applied elementwise, where a single value is compared with each
+/// value of a vector, and a vector is true if any of its values are true. For example,
+/// `FMT/DP > 10` selects records where any sample has a depth greater than 10.
+///
+/// Missing values are null. Null is false, and any comparison or arithmetic with a null is null,
+/// with the exception of `!`, which makes a null true. This allows, e.g., `!INFO/DB` to select
+/// records without the `DB` flag.
+///
+/// The right-hand side of a regular expression match must be a string literal. It is compiled
+/// once when the expression is parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression(Node);
+
+impl Expression {
+    /// Evaluates the expression for the given record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_util::variant::filter::Expression;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let header = vcf::Header::default();
+    /// let record = RecordBuf::builder()
+    ///     .set_reference_sequence_name("sq0")
+    ///     .set_variant_start(Position::try_from(8)?)
+    ///     .set_quality_score(13.0)
+    ///     .build();
+    ///
+    /// let expression: Expression = r#"CHROM == "sq0" && QUAL > 10"#.parse()?;
+    /// assert!(expression.evaluate(&header, &record)?);
+    ///
+    /// let expression: Expression = "POS < 5".parse()?;
+    /// assert!(!expression.evaluate(&header, &record)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn evaluate<R>(&self, header: &vcf::Header, record: &R) -> io::Result<bool>
+    where
+        R: Record + ?Sized,
+    {
+        let context = Context { header, record };
+        self.0.evaluate(&context).map(|values| is_truthy(&values))
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse(s).map(Self)
+    }
+}
+
+struct Context<'a, R: ?Sized> {
+    header: &'a vcf::Header,
+    record: &'a R,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Literal(Value),
+    Field(Field),
+    Tag(Tag),
+    GenotypeTest(Option<Selector>, GenotypeClass, bool),
+    Function(Function, Box<Node>),
+    Unary(UnaryOperator, Box<Node>),
+    Binary(BinaryOperator, Box<Node>, Box<Node>),
+    Match(Box<Node>, Regex, bool),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Field {
+    ReferenceSequenceName,
+    Position,
+    Ids,
+    ReferenceBases,
+    AlternateBases,
+    QualityScore,
+    Filters,
+    AlternateAlleleCount,
+    SampleCount,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Scope {
+    Info,
+    Format,
+    Any,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Tag {
+    scope: Scope,
+    key: String,
+    subscript: Option<Subscript>,
+}
+
+/// `[i]` or `[s:i]`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Subscript(Selector, Option<Selector>);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Selector {
+    All,
+    Index(usize),
+}
+
+impl Selector {
+    fn matches(&self, i: usize) -> bool {
+        match self {
+            Self::All => true,
+            Self::Index(j) => i == *j,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum GenotypeClass {
+    Heterozygous,
+    Homozygous,
+    Reference,
+    Alternate,
+    Missing,
+    Haploid,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Function {
+    Sum,
+    Min,
+    Max,
+    Avg,
+    Strlen,
+    Abs,
+}
+
+fn is_truthy(values: &[Value]) -> bool {
+    values.iter().any(Value::is_truthy)
+}
+
+fn boolean(b: bool) -> Vec<Value> {
+    vec![Value::from(b)]
+}
+
+impl Node {
+    fn evaluate<R>(&self, context: &Context<'_, R>) -> io::Result<Vec<Value>>
+    where
+        R: Record + ?Sized,
+    {
+        match self {
+            Self::Literal(value) => Ok(vec![value.clone()]),
+            Self::Field(field) => evaluate_field(context, *field),
+            Self::Tag(tag) => evaluate_tag(context, tag),
+            Self::GenotypeTest(selector, class, is_negated) => {
+                evaluate_genotype_test(context, *selector, *class, *is_negated)
+            }
+            Self::Function(function, node) => node
+                .evaluate(context)
+                .map(|values| evaluate_function(*function, values)),
+            Self::Unary(UnaryOperator::Not, node) => node
+                .evaluate(context)
+                .map(|values| boolean(!is_truthy(&values))),
+            Self::Unary(op, node) => node.evaluate(context).map(|values| {
+                values
+                    .iter()
+                    .map(|value| evaluate_unary(*op, value))
+                    .collect()
+            }),
+            Self::Binary(BinaryOperator::Or, lhs, rhs) => {
+                if is_truthy(&lhs.evaluate(context)?) {
+                    Ok(boolean(true))
+                } else {
+                    rhs.evaluate(context)
+                        .map(|values| boolean(is_truthy(&values)))
+                }
+            }
+            Self::Binary(BinaryOperator::And, lhs, rhs) => {
+                if is_truthy(&lhs.evaluate(context)?) {
+                    rhs.evaluate(context)
+                        .map(|values| boolean(is_truthy(&values)))
+                } else {
+                    Ok(boolean(false))
+                }
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let lhs = lhs.evaluate(context)?;
+                let rhs = rhs.evaluate(context)?;
+                Ok(broadcast(lhs, rhs, |a, b| evaluate_binary(*op, a, b)))
+            }
+            Self::Match(node, regex, is_negated) => node.evaluate(context).map(|values| {
+                values
+                    .iter()
+                    .map(|value| evaluate_match(regex, value, *is_negated))
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Applies an operator elementwise.
+///
+/// A single value is applied to each value of the other side. Vectors of different lengths
+/// evaluate to null.
+fn broadcast<F>(lhs: Vec<Value>, rhs: Vec<Value>, f: F) -> Vec<Value>
+where
+    F: Fn(&Value, &Value) -> Value,
+{
+    match (lhs.len(), rhs.len()) {
+        (1, _) => rhs.iter().map(|b| f(&lhs[0], b)).collect(),
+        (_, 1) => lhs.iter().map(|a| f(a, &rhs[0])).collect(),
+        (m, n) if m == n => lhs.iter().zip(&rhs).map(|(a, b)| f(a, b)).collect(),
+        _ => vec![Value::Null],
+    }
+}
+
+fn evaluate_field<R>(context: &Context<'_, R>, field: Field) -> io::Result<Vec<Value>>
+where
+    R: Record + ?Sized,
+{
+    let record = context.record;
+
+    let values = match field {
+        Field::ReferenceSequenceName => {
+            let name = record.reference_sequence_name(context.header)?;
+            vec![Value::String(name.into())]
+        }
+        Field::Position => {
+            let value = record
+                .variant_start()
+                .transpose()?
+                .map(|position| length_value(usize::from(position)))
+                .unwrap_or(Value::Null);
+
+            vec![value]
+        }
+        Field::Ids => record
+            .ids()
+            .iter()
+            .map(|id| Value::String(id.into()))
+            .collect(),
+        Field::ReferenceBases => {
+            let bases = record
+                .reference_bases()
+                .iter()
+                .collect::<io::Result<Vec<_>>>()?;
+
+            vec![Value::String(bases)]
+        }
+        Field::AlternateBases => record
+            .alternate_bases()
+            .iter()
+            .map(|result| result.map(|allele| Value::String(allele.into())))
+            .collect::<io::Result<_>>()?,
+        Field::QualityScore => {
+            let value = record
+                .quality_score()
+                .transpose()?
+                .map(|n| Value::Float(f64::from(n)))
+                .unwrap_or(Value::Null);
+
+            vec![value]
+        }
+        Field::Filters => record
+            .filters()
+            .iter(context.header)
+            .map(|result| result.map(|id| Value::String(id.into())))
+            .collect::<io::Result<_>>()?,
+        Field::AlternateAlleleCount => vec![length_value(record.alternate_bases().len())],
+        Field::SampleCount => vec![length_value(context.header.sample_names().len())],
+    };
+
+    Ok(values)
+}
+
+fn evaluate_tag<R>(context: &Context<'_, R>, tag: &Tag) -> io::Result<Vec<Value>>
+where
+    R: Record + ?Sized,
+{
+    let scope = match tag.scope {
+        Scope::Any if context.header.infos().contains_key(&tag.key) => Scope::Info,
+        Scope::Any => Scope::Format,
+        scope => scope,
+    };
+
+    match scope {
+        Scope::Info => evaluate_info(context, &tag.key, tag.subscript),
+        _ => evaluate_format(context, &tag.key, tag.subscript),
+    }
+}
+
+fn evaluate_info<R>(
+    context: &Context<'_, R>,
+    key: &str,
+    subscript: Option<Subscript>,
+) -> io::Result<Vec<Value>>
+where
+    R: Record + ?Sized,
+{
+    let selector = match subscript {
+        None => Selector::All,
+        Some(Subscript(selector, None)) => selector,
+        Some(Subscript(_, Some(_))) => return Ok(Vec::new()),
+    };
+
+    let info = context.record.info();
+
+    let values = match info.get(context.header, key).transpose()? {
+        None => Vec::new(),
+        Some(None) => vec![Value::Null],
+        Some(Some(value)) => match value {
+            InfoValue::Integer(n) => vec![Value::Integer(i64::from(n))],
+            InfoValue::Float(n) => vec![Value::Float(f64::from(n))],
+            InfoValue::Flag => vec![Value::Integer(1)],
+            InfoValue::Character(c) => vec![Value::String(c.to_string().into())],
+            InfoValue::String(s) => vec![Value::String(s.as_bytes().to_vec())],
+            InfoValue::Array(array) => info_array_values(&array)?,
+        },
+    };
+
+    Ok(select(values, selector))
+}
+
+fn info_array_values(array: &InfoArray<'_>) -> io::Result<Vec<Value>> {
+    match array {
+        InfoArray::Integer(values) => collect(values.iter(), |n| Value::Integer(i64::from(n))),
+        InfoArray::Float(values) => collect(values.iter(), |n| Value::Float(f64::from(n))),
+        InfoArray::Character(values) => {
+            collect(values.iter(), |c| Value::String(c.to_string().into()))
+        }
+        InfoArray::String(values) => {
+            collect(values.iter(), |s| Value::String(s.as_bytes().to_vec()))
+        }
+    }
+}
+
+fn sample_array_values(array: &SampleArray<'_>) -> io::Result<Vec<Value>> {
+    match array {
+        SampleArray::Integer(values) => collect(values.iter(), |n| Value::Integer(i64::from(n))),
+        SampleArray::Float(values) => collect(values.iter(), |n| Value::Float(f64::from(n))),
+        SampleArray::Character(values) => {
+            collect(values.iter(), |c| Value::String(c.to_string().into()))
+        }
+        SampleArray::String(values) => {
+            collect(values.iter(), |s| Value::String(s.as_bytes().to_vec()))
+        }
+    }
+}
+
+fn collect<I, T, F>(iter: I, f: F) -> io::Result<Vec<Value>>
+where
+    I: Iterator<Item = io::Result<Option<T>>>,
+    F: Fn(T) -> Value,
+{
+    iter.map(|result| result.map(|value| value.map(&f).unwrap_or(Value::Null)))
+        .collect()
+}
+
+fn select(values: Vec<Value>, selector: Selector) -> Vec<Value> {
+    match selector {
+        Selector::All => values,
+        Selector::Index(i) => values.into_iter().nth(i).into_iter().collect(),
+    }
+}
+
+fn evaluate_format<R>(
+    context: &Context<'_, R>,
+    key: &str,
+    subscript: Option<Subscript>,
+) -> io::Result<Vec<Value>>
+where
+    R: Record + ?Sized,
+{
+    let (sample_selector, value_selector) = match subscript {
+        None => (Selector::All, Selector::All),
+        Some(Subscript(sample_selector, value_selector)) => {
+            (sample_selector, value_selector.unwrap_or(Selector::All))
+        }
+    };
+
+    let samples = context.record.samples()?;
+    let mut values = Vec::new();
+
+    for (i, sample) in samples.iter().enumerate() {
+        if !sample_selector.matches(i) {
+            continue;
+        }
+
+        let sample_values = match sample.get(context.header, key).transpose()?.flatten() {
+            None => vec![Value::Null],
+            Some(SampleValue::Integer(n)) => vec![Value::Integer(i64::from(n))],
+            Some(SampleValue::Float(n)) => vec![Value::Float(f64::from(n))],
+            Some(SampleValue::Character(c)) => vec![Value::String(c.to_string().into())],
+            Some(SampleValue::String(s)) => vec![Value::String(s.as_bytes().to_vec())],
+            Some(SampleValue::Genotype(genotype)) => {
+                vec![Value::String(format_genotype(genotype.as_ref())?.into())]
+            }
+            Some(SampleValue::Array(array)) => sample_array_values(&array)?,
+        };
+
+        values.extend(select(sample_values, value_selector));
+    }
+
+    Ok(values)
+}
+
+fn format_genotype(genotype: &dyn Genotype) -> io::Result<String> {
+    let mut s = String::new();
+
+    for (i, result) in genotype.iter().enumerate() {
+        let (position, phasing) = result?;
+
+        if i > 0 {
+            s.push(match phasing {
+                Phasing::Phased => '|',
+                Phasing::Unphased => '/',
+            });
+        }
+
+        match position {
+            Some(n) => s.push_str(&n.to_string()),
+            None => s.push('.'),
+        }
+    }
+
+    Ok(s)
+}
+
+fn evaluate_genotype_test<R>(
+    context: &Context<'_, R>,
+    selector: Option<Selector>,
+    class: GenotypeClass,
+    is_negated: bool,
+) -> io::Result<Vec<Value>>
+where
+    R: Record + ?Sized,
+{
+    const KEY: &str = "GT";
+
+    let selector = selector.unwrap_or(Selector::All);
+    let samples = context.record.samples()?;
+    let mut values = Vec::new();
+
+    for (i, sample) in samples.iter().enumerate() {
+        if !selector.matches(i) {
+            continue;
+        }
+
+        let value = match sample.get(context.header, KEY).transpose()?.flatten() {
+            Some(SampleValue::Genotype(genotype)) => {
+                let positions = genotype
+                    .iter()
+                    .map(|result| result.map(|(position, _)| position))
+                    .collect::<io::Result<Vec<_>>>()?;
+
+                let is_match = classify(&positions, class) != is_negated;
+                Value::Integer(i64::from(is_match))
+            }
+            _ => Value::Null,
+        };
+
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+fn classify(positions: &[Option<usize>], class: GenotypeClass) -> bool {
+    let is_missing = positions.iter().any(Option::is_none);
+
+    match class {
+        GenotypeClass::Missing => is_missing || positions.is_empty(),
+        _ if is_missing || positions.is_empty() => false,
+        GenotypeClass::Heterozygous => positions.windows(2).any(|w| w[0] != w[1]),
+        GenotypeClass::Homozygous => {
+            positions.len() > 1 && positions.windows(2).all(|w| w[0] == w[1])
+        }
+        GenotypeClass::Reference => positions.iter().all(|&p| p == Some(0)),
+        GenotypeClass::Alternate => positions.iter().any(|&p| p != Some(0)),
+        GenotypeClass::Haploid => positions.len() == 1,
+    }
+}
+
+fn evaluate_function(function: Function, values: Vec<Value>) -> Vec<Value> {
+    let numbers = || values.iter().filter_map(Value::as_float);
+    let all_integers = values
+        .iter()
+        .all(|value| matches!(value, Value::Integer(_) | Value::Null));
+
+    let value = match function {
+        Function::Sum => {
+            if numbers().next().is_none() {
+                Value::Null
+            } else if all_integers {
+                Value::Integer(
+                    values
+                        .iter()
+                        .filter_map(|value| match value {
+                            Value::Integer(n) => Some(*n),
+                            _ => None,
+                        })
+                        .fold(0i64, |acc, n| acc.wrapping_add(n)),
+                )
+            } else {
+                Value::Float(numbers().sum())
+            }
+        }
+        Function::Min | Function::Max => {
+            let mut result: Option<&Value> = None;
+
+            for value in values.iter().filter(|value| value.as_float().is_some()) {
+                let is_better = match result {
+                    None => true,
+                    Some(current) => match compare(value, current) {
+                        Some(ordering) => {
+                            (function == Function::Min && ordering.is_lt())
+                                || (function == Function::Max && ordering.is_gt())
+                        }
+                        None => false,
+                    },
+                };
+
+                if is_better {
+                    result = Some(value);
+                }
+            }
+
+            result.cloned().unwrap_or(Value::Null)
+        }
+        Function::Avg => {
+            let (sum, n) = numbers().fold((0.0, 0), |(sum, n), m| (sum + m, n + 1));
+
+            if n == 0 {
+                Value::Null
+            } else {
+                Value::Float(sum / f64::from(n))
+            }
+        }
+        Function::Strlen => {
+            return values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Null => Value::Null,
+                    Value::String(s) => length_value(String::from_utf8_lossy(&s).chars().count()),
+                    value => length_value(value.to_string().len()),
+                })
+                .collect();
+        }
+        Function::Abs => {
+            return values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Integer(n) => Value::Integer(n.wrapping_abs()),
+                    Value::Float(n) => Value::Float(n.abs()),
+                    _ => Value::Null,
+                })
+                .collect();
+        }
+    };
+
+    vec![value]
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::variant::RecordBuf;
+
+    use super::*;
+
+    const DATA: &str = "##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##INFO=<ID=AF,Number=A,Type=Float,Description=\"Allele frequency\">
+##INFO=<ID=DB,Number=0,Type=Flag,Description=\"dbSNP membership\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"Read depth for each allele\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\tsample1\tsample2
+sq0\t8\trs13\tA\tC,AT\t21.5\tPASS\tDP=34;AF=0.25,0.5\tGT:DP:AD\t0/1:8:5,3,0\t2|2:13:0,1,12\t./.
+";
+
+    fn evaluate(header: &vcf::Header, record: &RecordBuf, s: &str) -> bool {
+        let expression: Expression = s.parse().unwrap();
+        expression.evaluate(header, record).unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() -> Result<(), Box<dyn std::error::Error>> {
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let mut record = RecordBuf::default();
+        reader.read_record_buf(&header, &mut record)?;
+
+        assert!(evaluate(&header, &record, r#"CHROM == "sq0" && POS == 8"#));
+        assert!(evaluate(&header, &record, r#"ID == "rs13" && REF = "A""#));
+        assert!(evaluate(&header, &record, r#"ALT == "AT" && N_ALT == 2"#));
+        assert!(evaluate(&header, &record, "QUAL > 20 & N_SAMPLES == 3"));
+        assert!(evaluate(&header, &record, r#"FILTER == "PASS""#));
+        assert!(evaluate(&header, &record, "INFO/DP > 30 && DP / 2 == 17"));
+        assert!(evaluate(&header, &record, "INFO/AF[1] == 0.5"));
+        assert!(evaluate(&header, &record, "QUAL>1e-3 && INFO/AF<2.5E+1"));
+        assert!(evaluate(
+            &header,
+            &record,
+            "INFO/AF > 0.4 && !(INFO/AF[0] > 0.4)"
+        ));
+        assert!(evaluate(&header, &record, "!INFO/DB && !INFO/XX"));
+        assert!(evaluate(
+            &header,
+            &record,
+            "FMT/DP > 10 && !(FMT/DP[0] > 10)"
+        ));
+        assert!(evaluate(&header, &record, "FORMAT/AD[1:2] == 12"));
+        assert!(evaluate(&header, &record, "sum(FMT/AD[*:1]) == 4"));
+        assert!(evaluate(
+            &header,
+            &record,
+            "sum(FMT/AD) == 21 && max(FMT/DP) == 13"
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            "min(FMT/AD[0]) == 0 && avg(FMT/DP) == 10.5"
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            "strlen(ALT) == 2 && abs(-DP) == 34"
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            r#"GT == "het" && GT == "hom" && GT == "mis""#
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            r#"GT[0] == "het" && GT[1] != "het""#
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            r#"GT[1] == "alt" && !(GT[1] == "ref")"#
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            r#"GT[0] == "0/1" && FMT/GT[1] ~ "\|""#
+        ));
+        assert!(evaluate(
+            &header,
+            &record,
+            r#"ID =~ "^rs[0-9]+$" && CHROM !~ "^chr""#
+        ));
+        assert!(evaluate(&header, &record, "POS > 100 || DP > 30"));
+
+        assert!(!evaluate(&header, &record, r#"GT == "hap""#));
+        assert!(!evaluate(&header, &record, "FMT/DP > 20"));
+        assert!(!evaluate(&header, &record, "INFO/AF[2] > 0"));
+        assert!(!evaluate(&header, &record, "INFO/AF<5e-4"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_evaluate_with_bcf_record() -> Result<(), Box<dyn std::error::Error>> {
+        use noodles_bcf as bcf;
+        use noodles_vcf::variant::io::Write;
+
+        let mut reader = vcf::io::Reader::new(DATA.as_bytes());
+        let header = reader.read_header()?;
+        let mut record = RecordBuf::default();
+        reader.read_record_buf(&header, &mut record)?;
+
+        let mut writer = bcf::io::Writer::from(Vec::new());
+        writer.write_header(&header)?;
+        writer.write_variant_record(&header, &record)?;
+        let data = writer.into_inner();
+
+        let mut reader = bcf::io::Reader::from(&data[..]);
+        let header = reader.read_header()?;
+        let mut bcf_record = bcf::Record::default();
+        reader.read_record(&mut bcf_record)?;
+
+        for s in [
+            r#"CHROM == "sq0" && POS == 8 && FILTER == "PASS""#,
+            "INFO/AF[1] == 0.5 && sum(FMT/AD[*:1]) == 4",
+            r#"GT[0] == "het" && GT[1] != "het""#,
+        ] {
+            let expression: Expression = s.parse()?;
+            assert!(expression.evaluate(&header, &bcf_record)?);
+            assert!(expression.evaluate(&header, &record)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify() {
+        assert!(classify(&[Some(0), Some(1)], GenotypeClass::Heterozygous));
+        assert!(classify(&[Some(1), Some(1)], GenotypeClass::Homozygous));
+        assert!(classify(&[Some(0), Some(0)], GenotypeClass::Homozygous));
+        assert!(classify(&[Some(0), Some(0)], GenotypeClass::Reference));
+        assert!(classify(&[Some(0), Some(2)], GenotypeClass::Alternate));
+        assert!(classify(&[Some(0), None], GenotypeClass::Missing));
+        assert!(classify(&[Some(1)], GenotypeClass::Haploid));
+
+        assert!(!classify(&[Some(0), None], GenotypeClass::Heterozygous));
+        assert!(!classify(&[Some(1)], GenotypeClass::Homozygous));
+    }
+}
@@ -0,0 +1,369 @@
+use std::fmt;
+
+use super::{Field, Function, GenotypeClass, Node, Scope, Selector, Subscript, Tag};
+use crate::expression::{
+    parser::{self, Parser, Syntax, Token},
+    BinaryOperator, ParseError, Regex, UnaryOperator, Value,
+};
+
+pub(super) fn parse(s: &str) -> Result<Node, ParseError> {
+    parser::parse::<Dialect>(s)
+}
+
+// The `bcftools view -i/-e` dialect.
+struct Dialect;
+
+impl Syntax for Dialect {
+    type Node = Node;
+
+    const BINARY_OPERATORS: &'static [&'static [(Token, BinaryOperator)]] = &[
+        &[
+            (Token::Or, BinaryOperator::Or),
+            (Token::Pipe, BinaryOperator::Or),
+        ],
+        &[
+            (Token::And, BinaryOperator::And),
+            (Token::Ampersand, BinaryOperator::And),
+        ],
+        &[
+            (Token::Eq, BinaryOperator::Eq),
+            (Token::Assign, BinaryOperator::Eq),
+            (Token::Ne, BinaryOperator::Ne),
+        ],
+        &[
+            (Token::Lt, BinaryOperator::Lt),
+            (Token::Le, BinaryOperator::Le),
+            (Token::Gt, BinaryOperator::Gt),
+            (Token::Ge, BinaryOperator::Ge),
+        ],
+        &[],
+        &[
+            (Token::Plus, BinaryOperator::Add),
+            (Token::Minus, BinaryOperator::Sub),
+        ],
+        &[
+            (Token::Star, BinaryOperator::Mul),
+            (Token::Slash, BinaryOperator::Div),
+        ],
+    ];
+
+    const MATCH_OPERATORS: &'static [(Token, bool)] = &[
+        (Token::Tilde, false),
+        (Token::Match, false),
+        (Token::NotMatch, true),
+    ];
+
+    const MATCH_PRECEDENCE: usize = 4;
+
+    const UNARY_OPERATORS: &'static [(Token, UnaryOperator)] = &[
+        (Token::Bang, UnaryOperator::Not),
+        (Token::Minus, UnaryOperator::Negate),
+    ];
+
+    const IDENTIFIER_PREFIXES: &'static [&'static str] = &["INFO", "FMT", "FORMAT"];
+
+    fn literal(value: Value) -> Node {
+        Node::Literal(value)
+    }
+
+    fn unary(op: UnaryOperator, node: Node) -> Node {
+        Node::Unary(op, Box::new(node))
+    }
+
+    fn binary(op: BinaryOperator, lhs: Node, rhs: Node) -> Node {
+        match genotype_test(op, &lhs, &rhs) {
+            Some(node) => node,
+            None => Node::Binary(op, Box::new(lhs), Box::new(rhs)),
+        }
+    }
+
+    fn regex_match(node: Node, regex: Regex, is_negated: bool) -> Node {
+        Node::Match(Box::new(node), regex, is_negated)
+    }
+
+    fn parse_term(
+        parser: &mut Parser<Self>,
+        offset: usize,
+        token: Token,
+    ) -> Result<Node, ParseError> {
+        match token {
+            Token::Identifier(s) if parser.peek() == Some(&Token::LeftParen) => {
+                let function = parse_function(&s)?;
+                let node = parser.parse_primary()?;
+                Ok(Node::Function(function, Box::new(node)))
+            }
+            Token::Identifier(s) => {
+                let subscript = match parser.peek() {
+                    Some(Token::Bracket(_)) => match parser.next()? {
+                        (_, Token::Bracket(t)) => Some(parse_subscript(&t)?),
+                        _ => unreachable!(),
+                    },
+                    _ => None,
+                };
+
+                parse_identifier(&s, subscript)
+            }
+            token => Err(ParseError::UnexpectedToken(offset, token.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Subscript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(a, b) = self;
+
+        write!(f, "[{a}")?;
+
+        if let Some(b) = b {
+            write!(f, ":{b}")?;
+        }
+
+        f.write_str("]")
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("*"),
+            Self::Index(i) => write!(f, "{i}"),
+        }
+    }
+}
+
+fn parse_subscript(s: &str) -> Result<Subscript, ParseError> {
+    let invalid_subscript = || ParseError::InvalidSubscript(s.into());
+
+    let raw = s
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .ok_or_else(invalid_subscript)?;
+
+    let parse_selector = |t: &str| match t.trim() {
+        "*" => Ok(Selector::All),
+        t => t
+            .parse()
+            .map(Selector::Index)
+            .map_err(|_| invalid_subscript()),
+    };
+
+    match raw.split_once(':') {
+        Some((a, b)) => Ok(Subscript(parse_selector(a)?, Some(parse_selector(b)?))),
+        None => Ok(Subscript(parse_selector(raw)?, None)),
+    }
+}
+
+fn parse_function(s: &str) -> Result<Function, ParseError> {
+    match s.to_ascii_lowercase().as_str() {
+        "sum" => Ok(Function::Sum),
+        "min" => Ok(Function::Min),
+        "max" => Ok(Function::Max),
+        "avg" | "mean" => Ok(Function::Avg),
+        "strlen" => Ok(Function::Strlen),
+        "abs" => Ok(Function::Abs),
+        _ => Err(ParseError::UnknownIdentifier(s.into())),
+    }
+}
+
+fn parse_identifier(s: &str, subscript: Option<Subscript>) -> Result<Node, ParseError> {
+    let field = match s {
+        "CHROM" => Some(Field::ReferenceSequenceName),
+        "POS" => Some(Field::Position),
+        "ID" => Some(Field::Ids),
+        "REF" => Some(Field::ReferenceBases),
+        "ALT" => Some(Field::AlternateBases),
+        "QUAL" => Some(Field::QualityScore),
+        "FILTER" => Some(Field::Filters),
+        "N_ALT" => Some(Field::AlternateAlleleCount),
+        "N_SAMPLES" => Some(Field::SampleCount),
+        _ => None,
+    };
+
+    if let Some(field) = field {
+        return match subscript {
+            Some(subscript) => Err(ParseError::InvalidSubscript(subscript.to_string())),
+            None => Ok(Node::Field(field)),
+        };
+    }
+
+    let (scope, key) = if let Some(key) = s.strip_prefix("INFO/") {
+        (Scope::Info, key)
+    } else if let Some(key) = s.strip_prefix("FMT/").or_else(|| s.strip_prefix("FORMAT/")) {
+        (Scope::Format, key)
+    } else if s == "GT" {
+        (Scope::Format, s)
+    } else {
+        (Scope::Any, s)
+    };
+
+    if key.is_empty() || key.contains('/') {
+        return Err(ParseError::InvalidTag(s.into()));
+    }
+
+    if let (Scope::Info, Some(subscript @ Subscript(_, Some(_)))) = (scope, subscript) {
+        return Err(ParseError::InvalidSubscript(subscript.to_string()));
+    }
+
+    Ok(Node::Tag(Tag {
+        scope,
+        key: key.into(),
+        subscript,
+    }))
+}
+
+// Rewrites `GT == "<class>"` and `GT != "<class>"` to a genotype test.
+fn genotype_test(op: BinaryOperator, lhs: &Node, rhs: &Node) -> Option<Node> {
+    let is_negated = match op {
+        BinaryOperator::Eq => false,
+        BinaryOperator::Ne => true,
+        _ => return None,
+    };
+
+    let selector = match lhs {
+        Node::Tag(Tag {
+            scope: Scope::Format,
+            key,
+            subscript,
+        }) if key == "GT" => match subscript {
+            None => None,
+            Some(Subscript(selector, None)) => Some(*selector),
+            Some(_) => return None,
+        },
+        _ => return None,
+    };
+
+    let class = match rhs {
+        Node::Literal(Value::String(s)) => match s.as_slice() {
+            b"het" => GenotypeClass::Heterozygous,
+            b"hom" => GenotypeClass::Homozygous,
+            b"ref" => GenotypeClass::Reference,
+            b"alt" => GenotypeClass::Alternate,
+            b"mis" => GenotypeClass::Missing,
+            b"hap" => GenotypeClass::Haploid,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some(Node::GenotypeTest(selector, class, is_negated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(scope: Scope, key: &str, subscript: Option<Subscript>) -> Node {
+        Node::Tag(Tag {
+            scope,
+            key: key.into(),
+            subscript,
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("QUAL >= 30 && !INFO/DB"),
+            Ok(Node::Binary(
+                BinaryOperator::And,
+                Box::new(Node::Binary(
+                    BinaryOperator::Ge,
+                    Box::new(Node::Field(Field::QualityScore)),
+                    Box::new(Node::Literal(Value::Integer(30))),
+                )),
+                Box::new(Node::Unary(
+                    UnaryOperator::Not,
+                    Box::new(tag(Scope::Info, "DB", None))
+                )),
+            ))
+        );
+
+        assert_eq!(
+            parse("DP/2 + 1"),
+            Ok(Node::Binary(
+                BinaryOperator::Add,
+                Box::new(Node::Binary(
+                    BinaryOperator::Div,
+                    Box::new(tag(Scope::Any, "DP", None)),
+                    Box::new(Node::Literal(Value::Integer(2))),
+                )),
+                Box::new(Node::Literal(Value::Integer(1))),
+            ))
+        );
+
+        assert_eq!(
+            parse("sum(FORMAT/AD[*:1])"),
+            Ok(Node::Function(
+                Function::Sum,
+                Box::new(tag(
+                    Scope::Format,
+                    "AD",
+                    Some(Subscript(Selector::All, Some(Selector::Index(1))))
+                ))
+            ))
+        );
+
+        assert_eq!(
+            parse(r#"GT[1] != "het""#),
+            Ok(Node::GenotypeTest(
+                Some(Selector::Index(1)),
+                GenotypeClass::Heterozygous,
+                true
+            ))
+        );
+
+        assert_eq!(
+            parse(r#"FMT/GT = "0/1""#),
+            Ok(Node::Binary(
+                BinaryOperator::Eq,
+                Box::new(tag(Scope::Format, "GT", None)),
+                Box::new(Node::Literal(Value::String(b"0/1".to_vec()))),
+            ))
+        );
+
+        assert_eq!(parse(""), Err(ParseError::Empty));
+        assert_eq!(parse("QUAL >="), Err(ParseError::UnexpectedEof));
+        assert_eq!(parse("(QUAL"), Err(ParseError::UnexpectedEof));
+        assert_eq!(
+            parse("QUAL 30"),
+            Err(ParseError::UnexpectedToken(5, String::from("30")))
+        );
+        assert_eq!(
+            parse("QUAL # 30"),
+            Err(ParseError::UnexpectedCharacter(5, '#'))
+        );
+        assert_eq!(
+            parse("len(REF) > 1"),
+            Err(ParseError::UnknownIdentifier(String::from("len")))
+        );
+        assert_eq!(
+            parse("INFO/ > 0"),
+            Err(ParseError::InvalidTag(String::from("INFO/")))
+        );
+        assert_eq!(
+            parse("INFO/AF[0:1] > 0"),
+            Err(ParseError::InvalidSubscript(String::from("[0:1]")))
+        );
+        assert_eq!(
+            parse("POS[0] > 0"),
+            Err(ParseError::InvalidSubscript(String::from("[0]")))
+        );
+        assert_eq!(
+            parse("FMT/AD[0 > 0"),
+            Err(ParseError::InvalidSubscript(String::from("[0 > 0")))
+        );
+        assert_eq!(
+            parse("FMT/AD[a] > 0"),
+            Err(ParseError::InvalidSubscript(String::from("[a]")))
+        );
+        assert_eq!(
+            parse(r#"CHROM == "sq0"#),
+            Err(ParseError::UnterminatedString)
+        );
+        assert_eq!(parse("CHROM =~ 0"), Err(ParseError::ExpectedPattern));
+        assert!(matches!(
+            parse(r#"CHROM =~ "(""#),
+            Err(ParseError::InvalidPattern(_))
+        ));
+    }
+}
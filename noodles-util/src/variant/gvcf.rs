@@ -0,0 +1,333 @@
+//! gVCF reference block handling.
+//!
+//! A gVCF reference block is a record whose only alternate allele is the unspecified allele
+//! (`<NON_REF>` or `<*>`) and that spans the positions from its start to its `END`. Reference
+//! blocks can be merged into larger blocks ([`Merger`]), expanded to per-base records
+//! ([`expand()`]), and queried for the call of a sample at a position ([`query_call`]).
+
+mod expand;
+mod merge;
+
+pub use self::{expand::expand, merge::Merger};
+
+use std::io;
+
+use noodles_bgzf as bgzf;
+use noodles_core::{Position, Region};
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{
+            alternate_bases::Allele,
+            samples::{keys::key, series::Value, Sample as _},
+            AlternateBases as _, Samples as _,
+        },
+        record_buf::samples::sample::value::Genotype,
+        Record,
+    },
+};
+
+/// Returns whether the given record is a reference block.
+///
+/// A reference block has at least one alternate allele, and all of its alternate alleles are
+/// the unspecified allele (`<NON_REF>` or `<*>`).
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::gvcf;
+/// use noodles_vcf::variant::{record_buf::AlternateBases, RecordBuf};
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("<NON_REF>")]))
+///     .build();
+/// assert!(gvcf::is_reference_block(&record)?);
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![
+///         String::from("C"),
+///         String::from("<NON_REF>"),
+///     ]))
+///     .build();
+/// assert!(!gvcf::is_reference_block(&record)?);
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn is_reference_block<R>(record: &R) -> io::Result<bool>
+where
+    R: Record + ?Sized,
+{
+    let alternate_bases = record.alternate_bases();
+
+    if alternate_bases.is_empty() {
+        return Ok(false);
+    }
+
+    for result in alternate_bases.alleles() {
+        match result? {
+            Allele::Symbol(symbol) if symbol.is_unspecified() => {}
+            _ => return Ok(false),
+        }
+    }
+
+    Ok(true)
+}
+
+/// The call of a sample in a gVCF record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    start: Position,
+    end: Position,
+    is_reference_block: bool,
+    genotype: Option<Genotype>,
+    genotype_quality: Option<i32>,
+}
+
+impl Call {
+    /// Returns the start position of the record.
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the end position of the record.
+    ///
+    /// For reference blocks, this is the value of `END`.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns whether the call comes from a reference block.
+    pub fn is_reference_block(&self) -> bool {
+        self.is_reference_block
+    }
+
+    /// Returns the genotype (`GT`) of the sample.
+    pub fn genotype(&self) -> Option<&Genotype> {
+        self.genotype.as_ref()
+    }
+
+    /// Returns the genotype quality (`GQ`) of the sample.
+    pub fn genotype_quality(&self) -> Option<i32> {
+        self.genotype_quality
+    }
+}
+
+/// Returns the call of the given sample in the given record.
+///
+/// This returns `None` if the sample is not in the header.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::Position;
+/// use noodles_util::variant::gvcf;
+/// use noodles_vcf as vcf;
+///
+/// let header: vcf::Header = "\
+/// ###fileformat=VCFv4.5
+/// ###INFO=<ID=END,Number=1,Type=Integer,Description=\"End position\">
+/// ###FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+/// ###FORMAT=<ID=GQ,Number=1,Type=Integer,Description=\"Genotype quality\">
+/// ##CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+/// ".parse()?;
+///
+/// let mut reader = vcf::io::Reader::new(&b"sq0\t8\t.\tA\t<NON_REF>\t.\t.\tEND=13\tGT:GQ\t0/0:30\n"[..]);
+/// let record = reader.records().next().transpose()?.unwrap();
+///
+/// let call = gvcf::call(&header, &record, "sample0")?.unwrap();
+/// assert_eq!(call.end(), Position::try_from(13)?);
+/// assert!(call.is_reference_block());
+/// assert_eq!(call.genotype_quality(), Some(30));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn call<R>(header: &vcf::Header, record: &R, sample_name: &str) -> io::Result<Option<Call>>
+where
+    R: Record + ?Sized,
+{
+    let Some(i) = header.sample_names().get_index_of(sample_name) else {
+        return Ok(None);
+    };
+
+    let start = record
+        .variant_start()
+        .transpose()?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing position"))?;
+
+    let end = record.variant_end(header)?;
+
+    let samples = record.samples()?;
+
+    let (genotype, genotype_quality) = match samples.iter().nth(i) {
+        Some(sample) => {
+            let genotype = match sample.get(header, key::GENOTYPE).transpose()? {
+                Some(Some(Value::Genotype(genotype))) => Some(genotype.as_ref().try_into()?),
+                _ => None,
+            };
+
+            let genotype_quality = match sample
+                .get(header, key::CONDITIONAL_GENOTYPE_QUALITY)
+                .transpose()?
+            {
+                Some(Some(Value::Integer(n))) => Some(n),
+                _ => None,
+            };
+
+            (genotype, genotype_quality)
+        }
+        None => (None, None),
+    };
+
+    Ok(Some(Call {
+        start,
+        end,
+        is_reference_block: is_reference_block(record)?,
+        genotype,
+        genotype_quality,
+    }))
+}
+
+/// Queries the call of the given sample at the given position.
+///
+/// This finds records that overlap the position, including reference blocks that start before
+/// it. A variant record that overlaps the position is preferred over a reference block.
+///
+/// This returns `None` if no record overlaps the position or the sample is not in the header.
+pub fn query_call<R>(
+    reader: &mut vcf::io::IndexedReader<R>,
+    header: &vcf::Header,
+    reference_sequence_name: &str,
+    position: Position,
+    sample_name: &str,
+) -> io::Result<Option<Call>>
+where
+    R: bgzf::io::BufRead + bgzf::io::Seek,
+{
+    let region = Region::new(reference_sequence_name, position..=position);
+
+    let mut reference_block_call = None;
+
+    for result in reader.query(header, &region)? {
+        let record = result?;
+
+        let Some(call) = call(header, &record, sample_name)? else {
+            return Ok(None);
+        };
+
+        if call.is_reference_block() {
+            reference_block_call.get_or_insert(call);
+        } else {
+            return Ok(Some(call));
+        }
+    }
+
+    Ok(reference_block_call)
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_csi::{self as csi, binning_index::index::reference_sequence::bin::Chunk};
+    use noodles_tabix as tabix;
+
+    use super::*;
+
+    const DATA: &[u8] = b"\
+##fileformat=VCFv4.5
+##INFO=<ID=END,Number=1,Type=Integer,Description=\"End position\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=GQ,Number=1,Type=Integer,Description=\"Genotype quality\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+sq0\t1\t.\tA\t<NON_REF>\t.\t.\tEND=9\tGT:GQ\t0/0:30
+sq0\t10\t.\tC\tT,<NON_REF>\t50\t.\t.\tGT:GQ\t0/1:45
+sq0\t11\t.\tG\t<NON_REF>\t.\t.\tEND=20\tGT:GQ\t0/0:60
+";
+
+    #[test]
+    fn test_query_call() -> Result<(), Box<dyn std::error::Error>> {
+        let mut writer = bgzf::Writer::new(Vec::new());
+        io::Write::write_all(&mut writer, DATA)?;
+        let data = writer.finish()?;
+
+        let mut reader = vcf::io::Reader::new(bgzf::Reader::new(&data[..]));
+        let header = reader.read_header()?;
+
+        let mut indexer = tabix::index::Indexer::default();
+        indexer.set_header(csi::binning_index::index::header::Builder::vcf().build());
+
+        let mut record = vcf::Record::default();
+        let mut start_position = reader.get_ref().virtual_position();
+
+        while reader.read_record(&mut record)? != 0 {
+            let end_position = reader.get_ref().virtual_position();
+            let start = record.variant_start().transpose()?.unwrap();
+            let end = record.variant_end(&header)?;
+            indexer.add_record(
+                record.reference_sequence_name(),
+                start,
+                end,
+                Chunk::new(start_position, end_position),
+            )?;
+            start_position = end_position;
+        }
+
+        let index = indexer.build();
+        let mut reader = vcf::io::IndexedReader::new(io::Cursor::new(data), index);
+
+        let call = query_call(
+            &mut reader,
+            &header,
+            "sq0",
+            Position::try_from(5)?,
+            "sample0",
+        )?
+        .unwrap();
+        assert!(call.is_reference_block());
+        assert_eq!(call.start(), Position::MIN);
+        assert_eq!(call.end(), Position::try_from(9)?);
+        assert_eq!(call.genotype(), Some(&"0/0".parse()?));
+        assert_eq!(call.genotype_quality(), Some(30));
+
+        let call = query_call(
+            &mut reader,
+            &header,
+            "sq0",
+            Position::try_from(10)?,
+            "sample0",
+        )?
+        .unwrap();
+        assert!(!call.is_reference_block());
+        assert_eq!(call.genotype(), Some(&"0/1".parse()?));
+        assert_eq!(call.genotype_quality(), Some(45));
+
+        let call = query_call(
+            &mut reader,
+            &header,
+            "sq0",
+            Position::try_from(20)?,
+            "sample0",
+        )?
+        .unwrap();
+        assert_eq!(call.start(), Position::try_from(11)?);
+        assert_eq!(call.genotype_quality(), Some(60));
+
+        assert!(query_call(
+            &mut reader,
+            &header,
+            "sq0",
+            Position::try_from(21)?,
+            "sample0"
+        )?
+        .is_none());
+        assert!(query_call(
+            &mut reader,
+            &header,
+            "sq0",
+            Position::try_from(5)?,
+            "sample1"
+        )?
+        .is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use std::io;
+
+use noodles_core::{Position, Region};
+use noodles_fasta as fasta;
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{info::field::key as info_key, Record as _},
+        RecordBuf,
+    },
+};
+
+use super::is_reference_block;
+
+/// Expands a reference block to per-base records within the given region.
+///
+/// Each expanded record is a copy of the reference block at a single position without `END`. The
+/// reference base of each record is taken from the given reference sequence, if any. Otherwise,
+/// the first position uses the first reference base of the block, and the other positions use
+/// `N`.
+///
+/// Records that are not reference blocks are returned unchanged if they overlap the region.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::Position;
+/// use noodles_util::variant::gvcf;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Info, Map},
+///     variant::{
+///         record::info::field::key,
+///         record_buf::{info::field::Value, AlternateBases},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::builder()
+///     .add_info(key::END_POSITION, Map::<Info>::from(key::END_POSITION))
+///     .build();
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_sequence_name("sq0")
+///     .set_variant_start(Position::try_from(8)?)
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("<NON_REF>")]))
+///     .set_info(
+///         [(String::from(key::END_POSITION), Some(Value::Integer(13)))]
+///             .into_iter()
+///             .collect(),
+///     )
+///     .build();
+///
+/// let records = gvcf::expand(&header, &record, &"sq0:10-20".parse()?, None)?;
+/// assert_eq!(records.len(), 4);
+/// assert_eq!(records[0].variant_start(), Position::new(10));
+/// assert_eq!(records[0].reference_bases(), "N");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn expand(
+    header: &vcf::Header,
+    record: &RecordBuf,
+    region: &Region,
+    reference_sequence: Option<&fasta::record::Sequence>,
+) -> io::Result<Vec<RecordBuf>> {
+    if region.name() != record.reference_sequence_name() {
+        return Ok(Vec::new());
+    }
+
+    let record_start = record
+        .variant_start()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing position"))?;
+
+    let record_end = record.variant_end(header)?;
+
+    let interval = region.interval();
+    let start = interval
+        .start()
+        .map_or(record_start, |p| p.max(record_start));
+    let end = interval.end().map_or(record_end, |p| p.min(record_end));
+
+    if start > end {
+        return Ok(Vec::new());
+    }
+
+    if !is_reference_block(record)? {
+        return Ok(vec![record.clone()]);
+    }
+
+    let mut template = record.clone();
+    template
+        .info_mut()
+        .as_mut()
+        .shift_remove(info_key::END_POSITION);
+
+    let mut records = Vec::with_capacity(usize::from(end) - usize::from(start) + 1);
+
+    for n in usize::from(start)..=usize::from(end) {
+        let position =
+            Position::try_from(n).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let reference_base = match reference_sequence {
+            Some(sequence) => sequence
+                .get(position)
+                .map(|&b| char::from(b.to_ascii_uppercase()))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "position out of reference sequence bounds",
+                    )
+                })?,
+            None if position == record_start => {
+                record.reference_bases().chars().next().unwrap_or('N')
+            }
+            None => 'N',
+        };
+
+        let mut expanded_record = template.clone();
+        *expanded_record.variant_start_mut() = Some(position);
+        *expanded_record.reference_bases_mut() = reference_base.to_string();
+
+        records.push(expanded_record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::{
+        header::record::value::{map::Info, Map},
+        variant::record_buf::{info::field::Value, AlternateBases},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_expand() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_info(
+                info_key::END_POSITION,
+                Map::<Info>::from(info_key::END_POSITION),
+            )
+            .build();
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("C")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("<*>")]))
+            .set_info(
+                [(
+                    String::from(info_key::END_POSITION),
+                    Some(Value::Integer(5)),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .build();
+
+        let records = expand(&header, &record, &"sq0".parse()?, None)?;
+        let actual: Vec<_> = records
+            .iter()
+            .map(|r| {
+                (
+                    r.variant_start(),
+                    r.reference_bases(),
+                    r.info().as_ref().is_empty(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            actual,
+            [
+                (Position::new(2), "C", true),
+                (Position::new(3), "N", true),
+                (Position::new(4), "N", true),
+                (Position::new(5), "N", true),
+            ]
+        );
+
+        let sequence = fasta::record::Sequence::from(b"acgtac".to_vec());
+        let records = expand(&header, &record, &"sq0:4-8".parse()?, Some(&sequence))?;
+        let actual: Vec<_> = records
+            .iter()
+            .map(|r| (r.variant_start(), r.reference_bases()))
+            .collect();
+        assert_eq!(actual, [(Position::new(4), "T"), (Position::new(5), "A")]);
+
+        assert!(expand(&header, &record, &"sq0:6-8".parse()?, None)?.is_empty());
+        assert!(expand(&header, &record, &"sq1".parse()?, None)?.is_empty());
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("C")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("T")]))
+            .set_info(
+                [(
+                    String::from(info_key::END_POSITION),
+                    Some(Value::Integer(5)),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .build();
+
+        assert_eq!(
+            expand(&header, &record, &"sq0:3-3".parse()?, None)?,
+            [record]
+        );
+
+        Ok(())
+    }
+}
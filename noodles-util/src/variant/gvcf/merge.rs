@@ -0,0 +1,360 @@
+use std::{io, iter, mem};
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{info::field::key as info_key, samples::keys::key, Record as _},
+        record_buf::{
+            info::field::Value as InfoValue,
+            samples::{sample::Value, Keys},
+            RecordBuf, Samples,
+        },
+    },
+};
+
+use super::is_reference_block;
+
+const MIN_READ_DEPTH: &str = "MIN_DP";
+
+const DEFAULT_GENOTYPE_QUALITY_BANDS: [i32; 2] = [20, 60];
+
+/// A gVCF reference block merger.
+///
+/// Adjacent reference blocks are merged when they are on the same reference sequence, are
+/// contiguous, and each sample has the same genotype (`GT`) and a genotype quality (`GQ`) in the
+/// same band. The `GQ`, `DP`, and `MIN_DP` values of a merged block are the minimums of the
+/// merged blocks, similar to GATK `CombineGVCFs --gvcf-gq-bands`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Merger {
+    genotype_quality_bands: Vec<i32>,
+}
+
+impl Merger {
+    /// Creates a reference block merger with the given genotype quality band boundaries.
+    ///
+    /// Each boundary is the inclusive lower bound of a band. For example, the boundaries
+    /// `[20, 60]` create the bands `[0, 20)`, `[20, 60)`, and `[60, ∞)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::gvcf::Merger;
+    /// let merger = Merger::new([10, 20, 30, 40, 50, 60]);
+    /// ```
+    pub fn new<I>(genotype_quality_bands: I) -> Self
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let mut genotype_quality_bands: Vec<_> = genotype_quality_bands.into_iter().collect();
+        genotype_quality_bands.sort_unstable();
+        genotype_quality_bands.dedup();
+
+        Self {
+            genotype_quality_bands,
+        }
+    }
+
+    /// Returns an iterator over records with adjacent reference blocks merged.
+    ///
+    /// The input records must be sorted. Records that are not reference blocks are passed
+    /// through unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::gvcf::Merger;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let header = vcf::Header::default();
+    /// let merger = Merger::default();
+    ///
+    /// let records = merger.merge(&header, Vec::new());
+    /// assert_eq!(records.count(), 0);
+    /// ```
+    pub fn merge<'a, I>(
+        &'a self,
+        header: &'a vcf::Header,
+        records: I,
+    ) -> impl Iterator<Item = io::Result<RecordBuf>> + 'a
+    where
+        I: IntoIterator<Item = io::Result<RecordBuf>>,
+        I::IntoIter: 'a,
+    {
+        let mut records = records.into_iter();
+        let mut pending: Option<RecordBuf> = None;
+
+        iter::from_fn(move || loop {
+            let record = match records.next() {
+                Some(Ok(record)) => record,
+                Some(Err(e)) => return Some(Err(e)),
+                None => return pending.take().map(Ok),
+            };
+
+            let Some(mut prev) = pending.take() else {
+                pending = Some(record);
+                continue;
+            };
+
+            match self.try_merge(header, &mut prev, &record) {
+                Ok(true) => pending = Some(prev),
+                Ok(false) => {
+                    pending = Some(record);
+                    return Some(Ok(prev));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        })
+    }
+
+    fn band(&self, genotype_quality: Option<i32>) -> Option<usize> {
+        genotype_quality.map(|n| {
+            self.genotype_quality_bands
+                .iter()
+                .take_while(|&&boundary| boundary <= n)
+                .count()
+        })
+    }
+
+    fn try_merge(
+        &self,
+        header: &vcf::Header,
+        prev: &mut RecordBuf,
+        next: &RecordBuf,
+    ) -> io::Result<bool> {
+        if !self.can_merge(header, prev, next)? {
+            return Ok(false);
+        }
+
+        let end = next.variant_end(header)?;
+
+        let (keys, mut values): (Keys, Vec<Vec<Option<Value>>>) =
+            mem::take(prev.samples_mut()).into();
+
+        for (prev_values, next_sample) in values.iter_mut().zip(next.samples().values()) {
+            for k in [
+                key::CONDITIONAL_GENOTYPE_QUALITY,
+                key::READ_DEPTH,
+                MIN_READ_DEPTH,
+            ] {
+                let Some(i) = keys.as_ref().get_index_of(k) else {
+                    continue;
+                };
+
+                if let (Some(Some(Value::Integer(a))), Some(Some(Value::Integer(b)))) =
+                    (prev_values.get_mut(i), next_sample.values().get(i))
+                {
+                    *a = (*a).min(*b);
+                }
+            }
+        }
+
+        *prev.samples_mut() = Samples::new(keys, values);
+
+        let end = i32::try_from(usize::from(end))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        prev.info_mut().insert(
+            String::from(info_key::END_POSITION),
+            Some(InfoValue::Integer(end)),
+        );
+
+        Ok(true)
+    }
+
+    fn can_merge(
+        &self,
+        header: &vcf::Header,
+        prev: &RecordBuf,
+        next: &RecordBuf,
+    ) -> io::Result<bool> {
+        if prev.reference_sequence_name() != next.reference_sequence_name()
+            || !is_reference_block(prev)?
+            || !is_reference_block(next)?
+        {
+            return Ok(false);
+        }
+
+        let prev_end = prev.variant_end(header)?;
+
+        if prev_end.checked_add(1) != next.variant_start() {
+            return Ok(false);
+        }
+
+        let keys = prev.samples().keys();
+
+        if keys != next.samples().keys() {
+            return Ok(false);
+        }
+
+        let genotype_index = keys.as_ref().get_index_of(key::GENOTYPE);
+        let genotype_quality_index = keys
+            .as_ref()
+            .get_index_of(key::CONDITIONAL_GENOTYPE_QUALITY);
+
+        let mut prev_samples = prev.samples().values();
+        let mut next_samples = next.samples().values();
+
+        loop {
+            let (prev_sample, next_sample) = match (prev_samples.next(), next_samples.next()) {
+                (Some(a), Some(b)) => (a, b),
+                (None, None) => return Ok(true),
+                _ => return Ok(false),
+            };
+
+            if get(prev_sample.values(), genotype_index)
+                != get(next_sample.values(), genotype_index)
+            {
+                return Ok(false);
+            }
+
+            let genotype_quality =
+                |values: &[Option<Value>]| match get(values, genotype_quality_index) {
+                    Some(Value::Integer(n)) => Some(*n),
+                    _ => None,
+                };
+
+            if self.band(genotype_quality(prev_sample.values()))
+                != self.band(genotype_quality(next_sample.values()))
+            {
+                return Ok(false);
+            }
+        }
+    }
+}
+
+impl Default for Merger {
+    fn default() -> Self {
+        Self::new(DEFAULT_GENOTYPE_QUALITY_BANDS)
+    }
+}
+
+fn get(values: &[Option<Value>], i: Option<usize>) -> Option<&Value> {
+    i.and_then(|i| values.get(i))
+        .and_then(|value| value.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::Position;
+    use vcf::{
+        header::record::value::{
+            map::{Format, Info},
+            Map,
+        },
+        variant::record_buf::AlternateBases,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_merge() -> Result<(), Box<dyn std::error::Error>> {
+        let header = vcf::Header::builder()
+            .add_info(
+                info_key::END_POSITION,
+                Map::<Info>::from(info_key::END_POSITION),
+            )
+            .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+            .add_format(
+                key::CONDITIONAL_GENOTYPE_QUALITY,
+                Map::<Format>::from(key::CONDITIONAL_GENOTYPE_QUALITY),
+            )
+            .add_format(key::READ_DEPTH, Map::<Format>::from(key::READ_DEPTH))
+            .add_sample_name("sample0")
+            .build();
+
+        let keys: Keys = [
+            key::GENOTYPE,
+            key::CONDITIONAL_GENOTYPE_QUALITY,
+            key::READ_DEPTH,
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        // (POS, END, ALT, GT, GQ, DP)
+        let to_record =
+            |(start, end, alternate_bases, genotype, genotype_quality, read_depth): (
+                usize,
+                Option<i32>,
+                &[&str],
+                &str,
+                i32,
+                i32,
+            )|
+             -> Result<RecordBuf, Box<dyn std::error::Error>> {
+                let info = end
+                    .map(|n| {
+                        (
+                            String::from(info_key::END_POSITION),
+                            Some(InfoValue::Integer(n)),
+                        )
+                    })
+                    .into_iter()
+                    .collect();
+
+                Ok(RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(start)?)
+                    .set_reference_bases("A")
+                    .set_alternate_bases(AlternateBases::from(
+                        alternate_bases
+                            .iter()
+                            .map(|s| String::from(*s))
+                            .collect::<Vec<_>>(),
+                    ))
+                    .set_info(info)
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![vec![
+                            Some(Value::Genotype(genotype.parse()?)),
+                            Some(Value::Integer(genotype_quality)),
+                            Some(Value::Integer(read_depth)),
+                        ]],
+                    ))
+                    .build())
+            };
+
+        let records = [
+            (1, Some(5), &["<NON_REF>"][..], "0/0", 30, 12),
+            (6, Some(9), &["<NON_REF>"], "0/0", 25, 8),
+            (10, Some(12), &["<NON_REF>"], "0/0", 70, 20),
+            (13, None, &["T", "<NON_REF>"], "0/1", 50, 18),
+            (14, Some(20), &["<*>"], "0/0", 75, 22),
+            (21, Some(25), &["<*>"], "0/0", 65, 19),
+            (30, Some(35), &["<*>"], "0/0", 65, 19),
+        ]
+        .into_iter()
+        .map(to_record)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        let merger = Merger::default();
+        let actual: Vec<_> = merger
+            .merge(&header, records.into_iter().map(Ok))
+            .collect::<io::Result<_>>()?;
+
+        let expected = [
+            (1, Some(9), &["<NON_REF>"][..], "0/0", 25, 8),
+            (10, Some(12), &["<NON_REF>"], "0/0", 70, 20),
+            (13, None, &["T", "<NON_REF>"], "0/1", 50, 18),
+            (14, Some(25), &["<*>"], "0/0", 65, 19),
+            (30, Some(35), &["<*>"], "0/0", 65, 19),
+        ]
+        .into_iter()
+        .map(to_record)
+        .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_band() {
+        let merger = Merger::new([60, 20]);
+        assert_eq!(merger.band(None), None);
+        assert_eq!(merger.band(Some(0)), Some(0));
+        assert_eq!(merger.band(Some(20)), Some(1));
+        assert_eq!(merger.band(Some(59)), Some(1));
+        assert_eq!(merger.band(Some(99)), Some(2));
+    }
+}
@@ -0,0 +1,1265 @@
+//! Local alleles.
+//!
+//! VCF 4.5 allows sample fields to be stored relative to a subset of alleles that are relevant to
+//! each sample (§ 1.6.2 "Genotype fields"). The local alternate alleles of a sample are listed in
+//! `LAA` as 1-based indices into ALT, and local fields (e.g., `LAD` and `LPL`) have a number of
+//! `LA`, `LR`, or `LG` relative to the reference allele and the local alternate alleles. This
+//! keeps records with many alternate alleles compact, particularly in large joint-called cohorts.
+//!
+//! The name of a local field is the name of its global field prefixed with `L`. The global
+//! genotype (`GT`) is kept by default but is converted to and from a local genotype (`LGT`) when
+//! the header defines it.
+
+use std::{io, mem};
+
+use noodles_vcf::{
+    self as vcf,
+    header::record::value::{
+        map::{format::Number, Format},
+        Map,
+    },
+    variant::{
+        record::samples::keys::key,
+        record_buf::{
+            samples::{
+                sample::{
+                    value::{genotype::Allele, Array, Genotype},
+                    Value,
+                },
+                Keys,
+            },
+            RecordBuf, Samples,
+        },
+    },
+};
+
+use super::multiallelic::{genotype_count, genotype_index, genotypes};
+
+/// The local genotype key (`LGT`).
+///
+/// This is not a reserved key in VCF 4.5 and is only used when defined in the header.
+pub const LOCAL_GENOTYPE: &str = "LGT";
+
+const LOCAL_KEY_PREFIX: char = 'L';
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    AlternateBases,
+    ReferenceAlternateBases,
+    Samples,
+    Genotype,
+}
+
+/// Adds local field definitions to the given header.
+///
+/// This adds `LAA` and, for each FORMAT definition with a number of `A`, `R`, or `G`, a local
+/// field definition with the same type. `LGT` is not added.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::local_alleles;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Format, Map},
+///     variant::record::samples::keys::key,
+/// };
+///
+/// let mut header = vcf::Header::builder()
+///     .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+///     .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+///     .build();
+///
+/// local_alleles::add_header_definitions(&mut header);
+///
+/// let formats = header.formats();
+/// assert!(formats.contains_key(key::LOCAL_ALTERNATIVE_ALLELE));
+/// assert!(formats.contains_key(key::LOCAL_READ_DEPTHS));
+/// assert!(!formats.contains_key(local_alleles::LOCAL_GENOTYPE));
+/// ```
+pub fn add_header_definitions(header: &mut vcf::Header) {
+    let definitions: Vec<_> = header
+        .formats()
+        .iter()
+        .filter_map(|(key, format)| {
+            let number = match format.number() {
+                Number::AlternateBases => Number::LocalAlternateBases,
+                Number::ReferenceAlternateBases => Number::LocalReferenceAlternateBases,
+                Number::Samples => Number::LocalSamples,
+                _ => return None,
+            };
+
+            Some((
+                local_key(key),
+                Map::<Format>::new(
+                    number,
+                    format.ty(),
+                    format!("Local-allele representation of {key}"),
+                ),
+            ))
+        })
+        .collect();
+
+    let formats = header.formats_mut();
+
+    if !formats.contains_key(key::LOCAL_ALTERNATIVE_ALLELE) {
+        formats.insert(
+            String::from(key::LOCAL_ALTERNATIVE_ALLELE),
+            Map::<Format>::from(key::LOCAL_ALTERNATIVE_ALLELE),
+        );
+    }
+
+    for (key, definition) in definitions {
+        formats.entry(key).or_insert(definition);
+    }
+}
+
+/// Converts the sample fields of the given record to local-allele representations.
+///
+/// The local alternate alleles of a sample are the alternate alleles in its genotype and, if
+/// present, those with nonzero read depths (`AD`). A global field is converted when its local
+/// field is defined in the header; otherwise, it is kept. Records that already have `LAA` are not
+/// modified.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::local_alleles;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Format, Map},
+///     variant::{
+///         record::samples::keys::key,
+///         record_buf::{
+///             samples::{sample::{value::Array, Value}, Keys},
+///             AlternateBases, Samples,
+///         },
+///         RecordBuf,
+///     },
+/// };
+///
+/// let mut header = vcf::Header::builder()
+///     .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+///     .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+///     .build();
+///
+/// local_alleles::add_header_definitions(&mut header);
+///
+/// let keys: Keys = [key::GENOTYPE, key::READ_DEPTHS].into_iter().map(String::from).collect();
+///
+/// let mut record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![
+///         String::from("C"),
+///         String::from("G"),
+///     ]))
+///     .set_samples(Samples::new(
+///         keys,
+///         vec![vec![
+///             Some(Value::Genotype("0/2".parse()?)),
+///             Some(Value::from(vec![Some(8), Some(0), Some(5)])),
+///         ]],
+///     ))
+///     .build();
+///
+/// local_alleles::localize(&header, &mut record)?;
+///
+/// let sample = record.samples().get_index(0).unwrap();
+/// assert_eq!(
+///     sample.get(key::LOCAL_ALTERNATIVE_ALLELE),
+///     Some(Some(&Value::from(vec![Some(2)])))
+/// );
+/// assert_eq!(
+///     sample.get(key::LOCAL_READ_DEPTHS),
+///     Some(Some(&Value::from(vec![Some(8), Some(5)])))
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn localize(header: &vcf::Header, record: &mut RecordBuf) -> io::Result<()> {
+    if record
+        .samples()
+        .keys()
+        .as_ref()
+        .contains(key::LOCAL_ALTERNATIVE_ALLELE)
+    {
+        return Ok(());
+    }
+
+    let allele_count = record.alternate_bases().as_ref().len() + 1;
+
+    let (keys, values): (Keys, Vec<Vec<Option<Value>>>) = mem::take(record.samples_mut()).into();
+
+    let conversions: Vec<_> = keys
+        .as_ref()
+        .iter()
+        .map(|key| global_kind(header, key))
+        .collect();
+
+    let genotype_index = keys.as_ref().get_index_of(key::GENOTYPE);
+    let read_depths_index = keys.as_ref().get_index_of(key::READ_DEPTHS);
+
+    let mut local_keys: Keys = keys
+        .as_ref()
+        .iter()
+        .zip(&conversions)
+        .map(|(key, kind)| match kind {
+            Some(Kind::Genotype) => String::from(LOCAL_GENOTYPE),
+            Some(_) => local_key(key),
+            None => key.clone(),
+        })
+        .collect();
+
+    // `GT` must be the first key, if present.
+    let local_alternate_alleles_index = usize::from(genotype_index == Some(0));
+
+    local_keys.as_mut().shift_insert(
+        local_alternate_alleles_index,
+        String::from(key::LOCAL_ALTERNATIVE_ALLELE),
+    );
+
+    let mut local_values = Vec::with_capacity(values.len());
+
+    for sample_values in values {
+        let genotype = genotype_index
+            .and_then(|i| sample_values.get(i))
+            .and_then(|value| value.as_ref());
+
+        let read_depths = read_depths_index
+            .and_then(|i| sample_values.get(i))
+            .and_then(|value| value.as_ref());
+
+        let alleles = select_local_alleles(allele_count, genotype, read_depths)?;
+        let ploidy_hint = genotype_ploidy(genotype);
+
+        let mut sample_local_values = Vec::with_capacity(sample_values.len() + 1);
+
+        for (value, kind) in sample_values.into_iter().zip(&conversions) {
+            let value = match kind {
+                Some(kind) => localize_value(*kind, value, &alleles, allele_count, ploidy_hint)?,
+                None => value,
+            };
+
+            sample_local_values.push(value);
+        }
+
+        let local_alternate_alleles = if alleles.len() > 1 {
+            let indices = alleles[1..]
+                .iter()
+                .map(|&i| i32::try_from(i).map(Some))
+                .collect::<Result<_, _>>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            Some(Value::Array(Array::Integer(indices)))
+        } else {
+            None
+        };
+
+        sample_local_values.insert(local_alternate_alleles_index, local_alternate_alleles);
+
+        local_values.push(sample_local_values);
+    }
+
+    *record.samples_mut() = Samples::new(local_keys, local_values);
+
+    Ok(())
+}
+
+/// Converts the local sample fields of the given record to global representations.
+///
+/// Values of alleles and genotypes that are not local to a sample are missing. `LAA` is removed.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::local_alleles;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Format, Map},
+///     variant::{
+///         record::samples::keys::key,
+///         record_buf::{
+///             samples::{sample::Value, Keys},
+///             AlternateBases, Samples,
+///         },
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::builder()
+///     .add_format(
+///         key::LOCAL_ALTERNATIVE_ALLELE,
+///         Map::<Format>::from(key::LOCAL_ALTERNATIVE_ALLELE),
+///     )
+///     .add_format(key::LOCAL_READ_DEPTHS, Map::<Format>::from(key::LOCAL_READ_DEPTHS))
+///     .build();
+///
+/// let keys: Keys = [key::LOCAL_ALTERNATIVE_ALLELE, key::LOCAL_READ_DEPTHS]
+///     .into_iter()
+///     .map(String::from)
+///     .collect();
+///
+/// let mut record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![
+///         String::from("C"),
+///         String::from("G"),
+///     ]))
+///     .set_samples(Samples::new(
+///         keys,
+///         vec![vec![
+///             Some(Value::from(vec![Some(2)])),
+///             Some(Value::from(vec![Some(8), Some(5)])),
+///         ]],
+///     ))
+///     .build();
+///
+/// local_alleles::globalize(&header, &mut record)?;
+///
+/// let sample = record.samples().get_index(0).unwrap();
+/// assert_eq!(sample.get(key::LOCAL_ALTERNATIVE_ALLELE), None);
+/// assert_eq!(
+///     sample.get(key::READ_DEPTHS),
+///     Some(Some(&Value::from(vec![Some(8), None, Some(5)])))
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn globalize(header: &vcf::Header, record: &mut RecordBuf) -> io::Result<()> {
+    let Some(local_alternate_alleles_index) = record
+        .samples()
+        .keys()
+        .as_ref()
+        .get_index_of(key::LOCAL_ALTERNATIVE_ALLELE)
+    else {
+        return Ok(());
+    };
+
+    let allele_count = record.alternate_bases().as_ref().len() + 1;
+
+    let (keys, values): (Keys, Vec<Vec<Option<Value>>>) = mem::take(record.samples_mut()).into();
+
+    let conversions: Vec<_> = keys
+        .as_ref()
+        .iter()
+        .map(|key| local_kind(header, key))
+        .collect();
+
+    let genotype_index = keys
+        .as_ref()
+        .get_index_of(key::GENOTYPE)
+        .or_else(|| keys.as_ref().get_index_of(LOCAL_GENOTYPE));
+
+    let global_keys: Keys = keys
+        .as_ref()
+        .iter()
+        .zip(&conversions)
+        .enumerate()
+        .filter(|(i, _)| *i != local_alternate_alleles_index)
+        .map(|(_, (key, kind))| match kind {
+            Some(Kind::Genotype) => String::from(key::GENOTYPE),
+            Some(_) => global_key(key).into(),
+            None => key.clone(),
+        })
+        .collect();
+
+    let mut global_values = Vec::with_capacity(values.len());
+
+    for sample_values in values {
+        let alleles = local_alleles(
+            allele_count,
+            sample_values
+                .get(local_alternate_alleles_index)
+                .and_then(|value| value.as_ref()),
+        )?;
+
+        let ploidy_hint = genotype_ploidy(
+            genotype_index
+                .and_then(|i| sample_values.get(i))
+                .and_then(|value| value.as_ref()),
+        );
+
+        let mut sample_global_values = Vec::with_capacity(sample_values.len());
+
+        for (i, (value, kind)) in sample_values.into_iter().zip(&conversions).enumerate() {
+            if i == local_alternate_alleles_index {
+                continue;
+            }
+
+            let value = match kind {
+                Some(kind) => globalize_value(*kind, value, &alleles, allele_count, ploidy_hint)?,
+                None => value,
+            };
+
+            sample_global_values.push(value);
+        }
+
+        global_values.push(sample_global_values);
+    }
+
+    *record.samples_mut() = Samples::new(global_keys, global_values);
+
+    Ok(())
+}
+
+/// Validates the local sample fields of the given record.
+///
+/// This checks that `LAA` values are increasing indices into ALT, that local fields are defined
+/// in the header and have lengths consistent with `LAA`, that local genotypes only reference local
+/// alleles, and that a field is not stored in both global and local representations.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::local_alleles;
+/// use noodles_vcf::{
+///     self as vcf,
+///     variant::{
+///         record::samples::keys::key,
+///         record_buf::{samples::{sample::Value, Keys}, AlternateBases, Samples},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::default();
+/// let keys: Keys = [String::from(key::LOCAL_ALTERNATIVE_ALLELE)].into_iter().collect();
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+///     .set_samples(Samples::new(keys, vec![vec![Some(Value::from(vec![Some(2)]))]]))
+///     .build();
+///
+/// assert!(local_alleles::validate(&header, &record).is_err());
+/// ```
+pub fn validate(header: &vcf::Header, record: &RecordBuf) -> io::Result<()> {
+    let keys = record.samples().keys();
+
+    let local_alternate_alleles_index = keys.as_ref().get_index_of(key::LOCAL_ALTERNATIVE_ALLELE);
+
+    for key in keys.as_ref() {
+        let is_local = if key == LOCAL_GENOTYPE {
+            header.formats().contains_key(LOCAL_GENOTYPE)
+        } else {
+            key.starts_with(LOCAL_KEY_PREFIX) && key != key::LOCAL_ALTERNATIVE_ALLELE && {
+                let global_key = global_key(key);
+                header.formats().contains_key(key.as_str())
+                    || header.formats().get(global_key).is_some_and(|format| {
+                        matches!(
+                            format.number(),
+                            Number::AlternateBases
+                                | Number::ReferenceAlternateBases
+                                | Number::Samples
+                        )
+                    })
+            }
+        };
+
+        if !is_local {
+            continue;
+        }
+
+        if local_kind(header, key).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid local field definition: {key}"),
+            ));
+        }
+
+        let global_key = if key == LOCAL_GENOTYPE {
+            key::GENOTYPE
+        } else {
+            global_key(key)
+        };
+
+        if keys.as_ref().contains(global_key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("field has both global and local representations: {global_key}, {key}"),
+            ));
+        }
+
+        if local_alternate_alleles_index.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "missing {} for local field: {key}",
+                    key::LOCAL_ALTERNATIVE_ALLELE
+                ),
+            ));
+        }
+    }
+
+    if local_alternate_alleles_index.is_some() {
+        let mut record = record.clone();
+        globalize(header, &mut record)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the global value of a sample field.
+///
+/// If the record does not have the global field but has its local field, the local value is
+/// converted using the local alternate alleles of the sample. This returns `None` if the sample
+/// does not have the field.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_util::variant::local_alleles;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Format, Map},
+///     variant::{
+///         record::samples::keys::key,
+///         record_buf::{samples::{sample::Value, Keys}, AlternateBases, Samples},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::builder()
+///     .add_format(
+///         key::LOCAL_ALTERNATIVE_ALLELE,
+///         Map::<Format>::from(key::LOCAL_ALTERNATIVE_ALLELE),
+///     )
+///     .add_format(key::LOCAL_READ_DEPTHS, Map::<Format>::from(key::LOCAL_READ_DEPTHS))
+///     .build();
+///
+/// let keys: Keys = [key::LOCAL_ALTERNATIVE_ALLELE, key::LOCAL_READ_DEPTHS]
+///     .into_iter()
+///     .map(String::from)
+///     .collect();
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+///     .set_samples(Samples::new(
+///         keys,
+///         vec![vec![Some(Value::from(vec![Some(1)])), Some(Value::from(vec![Some(3), Some(5)]))]],
+///     ))
+///     .build();
+///
+/// assert_eq!(
+///     local_alleles::get(&header, &record, 0, key::READ_DEPTHS)?,
+///     Some(Value::from(vec![Some(3), Some(5)]))
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn get(
+    header: &vcf::Header,
+    record: &RecordBuf,
+    sample_index: usize,
+    key: &str,
+) -> io::Result<Option<Value>> {
+    let Some(sample) = record.samples().get_index(sample_index) else {
+        return Ok(None);
+    };
+
+    if let Some(value) = sample.get(key) {
+        return Ok(value.cloned());
+    }
+
+    let local_key = if key == key::GENOTYPE {
+        String::from(LOCAL_GENOTYPE)
+    } else {
+        local_key(key)
+    };
+
+    let Some(kind) = local_kind(header, &local_key) else {
+        return Ok(None);
+    };
+
+    let Some(value) = sample.get(&local_key) else {
+        return Ok(None);
+    };
+
+    let allele_count = record.alternate_bases().as_ref().len() + 1;
+
+    let alleles = local_alleles(
+        allele_count,
+        sample.get(key::LOCAL_ALTERNATIVE_ALLELE).flatten(),
+    )?;
+
+    let ploidy_hint = genotype_ploidy(
+        sample
+            .get(key::GENOTYPE)
+            .or_else(|| sample.get(LOCAL_GENOTYPE))
+            .flatten(),
+    );
+
+    globalize_value(kind, value.cloned(), &alleles, allele_count, ploidy_hint)
+}
+
+fn local_key(key: &str) -> String {
+    format!("{LOCAL_KEY_PREFIX}{key}")
+}
+
+fn global_key(key: &str) -> &str {
+    key.strip_prefix(LOCAL_KEY_PREFIX).unwrap_or(key)
+}
+
+fn global_kind(header: &vcf::Header, key: &str) -> Option<Kind> {
+    if key == key::GENOTYPE {
+        return header
+            .formats()
+            .contains_key(LOCAL_GENOTYPE)
+            .then_some(Kind::Genotype);
+    }
+
+    let kind = match header.formats().get(key)?.number() {
+        Number::AlternateBases => Kind::AlternateBases,
+        Number::ReferenceAlternateBases => Kind::ReferenceAlternateBases,
+        Number::Samples => Kind::Samples,
+        _ => return None,
+    };
+
+    (local_kind(header, &local_key(key)) == Some(kind)).then_some(kind)
+}
+
+fn local_kind(header: &vcf::Header, key: &str) -> Option<Kind> {
+    let format = header.formats().get(key)?;
+
+    if key == LOCAL_GENOTYPE {
+        return Some(Kind::Genotype);
+    } else if !key.starts_with(LOCAL_KEY_PREFIX) {
+        return None;
+    }
+
+    match format.number() {
+        Number::LocalAlternateBases => Some(Kind::AlternateBases),
+        Number::LocalReferenceAlternateBases => Some(Kind::ReferenceAlternateBases),
+        Number::LocalSamples => Some(Kind::Samples),
+        _ => None,
+    }
+}
+
+// Returns the global indices of the local alleles, starting with the reference allele.
+fn select_local_alleles(
+    allele_count: usize,
+    genotype: Option<&Value>,
+    read_depths: Option<&Value>,
+) -> io::Result<Vec<usize>> {
+    let mut alleles = vec![0];
+
+    if let Some(Value::Genotype(genotype)) = genotype {
+        for allele in genotype.as_ref() {
+            if let Some(i) = allele.position() {
+                if i >= allele_count {
+                    return Err(invalid_allele_index_error(i));
+                }
+
+                alleles.push(i);
+            }
+        }
+    }
+
+    if let Some(Value::Array(Array::Integer(depths))) = read_depths {
+        for (i, depth) in depths.iter().enumerate().take(allele_count) {
+            if depth.is_some_and(|n| n > 0) {
+                alleles.push(i);
+            }
+        }
+    }
+
+    alleles.sort_unstable();
+    alleles.dedup();
+
+    Ok(alleles)
+}
+
+// Returns the global indices of the local alleles from an `LAA` value, starting with the
+// reference allele.
+fn local_alleles(allele_count: usize, value: Option<&Value>) -> io::Result<Vec<usize>> {
+    let mut alleles = vec![0];
+
+    let indices: Vec<Option<i32>> = match value {
+        None => Vec::new(),
+        Some(Value::Integer(n)) => vec![Some(*n)],
+        Some(Value::Array(Array::Integer(indices))) => indices.clone(),
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid {} value", key::LOCAL_ALTERNATIVE_ALLELE),
+            ))
+        }
+    };
+
+    for index in indices.into_iter().flatten() {
+        let i = usize::try_from(index)
+            .ok()
+            .filter(|&i| i > 0 && i < allele_count)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid {} index: {index}", key::LOCAL_ALTERNATIVE_ALLELE),
+                )
+            })?;
+
+        if alleles.last().is_some_and(|&prev| i <= prev) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} indices are not increasing",
+                    key::LOCAL_ALTERNATIVE_ALLELE
+                ),
+            ));
+        }
+
+        alleles.push(i);
+    }
+
+    Ok(alleles)
+}
+
+fn genotype_ploidy(genotype: Option<&Value>) -> Option<usize> {
+    match genotype {
+        Some(Value::Genotype(genotype)) => Some(genotype.as_ref().len()),
+        _ => None,
+    }
+}
+
+// Returns, for each local value, the index of its global value.
+/// Returns the ploidy of a field with a number of `G` with the given number of alleles and values.
+///
+/// The given ploidy hint is preferred if it is consistent with the number of values.
+fn infer_ploidy(allele_count: usize, len: usize, hint: Option<usize>) -> Option<usize> {
+    if let Some(ploidy) = hint.filter(|&p| p > 0 && genotype_count(allele_count, p) == len) {
+        return Some(ploidy);
+    }
+
+    if allele_count == 0 {
+        return None;
+    } else if allele_count == 1 {
+        return (len == 1).then_some(1);
+    }
+
+    (1..)
+        .map(|p| (p, genotype_count(allele_count, p)))
+        .take_while(|&(_, n)| n <= len)
+        .find_map(|(p, n)| (n == len).then_some(p))
+}
+
+fn local_to_global_indices(
+    kind: Kind,
+    alleles: &[usize],
+    ploidy: usize,
+    allele_count: usize,
+) -> Vec<usize> {
+    match kind {
+        Kind::ReferenceAlternateBases => alleles.to_vec(),
+        Kind::AlternateBases => alleles[1..].iter().map(|&i| i - 1).collect(),
+        Kind::Samples => genotypes(alleles.len(), ploidy)
+            .into_iter()
+            .map(|genotype| {
+                let mut global_genotype: Vec<_> = genotype.iter().map(|&i| alleles[i]).collect();
+                global_genotype.sort_unstable();
+                genotype_index(&global_genotype)
+            })
+            .collect(),
+        Kind::Genotype => (0..allele_count).collect(),
+    }
+}
+
+fn localize_value(
+    kind: Kind,
+    value: Option<Value>,
+    alleles: &[usize],
+    allele_count: usize,
+    ploidy_hint: Option<usize>,
+) -> io::Result<Option<Value>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    if kind == Kind::Genotype {
+        return map_genotype(value, |i| {
+            alleles
+                .iter()
+                .position(|&j| j == i)
+                .ok_or_else(|| invalid_allele_index_error(i))
+        })
+        .map(Some);
+    }
+
+    let array = into_array(value)?;
+
+    let ploidy = match kind {
+        Kind::Samples => infer_ploidy(allele_count, array_len(&array), ploidy_hint)
+            .ok_or_else(|| invalid_length_error(kind))?,
+        _ => 1,
+    };
+
+    let expected_len = global_len(kind, allele_count, ploidy);
+
+    if array_len(&array) != expected_len {
+        return Err(invalid_length_error(kind));
+    }
+
+    let indices: Vec<_> = local_to_global_indices(kind, alleles, ploidy, allele_count)
+        .into_iter()
+        .map(Some)
+        .collect();
+
+    Ok(Some(Value::Array(select(array, &indices))))
+}
+
+fn globalize_value(
+    kind: Kind,
+    value: Option<Value>,
+    alleles: &[usize],
+    allele_count: usize,
+    ploidy_hint: Option<usize>,
+) -> io::Result<Option<Value>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    if kind == Kind::Genotype {
+        return map_genotype(value, |i| {
+            alleles
+                .get(i)
+                .copied()
+                .ok_or_else(|| invalid_allele_index_error(i))
+        })
+        .map(Some);
+    }
+
+    let array = into_array(value)?;
+
+    let ploidy = match kind {
+        Kind::Samples => infer_ploidy(alleles.len(), array_len(&array), ploidy_hint)
+            .ok_or_else(|| invalid_length_error(kind))?,
+        _ => 1,
+    };
+
+    if array_len(&array) != global_len(kind, alleles.len(), ploidy) {
+        return Err(invalid_length_error(kind));
+    }
+
+    let mut indices = vec![None; global_len(kind, allele_count, ploidy)];
+
+    for (i, j) in local_to_global_indices(kind, alleles, ploidy, allele_count)
+        .into_iter()
+        .enumerate()
+    {
+        indices[j] = Some(i);
+    }
+
+    Ok(Some(Value::Array(select(array, &indices))))
+}
+
+fn global_len(kind: Kind, allele_count: usize, ploidy: usize) -> usize {
+    match kind {
+        Kind::AlternateBases => allele_count - 1,
+        Kind::ReferenceAlternateBases => allele_count,
+        Kind::Samples => genotype_count(allele_count, ploidy),
+        Kind::Genotype => 1,
+    }
+}
+
+fn map_genotype<F>(value: Value, f: F) -> io::Result<Value>
+where
+    F: Fn(usize) -> io::Result<usize>,
+{
+    let Value::Genotype(genotype) = value else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid genotype value",
+        ));
+    };
+
+    genotype
+        .as_ref()
+        .iter()
+        .map(|allele| {
+            let position = allele.position().map(&f).transpose()?;
+            Ok(Allele::new(position, allele.phasing()))
+        })
+        .collect::<io::Result<Genotype>>()
+        .map(Value::Genotype)
+}
+
+fn into_array(value: Value) -> io::Result<Array> {
+    match value {
+        Value::Integer(n) => Ok(Array::Integer(vec![Some(n)])),
+        Value::Float(n) => Ok(Array::Float(vec![Some(n)])),
+        Value::Character(c) => Ok(Array::Character(vec![Some(c)])),
+        Value::String(s) => Ok(Array::String(vec![Some(s)])),
+        Value::Array(array) => Ok(array),
+        Value::Genotype(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected genotype value",
+        )),
+    }
+}
+
+fn array_len(array: &Array) -> usize {
+    match array {
+        Array::Integer(values) => values.len(),
+        Array::Float(values) => values.len(),
+        Array::Character(values) => values.len(),
+        Array::String(values) => values.len(),
+    }
+}
+
+fn select(array: Array, indices: &[Option<usize>]) -> Array {
+    fn select_values<T: Clone>(values: &[Option<T>], indices: &[Option<usize>]) -> Vec<Option<T>> {
+        indices
+            .iter()
+            .map(|i| i.and_then(|i| values.get(i).cloned().flatten()))
+            .collect()
+    }
+
+    match array {
+        Array::Integer(values) => Array::Integer(select_values(&values, indices)),
+        Array::Float(values) => Array::Float(select_values(&values, indices)),
+        Array::Character(values) => Array::Character(select_values(&values, indices)),
+        Array::String(values) => Array::String(select_values(&values, indices)),
+    }
+}
+
+fn invalid_allele_index_error(i: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid allele index: {i}"),
+    )
+}
+
+fn invalid_length_error(kind: Kind) -> io::Error {
+    let number = match kind {
+        Kind::AlternateBases => "A",
+        Kind::ReferenceAlternateBases => "R",
+        Kind::Samples => "G",
+        Kind::Genotype => "1",
+    };
+
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid number of values for a field with number {number}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::variant::record_buf::AlternateBases;
+
+    use super::*;
+
+    const RAW_HEADER: &str = "##fileformat=VCFv4.5
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"Read depth for each allele\">
+##FORMAT=<ID=EC,Number=A,Type=Integer,Description=\"Expected alternate allele counts\">
+##FORMAT=<ID=PL,Number=G,Type=Integer,Description=\"Phred-scaled genotype likelihoods\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\tsample1
+";
+
+    fn integers(values: &[Option<i32>]) -> Option<Value> {
+        Some(Value::from(values.to_vec()))
+    }
+
+    #[test]
+    fn test_localize() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        add_header_definitions(&mut header);
+
+        // 4 alleles, diploid: 10 genotypes.
+        let pl: Vec<_> = (0..10).map(Some).collect();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("G"),
+                String::from("T"),
+            ]))
+            .set_samples(Samples::new(
+                [
+                    key::GENOTYPE,
+                    key::READ_DEPTHS,
+                    key::EXPECTED_ALTERNATE_ALLELE_COUNTS,
+                    key::ROUNDED_GENOTYPE_LIKELIHOODS,
+                    key::READ_DEPTH,
+                ]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+                vec![
+                    vec![
+                        Some(Value::Genotype("0/2".parse()?)),
+                        integers(&[Some(8), Some(0), Some(5), Some(1)]),
+                        integers(&[Some(1), Some(2), Some(3)]),
+                        integers(&pl),
+                        Some(Value::Integer(14)),
+                    ],
+                    vec![
+                        Some(Value::Genotype("0/0".parse()?)),
+                        integers(&[Some(9), Some(0), Some(0), Some(0)]),
+                        integers(&[Some(0), Some(0), Some(0)]),
+                        integers(&pl),
+                        Some(Value::Integer(9)),
+                    ],
+                ],
+            ))
+            .build();
+
+        let mut expected = record.clone();
+
+        localize(&header, &mut record)?;
+
+        // Local alleles of sample0: 0, 2, 3. Local genotypes: 0/0 (0), 0/2 (3), 2/2 (5), 0/3 (6),
+        // 2/3 (8), 3/3 (9).
+        *expected.samples_mut() = Samples::new(
+            [
+                key::GENOTYPE,
+                key::LOCAL_ALTERNATIVE_ALLELE,
+                key::LOCAL_READ_DEPTHS,
+                key::LOCAL_EXPECTED_ALTERNATE_ALLELE_COUNTS,
+                key::LOCAL_ROUNDED_GENOTYPE_LIKELIHOODS,
+                key::READ_DEPTH,
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            vec![
+                vec![
+                    Some(Value::Genotype("0/2".parse()?)),
+                    integers(&[Some(2), Some(3)]),
+                    integers(&[Some(8), Some(5), Some(1)]),
+                    integers(&[Some(2), Some(3)]),
+                    integers(&[Some(0), Some(3), Some(5), Some(6), Some(8), Some(9)]),
+                    Some(Value::Integer(14)),
+                ],
+                vec![
+                    Some(Value::Genotype("0/0".parse()?)),
+                    None,
+                    integers(&[Some(9)]),
+                    integers(&[]),
+                    integers(&[Some(0)]),
+                    Some(Value::Integer(9)),
+                ],
+            ],
+        );
+
+        assert_eq!(record, expected);
+        validate(&header, &record)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_globalize() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        add_header_definitions(&mut header);
+
+        // 4 alleles, diploid: 10 genotypes.
+        let pl: Vec<_> = (0..10).map(Some).collect();
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("G"),
+                String::from("T"),
+            ]))
+            .set_samples(Samples::new(
+                [
+                    key::GENOTYPE,
+                    key::READ_DEPTHS,
+                    key::EXPECTED_ALTERNATE_ALLELE_COUNTS,
+                    key::ROUNDED_GENOTYPE_LIKELIHOODS,
+                    key::READ_DEPTH,
+                ]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+                vec![
+                    vec![
+                        Some(Value::Genotype("0/2".parse()?)),
+                        integers(&[Some(8), Some(0), Some(5), Some(1)]),
+                        integers(&[Some(1), Some(2), Some(3)]),
+                        integers(&pl),
+                        Some(Value::Integer(14)),
+                    ],
+                    vec![
+                        Some(Value::Genotype("0/0".parse()?)),
+                        integers(&[Some(9), Some(0), Some(0), Some(0)]),
+                        integers(&[Some(0), Some(0), Some(0)]),
+                        integers(&pl),
+                        Some(Value::Integer(9)),
+                    ],
+                ],
+            ))
+            .build();
+
+        let mut expected = record.clone();
+
+        localize(&header, &mut record)?;
+        globalize(&header, &mut record)?;
+
+        let pl = |present: &[i32]| -> Vec<Option<i32>> {
+            (0..10).map(|i| present.contains(&i).then_some(i)).collect()
+        };
+
+        *expected.samples_mut() = Samples::new(
+            [
+                key::GENOTYPE,
+                key::READ_DEPTHS,
+                key::EXPECTED_ALTERNATE_ALLELE_COUNTS,
+                key::ROUNDED_GENOTYPE_LIKELIHOODS,
+                key::READ_DEPTH,
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            vec![
+                vec![
+                    Some(Value::Genotype("0/2".parse()?)),
+                    integers(&[Some(8), None, Some(5), Some(1)]),
+                    integers(&[None, Some(2), Some(3)]),
+                    integers(&pl(&[0, 3, 5, 6, 8, 9])),
+                    Some(Value::Integer(14)),
+                ],
+                vec![
+                    Some(Value::Genotype("0/0".parse()?)),
+                    integers(&[Some(9), None, None, None]),
+                    integers(&[None, None, None]),
+                    integers(&pl(&[0])),
+                    Some(Value::Integer(9)),
+                ],
+            ],
+        );
+
+        assert_eq!(record, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_localize_with_local_genotypes() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        add_header_definitions(&mut header);
+        header.formats_mut().insert(
+            String::from(LOCAL_GENOTYPE),
+            Map::<Format>::new(
+                Number::Count(1),
+                vcf::header::record::value::map::format::Type::String,
+                "Local genotype",
+            ),
+        );
+
+        let mut record = RecordBuf::builder()
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("G"),
+                String::from("T"),
+            ]))
+            .set_samples(Samples::new(
+                [String::from(key::GENOTYPE)].into_iter().collect(),
+                vec![
+                    vec![Some(Value::Genotype("1|3".parse()?))],
+                    vec![Some(Value::Genotype("./.".parse()?))],
+                ],
+            ))
+            .build();
+
+        let original = record.clone();
+
+        localize(&header, &mut record)?;
+
+        let mut expected = original.clone();
+        *expected.samples_mut() = Samples::new(
+            [LOCAL_GENOTYPE, key::LOCAL_ALTERNATIVE_ALLELE]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            vec![
+                vec![
+                    Some(Value::Genotype("1|2".parse()?)),
+                    integers(&[Some(1), Some(3)]),
+                ],
+                vec![Some(Value::Genotype("./.".parse()?)), None],
+            ],
+        );
+
+        assert_eq!(record, expected);
+
+        assert_eq!(
+            get(&header, &record, 0, key::GENOTYPE)?,
+            Some(Value::Genotype("1|3".parse()?))
+        );
+
+        globalize(&header, &mut record)?;
+        assert_eq!(record, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> Result<(), Box<dyn std::error::Error>> {
+        let mut header: vcf::Header = RAW_HEADER.parse()?;
+        add_header_definitions(&mut header);
+
+        let t = |keys: &[&str], values: Vec<Option<Value>>| {
+            let record = RecordBuf::builder()
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBases::from(vec![
+                    String::from("C"),
+                    String::from("G"),
+                    String::from("T"),
+                ]))
+                .set_samples(Samples::new(
+                    keys.iter().map(|key| String::from(*key)).collect(),
+                    vec![values.clone(), values],
+                ))
+                .build();
+
+            validate(&header, &record)
+        };
+
+        let laa = key::LOCAL_ALTERNATIVE_ALLELE;
+        let lad = key::LOCAL_READ_DEPTHS;
+
+        assert!(t(
+            &[laa, lad],
+            vec![integers(&[Some(2)]), integers(&[Some(1), Some(2)])]
+        )
+        .is_ok());
+
+        // unsorted LAA
+        assert!(t(&[laa], vec![integers(&[Some(3), Some(2)])]).is_err());
+        // LAA out of range
+        assert!(t(&[laa], vec![integers(&[Some(4)])]).is_err());
+        // invalid LAD length
+        assert!(t(
+            &[laa, lad],
+            vec![integers(&[Some(2)]), integers(&[Some(1)])]
+        )
+        .is_err());
+        // missing LAA
+        assert!(t(&[lad], vec![integers(&[Some(1)])]).is_err());
+        // both AD and LAD
+        assert!(t(
+            &[laa, lad, key::READ_DEPTHS],
+            vec![
+                integers(&[Some(2)]),
+                integers(&[Some(1), Some(2)]),
+                integers(&[Some(1), Some(0), Some(2), Some(0)]),
+            ]
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_infer_ploidy() {
+        assert_eq!(infer_ploidy(3, 3, None), Some(1));
+        assert_eq!(infer_ploidy(3, 6, None), Some(2));
+        assert_eq!(infer_ploidy(3, 10, None), Some(3));
+        assert_eq!(infer_ploidy(3, 4, None), None);
+        assert_eq!(infer_ploidy(1, 1, None), Some(1));
+        assert_eq!(infer_ploidy(1, 1, Some(2)), Some(2));
+        assert_eq!(infer_ploidy(3, 3, Some(2)), Some(1));
+    }
+}
@@ -0,0 +1,703 @@
+//! Variant record merging across samples.
+//!
+//! Records from sorted inputs with distinct samples are merged into records with the samples of
+//! all inputs. This is similar to `bcftools merge`.
+
+mod builder;
+mod header;
+
+pub use self::builder::Builder;
+
+pub(crate) use self::header::merge_definitions;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    iter::Peekable,
+};
+
+use noodles_core::Position;
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::samples::keys::key,
+        record_buf::{
+            samples::{
+                sample::{
+                    value::{genotype::Allele, Genotype},
+                    Value,
+                },
+                Keys,
+            },
+            AlternateBases, Filters, Samples,
+        },
+        RecordBuf,
+    },
+};
+
+use super::multiallelic::{join_alleles, join_info, join_sample_values};
+
+const MISSING_GENOTYPE_PLOIDY: usize = 2;
+
+/// How sample names that are in more than one input are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SampleNameCollision {
+    /// Duplicate sample names are errors.
+    #[default]
+    Error,
+    /// Duplicate sample names are prefixed with the 1-based input index, e.g., `2:sample0`.
+    Rename,
+}
+
+type Key = (usize, Option<Position>);
+
+/// A variant record merger.
+///
+/// Each input must be sorted by reference sequence, in the order of the merged header contigs,
+/// and position. At each position, records with compatible reference bases are merged. Unless
+/// allele joining is disabled, records of the same variant type (SNPs/MNPs or indels) are merged
+/// into multiallelic records; otherwise, only records with the same alleles are merged.
+///
+/// The merged record takes the positional fields and all other non-allelic fields of the first
+/// input with a record. IDs and filters are unioned, and the quality score is the maximum. Samples
+/// of inputs without a matching record have missing genotypes.
+pub struct Merger<I>
+where
+    I: Iterator<Item = io::Result<RecordBuf>>,
+{
+    header: vcf::Header,
+    inputs: Vec<Peekable<I>>,
+    sample_counts: Vec<usize>,
+    join_alleles: bool,
+    reference_sequence_ids: HashMap<String, usize>,
+    last_key: Option<Key>,
+    pending: VecDeque<RecordBuf>,
+}
+
+impl<I> Merger<I>
+where
+    I: Iterator<Item = io::Result<RecordBuf>>,
+{
+    /// Creates a variant record merger with default options.
+    ///
+    /// The headers and inputs must be in the same order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_util::variant::merge::Merger;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let headers = [
+    ///     vcf::Header::builder().add_sample_name("sample0").build(),
+    ///     vcf::Header::builder().add_sample_name("sample1").build(),
+    /// ];
+    ///
+    /// let inputs = vec![
+    ///     Vec::<io::Result<RecordBuf>>::new().into_iter(),
+    ///     Vec::new().into_iter(),
+    /// ];
+    ///
+    /// let merger = Merger::new(&headers, inputs)?;
+    /// assert_eq!(merger.header().sample_names().len(), 2);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn new(headers: &[vcf::Header], inputs: Vec<I>) -> io::Result<Self> {
+        Builder::default().build(headers, inputs)
+    }
+
+    /// Returns the merged header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_util::variant::merge::Merger;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let headers = [vcf::Header::default()];
+    /// let inputs = vec![Vec::<io::Result<RecordBuf>>::new().into_iter()];
+    /// let merger = Merger::new(&headers, inputs)?;
+    ///
+    /// assert!(merger.header().sample_names().is_empty());
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn header(&self) -> &vcf::Header {
+        &self.header
+    }
+
+    fn peek_key(&mut self, i: usize) -> io::Result<Option<Key>> {
+        match self.inputs[i].peek() {
+            Some(Ok(record)) => Ok(Some(key(&mut self.reference_sequence_ids, record))),
+            Some(Err(_)) => match self.inputs[i].next() {
+                Some(Err(e)) => Err(e),
+                _ => unreachable!(),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn merge_next(&mut self) -> io::Result<()> {
+        let mut keys = Vec::with_capacity(self.inputs.len());
+
+        for i in 0..self.inputs.len() {
+            keys.push(self.peek_key(i)?);
+        }
+
+        let Some(min_key) = keys.iter().flatten().min().copied() else {
+            return Ok(());
+        };
+
+        if self.last_key.is_some_and(|last_key| min_key < last_key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "input is not coordinate-sorted",
+            ));
+        }
+
+        self.last_key = Some(min_key);
+
+        let mut groups: Vec<Group> = Vec::new();
+
+        for (i, input_key) in keys.into_iter().enumerate() {
+            if input_key != Some(min_key) {
+                continue;
+            }
+
+            loop {
+                match self.inputs[i].peek() {
+                    Some(Ok(record))
+                        if key(&mut self.reference_sequence_ids, record) == min_key => {}
+                    _ => break,
+                }
+
+                // SAFETY: The next record was peeked.
+                let record = self.inputs[i].next().unwrap()?;
+
+                match groups
+                    .iter_mut()
+                    .find(|group| group.accepts(i, &record, self.join_alleles))
+                {
+                    Some(group) => group.push(i, record),
+                    None => groups.push(Group::new(i, record)),
+                }
+            }
+        }
+
+        for group in groups {
+            let record = merge_group(&self.header, &self.sample_counts, group)?;
+            self.pending.push_back(record);
+        }
+
+        Ok(())
+    }
+}
+
+impl<I> Iterator for Merger<I>
+where
+    I: Iterator<Item = io::Result<RecordBuf>>,
+{
+    type Item = io::Result<RecordBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            if let Err(e) = self.merge_next() {
+                return Some(Err(e));
+            }
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+fn key(reference_sequence_ids: &mut HashMap<String, usize>, record: &RecordBuf) -> Key {
+    let name = record.reference_sequence_name();
+
+    let id = match reference_sequence_ids.get(name) {
+        Some(id) => *id,
+        None => {
+            let id = reference_sequence_ids.len();
+            reference_sequence_ids.insert(name.into(), id);
+            id
+        }
+    };
+
+    (id, record.variant_start())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum VariantType {
+    Snp,
+    Indel,
+    Other,
+}
+
+impl VariantType {
+    fn from_record(record: &RecordBuf) -> Option<Self> {
+        let reference_bases = record.reference_bases();
+        let alternate_bases = record.alternate_bases().as_ref();
+
+        if alternate_bases.is_empty() {
+            return None;
+        }
+
+        if alternate_bases
+            .iter()
+            .any(|allele| allele == "*" || allele.starts_with('<') || allele.contains(['[', ']']))
+        {
+            Some(Self::Other)
+        } else if alternate_bases
+            .iter()
+            .all(|allele| allele.len() == reference_bases.len())
+        {
+            Some(Self::Snp)
+        } else {
+            Some(Self::Indel)
+        }
+    }
+}
+
+struct Group {
+    inputs: Vec<usize>,
+    records: Vec<RecordBuf>,
+    variant_type: Option<VariantType>,
+}
+
+impl Group {
+    fn new(input: usize, record: RecordBuf) -> Self {
+        Self {
+            inputs: vec![input],
+            variant_type: VariantType::from_record(&record),
+            records: vec![record],
+        }
+    }
+
+    fn accepts(&self, input: usize, record: &RecordBuf, join_alleles: bool) -> bool {
+        if self.inputs.contains(&input) {
+            return false;
+        }
+
+        let reference_bases = self.records[0].reference_bases();
+
+        let is_compatible = reference_bases.starts_with(record.reference_bases())
+            || record.reference_bases().starts_with(reference_bases);
+
+        if !is_compatible {
+            return false;
+        }
+
+        let variant_type = VariantType::from_record(record);
+
+        if variant_type.is_none() || self.variant_type.is_none() {
+            true
+        } else if join_alleles {
+            variant_type == self.variant_type && variant_type != Some(VariantType::Other)
+        } else {
+            self.records.iter().all(|r| {
+                r.reference_bases() == record.reference_bases()
+                    && r.alternate_bases() == record.alternate_bases()
+            })
+        }
+    }
+
+    fn push(&mut self, input: usize, record: RecordBuf) {
+        if self.variant_type.is_none() {
+            self.variant_type = VariantType::from_record(&record);
+        }
+
+        self.inputs.push(input);
+        self.records.push(record);
+    }
+}
+
+fn merge_group(
+    header: &vcf::Header,
+    sample_counts: &[usize],
+    group: Group,
+) -> io::Result<RecordBuf> {
+    let Group {
+        inputs, records, ..
+    } = group;
+
+    let (reference_bases, alternate_bases, allele_maps) = join_alleles(&records)?;
+    let allele_count = alternate_bases.len() + 1;
+
+    let mut record = records[0].clone();
+
+    *record.reference_bases_mut() = reference_bases;
+    *record.alternate_bases_mut() = AlternateBases::from(alternate_bases);
+
+    for r in &records[1..] {
+        record
+            .ids_mut()
+            .as_mut()
+            .extend(r.ids().as_ref().iter().cloned());
+    }
+
+    *record.quality_score_mut() = records
+        .iter()
+        .filter_map(|r| r.quality_score())
+        .reduce(f32::max);
+
+    *record.filters_mut() = merge_filters(&records);
+    *record.info_mut() = join_info(header, &records, &allele_maps, allele_count);
+    *record.samples_mut() = merge_samples(header, sample_counts, &inputs, &records, &allele_maps);
+
+    Ok(record)
+}
+
+fn merge_filters(records: &[RecordBuf]) -> Filters {
+    const PASS: &str = "PASS";
+
+    let mut filters: Filters = records
+        .iter()
+        .flat_map(|record| record.filters().as_ref().iter().cloned())
+        .collect();
+
+    if filters.as_ref().len() > 1 {
+        filters.as_mut().shift_remove(PASS);
+    }
+
+    filters
+}
+
+fn merge_samples(
+    header: &vcf::Header,
+    sample_counts: &[usize],
+    inputs: &[usize],
+    records: &[RecordBuf],
+    allele_maps: &[Vec<usize>],
+) -> Samples {
+    let allele_count = allele_maps
+        .iter()
+        .flatten()
+        .max()
+        .map(|n| n + 1)
+        .unwrap_or(1);
+
+    let parts: Vec<(Keys, Vec<Vec<Option<Value>>>)> = records
+        .iter()
+        .map(|record| record.samples().clone().into())
+        .collect();
+
+    let mut keys: Keys = Keys::default();
+
+    if parts
+        .iter()
+        .any(|(keys, _)| keys.as_ref().contains(key::GENOTYPE))
+    {
+        keys.as_mut().insert(key::GENOTYPE.into());
+    }
+
+    keys.extend(
+        parts
+            .iter()
+            .flat_map(|(keys, _)| keys.as_ref().iter().cloned()),
+    );
+
+    let mut values = Vec::with_capacity(sample_counts.iter().sum());
+
+    for (input, &sample_count) in sample_counts.iter().enumerate() {
+        let Some(k) = inputs.iter().position(|&i| i == input) else {
+            values.extend((0..sample_count).map(|_| missing_sample(&keys)));
+            continue;
+        };
+
+        let (record_keys, record_values) = &parts[k];
+        let allele_map = &allele_maps[k..=k];
+
+        for i in 0..sample_count {
+            let sample = keys
+                .as_ref()
+                .iter()
+                .map(|key| {
+                    let value = record_keys
+                        .as_ref()
+                        .get_index_of(key)
+                        .and_then(|j| record_values.get(i)?.get(j)?.as_ref());
+
+                    match value {
+                        Some(_) => {
+                            join_sample_values(header, key, &[value], allele_map, allele_count)
+                        }
+                        None if key == key::GENOTYPE => Some(missing_genotype()),
+                        None => None,
+                    }
+                })
+                .collect();
+
+            values.push(sample);
+        }
+    }
+
+    Samples::new(keys, values)
+}
+
+fn missing_sample(keys: &Keys) -> Vec<Option<Value>> {
+    keys.as_ref()
+        .iter()
+        .map(|key| (key == key::GENOTYPE).then(missing_genotype))
+        .collect()
+}
+
+fn missing_genotype() -> Value {
+    use noodles_vcf::variant::record::samples::series::value::genotype::Phasing;
+
+    let genotype: Genotype = (0..MISSING_GENOTYPE_PLOIDY)
+        .map(|_| Allele::new(None, Phasing::Unphased))
+        .collect();
+
+    Value::Genotype(genotype)
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::{
+        header::record::value::{map::Format, Map},
+        variant::record_buf::samples::sample::value::Array,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_next() -> Result<(), Box<dyn std::error::Error>> {
+        let headers = [
+            vcf::Header::builder()
+                .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+                .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+                .add_sample_name("sample0")
+                .build(),
+            vcf::Header::builder()
+                .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+                .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+                .add_sample_name("sample1")
+                .build(),
+        ];
+
+        let keys: Keys = [key::GENOTYPE, key::READ_DEPTHS]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let inputs = vec![
+            vec![
+                Ok(RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(8)?)
+                    .set_reference_bases("A")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![vec![
+                            Some(Value::Genotype("0/1".parse()?)),
+                            Some(Value::from(vec![Some(5), Some(3)])),
+                        ]],
+                    ))
+                    .build()),
+                Ok(RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(13)?)
+                    .set_reference_bases("T")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![vec![
+                            Some(Value::Genotype("1/1".parse()?)),
+                            Some(Value::from(vec![Some(0), Some(7)])),
+                        ]],
+                    ))
+                    .build()),
+            ]
+            .into_iter(),
+            vec![
+                Ok(RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(8)?)
+                    .set_reference_bases("A")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![vec![
+                            Some(Value::Genotype("0/1".parse()?)),
+                            Some(Value::from(vec![Some(4), Some(4)])),
+                        ]],
+                    ))
+                    .build()),
+                Ok(RecordBuf::builder()
+                    .set_reference_sequence_name("sq1")
+                    .set_variant_start(Position::try_from(5)?)
+                    .set_reference_bases("C")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("CA")]))
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![vec![
+                            Some(Value::Genotype("0/1".parse()?)),
+                            Some(Value::from(vec![Some(2), Some(6)])),
+                        ]],
+                    ))
+                    .build()),
+            ]
+            .into_iter(),
+        ];
+
+        let merger = Merger::new(&headers, inputs)?;
+        let header = merger.header().clone();
+        let records: Vec<_> = merger.collect::<io::Result<_>>()?;
+
+        assert_eq!(records.len(), 3);
+
+        assert_eq!(
+            header.sample_names().iter().collect::<Vec<_>>(),
+            ["sample0", "sample1"]
+        );
+
+        let record = &records[0];
+        assert_eq!(
+            record.alternate_bases().as_ref(),
+            [String::from("C"), String::from("G")]
+        );
+
+        let sample = record.samples().get_index(1).unwrap();
+        assert_eq!(
+            sample.get(key::GENOTYPE),
+            Some(Some(&Value::Genotype("0/2".parse().unwrap())))
+        );
+        assert_eq!(
+            sample.get(key::READ_DEPTHS),
+            Some(Some(&Value::Array(Array::Integer(vec![
+                Some(4),
+                None,
+                Some(4)
+            ]))))
+        );
+
+        let record = &records[1];
+        assert_eq!(record.variant_start(), Position::new(13));
+        let sample = record.samples().get_index(1).unwrap();
+        assert_eq!(sample.get(key::GENOTYPE), Some(Some(&missing_genotype())));
+        assert_eq!(sample.get(key::READ_DEPTHS), Some(None));
+
+        let record = &records[2];
+        assert_eq!(record.reference_sequence_name(), "sq1");
+        let sample = record.samples().get_index(0).unwrap();
+        assert_eq!(sample.get(key::GENOTYPE), Some(Some(&missing_genotype())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_without_joining_alleles() -> Result<(), Box<dyn std::error::Error>> {
+        let headers = [
+            vcf::Header::builder()
+                .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+                .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+                .add_sample_name("sample0")
+                .build(),
+            vcf::Header::builder()
+                .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+                .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+                .add_sample_name("sample1")
+                .build(),
+        ];
+
+        let keys: Keys = [key::GENOTYPE, key::READ_DEPTHS]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let inputs = vec![
+            vec![Ok(RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+                .set_samples(Samples::new(
+                    keys.clone(),
+                    vec![vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(5), Some(3)])),
+                    ]],
+                ))
+                .build())]
+            .into_iter(),
+            vec![Ok(RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                .set_samples(Samples::new(
+                    keys.clone(),
+                    vec![vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(4), Some(4)])),
+                    ]],
+                ))
+                .build())]
+            .into_iter(),
+        ];
+
+        let merger = Builder::default()
+            .set_join_alleles(false)
+            .build(&headers, inputs)?;
+
+        let records: Vec<_> = merger.collect::<io::Result<_>>()?;
+        assert_eq!(records.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_with_unsorted_input() -> Result<(), Box<dyn std::error::Error>> {
+        let headers = [vcf::Header::builder()
+            .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+            .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+            .add_sample_name("sample0")
+            .build()];
+
+        let keys: Keys = [key::GENOTYPE, key::READ_DEPTHS]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let inputs = vec![vec![
+            Ok(RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(13)?)
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+                .set_samples(Samples::new(
+                    keys.clone(),
+                    vec![vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(5), Some(3)])),
+                    ]],
+                ))
+                .build()),
+            Ok(RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+                .set_samples(Samples::new(
+                    keys.clone(),
+                    vec![vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(5), Some(3)])),
+                    ]],
+                ))
+                .build()),
+        ]
+        .into_iter()];
+
+        let mut merger = Merger::new(&headers, inputs)?;
+
+        assert!(merger.next().transpose()?.is_some());
+        assert!(matches!(
+            merger.next(),
+            Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, io};
+
+use noodles_vcf::{self as vcf, variant::RecordBuf};
+
+use super::{header, Merger, SampleNameCollision};
+
+/// A variant record merger builder.
+#[derive(Debug)]
+pub struct Builder {
+    sample_name_collision: SampleNameCollision,
+    join_alleles: bool,
+}
+
+impl Builder {
+    /// Sets how sample names that are in more than one input are handled.
+    ///
+    /// By default, duplicate sample names are errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::merge::{self, SampleNameCollision};
+    /// let builder = merge::Builder::default().set_sample_name_collision(SampleNameCollision::Rename);
+    /// ```
+    pub fn set_sample_name_collision(mut self, sample_name_collision: SampleNameCollision) -> Self {
+        self.sample_name_collision = sample_name_collision;
+        self
+    }
+
+    /// Sets whether records with different alleles of the same variant type are merged.
+    ///
+    /// When disabled, only records with the same alleles are merged. By default, this is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::merge;
+    /// let builder = merge::Builder::default().set_join_alleles(false);
+    /// ```
+    pub fn set_join_alleles(mut self, join_alleles: bool) -> Self {
+        self.join_alleles = join_alleles;
+        self
+    }
+
+    /// Builds a variant record merger.
+    ///
+    /// The headers and inputs must be in the same order. This returns an error if the headers
+    /// cannot be merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io;
+    /// use noodles_util::variant::merge;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let headers = [vcf::Header::default()];
+    /// let inputs = vec![Vec::<io::Result<RecordBuf>>::new().into_iter()];
+    /// let merger = merge::Builder::default().build(&headers, inputs)?;
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn build<I>(self, headers: &[vcf::Header], inputs: Vec<I>) -> io::Result<Merger<I>>
+    where
+        I: Iterator<Item = io::Result<RecordBuf>>,
+    {
+        if headers.len() != inputs.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "headers and inputs have different lengths",
+            ));
+        }
+
+        let header = header::merge(headers, self.sample_name_collision)?;
+
+        let sample_counts = headers
+            .iter()
+            .map(|header| header.sample_names().len())
+            .collect();
+
+        let reference_sequence_ids: HashMap<_, _> = header
+            .contigs()
+            .keys()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+
+        Ok(Merger {
+            header,
+            inputs: inputs.into_iter().map(Iterator::peekable).collect(),
+            sample_counts,
+            join_alleles: self.join_alleles,
+            reference_sequence_ids,
+            last_key: None,
+            pending: Default::default(),
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            sample_name_collision: SampleNameCollision::default(),
+            join_alleles: true,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::io;
+
+use noodles_vcf::{self as vcf, header::StringMaps};
+
+use super::SampleNameCollision;
+
+/// Merges the headers of the inputs.
+///
+/// Definitions are merged using [`merge_definitions`], and sample names are concatenated.
+pub(super) fn merge(
+    headers: &[vcf::Header],
+    sample_name_collision: SampleNameCollision,
+) -> io::Result<vcf::Header> {
+    let mut merged = vcf::Header::default();
+
+    if let Some(file_format) = headers.iter().map(|header| header.file_format()).max() {
+        *merged.file_format_mut() = file_format;
+    }
+
+    for (i, header) in headers.iter().enumerate() {
+        merge_definitions(&mut merged, header)?;
+
+        for sample_name in header.sample_names() {
+            let sample_name = if merged.sample_names().contains(sample_name) {
+                match sample_name_collision {
+                    SampleNameCollision::Error => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("duplicate sample name: {sample_name}"),
+                        ))
+                    }
+                    SampleNameCollision::Rename => format!("{}:{sample_name}", i + 1),
+                }
+            } else {
+                sample_name.clone()
+            };
+
+            if !merged.sample_names_mut().insert(sample_name.clone()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate sample name: {sample_name}"),
+                ));
+            }
+        }
+    }
+
+    *merged.string_maps_mut() = StringMaps::try_from(&merged)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    Ok(merged)
+}
+
+/// Adds the definitions of a header to another.
+///
+/// INFO and FORMAT definitions with the same ID must have the same number and type, and contigs
+/// with the same name must have the same length. Otherwise, the existing definition is kept.
+///
+/// The file format and sample names are not merged.
+pub(crate) fn merge_definitions(dst: &mut vcf::Header, src: &vcf::Header) -> io::Result<()> {
+    for (id, info) in src.infos() {
+        match dst.infos().get(id) {
+            Some(m) if m.number() != info.number() || m.ty() != info.ty() => {
+                return Err(conflict_error("INFO", id));
+            }
+            Some(_) => {}
+            None => {
+                dst.infos_mut().insert(id.clone(), info.clone());
+            }
+        }
+    }
+
+    for (id, format) in src.formats() {
+        match dst.formats().get(id) {
+            Some(m) if m.number() != format.number() || m.ty() != format.ty() => {
+                return Err(conflict_error("FORMAT", id));
+            }
+            Some(_) => {}
+            None => {
+                dst.formats_mut().insert(id.clone(), format.clone());
+            }
+        }
+    }
+
+    for (id, filter) in src.filters() {
+        if !dst.filters().contains_key(id) {
+            dst.filters_mut().insert(id.clone(), filter.clone());
+        }
+    }
+
+    for (id, alternative_allele) in src.alternative_alleles() {
+        if !dst.alternative_alleles().contains_key(id) {
+            dst.alternative_alleles_mut()
+                .insert(id.clone(), alternative_allele.clone());
+        }
+    }
+
+    for (name, contig) in src.contigs() {
+        match dst.contigs_mut().get_mut(name) {
+            Some(m) => match (m.length(), contig.length()) {
+                (Some(a), Some(b)) if a != b => return Err(conflict_error("contig", name)),
+                (None, Some(b)) => *m.length_mut() = Some(b),
+                _ => {}
+            },
+            None => {
+                dst.contigs_mut().insert(name.clone(), contig.clone());
+            }
+        }
+    }
+
+    for (key, collection) in src.other_records() {
+        if !dst.other_records().contains_key(key) {
+            dst.other_records_mut()
+                .insert(key.clone(), collection.clone());
+        }
+    }
+
+    Ok(())
+}
+
+fn conflict_error(kind: &str, id: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("conflicting {kind} definitions: {id}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::header::record::value::{
+        map::{info, Contig, Info},
+        Map,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_merge() -> io::Result<()> {
+        let headers = [
+            vcf::Header::builder()
+                .add_info("AC", Map::<Info>::from("AC"))
+                .add_contig("sq0", Map::<Contig>::new())
+                .add_sample_name("sample0")
+                .build(),
+            vcf::Header::builder()
+                .add_info("DP", Map::<Info>::from("DP"))
+                .add_contig("sq0", Map::<Contig>::new())
+                .add_contig("sq1", Map::<Contig>::new())
+                .add_sample_name("sample1")
+                .add_sample_name("sample0")
+                .build(),
+        ];
+
+        let header = merge(&headers, SampleNameCollision::Rename)?;
+
+        assert_eq!(header.infos().len(), 2);
+        assert_eq!(header.contigs().len(), 2);
+        assert_eq!(
+            header.sample_names().iter().collect::<Vec<_>>(),
+            ["sample0", "sample1", "2:sample0"]
+        );
+
+        assert!(matches!(
+            merge(&headers, SampleNameCollision::Error),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_with_conflicting_definitions() {
+        let headers = [
+            vcf::Header::builder()
+                .add_info("AC", Map::<Info>::from("AC"))
+                .build(),
+            vcf::Header::builder()
+                .add_info(
+                    "AC",
+                    Map::<Info>::new(info::Number::Count(1), info::Type::Integer, ""),
+                )
+                .build(),
+        ];
+
+        assert!(matches!(
+            merge(&headers, SampleNameCollision::Error),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+//! Multiallelic variant record splitting and joining.
+//!
+//! INFO and FORMAT fields with a number of `A`, `R`, or `G`, as defined by the header, are
+//! subset or merged to match the resulting alleles. Genotype (`G`) values are ordered as
+//! described in the VCF specification, and their ploidy is inferred from the number of values.
+//! Genotype (`GT`) allele indices are remapped. All other fields are copied as is.
+
+mod join;
+mod split;
+
+pub use self::{join::join, split::split};
+
+pub(crate) use self::join::{join_alleles, join_info, join_sample_values};
+
+use noodles_vcf::{
+    header::record::value::map::{format, info},
+    variant::record_buf::{info::field::value::Array as InfoArray, samples::sample::value::Array},
+    Header,
+};
+
+const MAX_PLOIDY: usize = 8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Cardinality {
+    AlternateBases,
+    ReferenceAlternateBases,
+    Genotypes,
+    Other,
+}
+
+fn info_cardinality(header: &Header, key: &str) -> Cardinality {
+    match header.infos().get(key).map(|info| info.number()) {
+        Some(info::Number::AlternateBases) => Cardinality::AlternateBases,
+        Some(info::Number::ReferenceAlternateBases) => Cardinality::ReferenceAlternateBases,
+        Some(info::Number::Samples) => Cardinality::Genotypes,
+        _ => Cardinality::Other,
+    }
+}
+
+fn format_cardinality(header: &Header, key: &str) -> Cardinality {
+    match header.formats().get(key).map(|format| format.number()) {
+        Some(format::Number::AlternateBases) => Cardinality::AlternateBases,
+        Some(format::Number::ReferenceAlternateBases) => Cardinality::ReferenceAlternateBases,
+        Some(format::Number::Samples) => Cardinality::Genotypes,
+        _ => Cardinality::Other,
+    }
+}
+
+/// The index of a source array and the index of a value in it.
+///
+/// `None` is a missing value.
+type Index = Option<(usize, usize)>;
+
+trait Gather: Sized {
+    fn len(&self) -> usize;
+
+    /// Builds an array by taking values from source arrays.
+    ///
+    /// The array type is the type of the first source. This returns `None` if there are no
+    /// sources.
+    fn gather(sources: &[Option<&Self>], indices: &[Index]) -> Option<Self>;
+}
+
+macro_rules! impl_gather {
+    ($ty:ty) => {
+        impl Gather for $ty {
+            fn len(&self) -> usize {
+                match self {
+                    Self::Integer(values) => values.len(),
+                    Self::Float(values) => values.len(),
+                    Self::Character(values) => values.len(),
+                    Self::String(values) => values.len(),
+                }
+            }
+
+            fn gather(sources: &[Option<&Self>], indices: &[Index]) -> Option<Self> {
+                let array = match sources.iter().flatten().next()? {
+                    Self::Integer(_) => {
+                        Self::Integer(gather_values(sources, indices, |a| match a {
+                            Self::Integer(values) => Some(values),
+                            _ => None,
+                        }))
+                    }
+                    Self::Float(_) => Self::Float(gather_values(sources, indices, |a| match a {
+                        Self::Float(values) => Some(values),
+                        _ => None,
+                    })),
+                    Self::Character(_) => {
+                        Self::Character(gather_values(sources, indices, |a| match a {
+                            Self::Character(values) => Some(values),
+                            _ => None,
+                        }))
+                    }
+                    Self::String(_) => Self::String(gather_values(sources, indices, |a| match a {
+                        Self::String(values) => Some(values),
+                        _ => None,
+                    })),
+                };
+
+                Some(array)
+            }
+        }
+    };
+}
+
+impl_gather!(InfoArray);
+impl_gather!(Array);
+
+fn gather_values<A, T, F>(sources: &[Option<&A>], indices: &[Index], f: F) -> Vec<Option<T>>
+where
+    T: Clone,
+    F: Fn(&A) -> Option<&Vec<Option<T>>>,
+{
+    indices
+        .iter()
+        .map(|index| {
+            let (k, i) = (*index)?;
+            let values = sources.get(k).copied().flatten().and_then(&f)?;
+            values.get(i).cloned().flatten()
+        })
+        .collect()
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+pub(crate) fn genotype_count(allele_count: usize, ploidy: usize) -> usize {
+    binomial(allele_count + ploidy - 1, ploidy)
+}
+
+/// Returns the index of a genotype in a `G` array.
+///
+/// The allele indices must be sorted.
+pub(crate) fn genotype_index(alleles: &[usize]) -> usize {
+    alleles
+        .iter()
+        .enumerate()
+        .map(|(m, &a)| binomial(a + m, m + 1))
+        .sum()
+}
+
+fn infer_ploidy(allele_count: usize, len: usize) -> Option<usize> {
+    (1..=MAX_PLOIDY).find(|&ploidy| genotype_count(allele_count, ploidy) == len)
+}
+
+/// Returns all genotypes in `G` array order.
+pub(crate) fn genotypes(allele_count: usize, ploidy: usize) -> Vec<Vec<usize>> {
+    fn push(
+        genotypes: &mut Vec<Vec<usize>>,
+        alleles: &mut Vec<usize>,
+        allele_count: usize,
+        ploidy: usize,
+    ) {
+        if alleles.len() == ploidy {
+            genotypes.push(alleles.clone());
+            return;
+        }
+
+        let min = alleles.last().copied().unwrap_or_default();
+
+        for a in min..allele_count {
+            alleles.push(a);
+            push(genotypes, alleles, allele_count, ploidy);
+            alleles.pop();
+        }
+    }
+
+    let mut genotypes = Vec::with_capacity(genotype_count(allele_count, ploidy));
+    push(&mut genotypes, &mut Vec::new(), allele_count, ploidy);
+    genotypes.sort_by_key(|alleles| genotype_index(alleles));
+    genotypes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genotype_index() {
+        // diploid, 3 alleles: 0/0, 0/1, 1/1, 0/2, 1/2, 2/2
+        assert_eq!(genotype_index(&[0, 0]), 0);
+        assert_eq!(genotype_index(&[0, 1]), 1);
+        assert_eq!(genotype_index(&[1, 1]), 2);
+        assert_eq!(genotype_index(&[0, 2]), 3);
+        assert_eq!(genotype_index(&[1, 2]), 4);
+        assert_eq!(genotype_index(&[2, 2]), 5);
+
+        // triploid, 2 alleles: 0/0/0, 0/0/1, 0/1/1, 1/1/1
+        assert_eq!(genotype_index(&[0, 0, 1]), 1);
+        assert_eq!(genotype_index(&[0, 1, 1]), 2);
+        assert_eq!(genotype_index(&[1, 1, 1]), 3);
+    }
+
+    #[test]
+    fn test_infer_ploidy() {
+        assert_eq!(infer_ploidy(3, 3), Some(1));
+        assert_eq!(infer_ploidy(3, 6), Some(2));
+        assert_eq!(infer_ploidy(2, 4), Some(3));
+        assert_eq!(infer_ploidy(3, 4), None);
+    }
+
+    #[test]
+    fn test_genotypes() {
+        assert_eq!(
+            genotypes(3, 2),
+            [
+                vec![0, 0],
+                vec![0, 1],
+                vec![1, 1],
+                vec![0, 2],
+                vec![1, 2],
+                vec![2, 2],
+            ]
+        );
+    }
+}
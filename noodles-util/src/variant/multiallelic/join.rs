@@ -0,0 +1,661 @@
+use std::io;
+
+use noodles_vcf::{
+    variant::{
+        record::samples::keys::key,
+        record_buf::{
+            info::field::{value::Array as InfoArray, Value as InfoValue},
+            samples::{
+                sample::{
+                    value::{genotype::Allele, Array, Genotype},
+                    Value,
+                },
+                Keys,
+            },
+            AlternateBases, Info, Samples,
+        },
+        RecordBuf,
+    },
+    Header,
+};
+
+use super::{
+    format_cardinality, genotype_index, genotypes, infer_ploidy, info_cardinality, Cardinality,
+    Gather, Index,
+};
+
+/// Joins records at the same position into a multiallelic record.
+///
+/// The records must have the same reference sequence name and start position. If the reference
+/// bases differ, each must be a prefix of the longest reference bases, and alternate alleles are
+/// extended to match.
+///
+/// Alternate alleles are deduplicated and ordered by first occurrence. `A`, `R`, and `G` fields
+/// are merged, and values that are in none of the records are missing. In genotypes, an
+/// alternate allele takes precedence over the reference allele. All other fields are taken from
+/// the first record that has them.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::Position;
+/// use noodles_util::variant::multiallelic;
+/// use noodles_vcf::{
+///     self as vcf,
+///     variant::{record_buf::AlternateBases, RecordBuf},
+/// };
+///
+/// let header = vcf::Header::default();
+///
+/// let records = [
+///     RecordBuf::builder()
+///         .set_reference_sequence_name("sq0")
+///         .set_variant_start(Position::MIN)
+///         .set_reference_bases("A")
+///         .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+///         .build(),
+///     RecordBuf::builder()
+///         .set_reference_sequence_name("sq0")
+///         .set_variant_start(Position::MIN)
+///         .set_reference_bases("AT")
+///         .set_alternate_bases(AlternateBases::from(vec![String::from("A")]))
+///         .build(),
+/// ];
+///
+/// let record = multiallelic::join(&header, &records)?;
+///
+/// assert_eq!(record.reference_bases(), "AT");
+/// assert_eq!(
+///     record.alternate_bases().as_ref(),
+///     [String::from("CT"), String::from("A")]
+/// );
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn join(header: &Header, records: &[RecordBuf]) -> io::Result<RecordBuf> {
+    let (first, rest) = records
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no records"))?;
+
+    if rest.iter().any(|record| {
+        record.reference_sequence_name() != first.reference_sequence_name()
+            || record.variant_start() != first.variant_start()
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "records are not at the same position",
+        ));
+    }
+
+    let (reference_bases, alternate_bases, allele_maps) = join_alleles(records)?;
+    let allele_count = alternate_bases.len() + 1;
+
+    let mut joined = first.clone();
+    *joined.reference_bases_mut() = reference_bases;
+    *joined.alternate_bases_mut() = AlternateBases::from(alternate_bases);
+    *joined.info_mut() = join_info(header, records, &allele_maps, allele_count);
+    *joined.samples_mut() = join_samples(header, records, &allele_maps, allele_count)?;
+
+    Ok(joined)
+}
+
+/// Returns the union of the alleles of the given records.
+///
+/// This returns the reference bases, the alternate bases, and, for each record, a map from its
+/// allele indices to the joined allele indices.
+pub(crate) fn join_alleles(
+    records: &[RecordBuf],
+) -> io::Result<(String, Vec<String>, Vec<Vec<usize>>)> {
+    let reference_bases = records
+        .iter()
+        .map(|record| record.reference_bases())
+        .max_by_key(|reference_bases| reference_bases.len())
+        .unwrap_or_default();
+
+    let mut alternate_bases: Vec<String> = Vec::new();
+    let mut allele_maps = Vec::with_capacity(records.len());
+
+    for record in records {
+        let suffix = reference_bases
+            .strip_prefix(record.reference_bases())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "incompatible reference bases: {} and {}",
+                        record.reference_bases(),
+                        reference_bases
+                    ),
+                )
+            })?;
+
+        let mut allele_map = vec![0];
+
+        for allele in record.alternate_bases().as_ref() {
+            let allele = extend_allele(allele, suffix)?;
+
+            let i = match alternate_bases.iter().position(|a| *a == allele) {
+                Some(i) => i,
+                None => {
+                    alternate_bases.push(allele);
+                    alternate_bases.len() - 1
+                }
+            };
+
+            allele_map.push(i + 1);
+        }
+
+        allele_maps.push(allele_map);
+    }
+
+    Ok((reference_bases.into(), alternate_bases, allele_maps))
+}
+
+fn extend_allele(allele: &str, suffix: &str) -> io::Result<String> {
+    if suffix.is_empty() || allele == "*" {
+        Ok(allele.into())
+    } else if allele.starts_with('<') || allele.contains(['[', ']']) {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot extend symbolic allele: {allele}"),
+        ))
+    } else {
+        Ok(format!("{allele}{suffix}"))
+    }
+}
+
+pub(crate) fn join_info(
+    header: &Header,
+    records: &[RecordBuf],
+    allele_maps: &[Vec<usize>],
+    allele_count: usize,
+) -> Info {
+    let mut info = Info::default();
+
+    for key in records.iter().flat_map(|record| record.info().keys()) {
+        if info.get(key).is_some() {
+            continue;
+        }
+
+        let values: Vec<_> = records
+            .iter()
+            .map(|record| record.info().get(key).flatten())
+            .collect();
+
+        let first_value = || values.iter().flatten().next().copied().cloned();
+
+        let value = match info_cardinality(header, key) {
+            Cardinality::Other => first_value(),
+            cardinality => {
+                let sources: Vec<_> = values
+                    .iter()
+                    .map(|value| match value {
+                        Some(InfoValue::Array(array)) => Some(array),
+                        _ => None,
+                    })
+                    .collect();
+
+                let indices = join_indices(cardinality, allele_maps, allele_count, &sources);
+
+                InfoArray::gather(&sources, &indices)
+                    .map(InfoValue::Array)
+                    .or_else(first_value)
+            }
+        };
+
+        info.insert(key.clone(), value);
+    }
+
+    info
+}
+
+fn join_samples(
+    header: &Header,
+    records: &[RecordBuf],
+    allele_maps: &[Vec<usize>],
+    allele_count: usize,
+) -> io::Result<Samples> {
+    let parts: Vec<(Keys, Vec<Vec<Option<Value>>>)> = records
+        .iter()
+        .map(|record| record.samples().clone().into())
+        .collect();
+
+    let sample_count = parts
+        .first()
+        .map(|(_, values)| values.len())
+        .unwrap_or_default();
+
+    if parts.iter().any(|(_, values)| values.len() != sample_count) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "records have different numbers of samples",
+        ));
+    }
+
+    let keys: Keys = parts
+        .iter()
+        .flat_map(|(keys, _)| keys.as_ref().iter().cloned())
+        .collect();
+
+    let mut values = Vec::with_capacity(sample_count);
+
+    for i in 0..sample_count {
+        let sample = keys
+            .as_ref()
+            .iter()
+            .map(|key| {
+                let sample_values: Vec<_> = parts
+                    .iter()
+                    .map(|(keys, values)| {
+                        let j = keys.as_ref().get_index_of(key)?;
+                        values[i].get(j)?.as_ref()
+                    })
+                    .collect();
+
+                join_sample_values(header, key, &sample_values, allele_maps, allele_count)
+            })
+            .collect();
+
+        values.push(sample);
+    }
+
+    Ok(Samples::new(keys, values))
+}
+
+/// Merges the values of a sample field.
+///
+/// Each value corresponds to the allele map at the same index.
+pub(crate) fn join_sample_values(
+    header: &Header,
+    key: &str,
+    values: &[Option<&Value>],
+    allele_maps: &[Vec<usize>],
+    allele_count: usize,
+) -> Option<Value> {
+    let first_value = || values.iter().flatten().next().copied().cloned();
+
+    if key == key::GENOTYPE {
+        return join_genotypes(values, allele_maps)
+            .map(Value::Genotype)
+            .or_else(first_value);
+    }
+
+    match format_cardinality(header, key) {
+        Cardinality::Other => first_value(),
+        cardinality => {
+            let sources: Vec<_> = values
+                .iter()
+                .map(|value| match value {
+                    Some(Value::Array(array)) => Some(array),
+                    _ => None,
+                })
+                .collect();
+
+            let indices = join_indices(cardinality, allele_maps, allele_count, &sources);
+
+            Array::gather(&sources, &indices)
+                .map(Value::Array)
+                .or_else(first_value)
+        }
+    }
+}
+
+fn join_indices<A>(
+    cardinality: Cardinality,
+    allele_maps: &[Vec<usize>],
+    allele_count: usize,
+    sources: &[Option<&A>],
+) -> Vec<Index>
+where
+    A: Gather,
+{
+    let find_allele = |allele: usize| {
+        sources
+            .iter()
+            .zip(allele_maps)
+            .enumerate()
+            .find_map(|(k, (source, allele_map))| {
+                (*source)?;
+                let i = allele_map.iter().position(|&a| a == allele)?;
+                Some((k, i))
+            })
+    };
+
+    match cardinality {
+        Cardinality::AlternateBases => (1..allele_count)
+            .map(|allele| find_allele(allele).map(|(k, i)| (k, i - 1)))
+            .collect(),
+        Cardinality::ReferenceAlternateBases => (0..allele_count).map(find_allele).collect(),
+        Cardinality::Genotypes => {
+            let ploidies: Vec<_> = sources
+                .iter()
+                .zip(allele_maps)
+                .map(|(source, allele_map)| {
+                    source.and_then(|array| infer_ploidy(allele_map.len(), array.len()))
+                })
+                .collect();
+
+            let Some(ploidy) = ploidies.iter().flatten().next().copied() else {
+                return vec![None];
+            };
+
+            genotypes(allele_count, ploidy)
+                .iter()
+                .map(|alleles| {
+                    ploidies.iter().zip(allele_maps).enumerate().find_map(
+                        |(k, (source_ploidy, allele_map))| {
+                            if *source_ploidy != Some(ploidy) {
+                                return None;
+                            }
+
+                            let mut local_alleles = alleles
+                                .iter()
+                                .map(|a| allele_map.iter().position(|b| b == a))
+                                .collect::<Option<Vec<_>>>()?;
+
+                            local_alleles.sort_unstable();
+
+                            Some((k, genotype_index(&local_alleles)))
+                        },
+                    )
+                })
+                .collect()
+        }
+        Cardinality::Other => Vec::new(),
+    }
+}
+
+fn join_genotypes(values: &[Option<&Value>], allele_maps: &[Vec<usize>]) -> Option<Genotype> {
+    let genotypes: Vec<_> = values
+        .iter()
+        .map(|value| match value {
+            Some(Value::Genotype(genotype)) => Some(genotype),
+            _ => None,
+        })
+        .collect();
+
+    let template = genotypes.iter().flatten().next()?;
+
+    let genotype = template
+        .as_ref()
+        .iter()
+        .enumerate()
+        .map(|(i, allele)| {
+            let mut position = None;
+
+            for (genotype, allele_map) in genotypes.iter().zip(allele_maps) {
+                let Some(a) = genotype
+                    .and_then(|g| g.as_ref().get(i))
+                    .and_then(|a| a.position())
+                    .and_then(|a| allele_map.get(a).copied())
+                else {
+                    continue;
+                };
+
+                if a != 0 {
+                    position = Some(a);
+                    break;
+                }
+
+                position.get_or_insert(a);
+            }
+
+            Allele::new(position, allele.phasing())
+        })
+        .collect();
+
+    Some(genotype)
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::Position;
+    use noodles_vcf::{
+        header::record::value::{
+            map::{Format, Info as InfoMap},
+            Map,
+        },
+        variant::record::info::field::key as info_key,
+    };
+
+    use super::*;
+    use crate::variant::multiallelic::split;
+
+    #[test]
+    fn test_split_and_join() -> Result<(), Box<dyn std::error::Error>> {
+        let header = Header::builder()
+            .add_info(
+                info_key::ALLELE_COUNT,
+                Map::<InfoMap>::from(info_key::ALLELE_COUNT),
+            )
+            .add_format(key::GENOTYPE, Map::<Format>::from(key::GENOTYPE))
+            .add_format(key::READ_DEPTHS, Map::<Format>::from(key::READ_DEPTHS))
+            .add_format(
+                key::ROUNDED_GENOTYPE_LIKELIHOODS,
+                Map::<Format>::from(key::ROUNDED_GENOTYPE_LIKELIHOODS),
+            )
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .build();
+
+        let keys: Keys = [
+            key::GENOTYPE,
+            key::READ_DEPTHS,
+            key::ROUNDED_GENOTYPE_LIKELIHOODS,
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(8)?)
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("G"),
+            ]))
+            .set_info(
+                [(
+                    String::from(info_key::ALLELE_COUNT),
+                    Some(InfoValue::from(vec![Some(1), Some(2)])),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .set_samples(Samples::new(
+                keys.clone(),
+                vec![
+                    vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(5), Some(3), Some(0)])),
+                        Some(Value::from(vec![
+                            Some(40),
+                            Some(0),
+                            Some(50),
+                            Some(45),
+                            Some(60),
+                            Some(99),
+                        ])),
+                    ],
+                    vec![
+                        Some(Value::Genotype("1/2".parse()?)),
+                        Some(Value::from(vec![Some(0), Some(4), Some(6)])),
+                        Some(Value::from(vec![
+                            Some(90),
+                            Some(30),
+                            Some(40),
+                            Some(20),
+                            Some(0),
+                            Some(35),
+                        ])),
+                    ],
+                ],
+            ))
+            .build();
+
+        let records = split(&header, &record);
+
+        assert_eq!(
+            records,
+            [
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(8)?)
+                    .set_reference_bases("A")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+                    .set_info(
+                        [(
+                            String::from(info_key::ALLELE_COUNT),
+                            Some(InfoValue::from(vec![Some(1)])),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    )
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![
+                            vec![
+                                Some(Value::Genotype("0/1".parse()?)),
+                                Some(Value::from(vec![Some(5), Some(3)])),
+                                Some(Value::from(vec![Some(40), Some(0), Some(50)])),
+                            ],
+                            vec![
+                                Some(Value::Genotype("1/0".parse()?)),
+                                Some(Value::from(vec![Some(0), Some(4)])),
+                                Some(Value::from(vec![Some(90), Some(30), Some(40)])),
+                            ],
+                        ],
+                    ))
+                    .build(),
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(8)?)
+                    .set_reference_bases("A")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                    .set_info(
+                        [(
+                            String::from(info_key::ALLELE_COUNT),
+                            Some(InfoValue::from(vec![Some(2)])),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    )
+                    .set_samples(Samples::new(
+                        keys.clone(),
+                        vec![
+                            vec![
+                                Some(Value::Genotype("0/0".parse()?)),
+                                Some(Value::from(vec![Some(5), Some(0)])),
+                                Some(Value::from(vec![Some(40), Some(45), Some(99)])),
+                            ],
+                            vec![
+                                Some(Value::Genotype("0/1".parse()?)),
+                                Some(Value::from(vec![Some(0), Some(6)])),
+                                Some(Value::from(vec![Some(90), Some(20), Some(35)])),
+                            ],
+                        ],
+                    ))
+                    .build(),
+            ]
+        );
+
+        let expected = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(8)?)
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("C"),
+                String::from("G"),
+            ]))
+            .set_info(
+                [(
+                    String::from(info_key::ALLELE_COUNT),
+                    Some(InfoValue::from(vec![Some(1), Some(2)])),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .set_samples(Samples::new(
+                keys.clone(),
+                vec![
+                    vec![
+                        Some(Value::Genotype("0/1".parse()?)),
+                        Some(Value::from(vec![Some(5), Some(3), Some(0)])),
+                        Some(Value::from(vec![
+                            Some(40),
+                            Some(0),
+                            Some(50),
+                            Some(45),
+                            None,
+                            Some(99),
+                        ])),
+                    ],
+                    vec![
+                        Some(Value::Genotype("1/2".parse()?)),
+                        Some(Value::from(vec![Some(0), Some(4), Some(6)])),
+                        Some(Value::from(vec![
+                            Some(90),
+                            Some(30),
+                            Some(40),
+                            Some(20),
+                            None,
+                            Some(35),
+                        ])),
+                    ],
+                ],
+            ))
+            .build();
+
+        assert_eq!(join(&header, &records)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_join_with_invalid_records() -> Result<(), Box<dyn std::error::Error>> {
+        let header = Header::default();
+
+        assert!(matches!(
+            join(&header, &[]),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        let records = [
+            RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("A")
+                .build(),
+            RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(13)?)
+                .set_reference_bases("A")
+                .build(),
+        ];
+
+        assert!(matches!(
+            join(&header, &records),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        let records = [
+            RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("AC")
+                .build(),
+            RecordBuf::builder()
+                .set_reference_sequence_name("sq0")
+                .set_variant_start(Position::try_from(8)?)
+                .set_reference_bases("AG")
+                .build(),
+        ];
+
+        assert!(matches!(
+            join(&header, &records),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use noodles_vcf::{
+    variant::{
+        record::samples::keys::key,
+        record_buf::{
+            info::field::Value as InfoValue,
+            samples::{
+                sample::{value::Genotype, Value},
+                Keys,
+            },
+            AlternateBases, Samples,
+        },
+        RecordBuf,
+    },
+    Header,
+};
+
+use super::{
+    format_cardinality, genotype_index, infer_ploidy, info_cardinality, Cardinality, Gather, Index,
+};
+
+/// Splits a multiallelic record into biallelic records.
+///
+/// One record is returned per alternate allele, in allele order. `A`, `R`, and `G` fields are
+/// subset to the reference allele and the given alternate allele. In genotypes, the other
+/// alternate alleles are set to the reference allele (`0`).
+///
+/// Records with less than two alternate alleles are returned as is.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::Position;
+/// use noodles_util::variant::multiallelic;
+/// use noodles_vcf::{
+///     self as vcf,
+///     header::record::value::{map::Info, Map},
+///     variant::{
+///         record::info::field::key,
+///         record_buf::{info::field::Value, AlternateBases},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::builder()
+///     .add_info(key::ALLELE_COUNT, Map::<Info>::from(key::ALLELE_COUNT))
+///     .build();
+///
+/// let record = RecordBuf::builder()
+///     .set_reference_sequence_name("sq0")
+///     .set_variant_start(Position::MIN)
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![
+///         String::from("C"),
+///         String::from("G"),
+///     ]))
+///     .set_info(
+///         [(
+///             String::from(key::ALLELE_COUNT),
+///             Some(Value::from(vec![Some(3), Some(5)])),
+///         )]
+///         .into_iter()
+///         .collect(),
+///     )
+///     .build();
+///
+/// let records = multiallelic::split(&header, &record);
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].alternate_bases().as_ref(), [String::from("G")]);
+/// assert_eq!(
+///     records[1].info().get(key::ALLELE_COUNT),
+///     Some(Some(&Value::from(vec![Some(5)])))
+/// );
+/// ```
+pub fn split(header: &Header, record: &RecordBuf) -> Vec<RecordBuf> {
+    let alternate_bases = record.alternate_bases().as_ref();
+
+    if alternate_bases.len() < 2 {
+        return vec![record.clone()];
+    }
+
+    let allele_count = alternate_bases.len() + 1;
+
+    alternate_bases
+        .iter()
+        .enumerate()
+        .map(|(i, alternate_base)| {
+            let allele = i + 1;
+
+            let mut biallelic = record.clone();
+            *biallelic.alternate_bases_mut() = AlternateBases::from(vec![alternate_base.clone()]);
+            split_info(header, &mut biallelic, allele_count, allele);
+            split_samples(header, &mut biallelic, allele_count, allele);
+
+            biallelic
+        })
+        .collect()
+}
+
+fn split_info(header: &Header, record: &mut RecordBuf, allele_count: usize, allele: usize) {
+    for (key, value) in record.info_mut().as_mut().iter_mut() {
+        let cardinality = info_cardinality(header, key);
+
+        if cardinality == Cardinality::Other {
+            continue;
+        }
+
+        if let Some(InfoValue::Array(array)) = value {
+            let indices = split_indices(cardinality, allele_count, allele, array.len());
+            *value = Gather::gather(&[Some(&*array)], &indices).map(InfoValue::Array);
+        }
+    }
+}
+
+fn split_samples(header: &Header, record: &mut RecordBuf, allele_count: usize, allele: usize) {
+    let (keys, mut values): (Keys, Vec<Vec<Option<Value>>>) = record.samples().clone().into();
+
+    let cardinalities: Vec<_> = keys
+        .as_ref()
+        .iter()
+        .map(|key| format_cardinality(header, key))
+        .collect();
+
+    for sample in &mut values {
+        for ((key, cardinality), value) in keys.as_ref().iter().zip(&cardinalities).zip(sample) {
+            match value {
+                Some(Value::Genotype(genotype)) if key == key::GENOTYPE => {
+                    remap_genotype(genotype, allele);
+                }
+                Some(Value::Array(array)) if *cardinality != Cardinality::Other => {
+                    let indices = split_indices(*cardinality, allele_count, allele, array.len());
+                    *value = Gather::gather(&[Some(&*array)], &indices).map(Value::Array);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    *record.samples_mut() = Samples::new(keys, values);
+}
+
+fn split_indices(
+    cardinality: Cardinality,
+    allele_count: usize,
+    allele: usize,
+    len: usize,
+) -> Vec<Index> {
+    match cardinality {
+        Cardinality::AlternateBases => vec![Some((0, allele - 1))],
+        Cardinality::ReferenceAlternateBases => vec![Some((0, 0)), Some((0, allele))],
+        Cardinality::Genotypes => match infer_ploidy(allele_count, len) {
+            Some(ploidy) => (0..=ploidy)
+                .map(|n| {
+                    let mut alleles = vec![0; ploidy - n];
+                    alleles.resize(ploidy, allele);
+                    Some((0, genotype_index(&alleles)))
+                })
+                .collect(),
+            None => vec![None],
+        },
+        Cardinality::Other => Vec::new(),
+    }
+}
+
+fn remap_genotype(genotype: &mut Genotype, allele: usize) {
+    for a in genotype.as_mut() {
+        if let Some(position) = a.position_mut() {
+            *position = usize::from(*position == allele);
+        }
+    }
+}
@@ -0,0 +1,584 @@
+//! Variant record normalization.
+//!
+//! A variant is normalized when it is parsimonious and left-aligned, i.e., its alleles cannot be
+//! shortened and its position cannot be shifted left while still representing the same
+//! haplotypes. This is similar to `bcftools norm` and `vt normalize`.
+
+mod builder;
+mod decompose;
+
+use std::io;
+
+use noodles_core::Position;
+use noodles_fasta as fasta;
+use noodles_vcf::variant::{record_buf::AlternateBases, RecordBuf};
+
+pub use self::builder::Builder;
+
+/// How reference bases that do not match the reference sequence are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReferenceCheck {
+    /// Mismatches are errors.
+    #[default]
+    Error,
+    /// Records with mismatches are not normalized.
+    ///
+    /// Normalizing these records returns [`Outcome::ReferenceMismatch`].
+    Skip,
+    /// Reference bases are not checked.
+    Off,
+}
+
+/// The result of normalizing a record.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The record is already normalized.
+    Unchanged,
+    /// The record was modified.
+    Normalized,
+    /// The reference bases do not match the reference sequence.
+    ///
+    /// The record is not modified.
+    ReferenceMismatch,
+    /// The record has symbolic, breakend, or overlapping deletion alleles or is missing a
+    /// position.
+    ///
+    /// The record is not modified.
+    Skipped,
+}
+
+/// A variant record normalizer.
+#[derive(Debug)]
+pub struct Normalizer {
+    reference_sequence_repository: fasta::Repository,
+    reference_check: ReferenceCheck,
+}
+
+impl Normalizer {
+    /// Creates a variant normalizer with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::normalize::Normalizer;
+    /// let normalizer = Normalizer::new(fasta::Repository::default());
+    /// ```
+    pub fn new(reference_sequence_repository: fasta::Repository) -> Self {
+        Builder::default().build(reference_sequence_repository)
+    }
+
+    /// Returns a builder to create a variant normalizer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::normalize::Normalizer;
+    /// let builder = Normalizer::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Left-aligns and trims the alleles of a record.
+    ///
+    /// Only the position, reference bases, and alternate bases are modified. Multiallelic records
+    /// are normalized as a whole.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::normalize::{Normalizer, Outcome};
+    /// use noodles_vcf::variant::{record_buf::AlternateBases, RecordBuf};
+    ///
+    /// let repository = fasta::Repository::new(vec![fasta::Record::new(
+    ///     fasta::record::Definition::new("sq0", None),
+    ///     fasta::record::Sequence::from(b"GCACACAT".to_vec()),
+    /// )]);
+    ///
+    /// let normalizer = Normalizer::new(repository);
+    ///
+    /// // sq0:4 CAC>C deletes one of the CA repeats.
+    /// let mut record = RecordBuf::builder()
+    ///     .set_reference_sequence_name("sq0")
+    ///     .set_variant_start(Position::try_from(4)?)
+    ///     .set_reference_bases("CAC")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+    ///     .build();
+    ///
+    /// assert_eq!(normalizer.normalize(&mut record)?, Outcome::Normalized);
+    /// assert_eq!(record.variant_start(), Some(Position::try_from(1)?));
+    /// assert_eq!(record.reference_bases(), "GCA");
+    /// assert_eq!(record.alternate_bases().as_ref(), [String::from("G")]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn normalize(&self, record: &mut RecordBuf) -> io::Result<Outcome> {
+        let Some(start) = record.variant_start() else {
+            return Ok(Outcome::Skipped);
+        };
+
+        let mut alleles = Vec::with_capacity(record.alternate_bases().as_ref().len() + 1);
+        alleles.push(record.reference_bases().as_bytes().to_vec());
+
+        for allele in record.alternate_bases().as_ref() {
+            alleles.push(allele.as_bytes().to_vec());
+        }
+
+        if !alleles.iter().all(|allele| is_sequence(allele)) {
+            return Ok(Outcome::Skipped);
+        }
+
+        let sequence = self
+            .reference_sequence_repository
+            .get(record.reference_sequence_name().as_bytes())
+            .transpose()?
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "missing reference sequence: {}",
+                        record.reference_sequence_name()
+                    ),
+                )
+            })?;
+
+        let reference_sequence = sequence.as_ref();
+
+        if self.reference_check != ReferenceCheck::Off
+            && !matches_reference_sequence(reference_sequence, start, &alleles[0])
+        {
+            return match self.reference_check {
+                ReferenceCheck::Error => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "reference bases mismatch at {}:{start}: {}",
+                        record.reference_sequence_name(),
+                        record.reference_bases()
+                    ),
+                )),
+                _ => Ok(Outcome::ReferenceMismatch),
+            };
+        }
+
+        let position = usize::from(start);
+        let Some(new_position) = left_align(reference_sequence, position, &mut alleles) else {
+            return Ok(Outcome::Unchanged);
+        };
+
+        if new_position == position && is_unchanged(record, &alleles) {
+            return Ok(Outcome::Unchanged);
+        }
+
+        let mut alleles = alleles
+            .into_iter()
+            .map(|allele| {
+                String::from_utf8(allele).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let alternate_bases = alleles.split_off(1);
+        // SAFETY: `alleles` is non-empty.
+        let reference_bases = alleles.pop().unwrap();
+
+        *record.variant_start_mut() = Some(
+            Position::try_from(new_position)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        );
+        *record.reference_bases_mut() = reference_bases;
+        *record.alternate_bases_mut() = AlternateBases::from(alternate_bases);
+
+        Ok(Outcome::Normalized)
+    }
+
+    /// Decomposes a biallelic record into primitive variants.
+    ///
+    /// Multiple nucleotide polymorphisms (MNPs) are split into single nucleotide polymorphisms
+    /// (SNPs), and complex variants are split into SNPs and a single trailing indel. The indel
+    /// keeps its first base as is, so it may be a complex substitution, e.g., `CA>T`. All other
+    /// fields are copied to each primitive. The decomposed records are in position order and are not
+    /// normalized.
+    ///
+    /// This returns `None` if the record is multiallelic, has a symbolic allele, or is already
+    /// primitive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::normalize::Normalizer;
+    /// use noodles_vcf::variant::{record_buf::AlternateBases, RecordBuf};
+    ///
+    /// let normalizer = Normalizer::new(fasta::Repository::default());
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_reference_sequence_name("sq0")
+    ///     .set_variant_start(Position::try_from(8)?)
+    ///     .set_reference_bases("ACG")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("TCA")]))
+    ///     .build();
+    ///
+    /// let records = normalizer.decompose(&record).expect("record is not decomposable");
+    ///
+    /// assert_eq!(records.len(), 2);
+    /// assert_eq!(records[0].variant_start(), Some(Position::try_from(8)?));
+    /// assert_eq!(records[0].reference_bases(), "A");
+    /// assert_eq!(records[1].variant_start(), Some(Position::try_from(10)?));
+    /// assert_eq!(records[1].reference_bases(), "G");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decompose(&self, record: &RecordBuf) -> Option<Vec<RecordBuf>> {
+        decompose::decompose(record)
+    }
+}
+
+fn is_sequence(allele: &[u8]) -> bool {
+    !allele.is_empty()
+        && allele
+            .iter()
+            .all(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'))
+}
+
+fn matches_reference_sequence(
+    reference_sequence: &[u8],
+    start: Position,
+    reference_bases: &[u8],
+) -> bool {
+    let i = usize::from(start) - 1;
+
+    reference_sequence
+        .get(i..i + reference_bases.len())
+        .map(|bases| {
+            bases
+                .iter()
+                .zip(reference_bases)
+                .all(|(a, b)| b.eq_ignore_ascii_case(&b'N') || a.eq_ignore_ascii_case(b))
+        })
+        .unwrap_or(false)
+}
+
+fn is_unchanged(record: &RecordBuf, alleles: &[Vec<u8>]) -> bool {
+    record.reference_bases().as_bytes() == alleles[0]
+        && record
+            .alternate_bases()
+            .as_ref()
+            .iter()
+            .zip(&alleles[1..])
+            .all(|(a, b)| a.as_bytes() == &b[..])
+}
+
+/// Left-aligns and trims alleles.
+///
+/// This is the algorithm from Tan et al. (2015), "Unified representation of genetic variants".
+/// The position is 1-based. This returns `None` if all alleles are equal.
+fn left_align(
+    reference_sequence: &[u8],
+    mut position: usize,
+    alleles: &mut [Vec<u8>],
+) -> Option<usize> {
+    let (first, rest) = alleles.split_first()?;
+
+    if rest.iter().all(|allele| allele.eq_ignore_ascii_case(first)) {
+        return None;
+    }
+
+    loop {
+        let mut is_changed = false;
+
+        let last = alleles[0].last().map(|b| b.to_ascii_uppercase());
+
+        if alleles
+            .iter()
+            .all(|allele| allele.last().map(|b| b.to_ascii_uppercase()) == last)
+        {
+            let would_empty = alleles.iter().any(|allele| allele.len() == 1);
+
+            // An allele can only be emptied if there is a preceding reference base to extend it.
+            if would_empty && previous_base(reference_sequence, position).is_none() {
+                break;
+            }
+
+            for allele in alleles.iter_mut() {
+                allele.pop();
+            }
+
+            is_changed = true;
+        }
+
+        if alleles.iter().any(|allele| allele.is_empty()) {
+            let Some(base) = previous_base(reference_sequence, position) else {
+                break;
+            };
+
+            for allele in alleles.iter_mut() {
+                allele.insert(0, base);
+            }
+
+            position -= 1;
+            is_changed = true;
+        }
+
+        if !is_changed {
+            break;
+        }
+    }
+
+    while alleles.iter().all(|allele| allele.len() >= 2) {
+        let first = alleles[0][0].to_ascii_uppercase();
+
+        if !alleles
+            .iter()
+            .all(|allele| allele[0].to_ascii_uppercase() == first)
+        {
+            break;
+        }
+
+        for allele in alleles.iter_mut() {
+            allele.remove(0);
+        }
+
+        position += 1;
+    }
+
+    Some(position)
+}
+
+fn previous_base(reference_sequence: &[u8], position: usize) -> Option<u8> {
+    position
+        .checked_sub(2)
+        .and_then(|i| reference_sequence.get(i))
+        .map(|b| b.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() -> Result<(), Box<dyn std::error::Error>> {
+        //                                                       1234567890
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq0", None),
+            fasta::record::Sequence::from(b"TTCACACAGT".to_vec()),
+        )]);
+
+        let normalizer = Normalizer::new(repository);
+
+        // Deletion of a CA repeat unit, right-aligned.
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(6)?)
+            .set_reference_bases("ACA")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("A")]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Normalized);
+        assert_eq!(record.variant_start(), Some(Position::try_from(2)?));
+        assert_eq!(record.reference_bases(), "TCA");
+        assert_eq!(record.alternate_bases().as_ref(), [String::from("T")]);
+
+        // Insertion, right-aligned and not parsimonious.
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(7)?)
+            .set_reference_bases("CAG")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("CACAG")]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Normalized);
+        assert_eq!(record.variant_start(), Some(Position::try_from(2)?));
+        assert_eq!(record.reference_bases(), "T");
+        assert_eq!(record.alternate_bases().as_ref(), [String::from("TCA")]);
+
+        // SNV with extra context.
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(9)?)
+            .set_reference_bases("GT")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("AT")]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Normalized);
+        assert_eq!(record.variant_start(), Some(Position::try_from(9)?));
+        assert_eq!(record.reference_bases(), "G");
+        assert_eq!(record.alternate_bases().as_ref(), [String::from("A")]);
+
+        // Multiallelic.
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(6)?)
+            .set_reference_bases("ACAG")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("AG"),
+                String::from("ACACAG"),
+            ]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Normalized);
+        assert_eq!(record.variant_start(), Some(Position::try_from(2)?));
+        assert_eq!(record.reference_bases(), "TCA");
+        assert_eq!(
+            record.alternate_bases().as_ref(),
+            [String::from("T"), String::from("TCACA")]
+        );
+
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("T")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Unchanged);
+
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("T")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("<DEL>")]))
+            .build();
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Skipped);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_with_reference_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+        //                                                       1234567890
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq0", None),
+            fasta::record::Sequence::from(b"TTCACACAGT".to_vec()),
+        )]);
+
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(2)?)
+            .set_reference_bases("G")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .build();
+
+        let normalizer = Normalizer::builder()
+            .set_reference_check(ReferenceCheck::Error)
+            .build(repository.clone());
+
+        assert!(matches!(
+            normalizer.normalize(&mut record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        let normalizer = Normalizer::builder()
+            .set_reference_check(ReferenceCheck::Skip)
+            .build(repository.clone());
+
+        assert_eq!(
+            normalizer.normalize(&mut record)?,
+            Outcome::ReferenceMismatch
+        );
+
+        let normalizer = Normalizer::builder()
+            .set_reference_check(ReferenceCheck::Off)
+            .build(repository);
+
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Unchanged);
+
+        // The preceding reference base is out of bounds, so the alleles cannot be extended.
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(20)?)
+            .set_reference_bases("AC")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .build();
+
+        let expected = record.clone();
+        assert_eq!(normalizer.normalize(&mut record)?, Outcome::Unchanged);
+        assert_eq!(record, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompose() -> Result<(), Box<dyn std::error::Error>> {
+        //                                                       1234567890
+        let repository = fasta::Repository::new(vec![fasta::Record::new(
+            fasta::record::Definition::new("sq0", None),
+            fasta::record::Sequence::from(b"TTCACACAGT".to_vec()),
+        )]);
+
+        let normalizer = Normalizer::new(repository);
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(3)?)
+            .set_reference_bases("CAC")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("GAT")]))
+            .build();
+
+        assert_eq!(
+            normalizer.decompose(&record),
+            Some(vec![
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(3)?)
+                    .set_reference_bases("C")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                    .build(),
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(5)?)
+                    .set_reference_bases("C")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("T")]))
+                    .build(),
+            ])
+        );
+
+        // complex: CACA > GAT = C>G, A=A, CA>T
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(3)?)
+            .set_reference_bases("CACA")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("GAT")]))
+            .build();
+
+        assert_eq!(
+            normalizer.decompose(&record),
+            Some(vec![
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(3)?)
+                    .set_reference_bases("C")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+                    .build(),
+                RecordBuf::builder()
+                    .set_reference_sequence_name("sq0")
+                    .set_variant_start(Position::try_from(5)?)
+                    .set_reference_bases("CA")
+                    .set_alternate_bases(AlternateBases::from(vec![String::from("T")]))
+                    .build(),
+            ])
+        );
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(3)?)
+            .set_reference_bases("C")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+            .build();
+        assert!(normalizer.decompose(&record).is_none());
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::try_from(3)?)
+            .set_reference_bases("C")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("G"),
+                String::from("T"),
+            ]))
+            .build();
+        assert!(normalizer.decompose(&record).is_none());
+
+        Ok(())
+    }
+}
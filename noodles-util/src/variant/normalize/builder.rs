@@ -0,0 +1,42 @@
+use noodles_fasta as fasta;
+
+use super::{Normalizer, ReferenceCheck};
+
+/// A variant normalizer builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    reference_check: ReferenceCheck,
+}
+
+impl Builder {
+    /// Sets how reference bases that do not match the reference sequence are handled.
+    ///
+    /// By default, mismatches are errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::normalize::{Normalizer, ReferenceCheck};
+    /// let builder = Normalizer::builder().set_reference_check(ReferenceCheck::Skip);
+    /// ```
+    pub fn set_reference_check(mut self, reference_check: ReferenceCheck) -> Self {
+        self.reference_check = reference_check;
+        self
+    }
+
+    /// Builds a variant normalizer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_fasta as fasta;
+    /// use noodles_util::variant::normalize::Normalizer;
+    /// let normalizer = Normalizer::builder().build(fasta::Repository::default());
+    /// ```
+    pub fn build(self, reference_sequence_repository: fasta::Repository) -> Normalizer {
+        Normalizer {
+            reference_sequence_repository,
+            reference_check: self.reference_check,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use noodles_vcf::variant::{record_buf::AlternateBases, RecordBuf};
+
+/// Decomposes a biallelic record into primitive variants.
+///
+/// This returns `None` if the record is not decomposable.
+pub(super) fn decompose(record: &RecordBuf) -> Option<Vec<RecordBuf>> {
+    let start = record.variant_start()?;
+
+    let [alternate_bases] = record.alternate_bases().as_ref() else {
+        return None;
+    };
+
+    let reference_bases = record.reference_bases().as_bytes();
+    let alternate_bases = alternate_bases.as_bytes();
+
+    if !super::is_sequence(reference_bases) || !super::is_sequence(alternate_bases) {
+        return None;
+    }
+
+    let mut primitives = Vec::new();
+
+    if reference_bases.len() == alternate_bases.len() {
+        for (i, (r, a)) in reference_bases.iter().zip(alternate_bases).enumerate() {
+            if !r.eq_ignore_ascii_case(a) {
+                primitives.push((i, vec![*r], vec![*a]));
+            }
+        }
+    } else {
+        let prefix_len = common_prefix_len(reference_bases, alternate_bases);
+
+        // Keep at least one base as the anchor of the remaining indel.
+        let start_offset = prefix_len.saturating_sub(1);
+        let r = &reference_bases[start_offset..];
+        let a = &alternate_bases[start_offset..];
+
+        let suffix_len = common_suffix_len(&r[1..], &a[1..]);
+        let r = &r[..r.len() - suffix_len];
+        let a = &a[..a.len() - suffix_len];
+
+        // The aligned bases before the last are compared base by base. The last aligned base and
+        // the length difference then form an indel, or a complex variant if that base differs.
+        let anchor = r.len().min(a.len()) - 1;
+
+        for i in 0..anchor {
+            if !r[i].eq_ignore_ascii_case(&a[i]) {
+                primitives.push((start_offset + i, vec![r[i]], vec![a[i]]));
+            }
+        }
+
+        primitives.push((
+            start_offset + anchor,
+            r[anchor..].to_vec(),
+            a[anchor..].to_vec(),
+        ));
+
+        primitives.sort_by_key(|(offset, _, _)| *offset);
+    }
+
+    if primitives.len() < 2
+        && primitives.first().map_or(true, |(offset, r, a)| {
+            *offset == 0 && r[..] == reference_bases[..] && a[..] == alternate_bases[..]
+        })
+    {
+        return None;
+    }
+
+    let records = primitives
+        .into_iter()
+        .filter_map(|(offset, r, a)| {
+            let position = start.checked_add(offset)?;
+
+            let mut primitive = record.clone();
+            *primitive.variant_start_mut() = Some(position);
+            *primitive.reference_bases_mut() = String::from_utf8(r).ok()?;
+            *primitive.alternate_bases_mut() =
+                AlternateBases::from(vec![String::from_utf8(a).ok()?]);
+
+            Some(primitive)
+        })
+        .collect();
+
+    Some(records)
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .zip(b)
+        .take_while(|(x, y)| x.eq_ignore_ascii_case(y))
+        .count()
+}
+
+fn common_suffix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x.eq_ignore_ascii_case(y))
+        .count()
+}
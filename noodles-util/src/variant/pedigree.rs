@@ -0,0 +1,327 @@
+//! Pedigrees.
+//!
+//! A pedigree lists the individuals of families and their parents. It is read from a PED (or
+//! PLINK FAM) file, which has six whitespace-separated columns: family ID, individual ID, paternal
+//! ID, maternal ID, sex, and phenotype. A paternal or maternal ID of `0` is a missing parent.
+
+pub mod mendelian;
+
+use std::{
+    collections::HashSet,
+    io::{self, BufRead},
+};
+
+const MISSING: &str = "0";
+
+/// The sex of an individual.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Sex {
+    /// Male (`1`).
+    Male,
+    /// Female (`2`).
+    Female,
+    /// Unknown (any other value).
+    #[default]
+    Unknown,
+}
+
+impl From<&str> for Sex {
+    fn from(s: &str) -> Self {
+        match s {
+            "1" => Self::Male,
+            "2" => Self::Female,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The phenotype of an individual.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Phenotype {
+    /// Missing (`-9`, `0`, or `.`).
+    #[default]
+    Missing,
+    /// Unaffected (`1`).
+    Unaffected,
+    /// Affected (`2`).
+    Affected,
+    /// Any other value, e.g., a quantitative trait.
+    Other(String),
+}
+
+impl From<&str> for Phenotype {
+    fn from(s: &str) -> Self {
+        match s {
+            "-9" | "0" | "." => Self::Missing,
+            "1" => Self::Unaffected,
+            "2" => Self::Affected,
+            _ => Self::Other(s.into()),
+        }
+    }
+}
+
+/// An individual in a pedigree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Individual {
+    family_id: String,
+    id: String,
+    father_id: Option<String>,
+    mother_id: Option<String>,
+    sex: Sex,
+    phenotype: Phenotype,
+}
+
+impl Individual {
+    /// Creates an individual without parents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::pedigree::Individual;
+    /// let individual = Individual::new("fam0", "sample0");
+    /// assert_eq!(individual.id(), "sample0");
+    /// ```
+    pub fn new<F, I>(family_id: F, id: I) -> Self
+    where
+        F: Into<String>,
+        I: Into<String>,
+    {
+        Self {
+            family_id: family_id.into(),
+            id: id.into(),
+            father_id: None,
+            mother_id: None,
+            sex: Sex::default(),
+            phenotype: Phenotype::default(),
+        }
+    }
+
+    /// Sets the ID of the father.
+    pub fn with_father_id<S>(mut self, father_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.father_id = Some(father_id.into());
+        self
+    }
+
+    /// Sets the ID of the mother.
+    pub fn with_mother_id<S>(mut self, mother_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.mother_id = Some(mother_id.into());
+        self
+    }
+
+    /// Sets the sex.
+    pub fn with_sex(mut self, sex: Sex) -> Self {
+        self.sex = sex;
+        self
+    }
+
+    /// Sets the phenotype.
+    pub fn with_phenotype(mut self, phenotype: Phenotype) -> Self {
+        self.phenotype = phenotype;
+        self
+    }
+
+    /// Returns the family ID.
+    pub fn family_id(&self) -> &str {
+        &self.family_id
+    }
+
+    /// Returns the individual ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the ID of the father.
+    pub fn father_id(&self) -> Option<&str> {
+        self.father_id.as_deref()
+    }
+
+    /// Returns the ID of the mother.
+    pub fn mother_id(&self) -> Option<&str> {
+        self.mother_id.as_deref()
+    }
+
+    /// Returns the sex.
+    pub fn sex(&self) -> Sex {
+        self.sex
+    }
+
+    /// Returns the phenotype.
+    pub fn phenotype(&self) -> &Phenotype {
+        &self.phenotype
+    }
+}
+
+/// A pedigree.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Pedigree(Vec<Individual>);
+
+impl Pedigree {
+    /// Reads a pedigree from a PED file.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. Individual IDs must be unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::pedigree::{Pedigree, Sex};
+    ///
+    /// let data = b"fam0\tchild\tfather\tmother\t1\t2
+    /// fam0\tfather\t0\t0\t1\t1
+    /// fam0\tmother\t0\t0\t2\t1
+    /// ";
+    ///
+    /// let pedigree = Pedigree::read(&data[..])?;
+    /// assert_eq!(pedigree.len(), 3);
+    ///
+    /// let child = pedigree.get("child").unwrap();
+    /// assert_eq!(child.father_id(), Some("father"));
+    /// assert_eq!(child.sex(), Sex::Male);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn read<R>(reader: R) -> io::Result<Self>
+    where
+        R: BufRead,
+    {
+        let mut pedigree = Self::default();
+        let mut ids = HashSet::new();
+
+        for (i, result) in reader.lines().enumerate() {
+            let line = result?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<_> = line.split_whitespace().collect();
+
+            let &[family_id, id, father_id, mother_id, sex, phenotype] = fields.as_slice() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid pedigree record at line {}: {line}", i + 1),
+                ));
+            };
+
+            let mut individual = Individual::new(family_id, id)
+                .with_sex(Sex::from(sex))
+                .with_phenotype(Phenotype::from(phenotype));
+
+            if father_id != MISSING {
+                individual = individual.with_father_id(father_id);
+            }
+
+            if mother_id != MISSING {
+                individual = individual.with_mother_id(mother_id);
+            }
+
+            if !ids.insert(id.to_string()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("duplicate individual ID at line {}: {id}", i + 1),
+                ));
+            }
+
+            pedigree.0.push(individual);
+        }
+
+        Ok(pedigree)
+    }
+
+    /// Returns the number of individuals.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are any individuals.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds an individual.
+    pub fn push(&mut self, individual: Individual) {
+        self.0.push(individual);
+    }
+
+    /// Returns the individual with the given ID.
+    pub fn get(&self, id: &str) -> Option<&Individual> {
+        self.0.iter().find(|individual| individual.id() == id)
+    }
+
+    /// Returns an iterator over the individuals.
+    pub fn iter(&self) -> impl Iterator<Item = &Individual> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the individuals with at least one parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::pedigree::{Individual, Pedigree};
+    ///
+    /// let mut pedigree = Pedigree::default();
+    /// pedigree.push(Individual::new("fam0", "child").with_mother_id("mother"));
+    /// pedigree.push(Individual::new("fam0", "mother"));
+    ///
+    /// let children: Vec<_> = pedigree.children().map(|individual| individual.id()).collect();
+    /// assert_eq!(children, ["child"]);
+    /// ```
+    pub fn children(&self) -> impl Iterator<Item = &Individual> {
+        self.0.iter().filter(|individual| {
+            individual.father_id().is_some() || individual.mother_id().is_some()
+        })
+    }
+}
+
+impl FromIterator<Individual> for Pedigree {
+    fn from_iter<T: IntoIterator<Item = Individual>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        let data = b"# pedigree
+fam0 child father 0 2 -9
+
+fam0\tfather\t0\t0\t1\t2
+fam0\tmother\t0\t0\t2\t3.5
+";
+
+        let pedigree = Pedigree::read(&data[..])?;
+
+        let expected: Pedigree = [
+            Individual::new("fam0", "child")
+                .with_father_id("father")
+                .with_sex(Sex::Female),
+            Individual::new("fam0", "father")
+                .with_sex(Sex::Male)
+                .with_phenotype(Phenotype::Affected),
+            Individual::new("fam0", "mother")
+                .with_sex(Sex::Female)
+                .with_phenotype(Phenotype::Other(String::from("3.5"))),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(pedigree, expected);
+
+        assert!(Pedigree::read(&b"fam0\tchild\t0\t0\t1\n"[..]).is_err());
+        assert!(
+            Pedigree::read(&b"fam0\tchild\t0\t0\t1\t1\nfam1\tchild\t0\t0\t1\t1\n"[..]).is_err()
+        );
+
+        Ok(())
+    }
+}
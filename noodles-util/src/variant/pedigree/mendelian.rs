@@ -0,0 +1,278 @@
+//! Mendelian inheritance checks.
+//!
+//! A diploid child genotype is consistent with Mendelian inheritance if one allele can be
+//! inherited from the father and the other from the mother. Parents without a called genotype are
+//! unknown and can pass on any allele. Sex chromosomes are not treated specially.
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::samples::keys::key,
+        record_buf::samples::sample::{value::Genotype, Value},
+        RecordBuf,
+    },
+};
+
+use super::Pedigree;
+
+/// Returns whether a child genotype is consistent with the genotypes of its parents.
+///
+/// This returns `None` if the child genotype is not diploid or has a missing allele, or if both
+/// parents are unknown. A parent is unknown if it has no genotype or a genotype with a missing
+/// allele.
+///
+/// # Examples
+///
+/// ```
+/// # use noodles_vcf::variant::record_buf::samples::sample::value::genotype::ParseError;
+/// use noodles_util::variant::pedigree::mendelian;
+///
+/// let child = "0/1".parse()?;
+/// let father = "0/0".parse()?;
+/// let mother = "1/1".parse()?;
+/// assert_eq!(mendelian::is_consistent(&child, Some(&father), Some(&mother)), Some(true));
+///
+/// let child = "1/1".parse()?;
+/// assert_eq!(mendelian::is_consistent(&child, Some(&father), Some(&mother)), Some(false));
+/// assert_eq!(mendelian::is_consistent(&child, None, Some(&mother)), Some(true));
+/// # Ok::<_, ParseError>(())
+/// ```
+pub fn is_consistent(
+    child: &Genotype,
+    father: Option<&Genotype>,
+    mother: Option<&Genotype>,
+) -> Option<bool> {
+    let [a, b] = child.as_ref() else {
+        return None;
+    };
+
+    let (a, b) = (a.position()?, b.position()?);
+
+    let father = father.and_then(called_positions);
+    let mother = mother.and_then(called_positions);
+
+    if father.is_none() && mother.is_none() {
+        return None;
+    }
+
+    let is_inheritable = |positions: &Option<Vec<usize>>, i: usize| {
+        positions
+            .as_ref()
+            .map(|positions| positions.contains(&i))
+            .unwrap_or(true)
+    };
+
+    Some(
+        (is_inheritable(&father, a) && is_inheritable(&mother, b))
+            || (is_inheritable(&father, b) && is_inheritable(&mother, a)),
+    )
+}
+
+fn called_positions(genotype: &Genotype) -> Option<Vec<usize>> {
+    let positions: Option<Vec<_>> = genotype
+        .as_ref()
+        .iter()
+        .map(|allele| allele.position())
+        .collect();
+
+    positions.filter(|positions| !positions.is_empty())
+}
+
+/// A child and its parents in the samples of a header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trio {
+    child: (String, usize),
+    father: Option<(String, usize)>,
+    mother: Option<(String, usize)>,
+}
+
+impl Trio {
+    /// Returns the sample name of the child.
+    pub fn child(&self) -> &str {
+        &self.child.0
+    }
+
+    /// Returns the sample name of the father, if it is in the header.
+    pub fn father(&self) -> Option<&str> {
+        self.father.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the sample name of the mother, if it is in the header.
+    pub fn mother(&self) -> Option<&str> {
+        self.mother.as_ref().map(|(name, _)| name.as_str())
+    }
+}
+
+/// A Mendelian inconsistency checker.
+///
+/// This checks the genotypes (`GT`) of the trios of a pedigree in the samples of a header.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Checker {
+    trios: Vec<Trio>,
+}
+
+impl Checker {
+    /// Creates a Mendelian inconsistency checker.
+    ///
+    /// Trios are children in the pedigree that are samples in the header and that have at least
+    /// one parent that is a sample in the header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::pedigree::{mendelian::Checker, Individual, Pedigree};
+    /// use noodles_vcf as vcf;
+    ///
+    /// let pedigree: Pedigree = [
+    ///     Individual::new("fam0", "child").with_father_id("father"),
+    ///     Individual::new("fam0", "father"),
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("father")
+    ///     .add_sample_name("child")
+    ///     .build();
+    ///
+    /// let checker = Checker::new(&pedigree, &header);
+    /// assert_eq!(checker.trios().len(), 1);
+    /// ```
+    pub fn new(pedigree: &Pedigree, header: &vcf::Header) -> Self {
+        let sample_names = header.sample_names();
+
+        let find = |name: Option<&str>| {
+            name.and_then(|name| {
+                sample_names
+                    .get_index_of(name)
+                    .map(|i| (String::from(name), i))
+            })
+        };
+
+        let trios = pedigree
+            .children()
+            .filter_map(|individual| {
+                let child = find(Some(individual.id()))?;
+                let father = find(individual.father_id());
+                let mother = find(individual.mother_id());
+
+                (father.is_some() || mother.is_some()).then_some(Trio {
+                    child,
+                    father,
+                    mother,
+                })
+            })
+            .collect();
+
+        Self { trios }
+    }
+
+    /// Returns the trios.
+    pub fn trios(&self) -> &[Trio] {
+        &self.trios
+    }
+
+    /// Returns the trios with genotypes that are inconsistent with Mendelian inheritance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// use noodles_util::variant::pedigree::{mendelian::Checker, Individual, Pedigree};
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     variant::{
+    ///         record::samples::keys::key,
+    ///         record_buf::{samples::sample::Value, Samples},
+    ///         RecordBuf,
+    ///     },
+    /// };
+    ///
+    /// let pedigree: Pedigree = [Individual::new("fam0", "child")
+    ///     .with_father_id("father")
+    ///     .with_mother_id("mother")]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("child")
+    ///     .add_sample_name("father")
+    ///     .add_sample_name("mother")
+    ///     .build();
+    ///
+    /// let checker = Checker::new(&pedigree, &header);
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_samples(Samples::new(
+    ///         [String::from(key::GENOTYPE)].into_iter().collect(),
+    ///         vec![
+    ///             vec![Some(Value::Genotype("1/1".parse()?))],
+    ///             vec![Some(Value::Genotype("0/1".parse()?))],
+    ///             vec![Some(Value::Genotype("0/0".parse()?))],
+    ///         ],
+    ///     ))
+    ///     .build();
+    ///
+    /// let trios = checker.inconsistent_trios(&record);
+    /// assert_eq!(trios.len(), 1);
+    /// assert_eq!(trios[0].child(), "child");
+    /// # Ok::<_, Box<dyn Error>>(())
+    /// ```
+    pub fn inconsistent_trios(&self, record: &RecordBuf) -> Vec<&Trio> {
+        let samples = record.samples();
+
+        let genotype = |i: usize| {
+            samples
+                .get_index(i)
+                .and_then(|sample| sample.get(key::GENOTYPE))
+                .flatten()
+                .and_then(|value| match value {
+                    Value::Genotype(genotype) => Some(genotype),
+                    _ => None,
+                })
+        };
+
+        self.trios
+            .iter()
+            .filter(|trio| {
+                let Some(child) = genotype(trio.child.1) else {
+                    return false;
+                };
+
+                let father = trio.father.as_ref().and_then(|(_, i)| genotype(*i));
+                let mother = trio.mother.as_ref().and_then(|(_, i)| genotype(*i));
+
+                is_consistent(child, father, mother) == Some(false)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_consistent() {
+        fn t(child: &str, father: Option<&str>, mother: Option<&str>) -> Option<bool> {
+            let child: Genotype = child.parse().unwrap();
+            let father: Option<Genotype> = father.map(|s| s.parse().unwrap());
+            let mother: Option<Genotype> = mother.map(|s| s.parse().unwrap());
+            is_consistent(&child, father.as_ref(), mother.as_ref())
+        }
+
+        assert_eq!(t("0/1", Some("0/1"), Some("0/1")), Some(true));
+        assert_eq!(t("1|0", Some("0/0"), Some("1/1")), Some(true));
+        assert_eq!(t("0/1", Some("1/1"), Some("0/0")), Some(true));
+        assert_eq!(t("0/0", Some("0/1"), Some("1/1")), Some(false));
+        assert_eq!(t("1/2", Some("0/1"), Some("0/1")), Some(false));
+        assert_eq!(t("0/2", Some("0/1"), Some("./.")), Some(true));
+        assert_eq!(t("2/2", Some("0/1"), None), Some(false));
+        assert_eq!(t("1/1", None, Some("0/0")), Some(false));
+
+        assert_eq!(t("./1", Some("0/0"), Some("0/0")), None);
+        assert_eq!(t("1", Some("0/0"), Some("0/0")), None);
+        assert_eq!(t("0/1", Some("./."), None), None);
+        assert_eq!(t("0/1", None, None), None);
+    }
+}
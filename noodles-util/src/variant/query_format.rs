@@ -0,0 +1,585 @@
+//! Variant record query format.
+
+mod parser;
+
+use std::io::{self, Write};
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{
+            info::field::{value::Array as InfoArray, Value as InfoValue},
+            samples::{
+                series::{
+                    value::{Array as SampleArray, Genotype},
+                    Value as SampleValue,
+                },
+                Sample,
+            },
+            AlternateBases as _, Filters as _, Ids as _, Info as _, ReferenceBases as _,
+            Samples as _,
+        },
+        Record,
+    },
+};
+
+pub use self::parser::ParseError;
+
+const MISSING: &str = ".";
+
+/// A variant record query format.
+///
+/// A query format describes how to render a variant record as text, similar to the format string
+/// of `bcftools query -f`. It is parsed once against a header and can then render any variant
+/// record, e.g., a VCF or BCF record, that uses that header.
+///
+/// A format is text with the following substitutions:
+///
+///   * record fields: `%CHROM`, `%POS`, `%POS0` (0-based), `%END`, `%END0` (0-based), `%ID`,
+///     `%REF`, `%ALT`, `%FIRST_ALT`, `%QUAL`, `%FILTER`, and `%INFO` (the entire INFO field);
+///   * INFO fields, e.g., `%INFO/DP`. A bare key (e.g., `%DP`) is an INFO field if it is not a
+///     record field;
+///   * sample loops: text in square brackets (`[` and `]`) is repeated for each sample. This can
+///     include `%SAMPLE` (the sample name), `%GT` (the genotype), `%TGT` (the genotype with allele
+///     bases), and FORMAT fields, e.g., `%FORMAT/AD` (or `%FMT/AD`). A bare key in a sample loop
+///     is a FORMAT field if the header defines one;
+///   * subscripts: `%ALT{0}`, `%INFO/AF{0}`, and `%FMT/AD{1}` select the value at the given
+///     0-based index of a list;
+///   * escape sequences: `\t`, `\n`, `\r`, `\\`, `\%`, `\[`, `\]`, `\{`, and `\}`.
+///
+/// INFO and FORMAT fields must be defined in the header.
+///
+/// Missing values, including fields absent from a record and subscripts out of range, are
+/// written as `.`. Missing values in lists are written in place, e.g., `1,.,3`. An INFO flag is
+/// written as `1` if set and `0` if not.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueryFormat(Vec<Element>);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Element {
+    Literal(String),
+    Field(Field),
+    Info {
+        key: String,
+        index: Option<usize>,
+        is_flag: bool,
+    },
+    Samples(Vec<Element>),
+    Sample(SampleField),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Field {
+    ReferenceSequenceName,
+    Position,
+    Position0,
+    End,
+    End0,
+    Ids,
+    ReferenceBases,
+    AlternateBases(Option<usize>),
+    QualityScore,
+    Filters,
+    Info,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum SampleField {
+    Name,
+    Genotype,
+    TranslatedGenotype,
+    Format(String, Option<usize>),
+}
+
+impl QueryFormat {
+    /// Parses a query format using the given header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::query_format::QueryFormat;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let header = vcf::Header::default();
+    /// let format = QueryFormat::parse(&header, r"%CHROM\t%POS\t%REF\t%ALT[\t%SAMPLE=%GT]\n")?;
+    /// # Ok::<_, noodles_util::variant::query_format::ParseError>(())
+    /// ```
+    pub fn parse(header: &vcf::Header, s: &str) -> Result<Self, ParseError> {
+        parser::parse(header, s).map(Self)
+    }
+
+    /// Writes a variant record using this format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_util::variant::query_format::QueryFormat;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Info, Map},
+    ///     variant::{
+    ///         record::info::field::key,
+    ///         record_buf::{info::field::Value, AlternateBases},
+    ///         RecordBuf,
+    ///     },
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_info(key::TOTAL_DEPTH, Map::<Info>::from(key::TOTAL_DEPTH))
+    ///     .build();
+    ///
+    /// let record = RecordBuf::builder()
+    ///     .set_reference_sequence_name("sq0")
+    ///     .set_variant_start(Position::try_from(8)?)
+    ///     .set_reference_bases("A")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+    ///     .set_info(
+    ///         [(String::from(key::TOTAL_DEPTH), Some(Value::from(13)))]
+    ///             .into_iter()
+    ///             .collect(),
+    ///     )
+    ///     .build();
+    ///
+    /// let format = QueryFormat::parse(&header, r"%CHROM:%POS %REF>%ALT %INFO/DP %QUAL\n")?;
+    ///
+    /// let mut buf = Vec::new();
+    /// format.write(&mut buf, &header, &record)?;
+    /// assert_eq!(buf, b"sq0:8 A>C 13 .\n");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write<W, R>(&self, writer: &mut W, header: &vcf::Header, record: &R) -> io::Result<()>
+    where
+        W: Write,
+        R: Record + ?Sized,
+    {
+        for element in &self.0 {
+            write_element(writer, header, record, element, None)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_element<W, R>(
+    writer: &mut W,
+    header: &vcf::Header,
+    record: &R,
+    element: &Element,
+    sample: Option<(usize, &dyn Sample)>,
+) -> io::Result<()>
+where
+    W: Write,
+    R: Record + ?Sized,
+{
+    match element {
+        Element::Literal(s) => writer.write_all(s.as_bytes()),
+        Element::Field(field) => write_field(writer, header, record, *field),
+        Element::Info {
+            key,
+            index,
+            is_flag,
+        } => write_info_field(writer, header, record, key, *index, *is_flag),
+        Element::Samples(elements) => {
+            let samples = record.samples()?;
+
+            for (i, sample) in samples.iter().enumerate() {
+                for element in elements {
+                    write_element(writer, header, record, element, Some((i, sample.as_ref())))?;
+                }
+            }
+
+            Ok(())
+        }
+        Element::Sample(field) => match sample {
+            Some((i, sample)) => write_sample_field(writer, header, record, i, sample, field),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "sample field outside sample loop",
+            )),
+        },
+    }
+}
+
+fn write_field<W, R>(
+    writer: &mut W,
+    header: &vcf::Header,
+    record: &R,
+    field: Field,
+) -> io::Result<()>
+where
+    W: Write,
+    R: Record + ?Sized,
+{
+    match field {
+        Field::ReferenceSequenceName => {
+            writer.write_all(record.reference_sequence_name(header)?.as_bytes())
+        }
+        Field::Position | Field::Position0 => match record.variant_start().transpose()? {
+            Some(position) => {
+                let n = usize::from(position);
+                let n = if field == Field::Position0 { n - 1 } else { n };
+                write!(writer, "{n}")
+            }
+            None => writer.write_all(MISSING.as_bytes()),
+        },
+        Field::End | Field::End0 => {
+            let n = usize::from(record.variant_end(header)?);
+            let n = if field == Field::End0 { n - 1 } else { n };
+            write!(writer, "{n}")
+        }
+        Field::Ids => {
+            let ids = record.ids();
+            write_list(writer, ";", ids.iter().map(|id| Ok(Some(id.to_string()))))
+        }
+        Field::ReferenceBases => {
+            let reference_bases: Vec<u8> =
+                record.reference_bases().iter().collect::<io::Result<_>>()?;
+            writer.write_all(&reference_bases)
+        }
+        Field::AlternateBases(index) => {
+            let alternate_bases = record.alternate_bases();
+            let alleles = alternate_bases
+                .iter()
+                .map(|result| result.map(|allele| Some(allele.to_string())));
+
+            match index {
+                Some(i) => write_value(writer, alleles.skip(i).take(1)),
+                None => write_list(writer, ",", alleles),
+            }
+        }
+        Field::QualityScore => match record.quality_score().transpose()? {
+            Some(n) => write!(writer, "{n}"),
+            None => writer.write_all(MISSING.as_bytes()),
+        },
+        Field::Filters => {
+            let filters = record.filters();
+            write_list(
+                writer,
+                ";",
+                filters
+                    .iter(header)
+                    .map(|result| result.map(|id| Some(id.to_string()))),
+            )
+        }
+        Field::Info => write_info(writer, header, record),
+    }
+}
+
+fn write_info<W, R>(writer: &mut W, header: &vcf::Header, record: &R) -> io::Result<()>
+where
+    W: Write,
+    R: Record + ?Sized,
+{
+    let info = record.info();
+
+    if info.is_empty() {
+        return writer.write_all(MISSING.as_bytes());
+    }
+
+    for (i, result) in info.iter(header).enumerate() {
+        let (key, value) = result?;
+
+        if i > 0 {
+            writer.write_all(b";")?;
+        }
+
+        writer.write_all(key.as_bytes())?;
+
+        match value {
+            Some(InfoValue::Flag) => {}
+            Some(value) => {
+                writer.write_all(b"=")?;
+                write_list(writer, ",", info_value_items(value)?.into_iter().map(Ok))?;
+            }
+            None => {
+                writer.write_all(b"=")?;
+                writer.write_all(MISSING.as_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_info_field<W, R>(
+    writer: &mut W,
+    header: &vcf::Header,
+    record: &R,
+    key: &str,
+    index: Option<usize>,
+    is_flag: bool,
+) -> io::Result<()>
+where
+    W: Write,
+    R: Record + ?Sized,
+{
+    let info = record.info();
+
+    let items = match info.get(header, key).transpose()? {
+        None if is_flag => vec![Some(String::from("0"))],
+        None | Some(None) => Vec::new(),
+        Some(Some(InfoValue::Flag)) => vec![Some(String::from("1"))],
+        Some(Some(value)) => info_value_items(value)?,
+    };
+
+    write_items(writer, items, index)
+}
+
+fn write_sample_field<W, R>(
+    writer: &mut W,
+    header: &vcf::Header,
+    record: &R,
+    sample_index: usize,
+    sample: &dyn Sample,
+    field: &SampleField,
+) -> io::Result<()>
+where
+    W: Write,
+    R: Record + ?Sized,
+{
+    use vcf::variant::record::samples::keys::key;
+
+    match field {
+        SampleField::Name => match header.sample_names().get_index(sample_index) {
+            Some(name) => writer.write_all(name.as_bytes()),
+            None => writer.write_all(MISSING.as_bytes()),
+        },
+        SampleField::Genotype | SampleField::TranslatedGenotype => {
+            match sample.get(header, key::GENOTYPE).transpose()?.flatten() {
+                Some(SampleValue::Genotype(genotype)) => {
+                    let alleles = if *field == SampleField::TranslatedGenotype {
+                        Some(alleles(record)?)
+                    } else {
+                        None
+                    };
+
+                    write_genotype(writer, genotype.as_ref(), alleles.as_deref())
+                }
+                Some(SampleValue::String(s)) => writer.write_all(s.as_bytes()),
+                _ => writer.write_all(MISSING.as_bytes()),
+            }
+        }
+        SampleField::Format(key, index) => {
+            let items = match sample.get(header, key).transpose()?.flatten() {
+                Some(value) => sample_value_items(value)?,
+                None => Vec::new(),
+            };
+
+            write_items(writer, items, *index)
+        }
+    }
+}
+
+fn alleles<R>(record: &R) -> io::Result<Vec<String>>
+where
+    R: Record + ?Sized,
+{
+    let reference_bases: Vec<u8> = record.reference_bases().iter().collect::<io::Result<_>>()?;
+    let mut alleles = vec![String::from_utf8_lossy(&reference_bases).into_owned()];
+
+    for result in record.alternate_bases().iter() {
+        alleles.push(result?.into());
+    }
+
+    Ok(alleles)
+}
+
+fn write_genotype<W>(
+    writer: &mut W,
+    genotype: &dyn Genotype,
+    alleles: Option<&[String]>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    use vcf::variant::record::samples::series::value::genotype::Phasing;
+
+    for (i, result) in genotype.iter().enumerate() {
+        let (position, phasing) = result?;
+
+        if i > 0 {
+            let separator = match phasing {
+                Phasing::Phased => b"|",
+                Phasing::Unphased => b"/",
+            };
+
+            writer.write_all(separator)?;
+        }
+
+        match (position, alleles) {
+            (Some(j), Some(alleles)) => match alleles.get(j) {
+                Some(allele) => writer.write_all(allele.as_bytes())?,
+                None => writer.write_all(MISSING.as_bytes())?,
+            },
+            (Some(j), None) => write!(writer, "{j}")?,
+            (None, _) => writer.write_all(MISSING.as_bytes())?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_items<W>(
+    writer: &mut W,
+    items: Vec<Option<String>>,
+    index: Option<usize>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    match index {
+        Some(i) => write_value(writer, items.into_iter().skip(i).take(1).map(Ok)),
+        None => write_list(writer, ",", items.into_iter().map(Ok)),
+    }
+}
+
+// Writes the first value or a missing value.
+fn write_value<W, I>(writer: &mut W, mut items: I) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = io::Result<Option<String>>>,
+{
+    match items.next().transpose()?.flatten() {
+        Some(s) => writer.write_all(s.as_bytes()),
+        None => writer.write_all(MISSING.as_bytes()),
+    }
+}
+
+// Writes the values separated by the given delimiter or a missing value if there are none.
+fn write_list<W, I>(writer: &mut W, delimiter: &str, items: I) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = io::Result<Option<String>>>,
+{
+    let mut is_empty = true;
+
+    for (i, result) in items.enumerate() {
+        if i > 0 {
+            writer.write_all(delimiter.as_bytes())?;
+        }
+
+        match result? {
+            Some(s) => writer.write_all(s.as_bytes())?,
+            None => writer.write_all(MISSING.as_bytes())?,
+        }
+
+        is_empty = false;
+    }
+
+    if is_empty {
+        writer.write_all(MISSING.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn info_value_items(value: InfoValue<'_>) -> io::Result<Vec<Option<String>>> {
+    match value {
+        InfoValue::Integer(n) => Ok(vec![Some(n.to_string())]),
+        InfoValue::Float(n) => Ok(vec![Some(n.to_string())]),
+        InfoValue::Flag => Ok(Vec::new()),
+        InfoValue::Character(c) => Ok(vec![Some(c.to_string())]),
+        InfoValue::String(s) => Ok(vec![Some(s.into())]),
+        InfoValue::Array(InfoArray::Integer(values)) => collect_items(values.iter()),
+        InfoValue::Array(InfoArray::Float(values)) => collect_items(values.iter()),
+        InfoValue::Array(InfoArray::Character(values)) => collect_items(values.iter()),
+        InfoValue::Array(InfoArray::String(values)) => collect_items(values.iter()),
+    }
+}
+
+fn sample_value_items(value: SampleValue<'_>) -> io::Result<Vec<Option<String>>> {
+    match value {
+        SampleValue::Integer(n) => Ok(vec![Some(n.to_string())]),
+        SampleValue::Float(n) => Ok(vec![Some(n.to_string())]),
+        SampleValue::Character(c) => Ok(vec![Some(c.to_string())]),
+        SampleValue::String(s) => Ok(vec![Some(s.into())]),
+        SampleValue::Genotype(genotype) => {
+            let mut buf = Vec::new();
+            write_genotype(&mut buf, genotype.as_ref(), None)?;
+            Ok(vec![Some(String::from_utf8_lossy(&buf).into_owned())])
+        }
+        SampleValue::Array(SampleArray::Integer(values)) => collect_items(values.iter()),
+        SampleValue::Array(SampleArray::Float(values)) => collect_items(values.iter()),
+        SampleValue::Array(SampleArray::Character(values)) => collect_items(values.iter()),
+        SampleValue::Array(SampleArray::String(values)) => collect_items(values.iter()),
+    }
+}
+
+fn collect_items<I, T>(values: I) -> io::Result<Vec<Option<String>>>
+where
+    I: Iterator<Item = io::Result<Option<T>>>,
+    T: ToString,
+{
+    values
+        .map(|result| result.map(|value| value.map(|v| v.to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW_HEADER: &str = "##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##INFO=<ID=AF,Number=A,Type=Float,Description=\"Allele frequency\">
+##INFO=<ID=DB,Number=0,Type=Flag,Description=\"dbSNP membership\">
+##FILTER=<ID=q10,Description=\"Quality below 10\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"Read depth for each allele\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\tsample1
+";
+
+    fn render(format: &str, record: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+        let format = QueryFormat::parse(&header, format)?;
+        let record = vcf::Record::try_from(record)?;
+
+        let mut buf = Vec::new();
+        format.write(&mut buf, &header, &record)?;
+
+        Ok(String::from_utf8(buf)?)
+    }
+
+    #[test]
+    fn test_write() -> Result<(), Box<dyn std::error::Error>> {
+        const RECORD: &[u8] =
+            b"sq0\t8\trs13;rs21\tA\tC,TG\t5.5\tq10\tDP=13;AF=0.25,.;DB\tGT:DP:AD\t0|1:8:3,5,.\t./.:.:.";
+
+        assert_eq!(
+            render(
+                r"%CHROM\t%POS\t%POS0\t%END\t%ID\t%REF\t%ALT\t%FIRST_ALT\t%QUAL\t%FILTER\n",
+                RECORD
+            )?,
+            "sq0\t8\t7\t8\trs13;rs21\tA\tC,TG\tC\t5.5\tq10\n"
+        );
+
+        assert_eq!(
+            render(r"%INFO/DP %DP %AF %AF{1} %AF{2} %DB %INFO", RECORD)?,
+            "13 13 0.25,. . . 1 DP=13;AF=0.25,.;DB"
+        );
+
+        assert_eq!(
+            render(r"%POS[\t%SAMPLE=%GT;%TGT;%DP;%AD;%AD{1}]\n", RECORD)?,
+            "8\tsample0=0|1;A|C;8;3,5,.;5\tsample1=./.;./.;.;.;.\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_with_missing_fields() -> Result<(), Box<dyn std::error::Error>> {
+        const RECORD: &[u8] = b"sq0\t8\t.\tA\t.\t.\t.\t.\tGT\t0\t1";
+
+        assert_eq!(
+            render(
+                r"%ID %ALT %ALT{0} %QUAL %FILTER %DP %DB %INFO[ %DP]",
+                RECORD
+            )?,
+            ". . . . . . 0 . . ."
+        );
+
+        Ok(())
+    }
+}
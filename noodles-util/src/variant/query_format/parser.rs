@@ -0,0 +1,359 @@
+use std::{error, fmt, iter::Peekable, str::CharIndices};
+
+use noodles_vcf::{self as vcf, header::record::value::map::info::Type};
+
+use super::{Element, Field, SampleField};
+
+/// An error returned when a variant record query format fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input ended unexpectedly.
+    UnexpectedEof,
+    /// An unexpected character was found at the given byte offset.
+    UnexpectedCharacter(usize, char),
+    /// An escape sequence is invalid.
+    InvalidEscapeSequence(char),
+    /// A subscript is invalid.
+    InvalidSubscript(String),
+    /// A field is not a known record field or a tag defined in the header.
+    UnknownField(String),
+    /// A sample field is used outside of a sample loop.
+    SampleFieldOutsideLoop(String),
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnexpectedCharacter(i, c) => {
+                write!(f, "unexpected character at offset {i}: {c:?}")
+            }
+            Self::InvalidEscapeSequence(c) => write!(f, "invalid escape sequence: \\{c}"),
+            Self::InvalidSubscript(s) => write!(f, "invalid subscript: {s}"),
+            Self::UnknownField(s) => write!(f, "unknown field: {s}"),
+            Self::SampleFieldOutsideLoop(s) => write!(f, "sample field outside loop: {s}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Scope {
+    Info,
+    Format,
+}
+
+pub(super) fn parse(header: &vcf::Header, s: &str) -> Result<Vec<Element>, ParseError> {
+    let mut chars = s.char_indices().peekable();
+
+    let mut elements = Vec::new();
+    let mut sample_elements: Option<Vec<Element>> = None;
+    let mut literal = String::new();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let (_, d) = chars.next().ok_or(ParseError::UnexpectedEof)?;
+                literal.push(unescape(d)?);
+            }
+            '%' => {
+                let dst = sample_elements.as_mut().unwrap_or(&mut elements);
+                flush_literal(dst, &mut literal);
+
+                let element = parse_field(header, &mut chars, sample_elements.is_some())?;
+                sample_elements
+                    .as_mut()
+                    .unwrap_or(&mut elements)
+                    .push(element);
+            }
+            '[' => {
+                if sample_elements.is_some() {
+                    return Err(ParseError::UnexpectedCharacter(i, c));
+                }
+
+                flush_literal(&mut elements, &mut literal);
+                sample_elements = Some(Vec::new());
+            }
+            ']' => {
+                let Some(mut dst) = sample_elements.take() else {
+                    return Err(ParseError::UnexpectedCharacter(i, c));
+                };
+
+                flush_literal(&mut dst, &mut literal);
+                elements.push(Element::Samples(dst));
+            }
+            _ => literal.push(c),
+        }
+    }
+
+    if sample_elements.is_some() {
+        return Err(ParseError::UnexpectedEof);
+    }
+
+    flush_literal(&mut elements, &mut literal);
+
+    Ok(elements)
+}
+
+fn unescape(c: char) -> Result<char, ParseError> {
+    match c {
+        't' => Ok('\t'),
+        'n' => Ok('\n'),
+        'r' => Ok('\r'),
+        '\\' | '%' | '[' | ']' | '{' | '}' => Ok(c),
+        _ => Err(ParseError::InvalidEscapeSequence(c)),
+    }
+}
+
+fn flush_literal(dst: &mut Vec<Element>, literal: &mut String) {
+    if !literal.is_empty() {
+        dst.push(Element::Literal(literal.split_off(0)));
+    }
+}
+
+fn parse_field(
+    header: &vcf::Header,
+    chars: &mut Peekable<CharIndices<'_>>,
+    is_in_sample_loop: bool,
+) -> Result<Element, ParseError> {
+    let mut name = read_name(chars)?;
+    let mut scope = None;
+
+    if chars.next_if(|&(_, c)| c == '/').is_some() {
+        scope = match name.as_str() {
+            "INFO" => Some(Scope::Info),
+            "FORMAT" | "FMT" => Some(Scope::Format),
+            _ => return Err(ParseError::UnknownField(name)),
+        };
+
+        name = read_name(chars)?;
+    }
+
+    let index = read_subscript(chars)?;
+
+    let element = match scope {
+        Some(Scope::Info) => parse_info_field(header, name.clone(), index)?,
+        Some(Scope::Format) => parse_format_field(header, name.clone(), index, is_in_sample_loop)?,
+        None => match name.as_str() {
+            "SAMPLE" | "GT" | "TGT" if !is_in_sample_loop => {
+                return Err(ParseError::SampleFieldOutsideLoop(name))
+            }
+            "SAMPLE" => Element::Sample(SampleField::Name),
+            "GT" => Element::Sample(SampleField::Genotype),
+            "TGT" => Element::Sample(SampleField::TranslatedGenotype),
+            _ if is_in_sample_loop && header.formats().contains_key(&name) => {
+                parse_format_field(header, name.clone(), index, is_in_sample_loop)?
+            }
+            "CHROM" => Element::Field(Field::ReferenceSequenceName),
+            "POS" => Element::Field(Field::Position),
+            "POS0" => Element::Field(Field::Position0),
+            "END" => Element::Field(Field::End),
+            "END0" => Element::Field(Field::End0),
+            "ID" => Element::Field(Field::Ids),
+            "REF" => Element::Field(Field::ReferenceBases),
+            "ALT" => Element::Field(Field::AlternateBases(index)),
+            "FIRST_ALT" => Element::Field(Field::AlternateBases(Some(0))),
+            "QUAL" => Element::Field(Field::QualityScore),
+            "FILTER" => Element::Field(Field::Filters),
+            "INFO" => Element::Field(Field::Info),
+            _ if header.infos().contains_key(&name) => {
+                parse_info_field(header, name.clone(), index)?
+            }
+            _ => return Err(ParseError::UnknownField(name)),
+        },
+    };
+
+    let is_indexable = matches!(
+        element,
+        Element::Field(Field::AlternateBases(_))
+            | Element::Info { .. }
+            | Element::Sample(SampleField::Format(..))
+    );
+
+    if index.is_some() && !is_indexable {
+        return Err(ParseError::InvalidSubscript(name));
+    }
+
+    Ok(element)
+}
+
+fn parse_info_field(
+    header: &vcf::Header,
+    key: String,
+    index: Option<usize>,
+) -> Result<Element, ParseError> {
+    match header.infos().get(&key) {
+        Some(info) => Ok(Element::Info {
+            is_flag: info.ty() == Type::Flag,
+            key,
+            index,
+        }),
+        None => Err(ParseError::UnknownField(format!("INFO/{key}"))),
+    }
+}
+
+fn parse_format_field(
+    header: &vcf::Header,
+    key: String,
+    index: Option<usize>,
+    is_in_sample_loop: bool,
+) -> Result<Element, ParseError> {
+    if !is_in_sample_loop {
+        Err(ParseError::SampleFieldOutsideLoop(format!("FORMAT/{key}")))
+    } else if header.formats().contains_key(&key) {
+        Ok(Element::Sample(SampleField::Format(key, index)))
+    } else {
+        Err(ParseError::UnknownField(format!("FORMAT/{key}")))
+    }
+}
+
+fn read_name(chars: &mut Peekable<CharIndices<'_>>) -> Result<String, ParseError> {
+    let mut name = String::new();
+
+    while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_') {
+        name.push(c);
+    }
+
+    if name.is_empty() {
+        match chars.peek() {
+            Some(&(i, c)) => Err(ParseError::UnexpectedCharacter(i, c)),
+            None => Err(ParseError::UnexpectedEof),
+        }
+    } else {
+        Ok(name)
+    }
+}
+
+fn read_subscript(chars: &mut Peekable<CharIndices<'_>>) -> Result<Option<usize>, ParseError> {
+    if chars.next_if(|&(_, c)| c == '{').is_none() {
+        return Ok(None);
+    }
+
+    let mut s = String::new();
+
+    loop {
+        match chars.next() {
+            Some((_, '}')) => break,
+            Some((_, c)) => s.push(c),
+            None => return Err(ParseError::UnexpectedEof),
+        }
+    }
+
+    s.parse()
+        .map(Some)
+        .map_err(|_| ParseError::InvalidSubscript(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW_HEADER: &str = "##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##INFO=<ID=DB,Number=0,Type=Flag,Description=\"dbSNP membership\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"Read depth for each allele\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0
+";
+
+    #[test]
+    fn test_parse() -> Result<(), vcf::header::ParseError> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+
+        assert_eq!(
+            parse(
+                &header,
+                r"%CHROM\t%ALT{1}\t%INFO/DP\t%DB[\t%SAMPLE=%GT:%DP:%FMT/AD{0}]\n"
+            ),
+            Ok(vec![
+                Element::Field(Field::ReferenceSequenceName),
+                Element::Literal(String::from("\t")),
+                Element::Field(Field::AlternateBases(Some(1))),
+                Element::Literal(String::from("\t")),
+                Element::Info {
+                    key: String::from("DP"),
+                    index: None,
+                    is_flag: false,
+                },
+                Element::Literal(String::from("\t")),
+                Element::Info {
+                    key: String::from("DB"),
+                    index: None,
+                    is_flag: true,
+                },
+                Element::Samples(vec![
+                    Element::Literal(String::from("\t")),
+                    Element::Sample(SampleField::Name),
+                    Element::Literal(String::from("=")),
+                    Element::Sample(SampleField::Genotype),
+                    Element::Literal(String::from(":")),
+                    Element::Sample(SampleField::Format(String::from("DP"), None)),
+                    Element::Literal(String::from(":")),
+                    Element::Sample(SampleField::Format(String::from("AD"), Some(0))),
+                ]),
+                Element::Literal(String::from("\n")),
+            ])
+        );
+
+        assert_eq!(
+            parse(&header, r"\%\[%POS\]"),
+            Ok(vec![
+                Element::Literal(String::from("%[")),
+                Element::Field(Field::Position),
+                Element::Literal(String::from("]")),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_with_invalid_input() -> Result<(), vcf::header::ParseError> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+
+        assert_eq!(parse(&header, "%"), Err(ParseError::UnexpectedEof));
+        assert_eq!(parse(&header, "[%GT"), Err(ParseError::UnexpectedEof));
+        assert_eq!(
+            parse(&header, "[[%GT]]"),
+            Err(ParseError::UnexpectedCharacter(1, '['))
+        );
+        assert_eq!(
+            parse(&header, "%POS]"),
+            Err(ParseError::UnexpectedCharacter(4, ']'))
+        );
+        assert_eq!(
+            parse(&header, r"\x"),
+            Err(ParseError::InvalidEscapeSequence('x'))
+        );
+        assert_eq!(
+            parse(&header, "%POS{0}"),
+            Err(ParseError::InvalidSubscript(String::from("POS")))
+        );
+        assert_eq!(
+            parse(&header, "%ALT{a}"),
+            Err(ParseError::InvalidSubscript(String::from("a")))
+        );
+        assert_eq!(
+            parse(&header, "%INFO/AF"),
+            Err(ParseError::UnknownField(String::from("INFO/AF")))
+        );
+        assert_eq!(
+            parse(&header, "%NS"),
+            Err(ParseError::UnknownField(String::from("NS")))
+        );
+        assert_eq!(
+            parse(&header, "%GT"),
+            Err(ParseError::SampleFieldOutsideLoop(String::from("GT")))
+        );
+        assert_eq!(
+            parse(&header, "%FMT/AD"),
+            Err(ParseError::SampleFieldOutsideLoop(String::from(
+                "FORMAT/AD"
+            )))
+        );
+
+        Ok(())
+    }
+}
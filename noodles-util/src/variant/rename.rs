@@ -0,0 +1,232 @@
+//! Variant contig renaming.
+
+use std::io;
+
+use noodles_vcf::{self as vcf, header::StringMaps, variant::RecordBuf};
+
+use crate::contig_map::ContigMap;
+
+/// A variant contig renamer.
+///
+/// This renames the contigs (`##contig`) of a header using a [`ContigMap`] and the reference
+/// sequence names of records, including the mate contigs of breakend alleles. Renamed contigs
+/// keep their BCF dictionary indices (`IDX`), and the string maps of the renamed header are
+/// rebuilt, so it can be used to write BCF records.
+#[derive(Debug)]
+pub struct Renamer {
+    contig_map: ContigMap,
+    header: vcf::Header,
+}
+
+impl Renamer {
+    /// Creates a variant contig renamer for the given source header.
+    ///
+    /// This returns an error if a contig is unmapped and unmapped names are errors, or if
+    /// multiple contigs are renamed to the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::{contig_map::ContigMap, variant::rename::Renamer};
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Contig, Map},
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_contig("chr1", Map::<Contig>::new())
+    ///     .build();
+    ///
+    /// let mut contig_map = ContigMap::new();
+    /// contig_map.insert("chr1", "1");
+    ///
+    /// let renamer = Renamer::new(&contig_map, &header)?;
+    /// assert!(renamer.header().contigs().contains_key("1"));
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn new(contig_map: &ContigMap, header: &vcf::Header) -> io::Result<Self> {
+        let mut renamed_header = header.clone();
+        let contigs = renamed_header.contigs_mut();
+
+        for (name, contig) in std::mem::take(contigs) {
+            let Some(dst) = contig_map.resolve(&name)? else {
+                continue;
+            };
+
+            if contigs.insert(dst.into(), contig).is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate contig name: {dst}"),
+                ));
+            }
+        }
+
+        *renamed_header.string_maps_mut() = StringMaps::try_from(&renamed_header)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(Self {
+            contig_map: contig_map.clone(),
+            header: renamed_header,
+        })
+    }
+
+    /// Returns the renamed header.
+    pub fn header(&self) -> &vcf::Header {
+        &self.header
+    }
+
+    /// Renames the reference sequence name of a record.
+    ///
+    /// This returns `false` if the record is on a dropped contig, i.e., the record should be
+    /// dropped. Breakend alleles with mates on dropped contigs are not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::{contig_map::ContigMap, variant::rename::Renamer};
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let mut contig_map = ContigMap::new();
+    /// contig_map.insert("chr1", "1");
+    ///
+    /// let renamer = Renamer::new(&contig_map, &vcf::Header::default())?;
+    ///
+    /// let mut record = RecordBuf::builder().set_reference_sequence_name("chr1").build();
+    /// assert!(renamer.rename_record(&mut record)?);
+    /// assert_eq!(record.reference_sequence_name(), "1");
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn rename_record(&self, record: &mut RecordBuf) -> io::Result<bool> {
+        match self.contig_map.resolve(record.reference_sequence_name())? {
+            Some(name) => {
+                if name != record.reference_sequence_name() {
+                    *record.reference_sequence_name_mut() = name.into();
+                }
+            }
+            None => return Ok(false),
+        }
+
+        for allele in record.alternate_bases_mut().as_mut() {
+            if let Some(renamed_allele) = rename_breakend(&self.contig_map, allele)? {
+                *allele = renamed_allele;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+// Renames the mate contig of a breakend allele, e.g., `G]chr17:198982]`.
+fn rename_breakend(contig_map: &ContigMap, allele: &str) -> io::Result<Option<String>> {
+    let Some(start) = allele.find(['[', ']']) else {
+        return Ok(None);
+    };
+
+    let Some(len) = allele[start + 1..].find(['[', ']']) else {
+        return Ok(None);
+    };
+
+    let end = start + 1 + len;
+    let mate = &allele[start + 1..end];
+
+    let Some((name, position)) = mate.rsplit_once(':') else {
+        return Ok(None);
+    };
+
+    let (prefix, bare_name, suffix) = match name.strip_prefix('<').and_then(|s| s.strip_suffix('>'))
+    {
+        Some(s) => ("<", s, ">"),
+        None => ("", name, ""),
+    };
+
+    match contig_map.resolve(bare_name)? {
+        Some(dst) if dst != bare_name => Ok(Some(format!(
+            "{}{prefix}{dst}{suffix}:{position}{}",
+            &allele[..=start],
+            &allele[end..]
+        ))),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::header::record::value::{map::Contig, Map};
+
+    use super::*;
+    use crate::contig_map::Unmapped;
+
+    #[test]
+    fn test_new() -> Result<(), Box<dyn std::error::Error>> {
+        let header: vcf::Header = "##fileformat=VCFv4.5
+##contig=<ID=chr1,IDX=0>
+##contig=<ID=chrUn,IDX=1>
+##contig=<ID=chr2,IDX=2>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+"
+        .parse()?;
+
+        let mut contig_map = ContigMap::new();
+        contig_map.insert("chr1", "1");
+        contig_map.insert("chr2", "2");
+
+        let renamer = Renamer::new(&contig_map.clone().with_unmapped(Unmapped::Drop), &header)?;
+        let renamed_header = renamer.header();
+
+        let names: Vec<_> = renamed_header.contigs().keys().collect();
+        assert_eq!(names, ["1", "2"]);
+
+        let contig_string_map = renamed_header.string_maps().contigs();
+        assert_eq!(contig_string_map.get_index_of("1"), Some(0));
+        assert_eq!(contig_string_map.get_index_of("2"), Some(2));
+        assert!(contig_string_map.get_index_of("chr1").is_none());
+
+        assert!(Renamer::new(&contig_map.clone().with_unmapped(Unmapped::Error), &header).is_err());
+
+        contig_map.insert("chrUn", "1");
+        assert!(Renamer::new(&contig_map, &header).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_record() -> io::Result<()> {
+        use vcf::variant::record_buf::AlternateBases;
+
+        let header = vcf::Header::builder()
+            .add_contig("chr1", Map::<Contig>::new())
+            .build();
+
+        let mut contig_map = ContigMap::new().with_unmapped(Unmapped::Drop);
+        contig_map.insert("chr1", "1");
+        contig_map.insert("chr2", "2");
+
+        let renamer = Renamer::new(&contig_map, &header)?;
+
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("chr1")
+            .set_alternate_bases(AlternateBases::from(vec![
+                String::from("G]chr2:198982]"),
+                String::from("[<chr2>:8[A"),
+                String::from("C[chrUn:13["),
+                String::from("<DEL>"),
+            ]))
+            .build();
+
+        assert!(renamer.rename_record(&mut record)?);
+        assert_eq!(record.reference_sequence_name(), "1");
+        assert_eq!(
+            record.alternate_bases().as_ref(),
+            ["G]2:198982]", "[<2>:8[A", "C[chrUn:13[", "<DEL>"]
+        );
+
+        let mut record = RecordBuf::builder()
+            .set_reference_sequence_name("chrUn")
+            .build();
+        assert!(!renamer.rename_record(&mut record)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Variant sample selection.
+//!
+//! A [`Selector`] renames, reorders, and subsets the samples of a header and its records in one
+//! pass. This is similar to `bcftools view --samples` and `bcftools reheader --samples`. INFO
+//! fields that summarize genotypes, e.g., `AC` and `AN`, are not updated; see
+//! [`super::fill_tags`].
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{io, mem};
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{record_buf::Samples, RecordBuf},
+};
+
+/// A variant sample selector.
+#[derive(Debug)]
+pub struct Selector {
+    header: vcf::Header,
+    src_sample_count: usize,
+    sample_indices: Vec<usize>,
+}
+
+impl Selector {
+    /// Returns a builder to create a variant sample selector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::samples::Selector;
+    /// let builder = Selector::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the header with the selected samples.
+    pub fn header(&self) -> &vcf::Header {
+        &self.header
+    }
+
+    /// Selects the samples of a record.
+    ///
+    /// Records without samples are not modified. This returns an error if the record does not
+    /// have the number of samples in the source header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::samples::Selector;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     variant::{
+    ///         record::samples::keys::key,
+    ///         record_buf::{samples::sample::Value, Samples},
+    ///         RecordBuf,
+    ///     },
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .add_sample_name("sample2")
+    ///     .build();
+    ///
+    /// let selector = Selector::builder()
+    ///     .set_sample_names(["sample2", "sample0"])
+    ///     .build(&header)?;
+    ///
+    /// let mut record = RecordBuf::builder()
+    ///     .set_samples(Samples::new(
+    ///         [String::from(key::READ_DEPTH)].into_iter().collect(),
+    ///         vec![
+    ///             vec![Some(Value::from(8))],
+    ///             vec![Some(Value::from(13))],
+    ///             vec![Some(Value::from(21))],
+    ///         ],
+    ///     ))
+    ///     .build();
+    ///
+    /// selector.select_record(&mut record)?;
+    ///
+    /// let depths: Vec<_> = record
+    ///     .samples()
+    ///     .values()
+    ///     .map(|sample| sample.get(key::READ_DEPTH).flatten().cloned())
+    ///     .collect();
+    ///
+    /// assert_eq!(depths, [Some(Value::from(21)), Some(Value::from(8))]);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn select_record(&self, record: &mut RecordBuf) -> io::Result<()> {
+        let samples = mem::take(record.samples_mut());
+        let (keys, mut values) = samples.into();
+
+        if values.is_empty() {
+            *record.samples_mut() = Samples::new(keys, values);
+            return Ok(());
+        }
+
+        if values.len() != self.src_sample_count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "sample count mismatch: expected {}, got {}",
+                    self.src_sample_count,
+                    values.len()
+                ),
+            ));
+        }
+
+        let selected_values = self
+            .sample_indices
+            .iter()
+            .map(|&i| mem::take(&mut values[i]))
+            .collect();
+
+        *record.samples_mut() = Samples::new(keys, selected_values);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vcf::variant::{record::samples::keys::key, record_buf::samples::sample::Value};
+
+    use super::*;
+
+    #[test]
+    fn test_select_record() -> io::Result<()> {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .build();
+
+        let selector = Selector::builder()
+            .set_sample_names(["sample1", "sample0"])
+            .add_rename("sample0", "sample1")
+            .add_rename("sample1", "sample0")
+            .build(&header)?;
+
+        let sample_names: Vec<_> = selector.header().sample_names().iter().collect();
+        assert_eq!(sample_names, ["sample0", "sample1"]);
+
+        let keys: vcf::variant::record_buf::samples::Keys =
+            [String::from(key::READ_DEPTH)].into_iter().collect();
+
+        let mut record = RecordBuf::builder()
+            .set_samples(Samples::new(
+                keys.clone(),
+                vec![vec![Some(Value::from(8))], vec![Some(Value::from(13))]],
+            ))
+            .build();
+
+        selector.select_record(&mut record)?;
+
+        assert_eq!(
+            record.samples(),
+            &Samples::new(
+                keys.clone(),
+                vec![vec![Some(Value::from(13))], vec![Some(Value::from(8))]],
+            )
+        );
+
+        let mut record = RecordBuf::default();
+        selector.select_record(&mut record)?;
+        assert!(record.samples().is_empty());
+
+        let mut record = RecordBuf::builder()
+            .set_samples(Samples::new(keys, vec![vec![Some(Value::from(8))]]))
+            .build();
+        assert!(selector.select_record(&mut record).is_err());
+
+        Ok(())
+    }
+}
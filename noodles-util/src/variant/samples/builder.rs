@@ -0,0 +1,181 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use noodles_vcf as vcf;
+
+use super::Selector;
+
+/// A variant sample selector builder.
+#[derive(Debug, Default)]
+pub struct Builder {
+    sample_names: Option<Vec<String>>,
+    renames: HashMap<String, String>,
+}
+
+impl Builder {
+    /// Sets the samples to keep, in output order.
+    ///
+    /// By default, all samples are kept in header order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::samples::Selector;
+    /// let builder = Selector::builder().set_sample_names(["sample1", "sample0"]);
+    /// ```
+    pub fn set_sample_names<I, S>(mut self, sample_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sample_names = Some(sample_names.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Adds a sample rename from a source sample name to a new sample name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::samples::Selector;
+    /// let builder = Selector::builder().add_rename("sample0", "NA12878");
+    /// ```
+    pub fn add_rename<S, D>(mut self, src: S, dst: D) -> Self
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.renames.insert(src.into(), dst.into());
+        self
+    }
+
+    /// Builds a variant sample selector for the given source header.
+    ///
+    /// This returns an error if a selected or renamed sample is not in the header, if a sample is
+    /// selected more than once, or if the output sample names are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::samples::Selector;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_sample_name("sample0")
+    ///     .add_sample_name("sample1")
+    ///     .build();
+    ///
+    /// let selector = Selector::builder()
+    ///     .set_sample_names(["sample1"])
+    ///     .add_rename("sample1", "NA12878")
+    ///     .build(&header)?;
+    ///
+    /// let sample_names: Vec<_> = selector.header().sample_names().iter().collect();
+    /// assert_eq!(sample_names, ["NA12878"]);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn build(self, header: &vcf::Header) -> io::Result<Selector> {
+        let src_sample_names = header.sample_names();
+
+        let index_of = |name: &str| {
+            src_sample_names.get_index_of(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("sample not in header: {name}"),
+                )
+            })
+        };
+
+        for src in self.renames.keys() {
+            index_of(src)?;
+        }
+
+        let sample_indices = match self.sample_names {
+            Some(sample_names) => {
+                let mut indices = Vec::with_capacity(sample_names.len());
+                let mut seen = HashSet::with_capacity(sample_names.len());
+
+                for name in &sample_names {
+                    let i = index_of(name)?;
+
+                    if !seen.insert(i) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("duplicate sample: {name}"),
+                        ));
+                    }
+
+                    indices.push(i);
+                }
+
+                indices
+            }
+            None => (0..src_sample_names.len()).collect(),
+        };
+
+        let mut dst_header = header.clone();
+        let dst_sample_names = dst_header.sample_names_mut();
+        dst_sample_names.clear();
+
+        for &i in &sample_indices {
+            // SAFETY: `i` is an index of a source sample name.
+            let src = src_sample_names.get_index(i).unwrap();
+            let dst = self.renames.get(src).unwrap_or(src);
+
+            if !dst_sample_names.insert(dst.clone()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate sample name: {dst}"),
+                ));
+            }
+        }
+
+        Ok(Selector {
+            header: dst_header,
+            src_sample_count: src_sample_names.len(),
+            sample_indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let header = vcf::Header::builder()
+            .add_sample_name("sample0")
+            .add_sample_name("sample1")
+            .build();
+
+        assert!(Builder::default()
+            .set_sample_names(["sample2"])
+            .build(&header)
+            .is_err());
+
+        assert!(Builder::default()
+            .set_sample_names(["sample0", "sample0"])
+            .build(&header)
+            .is_err());
+
+        assert!(Builder::default()
+            .add_rename("sample2", "NA12878")
+            .build(&header)
+            .is_err());
+
+        assert!(Builder::default()
+            .add_rename("sample0", "sample1")
+            .build(&header)
+            .is_err());
+
+        assert!(Builder::default()
+            .set_sample_names(["sample0"])
+            .add_rename("sample0", "sample1")
+            .build(&header)
+            .is_ok());
+    }
+}
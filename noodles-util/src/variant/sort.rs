@@ -0,0 +1,566 @@
+//! Variant record sorting.
+//!
+//! A [`Sorter`] orders records by reference sequence, position, and alleles. Reference sequences
+//! are ordered by the contigs of the header; reference sequences that are not in the header
+//! follow all contigs in the header and are ordered lexicographically. Records with the same
+//! reference sequence and position are ordered by their reference bases and then their alternate
+//! bases, and records that compare equal keep their input order.
+//!
+//! When the buffered records exceed a memory limit, they are sorted and written to a temporary
+//! file as uncompressed VCF. The sorted chunks are then merged. Since spilled records are read
+//! back using the header, all INFO and FORMAT fields must be defined in the header. The sorted
+//! records can be written to a bgzipped VCF or BCF, which can be indexed.
+
+mod builder;
+
+pub use self::builder::Builder;
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    mem,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{self, AtomicUsize},
+};
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        io::Write as _,
+        record::samples::keys::key,
+        record_buf::{info::field::Value as InfoValue, samples::sample::Value as SampleValue},
+        Record, RecordBuf,
+    },
+};
+
+static TEMP_FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A variant record sorter.
+#[derive(Debug)]
+pub struct Sorter {
+    header: vcf::Header,
+    contig_ids: HashMap<String, usize>,
+    memory_limit: usize,
+    temp_dir: PathBuf,
+    records: Vec<RecordBuf>,
+    memory_usage: usize,
+    chunks: Vec<TempFile>,
+}
+
+impl Sorter {
+    /// Creates a variant record sorter with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::sort::Sorter;
+    /// use noodles_vcf as vcf;
+    /// let sorter = Sorter::new(vcf::Header::default());
+    /// ```
+    pub fn new(header: vcf::Header) -> Self {
+        Builder::default().build(header)
+    }
+
+    /// Returns a builder to create a variant record sorter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::sort::Sorter;
+    /// let builder = Sorter::builder();
+    /// ```
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Returns the header.
+    pub fn header(&self) -> &vcf::Header {
+        &self.header
+    }
+
+    /// Adds a variant record.
+    ///
+    /// This returns an error if the record has an INFO or FORMAT field that is not defined in the
+    /// header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::sort::Sorter;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let mut sorter = Sorter::new(vcf::Header::default());
+    ///
+    /// let record = vcf::Record::try_from(&b"sq0\t1\t.\tA\t.\t.\t.\t."[..])?;
+    /// sorter.push(&record)?;
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn push<R>(&mut self, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let record = RecordBuf::try_from_variant_record(&self.header, record)?;
+        self.push_record_buf(record)
+    }
+
+    /// Adds a variant record buffer.
+    ///
+    /// This returns an error if the record has an INFO or FORMAT field that is not defined in the
+    /// header. Field values are expected to match their header definitions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::sort::Sorter;
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let mut sorter = Sorter::new(vcf::Header::default());
+    /// sorter.push_record_buf(RecordBuf::default())?;
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn push_record_buf(&mut self, record: RecordBuf) -> io::Result<()> {
+        validate_keys(&self.header, &record)?;
+
+        self.memory_usage += estimate_size(&record);
+        self.records.push(record);
+
+        if self.memory_usage > self.memory_limit {
+            self.spill()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sorts the added records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_core::Position;
+    /// use noodles_util::variant::sort::Sorter;
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::Contig, Map},
+    ///     variant::RecordBuf,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_contig("sq1", Map::<Contig>::new())
+    ///     .add_contig("sq0", Map::<Contig>::new())
+    ///     .build();
+    ///
+    /// let mut sorter = Sorter::new(header);
+    ///
+    /// for (name, position) in [("sq0", 8), ("sq1", 13), ("sq0", 5)] {
+    ///     let record = RecordBuf::builder()
+    ///         .set_reference_sequence_name(name)
+    ///         .set_variant_start(Position::try_from(position).unwrap())
+    ///         .build();
+    ///
+    ///     sorter.push_record_buf(record)?;
+    /// }
+    ///
+    /// let records: Vec<_> = sorter
+    ///     .finish()?
+    ///     .map(|result| {
+    ///         result.map(|record| {
+    ///             let name = record.reference_sequence_name().to_string();
+    ///             let position = record.variant_start().map(usize::from);
+    ///             (name, position)
+    ///         })
+    ///     })
+    ///     .collect::<io::Result<_>>()?;
+    ///
+    /// assert_eq!(
+    ///     records,
+    ///     [
+    ///         (String::from("sq1"), Some(13)),
+    ///         (String::from("sq0"), Some(5)),
+    ///         (String::from("sq0"), Some(8)),
+    ///     ]
+    /// );
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn finish(mut self) -> io::Result<Records> {
+        self.sort_records();
+
+        let mut sources = Vec::with_capacity(self.chunks.len() + 1);
+
+        for temp_file in self.chunks {
+            let reader = File::open(&temp_file.path)
+                .map(BufReader::new)
+                .map(vcf::io::Reader::new)?;
+
+            sources.push(Source::TempFile {
+                reader,
+                _temp_file: temp_file,
+            });
+        }
+
+        sources.push(Source::Memory(self.records.into_iter()));
+
+        let mut records = Records {
+            header: self.header,
+            contig_ids: self.contig_ids,
+            sources,
+            heap: BinaryHeap::new(),
+        };
+
+        for i in 0..records.sources.len() {
+            records.fill(i)?;
+        }
+
+        Ok(records)
+    }
+
+    fn sort_records(&mut self) {
+        let contig_ids = &self.contig_ids;
+
+        self.records.sort_by(|a, b| {
+            let a_id = contig_id(contig_ids, a);
+            let b_id = contig_id(contig_ids, b);
+            compare(a_id, a, b_id, b)
+        });
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        self.sort_records();
+
+        let (temp_file, file) = TempFile::create(&self.temp_dir)?;
+        let mut writer = vcf::io::Writer::new(BufWriter::new(file));
+
+        for record in self.records.drain(..) {
+            writer.write_variant_record(&self.header, &record)?;
+        }
+
+        writer.get_mut().flush()?;
+
+        self.chunks.push(temp_file);
+        self.memory_usage = 0;
+
+        Ok(())
+    }
+}
+
+/// An iterator over sorted variant records.
+///
+/// This is created by calling [`Sorter::finish`].
+pub struct Records {
+    header: vcf::Header,
+    contig_ids: HashMap<String, usize>,
+    sources: Vec<Source>,
+    heap: BinaryHeap<Entry>,
+}
+
+impl Records {
+    fn fill(&mut self, i: usize) -> io::Result<()> {
+        let record = match &mut self.sources[i] {
+            Source::Memory(records) => records.next(),
+            Source::TempFile { reader, .. } => {
+                let mut record = RecordBuf::default();
+
+                match reader.read_record_buf(&self.header, &mut record)? {
+                    0 => None,
+                    _ => Some(record),
+                }
+            }
+        };
+
+        if let Some(record) = record {
+            self.heap.push(Entry {
+                contig_id: contig_id(&self.contig_ids, &record),
+                source: i,
+                record,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for Records {
+    type Item = io::Result<RecordBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.heap.pop()?;
+
+        if let Err(e) = self.fill(entry.source) {
+            return Some(Err(e));
+        }
+
+        Some(Ok(entry.record))
+    }
+}
+
+enum Source {
+    Memory(std::vec::IntoIter<RecordBuf>),
+    TempFile {
+        reader: vcf::io::Reader<BufReader<File>>,
+        // The temporary file is removed when the source is dropped.
+        _temp_file: TempFile,
+    },
+}
+
+struct Entry {
+    contig_id: usize,
+    source: usize,
+    record: RecordBuf,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` is a max-heap.
+        compare(self.contig_id, &self.record, other.contig_id, &other.record)
+            .then_with(|| self.source.cmp(&other.source))
+            .reverse()
+    }
+}
+
+#[derive(Debug)]
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(dir: &Path) -> io::Result<(Self, File)> {
+        loop {
+            let n = TEMP_FILE_COUNT.fetch_add(1, atomic::Ordering::Relaxed);
+            let path = dir.join(format!("noodles-util-sort-{}-{n}.vcf", process::id()));
+
+            match File::options().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((Self { path }, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Spilled records are parsed using the header, so undefined fields would be read back with
+// different types than the records held in memory.
+fn validate_keys(header: &vcf::Header, record: &RecordBuf) -> io::Result<()> {
+    for key in record.info().as_ref().keys() {
+        if !header.infos().contains_key(key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("undefined INFO field: {key}"),
+            ));
+        }
+    }
+
+    for key in record.samples().keys().as_ref() {
+        if key != key::GENOTYPE && !header.formats().contains_key(key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("undefined FORMAT field: {key}"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Reference sequences that are not in the header are ordered after all contigs in the header.
+fn contig_id(contig_ids: &HashMap<String, usize>, record: &RecordBuf) -> usize {
+    contig_ids
+        .get(record.reference_sequence_name())
+        .copied()
+        .unwrap_or(contig_ids.len())
+}
+
+fn compare(a_contig_id: usize, a: &RecordBuf, b_contig_id: usize, b: &RecordBuf) -> Ordering {
+    a_contig_id
+        .cmp(&b_contig_id)
+        .then_with(|| a.reference_sequence_name().cmp(b.reference_sequence_name()))
+        .then_with(|| a.variant_start().cmp(&b.variant_start()))
+        .then_with(|| a.reference_bases().cmp(b.reference_bases()))
+        .then_with(|| {
+            a.alternate_bases()
+                .as_ref()
+                .cmp(b.alternate_bases().as_ref())
+        })
+}
+
+fn estimate_size(record: &RecordBuf) -> usize {
+    let strings_len = record.reference_sequence_name().len()
+        + record.ids().as_ref().iter().map(|s| s.len()).sum::<usize>()
+        + record.reference_bases().len()
+        + record
+            .alternate_bases()
+            .as_ref()
+            .iter()
+            .map(|s| s.len())
+            .sum::<usize>()
+        + record
+            .filters()
+            .as_ref()
+            .iter()
+            .map(|s| s.len())
+            .sum::<usize>();
+
+    let info_len = record.info().as_ref().len() * mem::size_of::<(String, Option<InfoValue>)>();
+
+    let samples_len = record
+        .samples()
+        .values()
+        .map(|sample| sample.values().len())
+        .sum::<usize>()
+        * mem::size_of::<Option<SampleValue>>();
+
+    mem::size_of::<RecordBuf>() + strings_len + info_len + samples_len
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use noodles_core::Position;
+    use vcf::{
+        header::record::value::{map::Contig, Map},
+        variant::record_buf::AlternateBases,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_finish() -> io::Result<()> {
+        let header = vcf::Header::builder()
+            .add_contig("sq1", Map::<Contig>::new())
+            .add_contig("sq0", Map::<Contig>::new())
+            .build();
+
+        let records: Vec<_> = [
+            ("sq0", 8, "A", "G"),
+            ("sqB", 1, "A", "T"),
+            ("sq1", 21, "C", "T"),
+            ("sqA", 5, "G", "A"),
+            ("sq0", 8, "A", "C"),
+            ("sq1", 13, "T", "A"),
+            ("sq0", 8, "AC", "A"),
+        ]
+        .into_iter()
+        .map(|(name, position, reference_bases, alternate_base)| {
+            RecordBuf::builder()
+                .set_reference_sequence_name(name)
+                .set_variant_start(Position::new(position).unwrap())
+                .set_reference_bases(reference_bases)
+                .set_alternate_bases(AlternateBases::from(vec![String::from(alternate_base)]))
+                .build()
+        })
+        .collect();
+
+        let expected: Vec<_> = [5, 2, 4, 0, 6, 3, 1]
+            .into_iter()
+            .map(|i| records[i].clone())
+            .collect();
+
+        for memory_limit in [usize::MAX, 0, mem::size_of::<RecordBuf>() * 2] {
+            let temp_dir = env::temp_dir();
+
+            let mut sorter = Sorter::builder()
+                .set_memory_limit(memory_limit)
+                .set_temp_dir(&temp_dir)
+                .build(header.clone());
+
+            for record in records.iter().cloned() {
+                sorter.push_record_buf(record)?;
+            }
+
+            let actual: Vec<_> = sorter.finish()?.collect::<io::Result<_>>()?;
+            assert_eq!(actual, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_record_buf_with_undefined_fields() {
+        use vcf::variant::record_buf::{info::field::Value, samples::sample, Info, Samples};
+
+        let mut sorter = Sorter::new(vcf::Header::default());
+
+        let record = RecordBuf::builder()
+            .set_info(
+                [(String::from("NS"), Some(Value::from(2)))]
+                    .into_iter()
+                    .collect::<Info>(),
+            )
+            .build();
+
+        assert!(matches!(
+            sorter.push_record_buf(record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        let record = RecordBuf::builder()
+            .set_samples(Samples::new(
+                [String::from(key::READ_DEPTH)].into_iter().collect(),
+                vec![vec![Some(sample::Value::from(8))]],
+            ))
+            .build();
+
+        assert!(matches!(
+            sorter.push_record_buf(record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        let record = RecordBuf::builder()
+            .set_samples(Samples::new(
+                [String::from(key::GENOTYPE)].into_iter().collect(),
+                vec![vec![Some(sample::Value::from("0/1"))]],
+            ))
+            .build();
+
+        assert!(sorter.push_record_buf(record).is_ok());
+    }
+
+    #[test]
+    fn test_finish_removes_temp_files() -> io::Result<()> {
+        let mut sorter = Sorter::builder()
+            .set_memory_limit(0)
+            .build(vcf::Header::default());
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_variant_start(Position::new(8).unwrap())
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+            .build();
+
+        sorter.push_record_buf(record)?;
+        let path = sorter.chunks[0].path.clone();
+        assert!(path.exists());
+
+        let records = sorter.finish()?;
+        drop(records);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+}
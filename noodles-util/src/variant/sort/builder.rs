@@ -0,0 +1,90 @@
+use std::{env, path::PathBuf};
+
+use noodles_vcf as vcf;
+
+use super::Sorter;
+
+// 768 MiB
+const DEFAULT_MEMORY_LIMIT: usize = 768 << 20;
+
+/// A variant record sorter builder.
+#[derive(Debug)]
+pub struct Builder {
+    memory_limit: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+impl Builder {
+    /// Sets the approximate maximum number of bytes of records to hold in memory.
+    ///
+    /// When the buffered records exceed this limit, they are sorted and written to a temporary
+    /// file. By default, this is 768 MiB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::sort::Sorter;
+    /// let builder = Sorter::builder().set_memory_limit(64 << 20);
+    /// ```
+    pub fn set_memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Sets the directory of temporary files.
+    ///
+    /// By default, this is the system temporary directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::sort::Sorter;
+    /// let builder = Sorter::builder().set_temp_dir("/tmp");
+    /// ```
+    pub fn set_temp_dir<P>(mut self, temp_dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.temp_dir = Some(temp_dir.into());
+        self
+    }
+
+    /// Builds a variant record sorter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::sort::Sorter;
+    /// use noodles_vcf as vcf;
+    ///
+    /// let header = vcf::Header::default();
+    /// let sorter = Sorter::builder().build(header);
+    /// ```
+    pub fn build(self, header: vcf::Header) -> Sorter {
+        let contig_ids = header
+            .contigs()
+            .keys()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+
+        Sorter {
+            header,
+            contig_ids,
+            memory_limit: self.memory_limit,
+            temp_dir: self.temp_dir.unwrap_or_else(env::temp_dir),
+            records: Vec::new(),
+            memory_usage: 0,
+            chunks: Vec::new(),
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            temp_dir: None,
+        }
+    }
+}
@@ -0,0 +1,489 @@
+//! Variant record statistics.
+//!
+//! A [`Collector`] accumulates statistics over any stream of variant records, similar to
+//! `bcftools stats`. The resulting [`Stats`] can be merged, e.g., when collecting over regions in
+//! parallel, and written in the text format of `bcftools stats`.
+
+mod collector;
+
+use std::{collections::BTreeMap, fmt};
+
+pub use self::collector::Collector;
+
+/// Summary counts of variant records.
+///
+/// A record is counted once for each variant type of its alternate alleles.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    /// The number of records.
+    pub records: u64,
+    /// The number of records without alternate alleles, excluding unspecified alleles (`<*>` and
+    /// `<NON_REF>`).
+    pub no_alts: u64,
+    /// The number of records with a single nucleotide variant.
+    pub snvs: u64,
+    /// The number of records with a multiple nucleotide variant.
+    pub mnps: u64,
+    /// The number of records with an insertion or deletion.
+    pub indels: u64,
+    /// The number of records with a structural variant, i.e., a symbolic or breakend allele.
+    pub structural_variants: u64,
+    /// The number of records with multiple alternate alleles.
+    pub multiallelic_sites: u64,
+    /// The number of records with multiple single nucleotide variants.
+    pub multiallelic_snv_sites: u64,
+    /// The number of transitions.
+    pub transitions: u64,
+    /// The number of transversions.
+    pub transversions: u64,
+    /// The number of transitions in the first alternate allele.
+    pub first_alternate_transitions: u64,
+    /// The number of transversions in the first alternate allele.
+    pub first_alternate_transversions: u64,
+}
+
+impl Summary {
+    /// Returns the transition/transversion ratio.
+    pub fn transition_transversion_ratio(&self) -> f64 {
+        ratio(self.transitions, self.transversions)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.records += other.records;
+        self.no_alts += other.no_alts;
+        self.snvs += other.snvs;
+        self.mnps += other.mnps;
+        self.indels += other.indels;
+        self.structural_variants += other.structural_variants;
+        self.multiallelic_sites += other.multiallelic_sites;
+        self.multiallelic_snv_sites += other.multiallelic_snv_sites;
+        self.transitions += other.transitions;
+        self.transversions += other.transversions;
+        self.first_alternate_transitions += other.first_alternate_transitions;
+        self.first_alternate_transversions += other.first_alternate_transversions;
+    }
+}
+
+/// Alternate allele counts by variant type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VariantTypeCounts {
+    /// The number of single nucleotide variants.
+    pub snvs: u64,
+    /// The number of transitions.
+    pub transitions: u64,
+    /// The number of transversions.
+    pub transversions: u64,
+    /// The number of insertions and deletions.
+    pub indels: u64,
+}
+
+impl VariantTypeCounts {
+    fn merge(&mut self, other: &Self) {
+        self.snvs += other.snvs;
+        self.transitions += other.transitions;
+        self.transversions += other.transversions;
+        self.indels += other.indels;
+    }
+}
+
+/// Insertion and deletion counts of a length.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IndelCounts {
+    /// The number of alternate alleles.
+    pub sites: u64,
+    /// The number of sample genotypes with the alternate allele.
+    pub genotypes: u64,
+}
+
+/// Read depth counts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DepthCounts {
+    /// The number of sample genotypes with the depth (`FORMAT/DP`).
+    pub genotypes: u64,
+    /// The number of records with the depth (`INFO/DP`).
+    pub sites: u64,
+}
+
+/// Genotype counts of a sample.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SampleCounts {
+    /// The number of homozygous reference genotypes.
+    pub reference_homozygous: u64,
+    /// The number of homozygous alternate genotypes.
+    pub alternate_homozygous: u64,
+    /// The number of heterozygous genotypes.
+    pub heterozygous: u64,
+    /// The number of haploid reference genotypes.
+    pub haploid_reference: u64,
+    /// The number of haploid alternate genotypes.
+    pub haploid_alternate: u64,
+    /// The number of genotypes with a missing allele.
+    pub missing: u64,
+    /// The number of transitions in the genotypes.
+    pub transitions: u64,
+    /// The number of transversions in the genotypes.
+    pub transversions: u64,
+    /// The number of insertions and deletions in the genotypes.
+    pub indels: u64,
+    /// The number of genotypes with an alternate allele that occurs once in the record.
+    pub singletons: u64,
+    /// The sum of the read depths (`FORMAT/DP`).
+    pub depth_sum: u64,
+    /// The number of genotypes with a read depth.
+    pub depth_count: u64,
+}
+
+impl SampleCounts {
+    /// Returns the mean read depth.
+    pub fn mean_depth(&self) -> f64 {
+        ratio(self.depth_sum, self.depth_count)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.reference_homozygous += other.reference_homozygous;
+        self.alternate_homozygous += other.alternate_homozygous;
+        self.heterozygous += other.heterozygous;
+        self.haploid_reference += other.haploid_reference;
+        self.haploid_alternate += other.haploid_alternate;
+        self.missing += other.missing;
+        self.transitions += other.transitions;
+        self.transversions += other.transversions;
+        self.indels += other.indels;
+        self.singletons += other.singletons;
+        self.depth_sum += other.depth_sum;
+        self.depth_count += other.depth_count;
+    }
+}
+
+/// Variant record statistics.
+///
+/// The [`fmt::Display`] implementation writes the statistics in the text format of
+/// `bcftools stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    sample_names: Vec<String>,
+    summary: Summary,
+    singletons: VariantTypeCounts,
+    allele_frequencies: BTreeMap<u8, VariantTypeCounts>,
+    qualities: BTreeMap<u32, VariantTypeCounts>,
+    indel_lengths: BTreeMap<i64, IndelCounts>,
+    depths: BTreeMap<u32, DepthCounts>,
+    sample_counts: Vec<SampleCounts>,
+}
+
+impl Stats {
+    /// Returns the sample names.
+    pub fn sample_names(&self) -> &[String] {
+        &self.sample_names
+    }
+
+    /// Returns the summary counts.
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    /// Returns the counts of alternate alleles that occur once.
+    pub fn singletons(&self) -> &VariantTypeCounts {
+        &self.singletons
+    }
+
+    /// Returns the allele frequency spectrum.
+    ///
+    /// This is keyed by alternate allele frequency as a whole percentage, rounded down.
+    pub fn allele_frequencies(&self) -> &BTreeMap<u8, VariantTypeCounts> {
+        &self.allele_frequencies
+    }
+
+    /// Returns the quality histogram.
+    ///
+    /// This is keyed by quality score, rounded down. Only the first alternate allele of each
+    /// record is counted, and records with a missing quality score are not counted.
+    pub fn qualities(&self) -> &BTreeMap<u32, VariantTypeCounts> {
+        &self.qualities
+    }
+
+    /// Returns the insertion and deletion length distribution.
+    ///
+    /// Deletions have negative lengths.
+    pub fn indel_lengths(&self) -> &BTreeMap<i64, IndelCounts> {
+        &self.indel_lengths
+    }
+
+    /// Returns the read depth histogram.
+    pub fn depths(&self) -> &BTreeMap<u32, DepthCounts> {
+        &self.depths
+    }
+
+    /// Returns the genotype counts per sample, in the order of the sample names.
+    pub fn sample_counts(&self) -> &[SampleCounts] {
+        &self.sample_counts
+    }
+
+    /// Merges another set of statistics into this one.
+    ///
+    /// The result is as if the records of both were collected together. Both sets of statistics
+    /// are expected to be collected over the same samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::stats::Stats;
+    /// let mut stats = Stats::default();
+    /// stats.merge(&Stats::default());
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        if self.sample_names.is_empty() {
+            self.sample_names.clone_from(&other.sample_names);
+        }
+
+        self.summary.merge(&other.summary);
+        self.singletons.merge(&other.singletons);
+
+        for (&percentage, counts) in &other.allele_frequencies {
+            self.allele_frequencies
+                .entry(percentage)
+                .or_default()
+                .merge(counts);
+        }
+
+        for (&quality, counts) in &other.qualities {
+            self.qualities.entry(quality).or_default().merge(counts);
+        }
+
+        for (&len, counts) in &other.indel_lengths {
+            let entry = self.indel_lengths.entry(len).or_default();
+            entry.sites += counts.sites;
+            entry.genotypes += counts.genotypes;
+        }
+
+        for (&depth, counts) in &other.depths {
+            let entry = self.depths.entry(depth).or_default();
+            entry.genotypes += counts.genotypes;
+            entry.sites += counts.sites;
+        }
+
+        if self.sample_counts.len() < other.sample_counts.len() {
+            self.sample_counts
+                .resize(other.sample_counts.len(), SampleCounts::default());
+        }
+
+        for (a, b) in self.sample_counts.iter_mut().zip(&other.sample_counts) {
+            a.merge(b);
+        }
+    }
+}
+
+fn ratio(a: u64, b: u64) -> f64 {
+    if b == 0 {
+        0.0
+    } else {
+        a as f64 / b as f64
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = &self.summary;
+
+        writeln!(f, "# SN, Summary numbers:")?;
+        writeln!(f, "# SN\t[2]id\t[3]key\t[4]value")?;
+        writeln!(f, "SN\t0\tnumber of samples:\t{}", self.sample_names.len())?;
+        writeln!(f, "SN\t0\tnumber of records:\t{}", s.records)?;
+        writeln!(f, "SN\t0\tnumber of no-ALTs:\t{}", s.no_alts)?;
+        writeln!(f, "SN\t0\tnumber of SNPs:\t{}", s.snvs)?;
+        writeln!(f, "SN\t0\tnumber of MNPs:\t{}", s.mnps)?;
+        writeln!(f, "SN\t0\tnumber of indels:\t{}", s.indels)?;
+        writeln!(f, "SN\t0\tnumber of others:\t{}", s.structural_variants)?;
+        writeln!(
+            f,
+            "SN\t0\tnumber of multiallelic sites:\t{}",
+            s.multiallelic_sites
+        )?;
+        writeln!(
+            f,
+            "SN\t0\tnumber of multiallelic SNP sites:\t{}",
+            s.multiallelic_snv_sites
+        )?;
+
+        writeln!(f, "# TSTV, transitions/transversions:")?;
+        writeln!(
+            f,
+            "# TSTV\t[2]id\t[3]ts\t[4]tv\t[5]ts/tv\t[6]ts (1st ALT)\t[7]tv (1st ALT)\t[8]ts/tv (1st ALT)"
+        )?;
+        writeln!(
+            f,
+            "TSTV\t0\t{}\t{}\t{:.2}\t{}\t{}\t{:.2}",
+            s.transitions,
+            s.transversions,
+            s.transition_transversion_ratio(),
+            s.first_alternate_transitions,
+            s.first_alternate_transversions,
+            ratio(
+                s.first_alternate_transitions,
+                s.first_alternate_transversions
+            )
+        )?;
+
+        writeln!(f, "# SiS, Singleton stats:")?;
+        writeln!(f, "# SiS\t[2]id\t[3]allele count\t[4]number of SNPs\t[5]number of transitions\t[6]number of transversions\t[7]number of indels")?;
+        write_variant_type_counts(f, "SiS\t0\t1", &self.singletons)?;
+
+        writeln!(f, "# AF, Stats by non-reference allele frequency:")?;
+        writeln!(f, "# AF\t[2]id\t[3]allele frequency\t[4]number of SNPs\t[5]number of transitions\t[6]number of transversions\t[7]number of indels")?;
+        for (&percentage, counts) in &self.allele_frequencies {
+            let prefix = format!("AF\t0\t{:.2}", f64::from(percentage) / 100.0);
+            write_variant_type_counts(f, &prefix, counts)?;
+        }
+
+        writeln!(f, "# QUAL, Stats by quality:")?;
+        writeln!(f, "# QUAL\t[2]id\t[3]Quality\t[4]number of SNPs\t[5]number of transitions (1st ALT)\t[6]number of transversions (1st ALT)\t[7]number of indels")?;
+        for (quality, counts) in &self.qualities {
+            write_variant_type_counts(f, &format!("QUAL\t0\t{quality}"), counts)?;
+        }
+
+        writeln!(f, "# IDD, InDel distribution:")?;
+        writeln!(f, "# IDD\t[2]id\t[3]length (deletions negative)\t[4]number of sites\t[5]number of genotypes")?;
+        for (len, counts) in &self.indel_lengths {
+            writeln!(f, "IDD\t0\t{len}\t{}\t{}", counts.sites, counts.genotypes)?;
+        }
+
+        let (genotype_count, site_count) = self
+            .depths
+            .values()
+            .fold((0, 0), |(genotypes, sites), counts| {
+                (genotypes + counts.genotypes, sites + counts.sites)
+            });
+
+        writeln!(f, "# DP, Depth distribution")?;
+        writeln!(f, "# DP\t[2]id\t[3]bin\t[4]number of genotypes\t[5]fraction of genotypes (%)\t[6]number of sites\t[7]fraction of sites (%)")?;
+        for (depth, counts) in &self.depths {
+            writeln!(
+                f,
+                "DP\t0\t{depth}\t{}\t{:.6}\t{}\t{:.6}",
+                counts.genotypes,
+                ratio(counts.genotypes, genotype_count) * 100.0,
+                counts.sites,
+                ratio(counts.sites, site_count) * 100.0
+            )?;
+        }
+
+        writeln!(f, "# PSC, Per-sample counts.")?;
+        writeln!(f, "# PSC\t[2]id\t[3]sample\t[4]nRefHom\t[5]nNonRefHom\t[6]nHets\t[7]nTransitions\t[8]nTransversions\t[9]nIndels\t[10]average depth\t[11]nSingletons\t[12]nHapRef\t[13]nHapAlt\t[14]nMissing")?;
+        for (name, counts) in self.sample_names.iter().zip(&self.sample_counts) {
+            writeln!(
+                f,
+                "PSC\t0\t{name}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.1}\t{}\t{}\t{}\t{}",
+                counts.reference_homozygous,
+                counts.alternate_homozygous,
+                counts.heterozygous,
+                counts.transitions,
+                counts.transversions,
+                counts.indels,
+                counts.mean_depth(),
+                counts.singletons,
+                counts.haploid_reference,
+                counts.haploid_alternate,
+                counts.missing
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_variant_type_counts(
+    f: &mut fmt::Formatter<'_>,
+    prefix: &str,
+    counts: &VariantTypeCounts,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "{prefix}\t{}\t{}\t{}\t{}",
+        counts.snvs, counts.transitions, counts.transversions, counts.indels
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = Stats {
+            sample_names: vec![String::from("sample0")],
+            allele_frequencies: [(
+                50,
+                VariantTypeCounts {
+                    snvs: 1,
+                    transitions: 1,
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            sample_counts: vec![SampleCounts {
+                heterozygous: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let b = Stats {
+            sample_names: vec![String::from("sample0")],
+            allele_frequencies: [
+                (
+                    50,
+                    VariantTypeCounts {
+                        snvs: 1,
+                        transversions: 1,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    100,
+                    VariantTypeCounts {
+                        indels: 1,
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            sample_counts: vec![SampleCounts {
+                heterozygous: 2,
+                missing: 1,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        a.merge(&b);
+
+        assert_eq!(a.sample_names(), ["sample0"]);
+        assert_eq!(
+            a.allele_frequencies(),
+            &[
+                (
+                    50,
+                    VariantTypeCounts {
+                        snvs: 2,
+                        transitions: 1,
+                        transversions: 1,
+                        indels: 0,
+                    }
+                ),
+                (
+                    100,
+                    VariantTypeCounts {
+                        indels: 1,
+                        ..Default::default()
+                    }
+                ),
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+        );
+        assert_eq!(a.sample_counts()[0].heterozygous, 3);
+        assert_eq!(a.sample_counts()[0].missing, 1);
+    }
+}
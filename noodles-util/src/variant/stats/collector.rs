@@ -0,0 +1,625 @@
+use std::io;
+
+use noodles_vcf::{
+    self as vcf,
+    variant::{
+        record::{
+            alternate_bases::Allele,
+            info::field::{key as info_key, value::Array as InfoArray, Value as InfoValue},
+            samples::{keys::key, series::Value, Sample as _},
+            AlternateBases as _, Info as _, Samples as _,
+        },
+        Record,
+    },
+};
+
+use super::{SampleCounts, Stats, VariantTypeCounts};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AlleleType {
+    Reference,
+    Snv { is_transition: bool },
+    Mnp,
+    Indel(i64),
+    StructuralVariant,
+    Unspecified,
+}
+
+impl AlleleType {
+    fn is_variant(&self) -> bool {
+        !matches!(self, Self::Reference | Self::Unspecified)
+    }
+}
+
+/// A variant record statistics collector.
+///
+/// Allele frequencies and singletons are computed from the genotypes (`GT`) of a record. If no
+/// sample has a called allele, the allele counts (`INFO/AC` and `INFO/AN`) are used instead.
+#[derive(Debug, Default)]
+pub struct Collector {
+    stats: Stats,
+}
+
+impl Collector {
+    /// Creates a variant record statistics collector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::stats::Collector;
+    /// let collector = Collector::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a variant record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// use noodles_util::variant::stats::Collector;
+    /// use noodles_vcf::{self as vcf, variant::{record_buf::AlternateBases, RecordBuf}};
+    ///
+    /// let header = vcf::Header::default();
+    /// let record = RecordBuf::builder()
+    ///     .set_reference_bases("A")
+    ///     .set_alternate_bases(AlternateBases::from(vec![String::from("G")]))
+    ///     .build();
+    ///
+    /// let mut collector = Collector::new();
+    /// collector.add(&header, &record)?;
+    ///
+    /// let stats = collector.finish();
+    /// assert_eq!(stats.summary().snvs, 1);
+    /// assert_eq!(stats.summary().transitions, 1);
+    /// # Ok::<_, io::Error>(())
+    /// ```
+    pub fn add<R>(&mut self, header: &vcf::Header, record: &R) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        if self.stats.sample_names.is_empty() {
+            self.stats.sample_names = header.sample_names().iter().cloned().collect();
+        }
+
+        let reference_bases: Vec<u8> =
+            record.reference_bases().iter().collect::<io::Result<_>>()?;
+
+        let allele_types: Vec<_> = record
+            .alternate_bases()
+            .alleles()
+            .map(|result| result.map(|allele| classify(&reference_bases, &allele)))
+            .collect::<io::Result<_>>()?;
+
+        self.add_summary(&allele_types);
+
+        let genotypes = read_genotypes(header, record)?;
+        let (allele_counts, allele_number) =
+            count_alleles(header, record, &genotypes, allele_types.len())?;
+
+        for (i, allele_type) in allele_types.iter().enumerate() {
+            let allele_count = allele_counts[i + 1];
+
+            if allele_count == 1 {
+                count_allele(&mut self.stats.singletons, *allele_type);
+            }
+
+            if let Some(percentage) = (allele_count * 100).checked_div(allele_number) {
+                let percentage = percentage.min(100) as u8;
+                let counts = self.stats.allele_frequencies.entry(percentage).or_default();
+                count_allele(counts, *allele_type);
+            }
+
+            if let AlleleType::Indel(len) = allele_type {
+                let genotype_count = genotypes
+                    .iter()
+                    .flatten()
+                    .filter(|positions| positions.contains(&Some(i + 1)))
+                    .count();
+
+                let counts = self.stats.indel_lengths.entry(*len).or_default();
+                counts.sites += 1;
+                counts.genotypes += genotype_count as u64;
+            }
+        }
+
+        if let (Some(quality_score), Some(allele_type)) =
+            (record.quality_score().transpose()?, allele_types.first())
+        {
+            let quality = quality_score.max(0.0) as u32;
+            let counts = self.stats.qualities.entry(quality).or_default();
+            count_allele(counts, *allele_type);
+        }
+
+        if let Some(depth) = info_integer(header, record, info_key::TOTAL_DEPTH)? {
+            if let Ok(depth) = u32::try_from(depth) {
+                self.stats.depths.entry(depth).or_default().sites += 1;
+            }
+        }
+
+        self.add_samples(header, record, &allele_types, &allele_counts, &genotypes)?;
+
+        Ok(())
+    }
+
+    /// Returns the collected statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::stats::Collector;
+    /// let stats = Collector::new().finish();
+    /// assert_eq!(stats.summary().records, 0);
+    /// ```
+    pub fn finish(self) -> Stats {
+        self.stats
+    }
+
+    fn add_summary(&mut self, allele_types: &[AlleleType]) {
+        let summary = &mut self.stats.summary;
+
+        summary.records += 1;
+
+        let variant_count = allele_types.iter().filter(|t| t.is_variant()).count();
+        let snv_count = allele_types
+            .iter()
+            .filter(|t| matches!(t, AlleleType::Snv { .. }))
+            .count();
+
+        if variant_count == 0 {
+            summary.no_alts += 1;
+        } else if variant_count > 1 {
+            summary.multiallelic_sites += 1;
+        }
+
+        if snv_count > 0 {
+            summary.snvs += 1;
+
+            if snv_count > 1 {
+                summary.multiallelic_snv_sites += 1;
+            }
+        }
+
+        if allele_types.contains(&AlleleType::Mnp) {
+            summary.mnps += 1;
+        }
+
+        if allele_types
+            .iter()
+            .any(|t| matches!(t, AlleleType::Indel(_)))
+        {
+            summary.indels += 1;
+        }
+
+        if allele_types.contains(&AlleleType::StructuralVariant) {
+            summary.structural_variants += 1;
+        }
+
+        for (i, allele_type) in allele_types.iter().enumerate() {
+            if let AlleleType::Snv { is_transition } = allele_type {
+                if *is_transition {
+                    summary.transitions += 1;
+
+                    if i == 0 {
+                        summary.first_alternate_transitions += 1;
+                    }
+                } else {
+                    summary.transversions += 1;
+
+                    if i == 0 {
+                        summary.first_alternate_transversions += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_samples<R>(
+        &mut self,
+        header: &vcf::Header,
+        record: &R,
+        allele_types: &[AlleleType],
+        allele_counts: &[u64],
+        genotypes: &[Option<Vec<Option<usize>>>],
+    ) -> io::Result<()>
+    where
+        R: Record + ?Sized,
+    {
+        let samples = record.samples()?;
+
+        if self.stats.sample_counts.len() < genotypes.len() {
+            self.stats
+                .sample_counts
+                .resize(genotypes.len(), SampleCounts::default());
+        }
+
+        for ((sample, genotype), counts) in samples
+            .iter()
+            .zip(genotypes)
+            .zip(&mut self.stats.sample_counts)
+        {
+            if let Some(positions) = genotype {
+                count_genotype(counts, positions, allele_types, allele_counts);
+            }
+
+            if let Some(Value::Integer(depth)) =
+                sample.get(header, key::READ_DEPTH).transpose()?.flatten()
+            {
+                if let Ok(depth) = u32::try_from(depth) {
+                    counts.depth_sum += u64::from(depth);
+                    counts.depth_count += 1;
+                    self.stats.depths.entry(depth).or_default().genotypes += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn classify(reference_bases: &[u8], allele: &Allele<'_>) -> AlleleType {
+    match allele {
+        Allele::Bases(bases) => {
+            let bases = bases.as_bytes();
+
+            if bases.len() != reference_bases.len() {
+                return AlleleType::Indel(bases.len() as i64 - reference_bases.len() as i64);
+            }
+
+            let mut substitutions = reference_bases
+                .iter()
+                .zip(bases)
+                .filter(|(a, b)| !a.eq_ignore_ascii_case(b));
+
+            match (substitutions.next(), substitutions.next()) {
+                (None, _) => AlleleType::Reference,
+                (Some((&a, &b)), None) => AlleleType::Snv {
+                    is_transition: is_transition(a, b),
+                },
+                (Some(_), Some(_)) => AlleleType::Mnp,
+            }
+        }
+        Allele::Symbol(symbol) if symbol.is_unspecified() => AlleleType::Unspecified,
+        Allele::Symbol(_) | Allele::Breakend(_) => AlleleType::StructuralVariant,
+        Allele::OverlappingDeletion => AlleleType::Unspecified,
+    }
+}
+
+fn is_transition(a: u8, b: u8) -> bool {
+    matches!(
+        (a.to_ascii_uppercase(), b.to_ascii_uppercase()),
+        (b'A', b'G') | (b'G', b'A') | (b'C', b'T') | (b'T', b'C')
+    )
+}
+
+fn count_allele(counts: &mut VariantTypeCounts, allele_type: AlleleType) {
+    match allele_type {
+        AlleleType::Snv { is_transition } => {
+            counts.snvs += 1;
+
+            if is_transition {
+                counts.transitions += 1;
+            } else {
+                counts.transversions += 1;
+            }
+        }
+        AlleleType::Indel(_) => counts.indels += 1,
+        _ => {}
+    }
+}
+
+fn count_genotype(
+    counts: &mut SampleCounts,
+    positions: &[Option<usize>],
+    allele_types: &[AlleleType],
+    allele_counts: &[u64],
+) {
+    let Some(positions) = positions
+        .iter()
+        .copied()
+        .collect::<Option<Vec<_>>>()
+        .filter(|positions| !positions.is_empty())
+    else {
+        counts.missing += 1;
+        return;
+    };
+
+    let is_reference = positions.iter().all(|&i| i == 0);
+
+    if positions.len() == 1 {
+        if is_reference {
+            counts.haploid_reference += 1;
+        } else {
+            counts.haploid_alternate += 1;
+        }
+    } else if positions.windows(2).all(|w| w[0] == w[1]) {
+        if is_reference {
+            counts.reference_homozygous += 1;
+        } else {
+            counts.alternate_homozygous += 1;
+        }
+    } else {
+        counts.heterozygous += 1;
+    }
+
+    let mut alternate_alleles: Vec<_> = positions.into_iter().filter(|&i| i > 0).collect();
+    alternate_alleles.sort_unstable();
+    alternate_alleles.dedup();
+
+    let mut is_singleton = false;
+
+    for i in alternate_alleles {
+        match allele_types[i - 1] {
+            AlleleType::Snv { is_transition } => {
+                if is_transition {
+                    counts.transitions += 1;
+                } else {
+                    counts.transversions += 1;
+                }
+            }
+            AlleleType::Indel(_) => counts.indels += 1,
+            _ => {}
+        }
+
+        is_singleton |= allele_counts[i] == 1;
+    }
+
+    if is_singleton {
+        counts.singletons += 1;
+    }
+}
+
+fn read_genotypes<R>(
+    header: &vcf::Header,
+    record: &R,
+) -> io::Result<Vec<Option<Vec<Option<usize>>>>>
+where
+    R: Record + ?Sized,
+{
+    let samples = record.samples()?;
+    let mut genotypes = Vec::new();
+
+    for sample in samples.iter() {
+        let positions = match sample.get(header, key::GENOTYPE).transpose()?.flatten() {
+            Some(Value::Genotype(genotype)) => Some(
+                genotype
+                    .iter()
+                    .map(|result| result.map(|(position, _)| position))
+                    .collect::<io::Result<Vec<_>>>()?,
+            ),
+            _ => None,
+        };
+
+        genotypes.push(positions);
+    }
+
+    Ok(genotypes)
+}
+
+// Returns the counts of each allele, including the reference allele, and the total number of
+// called alleles.
+fn count_alleles<R>(
+    header: &vcf::Header,
+    record: &R,
+    genotypes: &[Option<Vec<Option<usize>>>],
+    alternate_allele_count: usize,
+) -> io::Result<(Vec<u64>, u64)>
+where
+    R: Record + ?Sized,
+{
+    let mut allele_counts = vec![0; alternate_allele_count + 1];
+    let mut allele_number = 0;
+
+    for i in genotypes.iter().flatten().flatten().flatten().copied() {
+        let count = allele_counts.get_mut(i).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid allele index: {i}"),
+            )
+        })?;
+
+        *count += 1;
+        allele_number += 1;
+    }
+
+    if allele_number > 0 {
+        return Ok((allele_counts, allele_number));
+    }
+
+    let Some(allele_number) = info_integer(header, record, info_key::TOTAL_ALLELE_COUNT)?
+        .and_then(|n| u64::try_from(n).ok())
+    else {
+        return Ok((allele_counts, 0));
+    };
+
+    let info = record.info();
+
+    let values = match info
+        .get(header, info_key::ALLELE_COUNT)
+        .transpose()?
+        .flatten()
+    {
+        Some(InfoValue::Integer(n)) => vec![Some(n)],
+        Some(InfoValue::Array(InfoArray::Integer(values))) => {
+            values.iter().collect::<io::Result<_>>()?
+        }
+        _ => Vec::new(),
+    };
+
+    for (count, value) in allele_counts[1..].iter_mut().zip(values) {
+        *count = value.and_then(|n| u64::try_from(n).ok()).unwrap_or(0);
+    }
+
+    Ok((allele_counts, allele_number))
+}
+
+fn info_integer<R>(header: &vcf::Header, record: &R, key: &str) -> io::Result<Option<i32>>
+where
+    R: Record + ?Sized,
+{
+    let info = record.info();
+
+    let value = match info.get(header, key).transpose()?.flatten() {
+        Some(InfoValue::Integer(n)) => Some(n),
+        _ => None,
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW_HEADER: &str = "##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample0\tsample1
+";
+
+    const RAW_RECORDS: &[&str] = &[
+        "sq0\t1\t.\tA\tG\t30\tPASS\tDP=12\tGT:DP\t0/1:5\t0/0:7",
+        "sq0\t2\t.\tC\tA,T\t45.5\tPASS\t.\tGT:DP\t1/2:10\t2/2:.",
+        "sq0\t3\t.\tGT\tG\t.\tPASS\t.\tGT\t0/1\t./.",
+        "sq0\t4\t.\tAC\tGT\t.\tPASS\t.\tGT\t1\t0",
+        "sq0\t5\t.\tA\t<DEL>\t.\tPASS\t.\tGT\t0/0\t0/1",
+        "sq0\t6\t.\tA\t<*>\t.\tPASS\t.\tGT\t0/0\t0/0",
+    ];
+
+    #[test]
+    fn test_add() -> Result<(), Box<dyn std::error::Error>> {
+        let header: vcf::Header = RAW_HEADER.parse()?;
+
+        let mut collector = Collector::new();
+
+        for s in RAW_RECORDS {
+            let record = vcf::Record::try_from(s.as_bytes())?;
+            collector.add(&header, &record)?;
+        }
+
+        let stats = collector.finish();
+
+        let summary = stats.summary();
+        assert_eq!(summary.records, 6);
+        assert_eq!(summary.no_alts, 1);
+        assert_eq!(summary.snvs, 2);
+        assert_eq!(summary.mnps, 1);
+        assert_eq!(summary.indels, 1);
+        assert_eq!(summary.structural_variants, 1);
+        assert_eq!(summary.multiallelic_sites, 1);
+        assert_eq!(summary.multiallelic_snv_sites, 1);
+        assert_eq!(summary.transitions, 2);
+        assert_eq!(summary.transversions, 1);
+        assert_eq!(summary.first_alternate_transitions, 1);
+        assert_eq!(summary.first_alternate_transversions, 1);
+
+        // A>G (AC=1), C>A (AC=1), C>T (AC=3), GT>G (AC=1)
+        assert_eq!(
+            stats.singletons(),
+            &VariantTypeCounts {
+                snvs: 2,
+                transitions: 1,
+                transversions: 1,
+                indels: 1,
+            }
+        );
+
+        assert_eq!(
+            stats.allele_frequencies().get(&75),
+            Some(&VariantTypeCounts {
+                snvs: 1,
+                transitions: 1,
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(stats.qualities().len(), 2);
+        assert_eq!(
+            stats
+                .qualities()
+                .get(&45)
+                .map(|counts| counts.transversions),
+            Some(1)
+        );
+
+        let counts = stats.indel_lengths().get(&-1).copied().unwrap_or_default();
+        assert_eq!((counts.sites, counts.genotypes), (1, 1));
+
+        assert_eq!(stats.depths().get(&12).map(|counts| counts.sites), Some(1));
+        assert_eq!(
+            stats.depths().get(&10).map(|counts| counts.genotypes),
+            Some(1)
+        );
+
+        let sample_counts = stats.sample_counts();
+
+        assert_eq!(
+            sample_counts[0],
+            SampleCounts {
+                reference_homozygous: 2,
+                heterozygous: 3,
+                haploid_alternate: 1,
+                transitions: 2,
+                transversions: 1,
+                indels: 1,
+                singletons: 4,
+                depth_sum: 15,
+                depth_count: 2,
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            sample_counts[1],
+            SampleCounts {
+                reference_homozygous: 2,
+                alternate_homozygous: 1,
+                heterozygous: 1,
+                haploid_reference: 1,
+                missing: 1,
+                transitions: 1,
+                singletons: 1,
+                depth_sum: 7,
+                depth_count: 1,
+                ..Default::default()
+            }
+        );
+
+        let report = stats.to_string();
+        assert!(report.contains("SN\t0\tnumber of records:\t6\n"));
+        assert!(report.contains("TSTV\t0\t2\t1\t2.00\t1\t1\t1.00\n"));
+        assert!(report.contains("PSC\t0\tsample1\t2\t1\t1\t1\t0\t0\t7.0\t1\t1\t0\t1\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_with_info_allele_counts() -> Result<(), Box<dyn std::error::Error>> {
+        let header: vcf::Header = "##fileformat=VCFv4.5
+##INFO=<ID=AC,Number=A,Type=Integer,Description=\"Allele count\">
+##INFO=<ID=AN,Number=1,Type=Integer,Description=\"Total number of alleles\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+"
+        .parse()?;
+
+        let record = vcf::Record::try_from(&b"sq0\t1\t.\tA\tC,G\t.\tPASS\tAC=1,2;AN=8"[..])?;
+
+        let mut collector = Collector::new();
+        collector.add(&header, &record)?;
+        let stats = collector.finish();
+
+        assert_eq!(stats.singletons().transversions, 1);
+        assert_eq!(
+            stats
+                .allele_frequencies()
+                .get(&25)
+                .map(|counts| counts.transitions),
+            Some(1)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,877 @@
+//! Variant record validation.
+//!
+//! A record is validated against the definitions of its header: the reference sequence name must
+//! be a contig, filters and INFO and FORMAT keys must be defined, values must have the defined
+//! type and number, and genotype allele indices must refer to the reference or an alternate
+//! allele.
+//!
+//! This is useful to reject invalid records when reading or before writing, e.g., before
+//! encoding as BCF, which requires these definitions.
+
+use std::{fmt, io};
+
+use noodles_vcf::{
+    header::record::value::map::{format, info},
+    variant::{
+        record::{info::field::Value as InfoValue, samples::series::Value as SampleValue},
+        Record,
+    },
+    Header,
+};
+
+use super::multiallelic::genotype_count;
+
+const PASS: &str = "PASS";
+const GENOTYPE: &str = "GT";
+
+/// The validation mode.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Mode {
+    /// All issues are errors.
+    #[default]
+    Strict,
+    /// Undefined contigs, filters, and INFO and FORMAT keys are warnings.
+    ///
+    /// Values of undefined keys are not checked. All other issues are errors.
+    Lenient,
+}
+
+/// The severity of a diagnostic.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// The record can be used but may be rejected by stricter consumers.
+    Warning,
+    /// The record is invalid.
+    Error,
+}
+
+/// The record field a diagnostic refers to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    /// The reference sequence name (`CHROM`).
+    ReferenceSequenceName,
+    /// The filters (`FILTER`).
+    Filters,
+    /// The info fields (`INFO`).
+    Info,
+    /// The samples (`FORMAT` and sample columns).
+    Samples,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReferenceSequenceName => f.write_str("CHROM"),
+            Self::Filters => f.write_str("FILTER"),
+            Self::Info => f.write_str("INFO"),
+            Self::Samples => f.write_str("FORMAT"),
+        }
+    }
+}
+
+/// A validation issue.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// The contig, filter, or key is not defined in the header.
+    Undefined,
+    /// The value type does not match the header definition.
+    InvalidType {
+        /// The type in the header definition.
+        expected: String,
+        /// The type of the value.
+        actual: String,
+    },
+    /// The number of values does not match the header definition.
+    InvalidCardinality {
+        /// The number of values implied by the header definition.
+        expected: usize,
+        /// The number of values.
+        actual: usize,
+    },
+    /// A genotype allele index is not a reference or alternate allele.
+    InvalidAlleleIndex {
+        /// The allele index.
+        index: usize,
+        /// The number of alleles, including the reference allele.
+        allele_count: usize,
+    },
+    /// The field could not be decoded.
+    InvalidValue(String),
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined => f.write_str("not defined in header"),
+            Self::InvalidType { expected, actual } => {
+                write!(f, "invalid type: expected {expected}, got {actual}")
+            }
+            Self::InvalidCardinality { expected, actual } => write!(
+                f,
+                "invalid number of values: expected {expected}, got {actual}"
+            ),
+            Self::InvalidAlleleIndex {
+                index,
+                allele_count,
+            } => write!(
+                f,
+                "invalid allele index: expected < {allele_count}, got {index}"
+            ),
+            Self::InvalidValue(message) => write!(f, "invalid value: {message}"),
+        }
+    }
+}
+
+/// A validation diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    severity: Severity,
+    field: Field,
+    key: Option<String>,
+    sample_index: Option<usize>,
+    kind: Kind,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, field: Field, key: Option<&str>, kind: Kind) -> Self {
+        Self {
+            severity,
+            field,
+            key: key.map(String::from),
+            sample_index: None,
+            kind,
+        }
+    }
+
+    /// Returns the severity.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the record field.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Returns the contig name, filter ID, or INFO or FORMAT key, if known.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Returns the index of the sample, if the diagnostic refers to a sample value.
+    pub fn sample_index(&self) -> Option<usize> {
+        self.sample_index
+    }
+
+    /// Returns the issue.
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.field)?;
+
+        if let Some(key) = self.key() {
+            write!(f, "/{key}")?;
+        }
+
+        if let Some(i) = self.sample_index {
+            write!(f, " (sample {})", i + 1)?;
+        }
+
+        write!(f, ": {}", self.kind)
+    }
+}
+
+/// A variant record validator.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Validator {
+    mode: Mode,
+}
+
+impl Validator {
+    /// Creates a variant record validator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::validate::{Mode, Validator};
+    /// let validator = Validator::new(Mode::Lenient);
+    /// ```
+    pub fn new(mode: Mode) -> Self {
+        Self { mode }
+    }
+
+    /// Validates a record against a header.
+    ///
+    /// This returns all issues found. Fields that cannot be decoded are reported as
+    /// [`Kind::InvalidValue`] errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::validate::{Field, Kind, Severity, Validator};
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let header = vcf::Header::default();
+    /// let record = RecordBuf::builder().set_reference_sequence_name("sq0").build();
+    ///
+    /// let diagnostics = Validator::default().validate(&header, &record);
+    ///
+    /// assert_eq!(diagnostics.len(), 1);
+    /// assert_eq!(diagnostics[0].severity(), Severity::Error);
+    /// assert_eq!(diagnostics[0].field(), Field::ReferenceSequenceName);
+    /// assert_eq!(diagnostics[0].key(), Some("sq0"));
+    /// assert_eq!(diagnostics[0].kind(), &Kind::Undefined);
+    /// ```
+    pub fn validate<R>(&self, header: &Header, record: &R) -> Vec<Diagnostic>
+    where
+        R: Record + ?Sized,
+    {
+        let mut diagnostics = Vec::new();
+
+        self.validate_reference_sequence_name(header, record, &mut diagnostics);
+        self.validate_filters(header, record, &mut diagnostics);
+
+        let alternate_allele_count = record.alternate_bases().len();
+        self.validate_info(header, record, alternate_allele_count, &mut diagnostics);
+        self.validate_samples(header, record, alternate_allele_count, &mut diagnostics);
+
+        diagnostics
+    }
+
+    /// Validates a record against a header and returns an error if it is invalid.
+    ///
+    /// On success, this returns the warnings found, if any. Otherwise, the error message is the
+    /// first error diagnostic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_util::variant::validate::{Mode, Validator};
+    /// use noodles_vcf::{self as vcf, variant::RecordBuf};
+    ///
+    /// let header = vcf::Header::default();
+    /// let record = RecordBuf::builder().set_reference_sequence_name("sq0").build();
+    ///
+    /// assert!(Validator::new(Mode::Strict).check(&header, &record).is_err());
+    ///
+    /// let warnings = Validator::new(Mode::Lenient).check(&header, &record)?;
+    /// assert_eq!(warnings.len(), 1);
+    /// # Ok::<_, std::io::Error>(())
+    /// ```
+    pub fn check<R>(&self, header: &Header, record: &R) -> io::Result<Vec<Diagnostic>>
+    where
+        R: Record + ?Sized,
+    {
+        let diagnostics = self.validate(header, record);
+
+        if let Some(diagnostic) = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.severity() == Severity::Error)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                diagnostic.to_string(),
+            ));
+        }
+
+        Ok(diagnostics)
+    }
+
+    fn undefined_severity(&self) -> Severity {
+        match self.mode {
+            Mode::Strict => Severity::Error,
+            Mode::Lenient => Severity::Warning,
+        }
+    }
+
+    fn validate_reference_sequence_name<R>(
+        &self,
+        header: &Header,
+        record: &R,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) where
+        R: Record + ?Sized,
+    {
+        const FIELD: Field = Field::ReferenceSequenceName;
+
+        match record.reference_sequence_name(header) {
+            Ok(name) => {
+                if !header.contigs().contains_key(name) {
+                    diagnostics.push(Diagnostic::new(
+                        self.undefined_severity(),
+                        FIELD,
+                        Some(name),
+                        Kind::Undefined,
+                    ));
+                }
+            }
+            Err(e) => diagnostics.push(invalid_value(FIELD, None, e)),
+        }
+    }
+
+    fn validate_filters<R>(&self, header: &Header, record: &R, diagnostics: &mut Vec<Diagnostic>)
+    where
+        R: Record + ?Sized,
+    {
+        const FIELD: Field = Field::Filters;
+
+        let filters = record.filters();
+
+        for result in filters.iter(header) {
+            match result {
+                Ok(id) => {
+                    if id != PASS && !header.filters().contains_key(id) {
+                        diagnostics.push(Diagnostic::new(
+                            self.undefined_severity(),
+                            FIELD,
+                            Some(id),
+                            Kind::Undefined,
+                        ));
+                    }
+                }
+                Err(e) => diagnostics.push(invalid_value(FIELD, None, e)),
+            }
+        }
+    }
+
+    fn validate_info<R>(
+        &self,
+        header: &Header,
+        record: &R,
+        alternate_allele_count: usize,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) where
+        R: Record + ?Sized,
+    {
+        const FIELD: Field = Field::Info;
+
+        let info = record.info();
+
+        for result in info.iter(header) {
+            let (key, value) = match result {
+                Ok(field) => field,
+                Err(e) => {
+                    diagnostics.push(invalid_value(FIELD, None, e));
+                    continue;
+                }
+            };
+
+            let Some(definition) = header.infos().get(key) else {
+                diagnostics.push(Diagnostic::new(
+                    self.undefined_severity(),
+                    FIELD,
+                    Some(key),
+                    Kind::Undefined,
+                ));
+
+                continue;
+            };
+
+            let Some(value) = value else {
+                continue;
+            };
+
+            let (actual_type, actual_len) = match info_value_shape(&value) {
+                Ok(shape) => shape,
+                Err(e) => {
+                    diagnostics.push(invalid_value(FIELD, Some(key), e));
+                    continue;
+                }
+            };
+
+            let expected_type = definition.ty();
+
+            if !matches_info_type(expected_type, actual_type) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    FIELD,
+                    Some(key),
+                    Kind::InvalidType {
+                        expected: expected_type.to_string(),
+                        actual: actual_type.into(),
+                    },
+                ));
+
+                continue;
+            }
+
+            if expected_type == info::Type::Flag {
+                continue;
+            }
+
+            let expected_len = match definition.number() {
+                info::Number::Count(n) => Some(n),
+                info::Number::AlternateBases => Some(alternate_allele_count),
+                info::Number::ReferenceAlternateBases => Some(alternate_allele_count + 1),
+                info::Number::Samples | info::Number::Unknown => None,
+            };
+
+            if let Some(expected) = expected_len {
+                if actual_len != expected {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        FIELD,
+                        Some(key),
+                        Kind::InvalidCardinality {
+                            expected,
+                            actual: actual_len,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    fn validate_samples<R>(
+        &self,
+        header: &Header,
+        record: &R,
+        alternate_allele_count: usize,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) where
+        R: Record + ?Sized,
+    {
+        const FIELD: Field = Field::Samples;
+
+        let samples = match record.samples() {
+            Ok(samples) => samples,
+            Err(e) => {
+                diagnostics.push(invalid_value(FIELD, None, e));
+                return;
+            }
+        };
+
+        let keys = match samples.column_names(header).collect::<io::Result<Vec<_>>>() {
+            Ok(keys) => keys,
+            Err(e) => {
+                diagnostics.push(invalid_value(FIELD, None, e));
+                return;
+            }
+        };
+
+        let mut definitions = Vec::with_capacity(keys.len());
+
+        for &key in &keys {
+            let definition = header.formats().get(key);
+
+            if definition.is_none() {
+                diagnostics.push(Diagnostic::new(
+                    self.undefined_severity(),
+                    FIELD,
+                    Some(key),
+                    Kind::Undefined,
+                ));
+            }
+
+            definitions.push(definition);
+        }
+
+        let allele_count = alternate_allele_count + 1;
+
+        for (sample_index, sample) in samples.iter().enumerate() {
+            let mut push = |key: &str, severity, kind| {
+                let mut diagnostic = Diagnostic::new(severity, FIELD, Some(key), kind);
+                diagnostic.sample_index = Some(sample_index);
+                diagnostics.push(diagnostic);
+            };
+
+            let mut ploidy = None;
+
+            // The genotype is validated first to determine the ploidy.
+            let indices = keys
+                .iter()
+                .position(|&key| key == GENOTYPE)
+                .into_iter()
+                .chain((0..keys.len()).filter(|&i| keys[i] != GENOTYPE));
+
+            for i in indices {
+                let (key, definition) = (keys[i], definitions[i]);
+
+                let value = match sample.get_index(header, i) {
+                    Some(Ok(Some(value))) => value,
+                    Some(Ok(None)) | None => continue,
+                    Some(Err(e)) => {
+                        push(key, Severity::Error, Kind::InvalidValue(e.to_string()));
+                        continue;
+                    }
+                };
+
+                let Some(definition) = definition else {
+                    continue;
+                };
+
+                let shape = match &value {
+                    SampleValue::Genotype(genotype) => {
+                        let mut len = 0;
+
+                        for result in genotype.iter() {
+                            match result {
+                                Ok((Some(index), _)) if index >= allele_count => push(
+                                    key,
+                                    Severity::Error,
+                                    Kind::InvalidAlleleIndex {
+                                        index,
+                                        allele_count,
+                                    },
+                                ),
+                                Ok(_) => {}
+                                Err(e) => {
+                                    push(key, Severity::Error, Kind::InvalidValue(e.to_string()))
+                                }
+                            }
+
+                            len += 1;
+                        }
+
+                        if key == GENOTYPE {
+                            ploidy = Some(len);
+                        }
+
+                        Ok(("Genotype", 1))
+                    }
+                    _ => sample_value_shape(&value),
+                };
+
+                let (actual_type, actual_len) = match shape {
+                    Ok(shape) => shape,
+                    Err(e) => {
+                        push(key, Severity::Error, Kind::InvalidValue(e.to_string()));
+                        continue;
+                    }
+                };
+
+                let expected_type = definition.ty();
+
+                if !matches_format_type(expected_type, actual_type) {
+                    push(
+                        key,
+                        Severity::Error,
+                        Kind::InvalidType {
+                            expected: expected_type.to_string(),
+                            actual: actual_type.into(),
+                        },
+                    );
+
+                    continue;
+                }
+
+                if actual_type == "Genotype" {
+                    continue;
+                }
+
+                let expected_len = match definition.number() {
+                    format::Number::Count(n) => Some(n),
+                    format::Number::AlternateBases => Some(alternate_allele_count),
+                    format::Number::ReferenceAlternateBases => Some(allele_count),
+                    format::Number::Samples => {
+                        ploidy.map(|ploidy| genotype_count(allele_count, ploidy))
+                    }
+                    format::Number::Ploidy => ploidy,
+                    _ => None,
+                };
+
+                if let Some(expected) = expected_len {
+                    if actual_len != expected {
+                        push(
+                            key,
+                            Severity::Error,
+                            Kind::InvalidCardinality {
+                                expected,
+                                actual: actual_len,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn invalid_value(field: Field, key: Option<&str>, e: io::Error) -> Diagnostic {
+    Diagnostic::new(
+        Severity::Error,
+        field,
+        key,
+        Kind::InvalidValue(e.to_string()),
+    )
+}
+
+fn info_value_shape(value: &InfoValue<'_>) -> io::Result<(&'static str, usize)> {
+    use noodles_vcf::variant::record::info::field::value::Array;
+
+    let shape = match value {
+        InfoValue::Integer(_) => ("Integer", 1),
+        InfoValue::Float(_) => ("Float", 1),
+        InfoValue::Flag => ("Flag", 0),
+        InfoValue::Character(_) => ("Character", 1),
+        InfoValue::String(_) => ("String", 1),
+        InfoValue::Array(Array::Integer(values)) => ("Integer", count(values.iter())?),
+        InfoValue::Array(Array::Float(values)) => ("Float", count(values.iter())?),
+        InfoValue::Array(Array::Character(values)) => ("Character", count(values.iter())?),
+        InfoValue::Array(Array::String(values)) => ("String", count(values.iter())?),
+    };
+
+    Ok(shape)
+}
+
+fn sample_value_shape(value: &SampleValue<'_>) -> io::Result<(&'static str, usize)> {
+    use noodles_vcf::variant::record::samples::series::value::Array;
+
+    let shape = match value {
+        SampleValue::Integer(_) => ("Integer", 1),
+        SampleValue::Float(_) => ("Float", 1),
+        SampleValue::Character(_) => ("Character", 1),
+        SampleValue::String(_) => ("String", 1),
+        SampleValue::Genotype(_) => ("Genotype", 1),
+        SampleValue::Array(Array::Integer(values)) => ("Integer", count(values.iter())?),
+        SampleValue::Array(Array::Float(values)) => ("Float", count(values.iter())?),
+        SampleValue::Array(Array::Character(values)) => ("Character", count(values.iter())?),
+        SampleValue::Array(Array::String(values)) => ("String", count(values.iter())?),
+    };
+
+    Ok(shape)
+}
+
+/// Counts values, checking that each can be decoded.
+fn count<I, T>(values: I) -> io::Result<usize>
+where
+    I: Iterator<Item = io::Result<T>>,
+{
+    values.map(|result| result.map(|_| 1)).sum()
+}
+
+fn matches_info_type(expected: info::Type, actual: &str) -> bool {
+    let expected = match expected {
+        info::Type::Integer => "Integer",
+        info::Type::Float => "Float",
+        info::Type::Flag => "Flag",
+        info::Type::Character => "Character",
+        info::Type::String => "String",
+    };
+
+    expected == actual
+}
+
+fn matches_format_type(expected: format::Type, actual: &str) -> bool {
+    match expected {
+        format::Type::Integer => actual == "Integer",
+        format::Type::Float => actual == "Float",
+        format::Type::Character => actual == "Character",
+        // Genotypes are declared as strings.
+        format::Type::String => actual == "String" || actual == "Genotype",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_vcf::{
+        self as vcf,
+        header::record::value::{
+            map::{Contig, Filter, Format, Info},
+            Map,
+        },
+        variant::{
+            record::samples::keys::key as format_key,
+            record_buf::{
+                info::field::{value::Array as InfoArray, Value as InfoValueBuf},
+                samples::{
+                    sample::{value::Array as SampleArray, Value as SampleValueBuf},
+                    Keys,
+                },
+                AlternateBases, Filters, Samples,
+            },
+            RecordBuf,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_validate_with_valid_record() -> Result<(), Box<dyn std::error::Error>> {
+        let header = Header::builder()
+            .add_contig("sq0", Map::<Contig>::new())
+            .add_info("DP", Map::<Info>::from("DP"))
+            .add_info("AF", Map::<Info>::from("AF"))
+            .add_format(
+                format_key::GENOTYPE,
+                Map::<Format>::from(format_key::GENOTYPE),
+            )
+            .add_format(
+                format_key::READ_DEPTHS,
+                Map::<Format>::from(format_key::READ_DEPTHS),
+            )
+            .add_format(
+                format_key::ROUNDED_GENOTYPE_LIKELIHOODS,
+                Map::<Format>::from(format_key::ROUNDED_GENOTYPE_LIKELIHOODS),
+            )
+            .add_sample_name("sample0")
+            .build();
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq0")
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_filters(Filters::pass())
+            .set_info(
+                [
+                    (String::from("DP"), Some(InfoValueBuf::Integer(8))),
+                    (
+                        String::from("AF"),
+                        Some(InfoValueBuf::Array(InfoArray::Float(vec![Some(0.5)]))),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .set_samples(Samples::new(
+                Keys::from_iter([
+                    String::from(format_key::GENOTYPE),
+                    String::from(format_key::READ_DEPTHS),
+                    String::from(format_key::ROUNDED_GENOTYPE_LIKELIHOODS),
+                ]),
+                vec![vec![
+                    Some(SampleValueBuf::Genotype("0/1".parse()?)),
+                    Some(SampleValueBuf::Array(SampleArray::Integer(vec![
+                        Some(3),
+                        Some(5),
+                    ]))),
+                    Some(SampleValueBuf::Array(SampleArray::Integer(vec![
+                        Some(30),
+                        Some(0),
+                        Some(40),
+                    ]))),
+                ]],
+            ))
+            .build();
+
+        assert!(Validator::default().validate(&header, &record).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_invalid_record() -> Result<(), Box<dyn std::error::Error>> {
+        let header = Header::builder()
+            .add_contig("sq0", Map::<Contig>::new())
+            .add_filter("q10", Map::<Filter>::new("Quality below 10"))
+            .add_info("DP", Map::<Info>::from("DP"))
+            .add_info("AF", Map::<Info>::from("AF"))
+            .add_format(
+                format_key::GENOTYPE,
+                Map::<Format>::from(format_key::GENOTYPE),
+            )
+            .add_format(
+                format_key::READ_DEPTHS,
+                Map::<Format>::from(format_key::READ_DEPTHS),
+            )
+            .add_sample_name("sample0")
+            .build();
+
+        let record = RecordBuf::builder()
+            .set_reference_sequence_name("sq1")
+            .set_reference_bases("A")
+            .set_alternate_bases(AlternateBases::from(vec![String::from("C")]))
+            .set_filters(Filters::from_iter([String::from("q20")]))
+            .set_info(
+                [
+                    (String::from("DP"), Some(InfoValueBuf::Float(8.0))),
+                    (
+                        String::from("AF"),
+                        Some(InfoValueBuf::Array(InfoArray::Float(vec![
+                            Some(0.5),
+                            Some(0.5),
+                        ]))),
+                    ),
+                    (String::from("XX"), Some(InfoValueBuf::Flag)),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .set_samples(Samples::new(
+                Keys::from_iter([
+                    String::from(format_key::GENOTYPE),
+                    String::from(format_key::READ_DEPTHS),
+                ]),
+                vec![vec![
+                    Some(SampleValueBuf::Genotype("0/2".parse()?)),
+                    Some(SampleValueBuf::Array(SampleArray::Integer(vec![Some(3)]))),
+                ]],
+            ))
+            .build();
+
+        let diagnostics = Validator::new(Mode::Lenient).validate(&header, &record);
+
+        let actual: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity(), diagnostic.to_string()))
+            .collect();
+
+        let expected = [
+            (Severity::Warning, "CHROM/sq1: not defined in header"),
+            (Severity::Warning, "FILTER/q20: not defined in header"),
+            (
+                Severity::Error,
+                "INFO/DP: invalid type: expected Integer, got Float",
+            ),
+            (
+                Severity::Error,
+                "INFO/AF: invalid number of values: expected 1, got 2",
+            ),
+            (Severity::Warning, "INFO/XX: not defined in header"),
+            (
+                Severity::Error,
+                "FORMAT/GT (sample 1): invalid allele index: expected < 2, got 2",
+            ),
+            (
+                Severity::Error,
+                "FORMAT/AD (sample 1): invalid number of values: expected 2, got 1",
+            ),
+        ];
+
+        assert_eq!(actual.len(), expected.len());
+
+        for ((actual_severity, actual_message), (expected_severity, expected_message)) in
+            actual.iter().zip(expected)
+        {
+            assert_eq!(*actual_severity, expected_severity);
+            assert_eq!(actual_message, expected_message);
+        }
+
+        assert_eq!(diagnostics[5].sample_index(), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_undecodable_value() -> io::Result<()> {
+        let src = b"##fileformat=VCFv4.5
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Combined depth across samples\">
+##contig=<ID=sq0>
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+sq0\t1\t.\tA\t.\t.\tPASS\tDP=1.5
+";
+
+        let mut reader = vcf::io::Reader::new(&src[..]);
+        let header = reader.read_header()?;
+
+        let mut record = vcf::Record::default();
+        reader.read_record(&mut record)?;
+
+        let diagnostics = Validator::default().validate(&header, &record);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].field(), Field::Info);
+        assert!(matches!(diagnostics[0].kind(), Kind::InvalidValue(_)));
+
+        assert!(matches!(
+            Validator::default().check(&header, &record),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        Ok(())
+    }
+}
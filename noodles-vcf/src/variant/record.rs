@@ -1,11 +1,12 @@
 //! Variant record.
 
-mod alternate_bases;
+pub mod alternate_bases;
 mod filters;
 mod ids;
 pub mod info;
 mod reference_bases;
 pub mod samples;
+pub mod structural_variant;
 
 use std::io;
 
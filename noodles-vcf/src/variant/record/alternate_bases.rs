@@ -1,5 +1,11 @@
+//! Variant record alternate bases.
+
+pub mod allele;
+
 use std::io;
 
+pub use self::allele::Allele;
+
 /// Variant record alternate bases.
 pub trait AlternateBases {
     /// Returns whether there are any alternate bases.
@@ -10,6 +16,15 @@ pub trait AlternateBases {
 
     /// Returns an iterator over alternate bases.
     fn iter(&self) -> Box<dyn Iterator<Item = io::Result<&str>> + '_>;
+
+    /// Returns an iterator over parsed alternate alleles.
+    fn alleles(&self) -> Box<dyn Iterator<Item = io::Result<Allele<'_>>> + '_> {
+        Box::new(self.iter().map(|result| {
+            result.and_then(|s| {
+                Allele::try_from(s).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+        }))
+    }
 }
 
 impl AlternateBases for Box<dyn AlternateBases + '_> {
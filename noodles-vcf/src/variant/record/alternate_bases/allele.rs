@@ -0,0 +1,127 @@
+//! Variant record alternate bases allele.
+
+pub mod breakend;
+pub mod symbol;
+
+use std::{error, fmt};
+
+pub use self::{breakend::Breakend, symbol::Symbol};
+
+/// A variant record alternate bases allele.
+///
+/// See _The Variant Call Format Specification: VCFv4.5 and BCFv2.2_ (2024-06-28) § 1.6.1.5
+/// "ALT".
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Allele<'a> {
+    /// A sequence of bases.
+    Bases(&'a str),
+    /// A symbolic allele (e.g., `<DEL>`, `<INS:ME:ALU>`, or `<*>`).
+    Symbol(Symbol<'a>),
+    /// A breakend (e.g., `G]sq1:13]` or `.A`).
+    Breakend(Breakend<'a>),
+    /// An allele missing due to an overlapping deletion (`*`).
+    OverlappingDeletion,
+}
+
+/// An error returned when a raw alternate bases allele fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The symbol is invalid.
+    InvalidSymbol(symbol::ParseError),
+    /// The breakend is invalid.
+    InvalidBreakend(breakend::ParseError),
+    /// The bases are invalid.
+    InvalidBases,
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidSymbol(e) => Some(e),
+            Self::InvalidBreakend(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidSymbol(_) => f.write_str("invalid symbol"),
+            Self::InvalidBreakend(_) => f.write_str("invalid breakend"),
+            Self::InvalidBases => f.write_str("invalid bases"),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Allele<'a> {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        const OVERLAPPING_DELETION: &str = "*";
+
+        if s.is_empty() {
+            Err(ParseError::Empty)
+        } else if s == OVERLAPPING_DELETION {
+            Ok(Self::OverlappingDeletion)
+        } else if s.starts_with('<') && !s.contains(['[', ']']) {
+            Symbol::try_from(s)
+                .map(Self::Symbol)
+                .map_err(ParseError::InvalidSymbol)
+        } else if breakend::is_breakend(s) {
+            Breakend::try_from(s)
+                .map(Self::Breakend)
+                .map_err(ParseError::InvalidBreakend)
+        } else if is_valid_bases(s) {
+            Ok(Self::Bases(s))
+        } else {
+            Err(ParseError::InvalidBases)
+        }
+    }
+}
+
+fn is_valid_bases(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| matches!(b.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T' | b'N'))
+}
+
+#[cfg(test)]
+mod tests {
+    use noodles_core::Position;
+
+    use super::*;
+
+    #[test]
+    fn test_try_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(Allele::try_from("ACgt"), Ok(Allele::Bases("ACgt")));
+        assert_eq!(Allele::try_from("*"), Ok(Allele::OverlappingDeletion));
+
+        let symbol = Allele::try_from("<INS:ME:ALU>")?;
+        assert!(matches!(symbol, Allele::Symbol(s) if s.id() == "INS:ME:ALU"));
+
+        let Allele::Breakend(breakend) = Allele::try_from("G]sq1:13]")? else {
+            panic!("expected breakend");
+        };
+
+        let mate = breakend.mate().expect("missing mate");
+        assert_eq!(mate.reference_sequence_name(), "sq1");
+        assert_eq!(mate.position(), Position::try_from(13)?);
+
+        assert_eq!(Allele::try_from(""), Err(ParseError::Empty));
+        assert_eq!(Allele::try_from("ACXT"), Err(ParseError::InvalidBases));
+        assert!(matches!(
+            Allele::try_from("<>"),
+            Err(ParseError::InvalidSymbol(_))
+        ));
+        assert!(matches!(
+            Allele::try_from("G]sq1:0]"),
+            Err(ParseError::InvalidBreakend(_))
+        ));
+
+        Ok(())
+    }
+}
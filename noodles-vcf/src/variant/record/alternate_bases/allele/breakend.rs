@@ -0,0 +1,428 @@
+//! Variant record alternate bases allele breakend.
+
+use std::{error, fmt, num};
+
+use noodles_core::Position;
+
+use super::is_valid_bases;
+
+const MISSING: char = '.';
+
+/// The direction a joined sequence extends from a breakend position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// The sequence extends to the left of the position, i.e., it ends at the position.
+    Left,
+    /// The sequence extends to the right of the position, i.e., it starts at the position.
+    Right,
+}
+
+/// The mate of a breakend.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mate<'a> {
+    reference_sequence_name: &'a str,
+    position: Position,
+    direction: Direction,
+}
+
+impl<'a> Mate<'a> {
+    /// Returns the reference sequence name of the mate.
+    ///
+    /// A name in angle brackets (e.g., `<ctg1>`) refers to an assembly contig.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// let breakend = Breakend::try_from("G]sq1:13]")?;
+    /// let mate = breakend.mate().expect("missing mate");
+    /// assert_eq!(mate.reference_sequence_name(), "sq1");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reference_sequence_name(&self) -> &'a str {
+        self.reference_sequence_name
+    }
+
+    /// Returns the position of the mate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_core::Position;
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// let breakend = Breakend::try_from("G]sq1:13]")?;
+    /// let mate = breakend.mate().expect("missing mate");
+    /// assert_eq!(mate.position(), Position::try_from(13)?);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the direction the joined sequence of the mate extends from its position.
+    ///
+    /// This is [`Direction::Right`] for `[` and [`Direction::Left`] for `]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::{breakend::Direction, Breakend};
+    /// let breakend = Breakend::try_from("G]sq1:13]")?;
+    /// let mate = breakend.mate().expect("missing mate");
+    /// assert_eq!(mate.direction(), Direction::Left);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+/// A breakend.
+///
+/// The four paired forms are `t[p[`, `t]p]`, `]p]t`, and `[p[t`, where `t` are the bases and `p`,
+/// the mate position. Single breakends are `t.` and `.t`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Breakend<'a> {
+    bases: &'a str,
+    direction: Direction,
+    mate: Option<Mate<'a>>,
+}
+
+impl<'a> Breakend<'a> {
+    /// Returns the bases.
+    ///
+    /// This includes the reference base and any inserted sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// let breakend = Breakend::try_from("GTC[sq1:13[")?;
+    /// assert_eq!(breakend.bases(), "GTC");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn bases(&self) -> &'a str {
+        self.bases
+    }
+
+    /// Returns the direction the joined sequence of this breakend extends from the record
+    /// position.
+    ///
+    /// This is [`Direction::Left`] when the bases come first (`t[p[`, `t]p]`, `t.`) and
+    /// [`Direction::Right`] when the bases come last (`]p]t`, `[p[t`, `.t`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::{breakend::Direction, Breakend};
+    /// assert_eq!(Breakend::try_from("G[sq1:13[")?.direction(), Direction::Left);
+    /// assert_eq!(Breakend::try_from("]sq1:13]G")?.direction(), Direction::Right);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the reference base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// assert_eq!(Breakend::try_from("GTC[sq1:13[")?.reference_base(), 'G');
+    /// assert_eq!(Breakend::try_from("]sq1:13]TCG")?.reference_base(), 'G');
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reference_base(&self) -> char {
+        let base = match self.direction {
+            Direction::Left => self.bases.chars().next(),
+            Direction::Right => self.bases.chars().next_back(),
+        };
+
+        // Parsing guarantees the bases are nonempty.
+        base.unwrap_or('N')
+    }
+
+    /// Returns the sequence inserted at the breakend.
+    ///
+    /// This is the bases without the reference base.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// assert_eq!(Breakend::try_from("GTC[sq1:13[")?.inserted_sequence(), "TC");
+    /// assert_eq!(Breakend::try_from("]sq1:13]TCG")?.inserted_sequence(), "TC");
+    /// assert!(Breakend::try_from("G.")?.inserted_sequence().is_empty());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn inserted_sequence(&self) -> &'a str {
+        match self.direction {
+            Direction::Left => &self.bases[1..],
+            Direction::Right => &self.bases[..self.bases.len() - 1],
+        }
+    }
+
+    /// Returns the mate.
+    ///
+    /// This is `None` for single breakends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// assert!(Breakend::try_from("G]sq1:13]")?.mate().is_some());
+    /// assert!(Breakend::try_from("G.")?.mate().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mate(&self) -> Option<Mate<'a>> {
+        self.mate
+    }
+
+    /// Returns whether this is a single breakend.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Breakend;
+    /// assert!(Breakend::try_from(".G")?.is_single());
+    /// assert!(!Breakend::try_from("G]sq1:13]")?.is_single());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_single(&self) -> bool {
+        self.mate.is_none()
+    }
+}
+
+/// An error returned when a raw breakend fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The mate position brackets are invalid.
+    InvalidBrackets,
+    /// The mate position is missing a reference sequence name.
+    MissingReferenceSequenceName,
+    /// The mate position is invalid.
+    InvalidPosition(num::ParseIntError),
+    /// The mate position is zero.
+    ZeroPosition,
+    /// The bases are invalid.
+    InvalidBases,
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidPosition(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::InvalidBrackets => f.write_str("invalid brackets"),
+            Self::MissingReferenceSequenceName => f.write_str("missing reference sequence name"),
+            Self::InvalidPosition(_) => f.write_str("invalid position"),
+            Self::ZeroPosition => f.write_str("zero position"),
+            Self::InvalidBases => f.write_str("invalid bases"),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Breakend<'a> {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let (bases, direction, mate) = if let Some(i) = s.find(['[', ']']) {
+            parse_paired(s, i)?
+        } else if let Some(bases) = s.strip_prefix(MISSING) {
+            (bases, Direction::Right, None)
+        } else if let Some(bases) = s.strip_suffix(MISSING) {
+            (bases, Direction::Left, None)
+        } else {
+            return Err(ParseError::InvalidBrackets);
+        };
+
+        if !is_valid_bases(bases) {
+            return Err(ParseError::InvalidBases);
+        }
+
+        Ok(Self {
+            bases,
+            direction,
+            mate,
+        })
+    }
+}
+
+pub(super) fn is_breakend(s: &str) -> bool {
+    s.contains(['[', ']']) || (s.len() > 1 && (s.starts_with(MISSING) || s.ends_with(MISSING)))
+}
+
+fn parse_paired(s: &str, start: usize) -> Result<(&str, Direction, Option<Mate<'_>>), ParseError> {
+    let bracket = s[start..]
+        .chars()
+        .next()
+        .ok_or(ParseError::InvalidBrackets)?;
+
+    let end = s
+        .rfind(bracket)
+        .filter(|&end| end > start)
+        .ok_or(ParseError::InvalidBrackets)?;
+
+    let (bases, direction) = if start == 0 {
+        (&s[end + 1..], Direction::Right)
+    } else if end == s.len() - 1 {
+        (&s[..start], Direction::Left)
+    } else {
+        return Err(ParseError::InvalidBrackets);
+    };
+
+    let raw_mate = &s[start + 1..end];
+
+    if raw_mate.contains(['[', ']']) || bases.contains(['[', ']']) {
+        return Err(ParseError::InvalidBrackets);
+    }
+
+    let (reference_sequence_name, raw_position) = raw_mate
+        .rsplit_once(':')
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or(ParseError::MissingReferenceSequenceName)?;
+
+    let position = raw_position
+        .parse::<usize>()
+        .map_err(ParseError::InvalidPosition)
+        .and_then(|n| Position::new(n).ok_or(ParseError::ZeroPosition))?;
+
+    let mate_direction = if bracket == '[' {
+        Direction::Right
+    } else {
+        Direction::Left
+    };
+
+    let mate = Mate {
+        reference_sequence_name,
+        position,
+        direction: mate_direction,
+    };
+
+    Ok((bases, direction, Some(mate)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_str() -> Result<(), Box<dyn std::error::Error>> {
+        fn mate(
+            reference_sequence_name: &str,
+            position: usize,
+            direction: Direction,
+        ) -> Result<Option<Mate<'_>>, Box<dyn std::error::Error>> {
+            Ok(Some(Mate {
+                reference_sequence_name,
+                position: Position::try_from(position)?,
+                direction,
+            }))
+        }
+
+        // § 5.4 "Specifying complex rearrangements with breakends" (2024-06-28)
+        assert_eq!(
+            Breakend::try_from("G]17:198982]"),
+            Ok(Breakend {
+                bases: "G",
+                direction: Direction::Left,
+                mate: mate("17", 198982, Direction::Left)?,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from("]13:123456]T"),
+            Ok(Breakend {
+                bases: "T",
+                direction: Direction::Right,
+                mate: mate("13", 123456, Direction::Left)?,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from("C[2:321682["),
+            Ok(Breakend {
+                bases: "C",
+                direction: Direction::Left,
+                mate: mate("2", 321682, Direction::Right)?,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from("[17:198983[A"),
+            Ok(Breakend {
+                bases: "A",
+                direction: Direction::Right,
+                mate: mate("17", 198983, Direction::Right)?,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from("A]<ctg1>:1]"),
+            Ok(Breakend {
+                bases: "A",
+                direction: Direction::Left,
+                mate: mate("<ctg1>", 1, Direction::Left)?,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from("G."),
+            Ok(Breakend {
+                bases: "G",
+                direction: Direction::Left,
+                mate: None,
+            })
+        );
+
+        assert_eq!(
+            Breakend::try_from(".TCG"),
+            Ok(Breakend {
+                bases: "TCG",
+                direction: Direction::Right,
+                mate: None,
+            })
+        );
+
+        assert_eq!(Breakend::try_from(""), Err(ParseError::Empty));
+        assert_eq!(
+            Breakend::try_from("G[17:198982]"),
+            Err(ParseError::InvalidBrackets)
+        );
+        assert_eq!(
+            Breakend::try_from("G[17:198982[A"),
+            Err(ParseError::InvalidBrackets)
+        );
+        assert_eq!(
+            Breakend::try_from("G[198982["),
+            Err(ParseError::MissingReferenceSequenceName)
+        );
+        assert!(matches!(
+            Breakend::try_from("G[17:ndls["),
+            Err(ParseError::InvalidPosition(_))
+        ));
+        assert_eq!(Breakend::try_from("G[17:0["), Err(ParseError::ZeroPosition));
+        assert_eq!(
+            Breakend::try_from("[17:198982["),
+            Err(ParseError::InvalidBases)
+        );
+        assert_eq!(Breakend::try_from("."), Err(ParseError::InvalidBases));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Variant record alternate bases allele symbol.
+
+use std::{error, fmt};
+
+use crate::{
+    header::record::value::{map::AlternativeAllele, Map},
+    Header,
+};
+
+const DELIMITER: char = ':';
+
+/// A symbolic alternate allele.
+///
+/// Symbol IDs are hierarchical, with levels separated by colons, e.g., `INS:ME:ALU`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Symbol<'a>(&'a str);
+
+impl<'a> Symbol<'a> {
+    /// Returns the ID.
+    ///
+    /// This does not include the angle brackets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Symbol;
+    /// let symbol = Symbol::try_from("<INS:ME:ALU>")?;
+    /// assert_eq!(symbol.id(), "INS:ME:ALU");
+    /// # Ok::<_, noodles_vcf::variant::record::alternate_bases::allele::symbol::ParseError>(())
+    /// ```
+    pub fn id(&self) -> &'a str {
+        self.0
+    }
+
+    /// Returns the structural variant type.
+    ///
+    /// This is the first level of the ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Symbol;
+    /// let symbol = Symbol::try_from("<INS:ME:ALU>")?;
+    /// assert_eq!(symbol.ty(), "INS");
+    /// # Ok::<_, noodles_vcf::variant::record::alternate_bases::allele::symbol::ParseError>(())
+    /// ```
+    pub fn ty(&self) -> &'a str {
+        self.levels().next().unwrap_or(self.0)
+    }
+
+    /// Returns an iterator over the levels of the ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Symbol;
+    /// let symbol = Symbol::try_from("<INS:ME:ALU>")?;
+    /// assert_eq!(symbol.levels().collect::<Vec<_>>(), ["INS", "ME", "ALU"]);
+    /// # Ok::<_, noodles_vcf::variant::record::alternate_bases::allele::symbol::ParseError>(())
+    /// ```
+    pub fn levels(&self) -> impl Iterator<Item = &'a str> {
+        self.0.split(DELIMITER)
+    }
+
+    /// Returns whether this is an unspecified allele (`<*>` or `<NON_REF>`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::variant::record::alternate_bases::allele::Symbol;
+    /// assert!(Symbol::try_from("<*>")?.is_unspecified());
+    /// assert!(!Symbol::try_from("<DEL>")?.is_unspecified());
+    /// # Ok::<_, noodles_vcf::variant::record::alternate_bases::allele::symbol::ParseError>(())
+    /// ```
+    pub fn is_unspecified(&self) -> bool {
+        const UNSPECIFIED: &str = "*";
+        const NON_REF: &str = "NON_REF";
+
+        matches!(self.0, UNSPECIFIED | NON_REF)
+    }
+
+    /// Resolves the symbol against the `ALT` definitions of a header.
+    ///
+    /// The most specific definition is returned, e.g., for `<INS:ME:ALU>`, `INS:ME:ALU`, `INS:ME`,
+    /// and `INS` are tried in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use noodles_vcf::{
+    ///     self as vcf,
+    ///     header::record::value::{map::AlternativeAllele, Map},
+    ///     variant::record::alternate_bases::allele::Symbol,
+    /// };
+    ///
+    /// let header = vcf::Header::builder()
+    ///     .add_alternative_allele("INS:ME", Map::<AlternativeAllele>::new("Insertion of a mobile element"))
+    ///     .build();
+    ///
+    /// let symbol = Symbol::try_from("<INS:ME:ALU>")?;
+    /// let (id, _) = symbol.resolve(&header).expect("missing definition");
+    /// assert_eq!(id, "INS:ME");
+    ///
+    /// let symbol = Symbol::try_from("<DEL>")?;
+    /// assert!(symbol.resolve(&header).is_none());
+    /// # Ok::<_, noodles_vcf::variant::record::alternate_bases::allele::symbol::ParseError>(())
+    /// ```
+    pub fn resolve<'h>(&self, header: &'h Header) -> Option<(&'h str, &'h Map<AlternativeAllele>)> {
+        let mut id = self.0;
+
+        loop {
+            if let Some((id, map)) = header.alternative_alleles().get_key_value(id) {
+                return Some((id.as_str(), map));
+            }
+
+            let (parent, _) = id.rsplit_once(DELIMITER)?;
+            id = parent;
+        }
+    }
+}
+
+/// An error returned when a raw symbolic allele fails to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The input is not enclosed in angle brackets.
+    MissingBrackets,
+    /// The ID is invalid.
+    InvalidId,
+}
+
+impl error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty input"),
+            Self::MissingBrackets => f.write_str("missing brackets"),
+            Self::InvalidId => f.write_str("invalid ID"),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Symbol<'a> {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let id = s
+            .strip_prefix('<')
+            .and_then(|t| t.strip_suffix('>'))
+            .ok_or(ParseError::MissingBrackets)?;
+
+        if is_valid_id(id) {
+            Ok(Self(id))
+        } else {
+            Err(ParseError::InvalidId)
+        }
+    }
+}
+
+fn is_valid_id(s: &str) -> bool {
+    !s.is_empty()
+        && s.split(DELIMITER).all(|level| !level.is_empty())
+        && !s.contains(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | ','))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let header = Header::builder()
+            .add_alternative_allele("INS", Map::<AlternativeAllele>::new("Insertion"))
+            .add_alternative_allele(
+                "INS:ME:ALU",
+                Map::<AlternativeAllele>::new("Insertion of an ALU element"),
+            )
+            .build();
+
+        let resolve = |s| {
+            Symbol::try_from(s)
+                .ok()
+                .and_then(|symbol| symbol.resolve(&header))
+        };
+
+        assert_eq!(
+            resolve("<INS:ME:ALU>").map(|(id, _)| id),
+            Some("INS:ME:ALU")
+        );
+        assert_eq!(resolve("<INS:ME:L1>").map(|(id, _)| id), Some("INS"));
+        assert!(resolve("<DEL>").is_none());
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        assert_eq!(Symbol::try_from("<DEL>"), Ok(Symbol("DEL")));
+        assert_eq!(Symbol::try_from("<*>"), Ok(Symbol("*")));
+
+        assert_eq!(Symbol::try_from(""), Err(ParseError::Empty));
+        assert_eq!(Symbol::try_from("DEL"), Err(ParseError::MissingBrackets));
+        assert_eq!(Symbol::try_from("<DEL"), Err(ParseError::MissingBrackets));
+        assert_eq!(Symbol::try_from("<>"), Err(ParseError::InvalidId));
+        assert_eq!(Symbol::try_from("<INS::ALU>"), Err(ParseError::InvalidId));
+        assert_eq!(Symbol::try_from("<D<EL>"), Err(ParseError::InvalidId));
+    }
+}
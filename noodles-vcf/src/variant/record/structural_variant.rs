@@ -0,0 +1,367 @@
+//! Variant record structural variant helpers.
+//!
+//! These read the structural variant INFO fields `SVLEN`, `END`, `CIPOS`, and `CIEND`.
+
+use std::io;
+
+use noodles_core::Position;
+
+use super::{
+    alternate_bases::Allele,
+    info::field::{key, value::Array, Value},
+    AlternateBases, Info, Record, ReferenceBases,
+};
+use crate::Header;
+
+/// A confidence interval, as offsets relative to a position.
+pub type ConfidenceInterval = (i32, i32);
+
+/// The reference interval affected by a structural variant allele.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Interval {
+    start: Position,
+    end: Position,
+    start_confidence_interval: ConfidenceInterval,
+    end_confidence_interval: ConfidenceInterval,
+}
+
+impl Interval {
+    /// Returns the start position.
+    ///
+    /// This is the record position, i.e., it includes the padding base of symbolic alleles.
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// Returns the end position.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns the confidence interval around the start position (`CIPOS`).
+    ///
+    /// This is `(0, 0)` if the record has none.
+    pub fn start_confidence_interval(&self) -> ConfidenceInterval {
+        self.start_confidence_interval
+    }
+
+    /// Returns the confidence interval around the end position (`CIEND`).
+    ///
+    /// This is `(0, 0)` if the record has none.
+    pub fn end_confidence_interval(&self) -> ConfidenceInterval {
+        self.end_confidence_interval
+    }
+}
+
+/// Returns the structural variant lengths (`SVLEN`).
+///
+/// There is one length per alternate allele. Lengths are absolute, i.e., negative lengths of
+/// deletions, as written by VCF < 4.4, are made positive.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_vcf::{
+///     self as vcf,
+///     variant::{
+///         record::{info::field::key, structural_variant},
+///         record_buf::{info::field::Value, AlternateBases},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::default();
+///
+/// let record = RecordBuf::builder()
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("<DEL>")]))
+///     .set_info(
+///         [(String::from(key::SV_LENGTHS), Some(Value::from(vec![Some(-8)])))]
+///             .into_iter()
+///             .collect(),
+///     )
+///     .build();
+///
+/// assert_eq!(structural_variant::lengths(&header, &record)?, [Some(8)]);
+/// # Ok::<_, std::io::Error>(())
+/// ```
+pub fn lengths<R>(header: &Header, record: &R) -> io::Result<Vec<Option<usize>>>
+where
+    R: Record + ?Sized,
+{
+    let info = record.info();
+
+    let Some(values) = get_integers(header, &info, key::SV_LENGTHS)? else {
+        return Ok(Vec::new());
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            value
+                .map(|n| {
+                    usize::try_from(n.unsigned_abs()).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidData, "invalid INFO SVLEN value")
+                    })
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+/// Returns the reference interval affected by an alternate allele.
+///
+/// The allele index is 0-based and only counts alternate alleles.
+///
+/// The end position is `END`, if set. Otherwise, it is the start position plus `SVLEN` for
+/// symbolic alleles other than insertions, and the end of the reference bases for all others.
+///
+/// # Examples
+///
+/// ```
+/// use noodles_core::Position;
+/// use noodles_vcf::{
+///     self as vcf,
+///     variant::{
+///         record::{info::field::key, structural_variant},
+///         record_buf::{info::field::Value, AlternateBases},
+///         RecordBuf,
+///     },
+/// };
+///
+/// let header = vcf::Header::default();
+///
+/// let record = RecordBuf::builder()
+///     .set_variant_start(Position::try_from(8)?)
+///     .set_reference_bases("A")
+///     .set_alternate_bases(AlternateBases::from(vec![String::from("<DEL>")]))
+///     .set_info(
+///         [
+///             (String::from(key::SV_LENGTHS), Some(Value::from(vec![Some(5)]))),
+///             (
+///                 String::from(key::POSITION_CONFIDENCE_INTERVALS),
+///                 Some(Value::from(vec![Some(-2), Some(3)])),
+///             ),
+///         ]
+///         .into_iter()
+///         .collect(),
+///     )
+///     .build();
+///
+/// let interval = structural_variant::interval(&header, &record, 0)?;
+/// assert_eq!(interval.start(), Position::try_from(8)?);
+/// assert_eq!(interval.end(), Position::try_from(13)?);
+/// assert_eq!(interval.start_confidence_interval(), (-2, 3));
+/// assert_eq!(interval.end_confidence_interval(), (0, 0));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn interval<R>(header: &Header, record: &R, allele_index: usize) -> io::Result<Interval>
+where
+    R: Record + ?Sized,
+{
+    let start = record
+        .variant_start()
+        .transpose()?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing position"))?;
+
+    let alternate_bases = record.alternate_bases();
+    let allele_count = alternate_bases.len();
+
+    let allele = alternate_bases
+        .alleles()
+        .nth(allele_index)
+        .transpose()?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid allele index"))?;
+
+    let info = record.info();
+
+    let end = if let Some(end) = get_end(header, &info)? {
+        end
+    } else {
+        let len = match allele {
+            Allele::Symbol(symbol) if symbol.ty() != "INS" => lengths(header, record)?
+                .get(allele_index)
+                .copied()
+                .flatten(),
+            _ => None,
+        };
+
+        let offset = match len {
+            Some(len) => len,
+            None => record.reference_bases().len().saturating_sub(1),
+        };
+
+        start
+            .checked_add(offset)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "position overflow"))?
+    };
+
+    let start_confidence_interval = get_confidence_interval(
+        header,
+        &info,
+        key::POSITION_CONFIDENCE_INTERVALS,
+        allele_count,
+        allele_index,
+    )?;
+
+    let end_confidence_interval = get_confidence_interval(
+        header,
+        &info,
+        key::END_CONFIDENCE_INTERVALS,
+        allele_count,
+        allele_index,
+    )?;
+
+    Ok(Interval {
+        start,
+        end,
+        start_confidence_interval,
+        end_confidence_interval,
+    })
+}
+
+fn get_integers<I>(header: &Header, info: &I, key: &str) -> io::Result<Option<Vec<Option<i32>>>>
+where
+    I: Info,
+{
+    match info.get(header, key).transpose()?.flatten() {
+        None => Ok(None),
+        Some(Value::Integer(n)) => Ok(Some(vec![Some(n)])),
+        Some(Value::Array(Array::Integer(values))) => {
+            values.iter().collect::<io::Result<_>>().map(Some)
+        }
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid INFO {key} value"),
+        )),
+    }
+}
+
+fn get_end<I>(header: &Header, info: &I) -> io::Result<Option<Position>>
+where
+    I: Info,
+{
+    let Some(values) = get_integers(header, info, key::END_POSITION)? else {
+        return Ok(None);
+    };
+
+    match values[..] {
+        [Some(n)] => usize::try_from(n)
+            .ok()
+            .and_then(Position::new)
+            .map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid INFO END value")),
+        [None] => Ok(None),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid INFO END value",
+        )),
+    }
+}
+
+/// Returns the confidence interval of an allele.
+///
+/// Before VCF 4.5, there is one pair for all alleles. Otherwise, there is one pair per alternate
+/// allele.
+fn get_confidence_interval<I>(
+    header: &Header,
+    info: &I,
+    key: &str,
+    allele_count: usize,
+    allele_index: usize,
+) -> io::Result<ConfidenceInterval>
+where
+    I: Info,
+{
+    let Some(values) = get_integers(header, info, key)? else {
+        return Ok((0, 0));
+    };
+
+    let i = if values.len() == 2 {
+        0
+    } else if values.len() == 2 * allele_count {
+        2 * allele_index
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid INFO {key} length"),
+        ));
+    };
+
+    match values[i..i + 2] {
+        [Some(a), Some(b)] => Ok((a, b)),
+        _ => Ok((0, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{
+        record_buf::{info::field::Value as ValueBuf, AlternateBases as AlternateBasesBuf},
+        RecordBuf,
+    };
+
+    #[test]
+    fn test_interval() -> Result<(), Box<dyn std::error::Error>> {
+        let header = Header::default();
+
+        let build_record = |alleles: &[&str], info: Vec<(&str, ValueBuf)>| {
+            RecordBuf::builder()
+                .set_variant_start(Position::MIN)
+                .set_reference_bases("A")
+                .set_alternate_bases(AlternateBasesBuf::from(
+                    alleles.iter().map(|s| String::from(*s)).collect::<Vec<_>>(),
+                ))
+                .set_info(
+                    info.into_iter()
+                        .map(|(key, value)| (String::from(key), Some(value)))
+                        .collect(),
+                )
+                .build()
+        };
+
+        // VCF 4.5: one SVLEN and CIPOS pair per allele.
+        let record = build_record(
+            &["<DEL>", "<INS>"],
+            vec![
+                (key::SV_LENGTHS, ValueBuf::from(vec![Some(5), Some(8)])),
+                (
+                    key::POSITION_CONFIDENCE_INTERVALS,
+                    ValueBuf::from(vec![Some(-1), Some(1), Some(-2), Some(2)]),
+                ),
+            ],
+        );
+
+        let interval = interval(&header, &record, 0)?;
+        assert_eq!(interval.end(), Position::try_from(6)?);
+        assert_eq!(interval.start_confidence_interval(), (-1, 1));
+
+        let interval = super::interval(&header, &record, 1)?;
+        assert_eq!(interval.end(), Position::MIN);
+        assert_eq!(interval.start_confidence_interval(), (-2, 2));
+
+        // END takes precedence.
+        let record = build_record(
+            &["<DUP>"],
+            vec![
+                (key::END_POSITION, ValueBuf::from(21)),
+                (key::SV_LENGTHS, ValueBuf::from(vec![Some(5)])),
+            ],
+        );
+
+        assert_eq!(
+            super::interval(&header, &record, 0)?.end(),
+            Position::try_from(21)?
+        );
+
+        let record = build_record(&["C[sq1:13["], Vec::new());
+        assert_eq!(super::interval(&header, &record, 0)?.end(), Position::MIN);
+
+        assert!(matches!(
+            super::interval(&header, &record, 1),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput
+        ));
+
+        Ok(())
+    }
+}